@@ -0,0 +1,315 @@
+//! Persistent pen strokes and arrows, saved next to the deck.
+//!
+//! Annotations drawn while persistence is on are kept per slide in
+//! `<deck>.annotations.json`, in slide coordinates normalized to the slide
+//! size (0.0 to 1.0; y can go past 1.0 on slides that scroll). The file is
+//! reloaded when the deck is presented again, and `mdeck export
+//! --annotations` draws them into the exported slides.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::theme::Theme;
+
+/// Saved annotations of a deck, keyed by slide number (counting from 1).
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Annotations {
+    #[serde(default)]
+    slides: BTreeMap<usize, SlideAnnotations>,
+}
+
+/// The annotations on one slide.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlideAnnotations {
+    /// Freehand pen strokes, as the points of each stroke.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strokes: Vec<Vec<[f32; 2]>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arrows: Vec<Arrow>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Arrow {
+    pub from: [f32; 2],
+    pub to: [f32; 2],
+}
+
+/// `talk.annotations.json` for `talk.md`.
+pub fn sidecar_path(deck: &Path) -> PathBuf {
+    deck.with_extension("annotations.json")
+}
+
+/// The point `pos` inside `rect`, normalized to the rect size and rounded so
+/// the file stays small.
+pub fn normalize(pos: egui::Pos2, rect: egui::Rect) -> [f32; 2] {
+    let round = |v: f32| (v * 10_000.0).round() / 10_000.0;
+    [
+        round((pos.x - rect.left()) / rect.width()),
+        round((pos.y - rect.top()) / rect.height()),
+    ]
+}
+
+fn denormalize([x, y]: [f32; 2], rect: egui::Rect) -> egui::Pos2 {
+    egui::pos2(
+        rect.left() + x * rect.width(),
+        rect.top() + y * rect.height(),
+    )
+}
+
+impl Annotations {
+    /// Read the annotations saved at `path`; none when the file does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+        serde_json::from_str(&json)
+            .with_context(|| format!("Invalid annotations in {}", path.display()))
+    }
+
+    /// Write the annotations to `path`, removing the file once none are left.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if self.is_empty() {
+            return match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+                }
+                _ => Ok(()),
+            };
+        }
+        let json = serde_json::to_string(self)?;
+        std::fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slides.is_empty()
+    }
+
+    /// The annotations on slide `idx` (counting from 0).
+    pub fn slide(&self, idx: usize) -> Option<&SlideAnnotations> {
+        self.slides.get(&(idx + 1))
+    }
+
+    pub fn add_stroke(&mut self, idx: usize, points: Vec<[f32; 2]>) {
+        self.slides.entry(idx + 1).or_default().strokes.push(points);
+    }
+
+    pub fn add_arrow(&mut self, idx: usize, arrow: Arrow) {
+        self.slides.entry(idx + 1).or_default().arrows.push(arrow);
+    }
+
+    /// Remove the annotations on slide `idx`; whether there were any.
+    pub fn clear_slide(&mut self, idx: usize) -> bool {
+        self.slides.remove(&(idx + 1)).is_some()
+    }
+
+    /// Draw the annotations of slide `idx` onto `rect`, the slide's area.
+    pub fn paint(
+        &self,
+        painter: &egui::Painter,
+        idx: usize,
+        rect: egui::Rect,
+        theme: &Theme,
+        scale: f32,
+    ) {
+        let Some(slide) = self.slide(idx) else {
+            return;
+        };
+        for stroke in &slide.strokes {
+            let points = stroke.iter().map(|&p| denormalize(p, rect)).collect();
+            draw_pen_stroke(painter, points, theme, 1.0, scale);
+        }
+        for arrow in &slide.arrows {
+            let from = denormalize(arrow.from, rect);
+            let to = denormalize(arrow.to, rect);
+            draw_arrow(painter, from, to, theme, 1.0, scale);
+        }
+    }
+}
+
+/// Draw a freehand pen stroke (blue) through `points`, with an outline.
+pub fn draw_pen_stroke(
+    painter: &egui::Painter,
+    points: Vec<egui::Pos2>,
+    theme: &Theme,
+    opacity: f32,
+    scale: f32,
+) {
+    if points.len() < 2 {
+        return;
+    }
+    let pen_width = 6.0 * scale;
+    let dark = theme.name == "dark";
+    // Outline pass
+    painter.add(egui::Shape::line(
+        points.clone(),
+        egui::Stroke::new(pen_width + 2.0 * scale, pen_outline_color(dark, opacity)),
+    ));
+    // Main pass
+    painter.add(egui::Shape::line(
+        points,
+        egui::Stroke::new(pen_width, pen_color(dark, opacity)),
+    ));
+}
+
+/// Draw an arrow (orange, or red on light themes) with an outline.
+pub fn draw_arrow(
+    painter: &egui::Painter,
+    from: egui::Pos2,
+    to: egui::Pos2,
+    theme: &Theme,
+    opacity: f32,
+    scale: f32,
+) {
+    let arrow_width = 5.0 * scale;
+    let arrow_size = 22.0 * scale;
+    let dark = theme.name == "dark";
+    // Outline pass
+    draw_arrow_shape(
+        painter,
+        from,
+        to,
+        arrow_width + 2.0 * scale,
+        arrow_size + 3.0 * scale,
+        arrow_outline_color(dark, opacity),
+    );
+    // Main pass
+    draw_arrow_shape(
+        painter,
+        from,
+        to,
+        arrow_width,
+        arrow_size,
+        arrow_color(dark, opacity),
+    );
+}
+
+/// Pen color: cyan/blue tones
+fn pen_color(dark: bool, opacity: f32) -> egui::Color32 {
+    if dark {
+        egui::Color32::from_rgba_unmultiplied(80, 200, 255, (opacity * 230.0) as u8)
+    } else {
+        egui::Color32::from_rgba_unmultiplied(30, 80, 200, (opacity * 230.0) as u8)
+    }
+}
+
+/// Pen outline color: darker cyan/blue
+fn pen_outline_color(dark: bool, opacity: f32) -> egui::Color32 {
+    if dark {
+        egui::Color32::from_rgba_unmultiplied(30, 130, 180, (opacity * 140.0) as u8)
+    } else {
+        egui::Color32::from_rgba_unmultiplied(15, 40, 130, (opacity * 140.0) as u8)
+    }
+}
+
+/// Arrow color: yellow-orange / red tones
+fn arrow_color(dark: bool, opacity: f32) -> egui::Color32 {
+    if dark {
+        egui::Color32::from_rgba_unmultiplied(255, 200, 50, (opacity * 230.0) as u8)
+    } else {
+        egui::Color32::from_rgba_unmultiplied(220, 40, 40, (opacity * 230.0) as u8)
+    }
+}
+
+/// Arrow outline color: darker orange / red
+fn arrow_outline_color(dark: bool, opacity: f32) -> egui::Color32 {
+    if dark {
+        egui::Color32::from_rgba_unmultiplied(200, 140, 0, (opacity * 140.0) as u8)
+    } else {
+        egui::Color32::from_rgba_unmultiplied(150, 20, 20, (opacity * 140.0) as u8)
+    }
+}
+
+/// Draw an arrow from `from` to `to` with a filled triangular arrowhead
+fn draw_arrow_shape(
+    painter: &egui::Painter,
+    from: egui::Pos2,
+    to: egui::Pos2,
+    stroke_width: f32,
+    arrow_size: f32,
+    color: egui::Color32,
+) {
+    let delta = to - from;
+    let len = delta.length();
+    if len < 1.0 {
+        return;
+    }
+    let dir = delta / len;
+    let perp = egui::vec2(-dir.y, dir.x);
+
+    // Arrowhead triangle points (wider spread)
+    let p1 = to - dir * arrow_size + perp * arrow_size * 0.45;
+    let p2 = to - dir * arrow_size - perp * arrow_size * 0.45;
+
+    // Shaft (stop further back from head to avoid blunt overlap)
+    painter.line_segment(
+        [from, to - dir * arrow_size * 0.7],
+        egui::Stroke::new(stroke_width, color),
+    );
+    // Arrowhead
+    painter.add(egui::Shape::convex_polygon(
+        vec![to, p1, p2],
+        color,
+        egui::Stroke::NONE,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn normalizes_to_the_slide_rect() {
+        let rect = egui::Rect::from_min_size(egui::pos2(100.0, 50.0), egui::vec2(1600.0, 900.0));
+        let point = normalize(egui::pos2(500.0, 950.0), rect);
+        assert_eq!(point, [0.25, 1.0]);
+        assert_eq!(denormalize(point, rect), egui::pos2(500.0, 950.0));
+
+        let half = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 450.0));
+        assert_eq!(denormalize(point, half), egui::pos2(200.0, 450.0));
+    }
+
+    #[test]
+    fn saves_per_slide_and_removes_the_file_when_cleared() {
+        let dir = TempDir::new("annotations");
+        let path = sidecar_path(&dir.join("talk.md"));
+        assert_eq!(path.file_name().unwrap(), "talk.annotations.json");
+        assert_eq!(Annotations::load(&path).unwrap(), Annotations::default());
+
+        let mut annotations = Annotations::default();
+        annotations.add_stroke(2, vec![[0.1, 0.2], [0.3, 0.4]]);
+        annotations.add_arrow(
+            2,
+            Arrow {
+                from: [0.5, 0.5],
+                to: [0.75, 0.25],
+            },
+        );
+        annotations.save(&path).unwrap();
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            json.contains("\"3\":{\"strokes\":[[[0.1,0.2],[0.3,0.4]]]"),
+            "{json}"
+        );
+
+        let loaded = Annotations::load(&path).unwrap();
+        assert_eq!(loaded, annotations);
+        assert!(loaded.slide(1).is_none());
+        assert_eq!(loaded.slide(2).unwrap().arrows.len(), 1);
+
+        annotations.clear_slide(2);
+        annotations.save(&path).unwrap();
+        assert!(!path.exists());
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(Annotations::load(&path).is_err());
+    }
+}
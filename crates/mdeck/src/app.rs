@@ -1,26 +1,38 @@
 use eframe::egui;
 use std::hash::{DefaultHasher, Hash, Hasher};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, mpsc};
-use std::time::Instant;
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{Duration, Instant};
 
 use notify_debouncer_mini::{DebouncedEventKind, Debouncer, new_debouncer, notify};
 
+use crate::annotations::{self, Annotations};
 use crate::check::CheckReport;
 use crate::config::Config;
 use crate::incident_log::IncidentLog;
-use crate::parser::{self, Presentation};
+use crate::parser::{self, DiagramKind, Presentation};
+use crate::rehearsal::{self, Rehearsal};
+use crate::remote::{Remote, RemoteCommand, RemoteState};
 use crate::render;
+use crate::render::aspect::AspectRatio;
+use crate::render::footer::FooterContext;
 use crate::render::image_cache::ImageCache;
+use crate::render::links::{self, LinkRegion};
+use crate::render::text;
 use crate::render::transition::{
     ActiveTransition, TransitionDirection, TransitionKind, ease_in_out,
 };
-use crate::theme::Theme;
+use crate::search::{self, Palette};
+use crate::theme::{CustomThemes, Theme};
 
 const OVERVIEW_TRANSITION_DURATION: f32 = 0.4;
 const DRAW_FADE_DURATION: f32 = 8.0;
 const DRAG_THRESHOLD: f32 = 5.0;
+const LASER_TRAIL_DURATION: f32 = 0.5;
+const MAGNIFIER_ZOOM: f32 = 2.0;
+const SLIDE_JUMP_TIMEOUT: f32 = 3.0;
+const PRESENTER_BG: egui::Color32 = egui::Color32::from_rgb(0x14, 0x14, 0x14);
 
 /// A freehand pen stroke (left-drag)
 struct PenStroke {
@@ -61,6 +73,19 @@ enum ActiveDraw {
     },
 }
 
+/// What the mouse pointer does over a slide
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PointerTool {
+    /// Left-drag draws with the pen, right-drag draws arrows
+    Draw,
+    /// A red dot with a fading trail (L)
+    Laser,
+    /// Dims everything outside a circle around the pointer (O)
+    Spotlight,
+    /// A lens showing the slide enlarged around the pointer (M)
+    Magnifier,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum RawOverlaySide {
     Off,
@@ -80,15 +105,29 @@ struct PresentationApp {
     file_path: PathBuf,
     current_slide: usize,
     watcher_rx: mpsc::Receiver<()>,
-    _watcher: Debouncer<notify::RecommendedWatcher>,
+    watcher: Debouncer<notify::RecommendedWatcher>,
+    /// Theme files currently watched alongside the deck.
+    watched_theme_files: Vec<PathBuf>,
     mode: AppMode,
     theme: Theme,
+    /// Custom themes the deck references, for deck and slide `@theme`.
+    custom_themes: CustomThemes,
+    /// Slide aspect ratio from `@aspect`; slides are letterboxed to it.
+    aspect: AspectRatio,
     default_transition: TransitionKind,
     transition: Option<ActiveTransition>,
     image_cache: ImageCache,
     show_hud: bool,
     raw_overlay_side: RawOverlaySide,
     toast: Option<Toast>,
+    /// Search palette (`/` or Ctrl+F), while open.
+    search: Option<Palette>,
+    /// Slide number being typed, and when its last digit was; Enter jumps.
+    slide_jump: Option<(String, Instant)>,
+    /// Links on the slide as last drawn, in screen coordinates.
+    link_regions: Vec<LinkRegion>,
+    /// Slides left by following intra-deck links; Backspace goes back.
+    link_history: Vec<usize>,
     last_ctrl_c: Option<Instant>,
     last_esc: Option<Instant>,
     reveal_steps: Vec<usize>,
@@ -104,6 +143,18 @@ struct PresentationApp {
     pen_strokes: Vec<PenStroke>,
     arrows: Vec<ArrowAnnotation>,
     active_draw: ActiveDraw,
+    /// Annotations kept in the deck's sidecar file instead of fading out.
+    saved_annotations: Annotations,
+    /// Where `saved_annotations` are stored, next to the deck.
+    annotations_path: PathBuf,
+    /// Whether new annotations are saved (toggled with `A`) rather than fading.
+    persist_annotations: bool,
+    /// The laser, spotlight or magnifier replacing the pen and arrow.
+    pointer_tool: PointerTool,
+    /// Recent pointer positions on screen, drawn as the laser trail.
+    laser_trail: Vec<(egui::Pos2, Instant)>,
+    /// Pointer position on screen while it is over the slide window.
+    pointer_pos: Option<egui::Pos2>,
     /// Cached slide rect from last frame, used for mouse coordinate conversion
     last_slide_rect: egui::Rect,
     /// Which grid cell the mouse is hovering over
@@ -116,7 +167,7 @@ struct PresentationApp {
     grid_scroll_offset: f32,
     /// Target scroll position in grid
     grid_scroll_target: f32,
-    /// Hash of last loaded file content (to skip spurious watcher events)
+    /// Hash of last loaded deck and theme file content (to skip spurious watcher events)
     last_content_hash: u64,
     /// Cancel flag for the background diagram route pre-caching thread.
     precache_cancel: Arc<AtomicBool>,
@@ -138,6 +189,22 @@ struct PresentationApp {
     incident_log: Arc<IncidentLog>,
     /// Timestamp of the previous frame, used to detect power-state time jumps.
     last_frame: Instant,
+    /// Whether the presenter view window (notes, next slide, timer) is open.
+    presenter_open: bool,
+    /// Start of the presentation timer (reset with Z in the presenter view).
+    timer_start: Instant,
+    /// Target talk length from `@duration`, shown as remaining time.
+    target_duration: Option<Duration>,
+    /// Scroll offset of the notes panel in the presenter view.
+    notes_scroll: f32,
+    /// Slide whose notes `notes_scroll` refers to (scroll resets on slide change).
+    notes_scroll_slide: usize,
+    /// Parsed speaker notes of `notes_scroll_slide`, so notes are parsed once per slide.
+    notes_blocks: Option<Vec<parser::Block>>,
+    /// Timing of `mdeck rehearse`, fed the slide and step on screen every frame.
+    rehearsal: Option<Arc<Mutex<Rehearsal>>>,
+    /// Remote control server (`--remote`), fed the slide state every frame.
+    remote: Option<Arc<Remote>>,
 }
 
 struct Toast {
@@ -176,6 +243,7 @@ impl PresentationApp {
     fn new(
         file: PathBuf,
         presentation: Presentation,
+        custom_themes: CustomThemes,
         windowed: bool,
         watcher_rx: mpsc::Receiver<()>,
         watcher: Debouncer<notify::RecommendedWatcher>,
@@ -185,18 +253,34 @@ impl PresentationApp {
     ) -> Self {
         let _ = windowed; // used at window creation time
 
-        let theme_name = presentation.meta.theme.as_deref().unwrap_or("light");
-        let theme = Theme::from_name(theme_name);
+        let theme = Theme::for_deck(&presentation.meta, &custom_themes);
+        let aspect = AspectRatio::for_deck(&presentation.meta);
 
         let transition_name = presentation.meta.transition.as_deref().unwrap_or("slide");
         let default_transition = TransitionKind::from_name(transition_name);
 
+        let target_duration = presentation
+            .meta
+            .duration
+            .as_deref()
+            .and_then(parser::frontmatter::parse_duration);
+
         let base_path = file
             .parent()
             .unwrap_or(std::path::Path::new("."))
             .to_path_buf();
         let image_cache = ImageCache::new(base_path);
 
+        let annotations_path = annotations::sidecar_path(&file);
+        let saved_annotations = Annotations::load(&annotations_path).unwrap_or_else(|e| {
+            if !quiet {
+                eprintln!("Warning: {e:#}");
+            }
+            Annotations::default()
+        });
+        // A deck that was annotated before keeps saving new annotations
+        let persist_annotations = !saved_annotations.is_empty();
+
         let max_steps: Vec<usize> = presentation
             .slides
             .iter()
@@ -214,15 +298,22 @@ impl PresentationApp {
             file_path: file,
             current_slide: 0,
             watcher_rx,
-            _watcher: watcher,
+            watcher,
+            watched_theme_files: Vec::new(),
             mode: AppMode::Presentation,
             theme,
+            custom_themes,
+            aspect,
             default_transition,
             transition: None,
             image_cache,
             show_hud: false,
             raw_overlay_side: RawOverlaySide::Off,
             toast: None,
+            search: None,
+            slide_jump: None,
+            link_regions: Vec::new(),
+            link_history: Vec::new(),
             last_ctrl_c: None,
             last_esc: None,
             reveal_steps,
@@ -237,6 +328,12 @@ impl PresentationApp {
             pen_strokes: Vec::new(),
             arrows: Vec::new(),
             active_draw: ActiveDraw::None,
+            saved_annotations,
+            annotations_path,
+            persist_annotations,
+            pointer_tool: PointerTool::Draw,
+            laser_trail: Vec::new(),
+            pointer_pos: None,
             last_slide_rect: egui::Rect::ZERO,
             hover_slide: None,
             use_hover: false,
@@ -254,6 +351,14 @@ impl PresentationApp {
             shared_slide: None,
             incident_log,
             last_frame: now,
+            presenter_open: false,
+            timer_start: now,
+            target_duration,
+            notes_scroll: 0.0,
+            notes_scroll_slide: 0,
+            notes_blocks: None,
+            rehearsal: None,
+            remote: None,
         }
     }
 
@@ -303,7 +408,7 @@ impl PresentationApp {
         self.transition = Some(ActiveTransition::new(
             idx,
             idx + 1,
-            self.default_transition,
+            self.transition_for(idx + 1),
             TransitionDirection::Forward,
         ));
     }
@@ -338,14 +443,26 @@ impl PresentationApp {
         // Show previous slide fully revealed
         self.reveal_steps[prev] = self.max_steps[prev];
 
+        // Leaving a slide backwards replays the transition it was entered with
         self.transition = Some(ActiveTransition::new(
             idx,
             prev,
-            self.default_transition,
+            self.transition_for(idx),
             TransitionDirection::Backward,
         ));
     }
 
+    /// Transition used when entering `index`: its `@transition` directive,
+    /// otherwise the deck default.
+    fn transition_for(&self, index: usize) -> TransitionKind {
+        self.presentation
+            .slides
+            .get(index)
+            .and_then(|s| s.directive("transition"))
+            .map(TransitionKind::from_name)
+            .unwrap_or(self.default_transition)
+    }
+
     fn jump_to_slide(&mut self, index: usize) {
         if index < self.slide_count() && self.transition.is_none() {
             let cur = self.current_slide;
@@ -356,8 +473,165 @@ impl PresentationApp {
         }
     }
 
+    /// Follow a clicked link: `#anchor` and `#slide-N` jump within the deck,
+    /// anything else opens in the system browser.
+    fn follow_link(&mut self, ctx: &egui::Context, url: &str) {
+        if !url.starts_with('#') {
+            ctx.open_url(egui::OpenUrl::new_tab(external_link(url, &self.file_path)));
+            return;
+        }
+        match self.presentation.link_slide(url) {
+            Some(index) => {
+                self.link_history.push(self.current_slide);
+                self.jump_to_slide(index);
+            }
+            None => self.toast = Some(Toast::new(format!("No slide for link {url}"))),
+        }
+    }
+
+    /// Return to the slide the last followed link was clicked on.
+    fn link_back(&mut self) {
+        if self.transition.is_none() {
+            if let Some(index) = self.link_history.pop() {
+                self.jump_to_slide(index);
+            }
+        }
+    }
+
+    /// Apply a command from the remote control, as the keyboard would.
+    fn apply_remote_command(&mut self, command: RemoteCommand) {
+        if command == RemoteCommand::Blackout {
+            self.blackout = !self.blackout;
+            return;
+        }
+        if self.blackout {
+            return;
+        }
+        if matches!(self.mode, AppMode::Grid { .. }) {
+            self.mode = AppMode::Presentation;
+        }
+        match command {
+            RemoteCommand::Next => self.navigate_forward(),
+            RemoteCommand::Previous => self.navigate_backward(),
+            RemoteCommand::Goto(slide) => self.jump_to_slide(slide - 1),
+            RemoteCommand::Blackout => {}
+        }
+    }
+
+    /// Go to slide `index` from the search palette or a typed number: jump
+    /// in the presentation, or zoom out of the grid onto it.
+    fn go_to_slide(&mut self, index: usize, mode: AppMode) {
+        if let AppMode::Grid { .. } = mode {
+            self.use_hover = false;
+            self.mode = AppMode::OverviewTransition {
+                selected: index,
+                entering: false,
+            };
+            self.overview_transition_start = Some(Instant::now());
+        } else {
+            self.jump_to_slide(index);
+        }
+    }
+
+    /// Keys while the search palette is open: typing edits the query,
+    /// Up/Down pick a hit, Enter goes to it and Esc closes the palette.
+    fn handle_search_input(&mut self, i: &egui::InputState, mode: AppMode) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        if i.key_pressed(egui::Key::Escape) {
+            self.search = None;
+            return;
+        }
+        if i.key_pressed(egui::Key::Enter) {
+            let slide = search.selected().map(|hit| hit.slide);
+            self.search = None;
+            if let Some(slide) = slide {
+                self.go_to_slide(slide, mode);
+            }
+            return;
+        }
+        if i.key_pressed(egui::Key::ArrowDown) {
+            search.move_selection(1);
+        }
+        if i.key_pressed(egui::Key::ArrowUp) {
+            search.move_selection(-1);
+        }
+        if i.key_pressed(egui::Key::Backspace) {
+            search.backspace();
+        }
+        if !i.modifiers.command {
+            for event in &i.events {
+                if let egui::Event::Text(text) = event {
+                    search.insert(text);
+                }
+            }
+        }
+        // The grid follows the selected hit
+        if let (AppMode::Grid { .. }, Some(hit)) = (mode, search.selected()) {
+            self.mode = AppMode::Grid {
+                selected: hit.slide,
+            };
+            self.use_hover = false;
+        }
+    }
+
+    /// Collect digits typed for jump-to-slide; whether Enter used them.
+    fn handle_slide_jump(&mut self, i: &egui::InputState, mode: AppMode) -> bool {
+        let typed: String = i
+            .events
+            .iter()
+            .filter_map(|e| match e {
+                egui::Event::Text(t) if t.chars().all(|c| c.is_ascii_digit()) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        if !typed.is_empty() && !i.modifiers.command {
+            let mut digits = self.slide_jump.take().map(|(d, _)| d).unwrap_or_default();
+            digits.push_str(&typed);
+            // Five digits are more slides than any deck has
+            digits.truncate(5);
+            self.slide_jump = Some((digits, Instant::now()));
+        }
+        if !i.key_pressed(egui::Key::Enter) {
+            return false;
+        }
+        let Some((digits, _)) = self.slide_jump.take() else {
+            return false;
+        };
+        match digits.parse::<usize>() {
+            Ok(n) if (1..=self.slide_count()).contains(&n) => self.go_to_slide(n - 1, mode),
+            _ => self.toast = Some(Toast::new(format!("No slide {digits}"))),
+        }
+        true
+    }
+
+    /// Whether remote commands wait for a slide or overview animation to end.
+    fn remote_waits(&self) -> bool {
+        self.transition.is_some() || matches!(self.mode, AppMode::OverviewTransition { .. })
+    }
+
+    /// The slide state shown on the remote control page.
+    fn remote_state(&self) -> RemoteState {
+        let idx = self.current_slide;
+        let slide = self.presentation.slides.get(idx);
+        RemoteState {
+            title: self.presentation.meta.title.clone().unwrap_or_default(),
+            slide: idx + 1,
+            total: self.slide_count(),
+            step: self.reveal_steps.get(idx).copied().unwrap_or(0),
+            steps: self.max_steps.get(idx).copied().unwrap_or(0),
+            end: self.on_end_slide,
+            blackout: self.blackout,
+            heading: slide.map(parser::Slide::title).unwrap_or_default(),
+            notes: slide.and_then(|s| s.notes.clone()).unwrap_or_default(),
+        }
+    }
+
     fn toggle_theme(&mut self) {
+        let code_theme = self.theme.code_theme.take();
         self.theme = self.theme.next();
+        self.theme.code_theme = code_theme;
         self.toast = Some(Toast::new(format!("Theme: {}", self.theme.name)));
     }
 
@@ -387,7 +661,7 @@ impl PresentationApp {
         }
     }
 
-    fn reload_presentation(&mut self) {
+    fn reload_presentation(&mut self, ctx: &egui::Context) {
         let content = match std::fs::read_to_string(&self.file_path) {
             Ok(c) => c,
             Err(e) => {
@@ -403,20 +677,35 @@ impl PresentationApp {
 
         // Skip reload if file content hasn't actually changed (macOS FSEvents
         // can fire spuriously, and each reload resets per-slide state).
-        let new_hash = hash_content(&content);
+        let new_hash = hash_sources(&content, self.custom_themes.files());
         if new_hash == self.last_content_hash {
             return;
         }
         self.last_content_hash = new_hash;
 
         let base_path = self.file_path.parent().unwrap_or(std::path::Path::new("."));
-        let new_presentation = parser::parse(&content, base_path);
+        let mut new_presentation = parser::parse(&content, base_path);
+        Config::load_or_default().apply_deck_defaults(&mut new_presentation.meta);
 
         if new_presentation.slides.is_empty() {
             self.toast = Some(Toast::new("Reload: no slides found".to_string()));
             return;
         }
 
+        let (custom_themes, mut theme_warnings) =
+            crate::theme::load_deck_themes(&new_presentation, base_path);
+        theme_warnings.extend(crate::fonts::install_deck_fonts(
+            ctx,
+            &new_presentation.meta,
+            &custom_themes,
+            base_path,
+        ));
+        if !self.quiet {
+            for w in &theme_warnings {
+                eprintln!("Warning: {}", w.message);
+            }
+        }
+
         // Preserve slide position
         let old_raw = self
             .presentation
@@ -440,14 +729,37 @@ impl PresentationApp {
         self.scroll_targets = vec![0.0; slide_count];
 
         // Update theme/transition from new frontmatter
-        if let Some(name) = &new_presentation.meta.theme {
-            self.theme = Theme::from_name(name);
+        if new_presentation.meta.theme.is_some() {
+            self.theme = Theme::for_deck(&new_presentation.meta, &custom_themes);
+        } else {
+            self.theme.code_theme = new_presentation.meta.code_theme.clone();
         }
         if let Some(name) = &new_presentation.meta.transition {
             self.default_transition = TransitionKind::from_name(name);
         }
+        self.aspect = AspectRatio::for_deck(&new_presentation.meta);
+        // A rehearsal keeps the target it started with
+        let rehearsal_target = self
+            .rehearsal
+            .as_ref()
+            .and_then(|r| r.lock().unwrap().target());
+        self.target_duration = rehearsal_target.or_else(|| {
+            new_presentation
+                .meta
+                .duration
+                .as_deref()
+                .and_then(parser::frontmatter::parse_duration)
+        });
 
         self.presentation = new_presentation;
+        self.custom_themes = custom_themes;
+        self.watch_theme_files();
+        // A newly watched theme file changes the hash inputs
+        self.last_content_hash = hash_sources(&content, self.custom_themes.files());
+        self.notes_blocks = None;
+        if let Some(search) = &mut self.search {
+            search.reindex(&self.presentation);
+        }
         self.image_cache.clear();
         self.precache_cancel.store(true, Ordering::Relaxed);
         render::diagram::clear_route_cache();
@@ -464,26 +776,50 @@ impl PresentationApp {
             *selected = (*selected).min(slide_count.saturating_sub(1));
         }
 
-        self.toast = Some(Toast::new("Presentation Change Detected".to_string()));
+        self.toast = Some(Toast::new(match theme_warnings.as_slice() {
+            [] => "Presentation Change Detected".to_string(),
+            [w] => w.message.clone(),
+            [w, rest @ ..] => format!("{} (+{} more warnings)", w.message, rest.len()),
+        }));
 
         self.spawn_diagram_precache();
     }
 
+    /// Watch the deck's theme files so editing a theme reloads the presentation.
+    fn watch_theme_files(&mut self) {
+        for path in self.custom_themes.files() {
+            if self.watched_theme_files.contains(path) {
+                continue;
+            }
+            match self
+                .watcher
+                .watcher()
+                .watch(path, notify::RecursiveMode::NonRecursive)
+            {
+                Ok(()) => self.watched_theme_files.push(path.clone()),
+                Err(e) => self.incident_log.record(
+                    "file_watcher_error",
+                    "failed to watch theme file",
+                    &format!("{e}\npath: {}", path.display()),
+                ),
+            }
+        }
+    }
+
     /// Collect all diagram content from every slide and spawn a background thread
     /// to pre-compute their routing caches at reference resolution (1920×1080).
     fn spawn_diagram_precache(&mut self) {
-        let diagrams: Vec<(usize, String)> = self
+        let diagrams: Vec<(usize, DiagramKind, String)> = self
             .presentation
             .slides
             .iter()
             .enumerate()
             .flat_map(|(i, s)| {
-                s.blocks.iter().filter_map(move |b| {
-                    if let parser::Block::Diagram { content } = b {
-                        Some((i + 1, content.clone()))
-                    } else {
-                        None
+                s.blocks.iter().filter_map(move |b| match b {
+                    parser::Block::Diagram { kind, content } => {
+                        Some((i + 1, *kind, content.clone()))
                     }
+                    _ => None,
                 })
             })
             .collect();
@@ -500,6 +836,15 @@ impl PresentationApp {
         self.precache_report_printed = false;
     }
 
+    /// Footer and header token values for slide `index`.
+    fn footer_context(&self, index: usize) -> FooterContext<'_> {
+        FooterContext {
+            meta: &self.presentation.meta,
+            number: index + 1,
+            total: self.slide_count(),
+        }
+    }
+
     fn draw_slide(&self, ui: &egui::Ui, index: usize, rect: egui::Rect, opacity: f32, scale: f32) {
         if index < self.presentation.slides.len() {
             let reveal = self.reveal_steps.get(index).copied().unwrap_or(0);
@@ -508,12 +853,14 @@ impl PresentationApp {
                 ui,
                 &self.presentation.slides[index],
                 &self.theme,
+                &self.custom_themes,
                 rect,
                 opacity,
                 &self.image_cache,
                 reveal,
                 timestamp,
                 scale,
+                &self.footer_context(index),
             );
         }
     }
@@ -651,7 +998,7 @@ impl PresentationApp {
         let grid_height = rect.bottom() - grid_top - padding;
 
         let cell_width = (grid_width - gap * (cols as f32 - 1.0)) / cols as f32;
-        let natural_height = cell_width * 9.0 / 16.0;
+        let natural_height = cell_width / self.aspect.ratio();
         let total_natural = rows as f32 * natural_height + (rows as f32 - 1.0) * gap;
 
         // If natural layout fits in the viewport, clamp to viewport; otherwise use natural size
@@ -680,7 +1027,7 @@ impl PresentationApp {
         let gap = 12.0 * scale;
         let grid_width = rect.width() - padding * 2.0;
         let cell_width = (grid_width - gap * (cols as f32 - 1.0)) / cols as f32;
-        let cell_height = cell_width * 9.0 / 16.0;
+        let cell_height = cell_width / self.aspect.ratio();
 
         rows as f32 * cell_height + (rows as f32 - 1.0) * gap
     }
@@ -692,12 +1039,6 @@ impl PresentationApp {
         rect.bottom() - grid_top - padding
     }
 
-    fn compute_scale(rect: egui::Rect) -> f32 {
-        let ref_w = 1920.0;
-        let ref_h = 1080.0;
-        (rect.width() / ref_w).min(rect.height() / ref_h)
-    }
-
     /// Convert screen position to slide-local coordinates (accounting for scroll)
     fn screen_to_local(&self, screen_pos: egui::Pos2) -> egui::Pos2 {
         let rect = self.last_slide_rect;
@@ -726,8 +1067,19 @@ impl PresentationApp {
                 (pp, pd, sp, sd, pos)
             });
 
+        self.pointer_pos = pointer_pos;
         let Some(pos) = pointer_pos else { return };
         let local = self.screen_to_local(pos);
+        let link = links::hit(&self.link_regions, pos).map(|link| link.url.clone());
+        if link.is_some() {
+            ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+
+        if self.pointer_tool == PointerTool::Laser
+            && self.laser_trail.last().is_none_or(|(last, _)| *last != pos)
+        {
+            self.laser_trail.push((pos, Instant::now()));
+        }
 
         // Left button press → start PenPending
         if primary_pressed {
@@ -788,23 +1140,52 @@ impl PresentationApp {
             return;
         }
 
-        // Button released — commit or navigate
-        match std::mem::replace(&mut self.active_draw, ActiveDraw::None) {
-            ActiveDraw::PenPending { .. } => {
-                self.navigate_forward();
+        // Button released — commit or navigate. Drags only draw with the pen
+        // and arrow tool; clicks navigate with every tool.
+        let released = std::mem::replace(&mut self.active_draw, ActiveDraw::None);
+        if self.pointer_tool != PointerTool::Draw
+            && matches!(
+                released,
+                ActiveDraw::PenDrawing { .. } | ActiveDraw::ArrowDrawing { .. }
+            )
+        {
+            return;
+        }
+        match released {
+            ActiveDraw::PenPending { .. } => match link {
+                Some(url) => self.follow_link(ctx, &url),
+                None => self.navigate_forward(),
+            },
+            ActiveDraw::PenDrawing { points } if points.len() < 2 => {}
+            ActiveDraw::PenDrawing { points } if self.persist_annotations => {
+                let rect = self.local_slide_rect();
+                let points = points
+                    .iter()
+                    .map(|&p| annotations::normalize(p, rect))
+                    .collect();
+                self.saved_annotations
+                    .add_stroke(self.current_slide, points);
+                self.save_annotations();
             }
             ActiveDraw::PenDrawing { points } => {
-                if points.len() >= 2 {
-                    self.pen_strokes.push(PenStroke {
-                        points,
-                        start: Instant::now(),
-                        slide_index: self.current_slide,
-                    });
-                }
+                self.pen_strokes.push(PenStroke {
+                    points,
+                    start: Instant::now(),
+                    slide_index: self.current_slide,
+                });
             }
             ActiveDraw::ArrowPending { .. } => {
                 self.navigate_backward();
             }
+            ActiveDraw::ArrowDrawing { from, current } if self.persist_annotations => {
+                let rect = self.local_slide_rect();
+                let arrow = annotations::Arrow {
+                    from: annotations::normalize(from, rect),
+                    to: annotations::normalize(current, rect),
+                };
+                self.saved_annotations.add_arrow(self.current_slide, arrow);
+                self.save_annotations();
+            }
             ActiveDraw::ArrowDrawing { from, current } => {
                 self.arrows.push(ArrowAnnotation {
                     from,
@@ -816,6 +1197,75 @@ impl PresentationApp {
             ActiveDraw::None => {}
         }
     }
+
+    /// The slide area in slide-local coordinates, which saved annotations
+    /// are normalized to.
+    fn local_slide_rect(&self) -> egui::Rect {
+        egui::Rect::from_min_size(egui::Pos2::ZERO, self.last_slide_rect.size())
+    }
+
+    /// Write the saved annotations to the sidecar file.
+    fn save_annotations(&mut self) {
+        if let Err(e) = self.saved_annotations.save(&self.annotations_path) {
+            self.incident_log.record(
+                "annotations_save_error",
+                "failed to save annotations",
+                &format!("{e:#}"),
+            );
+            self.toast = Some(Toast::new(format!("Could not save annotations: {e}")));
+        }
+    }
+
+    /// Switch to `tool`, or back to the pen and arrow when it is on already.
+    fn toggle_pointer_tool(&mut self, tool: PointerTool) {
+        self.pointer_tool = if self.pointer_tool == tool {
+            PointerTool::Draw
+        } else {
+            tool
+        };
+        self.laser_trail.clear();
+        let name = match self.pointer_tool {
+            PointerTool::Draw => "Pen and arrow",
+            PointerTool::Laser => "Laser pointer",
+            PointerTool::Spotlight => "Spotlight",
+            PointerTool::Magnifier => "Magnifier",
+        };
+        self.toast = Some(Toast::new(name.to_string()));
+    }
+
+    /// Switch between fading and saved annotations. Turning persistence on
+    /// keeps the annotations still on screen.
+    fn toggle_persist_annotations(&mut self) {
+        self.persist_annotations = !self.persist_annotations;
+        if !self.persist_annotations {
+            self.toast = Some(Toast::new("Annotations fade out".to_string()));
+            return;
+        }
+        let file_name = self
+            .annotations_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        self.toast = Some(Toast::new(format!("Annotations are saved to {file_name}")));
+        let rect = self.local_slide_rect();
+        for stroke in std::mem::take(&mut self.pen_strokes) {
+            let points = stroke
+                .points
+                .iter()
+                .map(|&p| annotations::normalize(p, rect))
+                .collect();
+            self.saved_annotations
+                .add_stroke(stroke.slide_index, points);
+        }
+        for arrow in std::mem::take(&mut self.arrows) {
+            let saved = annotations::Arrow {
+                from: annotations::normalize(arrow.from, rect),
+                to: annotations::normalize(arrow.to, rect),
+            };
+            self.saved_annotations.add_arrow(arrow.slide_index, saved);
+        }
+        self.save_annotations();
+    }
 }
 
 impl eframe::App for PresentationApp {
@@ -868,11 +1318,22 @@ impl eframe::App for PresentationApp {
             shared.store(self.current_slide, Ordering::Relaxed);
         }
 
+        // Time the slide and reveal step on screen for the rehearsal report.
+        // Reveal steps change with `reveal_steps`/`reveal_timestamps`, so
+        // each step is timed from the frame it first shows.
+        if let Some(rehearsal) = &self.rehearsal {
+            let idx = self.current_slide;
+            let shown = (!self.on_end_slide)
+                .then(|| (idx, self.reveal_steps.get(idx).copied().unwrap_or(0)));
+            rehearsal.lock().unwrap().observe(shown);
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+
         // Check for file changes
         if self.watcher_rx.try_recv().is_ok() {
             // Drain any extra queued events
             while self.watcher_rx.try_recv().is_ok() {}
-            self.reload_presentation();
+            self.reload_presentation(ctx);
         }
 
         // Poll for diagram precache report
@@ -886,6 +1347,23 @@ impl eframe::App for PresentationApp {
             }
         }
 
+        // Apply remote control commands one at a time, leaving the rest
+        // queued while a transition plays so none are dropped
+        if let Some(remote) = self.remote.clone() {
+            while !self.remote_waits() {
+                let Some(command) = remote.try_recv() else {
+                    break;
+                };
+                self.apply_remote_command(command);
+            }
+            remote.publish(self.remote_state());
+            // One more frame after the animation ends, to publish where it
+            // landed and apply what is still queued
+            if self.remote_waits() {
+                ctx.request_repaint();
+            }
+        }
+
         let mode = self.mode;
 
         // Collect viewport commands to send AFTER the input closure
@@ -894,6 +1372,12 @@ impl eframe::App for PresentationApp {
 
         // Handle keyboard input
         ctx.input(|i| {
+            // The open search palette takes all keys, so Q and F can be typed
+            if self.search.is_some() {
+                self.handle_search_input(i, mode);
+                return;
+            }
+
             // Quit: Q from any mode
             if i.key_pressed(egui::Key::Q) {
                 viewport_cmds.push(egui::ViewportCommand::Close);
@@ -913,14 +1397,37 @@ impl eframe::App for PresentationApp {
                 return;
             }
 
-            // ESC: clear drawings first (presentation mode), then double-tap to quit
+            // Search palette: / or Ctrl+F (Cmd+F on macOS)
+            let slash = i
+                .events
+                .iter()
+                .any(|e| matches!(e, egui::Event::Text(t) if t == "/"));
+            if (slash || (i.modifiers.command && i.key_pressed(egui::Key::F)))
+                && !self.blackout
+                && matches!(mode, AppMode::Presentation | AppMode::Grid { .. })
+            {
+                self.search = Some(Palette::new(&self.presentation));
+                self.slide_jump = None;
+                return;
+            }
+
+            // ESC: cancel a typed slide number, clear drawings (presentation
+            // mode), then double-tap to quit
             if i.key_pressed(egui::Key::Escape) {
+                if self.slide_jump.take().is_some() {
+                    self.last_esc = None;
+                    return;
+                }
                 // In presentation mode, first ESC clears annotations if any exist
                 if matches!(mode, AppMode::Presentation) {
                     let idx = self.current_slide;
                     let has_annotations = self.pen_strokes.iter().any(|s| s.slide_index == idx)
                         || self.arrows.iter().any(|a| a.slide_index == idx);
-                    if has_annotations {
+                    let had_saved = self.saved_annotations.clear_slide(idx);
+                    if had_saved {
+                        self.save_annotations();
+                    }
+                    if has_annotations || had_saved {
                         self.pen_strokes.retain(|s| s.slide_index != idx);
                         self.arrows.retain(|a| a.slide_index != idx);
                         self.last_esc = None;
@@ -947,6 +1454,12 @@ impl eframe::App for PresentationApp {
                 return;
             }
 
+            // Presenter view toggle: S (from any mode)
+            if i.key_pressed(egui::Key::S) {
+                self.presenter_open = !self.presenter_open;
+                return;
+            }
+
             // Cycle theme: Shift+T (from any mode)
             if i.modifiers.shift && i.key_pressed(egui::Key::T) {
                 self.toggle_theme();
@@ -970,23 +1483,52 @@ impl eframe::App for PresentationApp {
                 return;
             }
 
+            // Number then Enter: jump to that slide
+            if matches!(mode, AppMode::Presentation | AppMode::Grid { .. })
+                && self.handle_slide_jump(i, mode)
+            {
+                return;
+            }
+
             match mode {
                 AppMode::Presentation => {
-                    // Forward: Right, N, Space
+                    // Forward: Right, N, Space, PageDown (clickers)
                     if i.key_pressed(egui::Key::ArrowRight)
                         || i.key_pressed(egui::Key::N)
                         || i.key_pressed(egui::Key::Space)
+                        || i.key_pressed(egui::Key::PageDown)
                     {
                         self.navigate_forward();
                     }
-                    // Backward: Left, P
-                    if i.key_pressed(egui::Key::ArrowLeft) || i.key_pressed(egui::Key::P) {
+                    // Backward: Left, P, PageUp
+                    if i.key_pressed(egui::Key::ArrowLeft)
+                        || i.key_pressed(egui::Key::P)
+                        || i.key_pressed(egui::Key::PageUp)
+                    {
                         self.navigate_backward();
                     }
+                    // Back along followed links: Backspace
+                    if i.key_pressed(egui::Key::Backspace) {
+                        self.link_back();
+                    }
                     // Toggle HUD: H
                     if i.key_pressed(egui::Key::H) {
                         self.show_hud = !self.show_hud;
                     }
+                    // Toggle persistent annotations: A
+                    if i.key_pressed(egui::Key::A) {
+                        self.toggle_persist_annotations();
+                    }
+                    // Pointer tools: L (laser), O (spotlight), M (magnifier)
+                    for (key, tool) in [
+                        (egui::Key::L, PointerTool::Laser),
+                        (egui::Key::O, PointerTool::Spotlight),
+                        (egui::Key::M, PointerTool::Magnifier),
+                    ] {
+                        if i.key_pressed(key) {
+                            self.toggle_pointer_tool(tool);
+                        }
+                    }
                     // Cycle debug overlay: R (Off → Left → Right → Off)
                     if i.key_pressed(egui::Key::R) {
                         self.raw_overlay_side = match self.raw_overlay_side {
@@ -1084,7 +1626,11 @@ impl eframe::App for PresentationApp {
         }
 
         // Mouse input handling (presentation mode only, outside ctx.input closure)
-        if matches!(mode, AppMode::Presentation) && self.transition.is_none() && !self.blackout {
+        if matches!(mode, AppMode::Presentation)
+            && self.transition.is_none()
+            && !self.blackout
+            && self.search.is_none()
+        {
             self.handle_mouse_input(ctx);
         }
 
@@ -1093,10 +1639,22 @@ impl eframe::App for PresentationApp {
             .retain(|s| s.start.elapsed().as_secs_f32() < DRAW_FADE_DURATION);
         self.arrows
             .retain(|a| a.start.elapsed().as_secs_f32() < DRAW_FADE_DURATION);
-        if !self.pen_strokes.is_empty() || !self.arrows.is_empty() {
+        self.laser_trail
+            .retain(|(_, t)| t.elapsed().as_secs_f32() < LASER_TRAIL_DURATION);
+        if !self.pen_strokes.is_empty() || !self.arrows.is_empty() || !self.laser_trail.is_empty() {
             ctx.request_repaint();
         }
 
+        // The laser dot and the spotlight stand in for the cursor
+        if matches!(mode, AppMode::Presentation)
+            && matches!(
+                self.pointer_tool,
+                PointerTool::Laser | PointerTool::Spotlight
+            )
+        {
+            ctx.set_cursor_icon(egui::CursorIcon::None);
+        }
+
         // Advance transition
         if let Some(ref t) = self.transition {
             if t.is_complete() {
@@ -1126,6 +1684,15 @@ impl eframe::App for PresentationApp {
             self.toast = None;
         }
 
+        // Forget a half-typed slide number
+        if let Some((_, typed)) = self.slide_jump {
+            if typed.elapsed().as_secs_f32() >= SLIDE_JUMP_TIMEOUT {
+                self.slide_jump = None;
+            } else {
+                ctx.request_repaint_after(Duration::from_millis(250));
+            }
+        }
+
         let bg = if self.blackout || self.on_end_slide {
             egui::Color32::BLACK
         } else {
@@ -1143,7 +1710,7 @@ impl eframe::App for PresentationApp {
                     return;
                 }
 
-                let scale = Self::compute_scale(rect);
+                let scale = self.aspect.scale(rect);
 
                 // End slide: "The End" with logo attribution
                 if self.on_end_slide {
@@ -1153,7 +1720,19 @@ impl eframe::App for PresentationApp {
 
                 match self.mode {
                     AppMode::Presentation => {
-                        self.draw_presentation_with_scroll(ui, ctx, rect, scale);
+                        // Letterbox the slide canvas; the bars stay black
+                        let slide_rect = self.aspect.fit(rect);
+                        if slide_rect != rect {
+                            ui.painter().rect_filled(rect, 0.0, egui::Color32::BLACK);
+                            ui.painter().rect_filled(slide_rect, 0.0, bg);
+                        }
+                        let mut slide_ui = ui.new_child(
+                            egui::UiBuilder::new()
+                                .max_rect(slide_rect)
+                                .id_salt("slide_canvas"),
+                        );
+                        slide_ui.set_clip_rect(slide_rect.intersect(ui.clip_rect()));
+                        self.draw_presentation_with_scroll(&mut slide_ui, ctx, slide_rect, scale);
                     }
                     AppMode::Grid { selected } => {
                         self.draw_grid(ui, ctx, rect, selected, scale);
@@ -1163,33 +1742,22 @@ impl eframe::App for PresentationApp {
                     }
                 }
 
+                // Search palette (presentation and grid)
+                if self.search.is_some() {
+                    self.draw_search_palette(ui, rect, scale);
+                }
+
+                // Slide number being typed
+                if let Some((digits, _)) = &self.slide_jump {
+                    let message = format!("Go to slide {digits} \u{2014} Enter");
+                    draw_toast(ui, &self.theme, &message, rect, scale, 1.0);
+                }
+
                 // Toast notification (shown in both modes)
                 if let Some(ref toast) = self.toast {
                     let opacity = toast.opacity();
                     if opacity > 0.0 {
-                        let toast_color = Theme::with_opacity(self.theme.foreground, opacity * 0.9);
-                        let toast_bg =
-                            Theme::with_opacity(self.theme.code_background, opacity * 0.9);
-                        let galley = ui.painter().layout_no_wrap(
-                            toast.message.clone(),
-                            egui::FontId::proportional(20.0 * scale),
-                            toast_color,
-                        );
-                        let padding = 16.0 * scale;
-                        let toast_rect = egui::Rect::from_min_size(
-                            egui::pos2(
-                                rect.center().x - galley.rect.width() / 2.0 - padding,
-                                rect.bottom() - 80.0 * scale,
-                            ),
-                            egui::vec2(
-                                galley.rect.width() + padding * 2.0,
-                                galley.rect.height() + padding * 2.0,
-                            ),
-                        );
-                        ui.painter().rect_filled(toast_rect, 8.0 * scale, toast_bg);
-                        let text_pos =
-                            egui::pos2(toast_rect.left() + padding, toast_rect.top() + padding);
-                        ui.painter().galley(text_pos, galley, toast_color);
+                        draw_toast(ui, &self.theme, &toast.message, rect, scale, opacity);
                         ctx.request_repaint();
                     }
                 }
@@ -1205,12 +1773,13 @@ impl eframe::App for PresentationApp {
                 {
                     let slide = &self.presentation.slides[self.current_slide];
                     let raw = &slide.raw_source;
-                    let debug_info = slide.blocks.iter().find_map(|b| {
-                        if let parser::Block::Diagram { content } = b {
-                            Some(render::diagram::diagram_debug_info(content))
-                        } else {
-                            None
+                    let debug_info = slide.blocks.iter().find_map(|b| match b {
+                        parser::Block::Diagram { kind, content }
+                            if *kind != DiagramKind::Sequence =>
+                        {
+                            Some(render::diagram::diagram_debug_info(*kind, content))
                         }
+                        _ => None,
                     });
                     draw_raw_markdown_overlay(
                         ui,
@@ -1224,6 +1793,10 @@ impl eframe::App for PresentationApp {
                 }
             });
 
+        if self.presenter_open {
+            self.show_presenter_view(ctx);
+        }
+
         // Keep the display pipeline alive with periodic repaints. Without this,
         // eframe enters ControlFlow::Wait when idle, and on Linux the EGL/GLX
         // context can become stale after ~30 s, crashing with EINVAL (os error 22).
@@ -1249,8 +1822,10 @@ impl PresentationApp {
 
         // During transitions, just render normally (no scroll)
         if self.transition.is_some() {
+            self.link_regions.clear();
             self.draw_presentation(ui, ctx, rect, scale);
             self.draw_annotations(ui, scale);
+            self.draw_pointer_tool(ui, scale);
             return;
         }
 
@@ -1264,8 +1839,11 @@ impl PresentationApp {
             // No overflow — render normally, reset scroll
             self.scroll_offsets[idx] = 0.0;
             self.scroll_targets[idx] = 0.0;
+            links::begin(ctx);
             self.draw_presentation(ui, ctx, rect, scale);
+            self.link_regions = links::finish(ctx);
             self.draw_annotations(ui, scale);
+            self.draw_pointer_tool(ui, scale);
             return;
         }
 
@@ -1290,10 +1868,22 @@ impl PresentationApp {
         let reveal = self.reveal_steps.get(idx).copied().unwrap_or(0);
         let timestamp = self.reveal_timestamps.get(idx).copied().flatten();
         let child_ui = ui.new_child(egui::UiBuilder::new().max_rect(rect).id_salt("scroll_clip"));
-        render::render_slide(
+        // The slide background stays put; only the content scrolls
+        render::draw_slide_background(
+            ui,
+            slide,
+            &self.theme,
+            &self.custom_themes,
+            rect,
+            1.0,
+            &self.image_cache,
+        );
+        links::begin(ctx);
+        render::render_slide_content(
             &child_ui,
             slide,
             &self.theme,
+            &self.custom_themes,
             scrolled_rect,
             1.0,
             &self.image_cache,
@@ -1301,15 +1891,17 @@ impl PresentationApp {
             timestamp,
             scale,
         );
+        self.link_regions = links::finish(ctx);
 
         // Draw fade-out gradient at bottom
         let fade_h = 80.0 * scale;
+        let slide_theme = self.theme.for_slide(slide, &self.custom_themes);
         if scroll_offset < overflow - 0.5 {
-            draw_fade_gradient(ui, rect, fade_h, &self.theme, false);
+            draw_fade_gradient(ui, rect, fade_h, &slide_theme, false);
         }
         // Draw fade-in gradient at top when scrolled
         if scroll_offset > 0.5 {
-            draw_fade_gradient(ui, rect, fade_h, &self.theme, true);
+            draw_fade_gradient(ui, rect, fade_h, &slide_theme, true);
         }
 
         // Draw scroll indicators
@@ -1340,10 +1932,23 @@ impl PresentationApp {
             ui.painter().galley(pos, galley, indicator_color);
         }
 
+        // Header and footer stay put while the content scrolls
+        render::footer::draw(
+            ui,
+            slide,
+            &self.theme,
+            &self.custom_themes,
+            rect,
+            1.0,
+            scale,
+            &self.footer_context(idx),
+        );
+
         // Draw annotations on top of slide content
         self.draw_annotations(ui, scale);
+        self.draw_pointer_tool(ui, scale);
 
-        // Footer, counter, FPS
+        // Counter, FPS
         self.draw_presentation_chrome(ui, rect, scale);
     }
 
@@ -1403,24 +2008,14 @@ impl PresentationApp {
     }
 
     fn draw_presentation_chrome(&self, ui: &egui::Ui, rect: egui::Rect, scale: f32) {
-        // Footer
-        if let Some(ref footer) = self.presentation.meta.footer {
-            let footer_color = Theme::with_opacity(self.theme.foreground, 0.4);
-            let galley = ui.painter().layout_no_wrap(
-                footer.clone(),
-                egui::FontId::proportional(14.0 * scale),
-                footer_color,
-            );
-            let pos = egui::pos2(
-                rect.center().x - galley.rect.width() / 2.0,
-                rect.bottom() - 30.0 * scale,
-            );
-            ui.painter().galley(pos, galley, footer_color);
-        }
+        let Some(slide) = self.presentation.slides.get(self.current_slide) else {
+            return;
+        };
+        let theme = self.theme.for_slide(slide, &self.custom_themes);
 
         // Slide counter
         let counter_text = format!("{} / {}", self.current_slide + 1, self.slide_count());
-        let counter_color = Theme::with_opacity(self.theme.foreground, 0.3);
+        let counter_color = Theme::with_opacity(theme.foreground, 0.3);
         let counter_galley = ui.painter().layout_no_wrap(
             counter_text,
             egui::FontId::monospace(14.0 * scale),
@@ -1435,7 +2030,7 @@ impl PresentationApp {
 
         // FPS overlay
         let fps_text = format!("{:.0} fps", self.fps);
-        let fps_color = Theme::with_opacity(self.theme.foreground, 0.3);
+        let fps_color = Theme::with_opacity(theme.foreground, 0.3);
         let fps_galley =
             ui.painter()
                 .layout_no_wrap(fps_text, egui::FontId::monospace(14.0 * scale), fps_color);
@@ -1444,6 +2039,62 @@ impl PresentationApp {
             rect.top() + 10.0 * scale,
         );
         ui.painter().galley(fps_pos, fps_galley, fps_color);
+
+        self.draw_rehearsal_status(ui, rect, scale);
+    }
+
+    /// Rehearsal time, slide time and pacing, e.g.
+    /// `12:04  ·  slide 0:32 / 1:00  ·  0:42 behind`; the seconds behind
+    /// (negative when ahead) come along when there is a target.
+    fn rehearsal_status(&self) -> Option<(String, Option<f64>)> {
+        let rehearsal = self.rehearsal.as_ref()?.lock().unwrap();
+        let idx = self.current_slide;
+        let count = self.slide_count();
+        let mut status = format!(
+            "{}  \u{00b7}  slide {}",
+            format_timer(rehearsal.elapsed()),
+            rehearsal::format_seconds(rehearsal.slide_seconds(idx))
+        );
+        if let Some(budget) = rehearsal.budgets(count).and_then(|b| b.get(idx).copied()) {
+            status.push_str(&format!(" / {}", rehearsal::format_seconds(budget)));
+        }
+        let last = rehearsal
+            .previous()
+            .and_then(|p| p.slides.iter().find(|s| s.slide == idx + 1));
+        if let Some(last) = last {
+            status.push_str(&format!(
+                "  \u{00b7}  last {}",
+                rehearsal::format_seconds(last.seconds)
+            ));
+        }
+        let pace = rehearsal.pace(idx, count);
+        if let Some(pace) = pace {
+            status.push_str("  \u{00b7}  ");
+            status.push_str(&rehearsal::pace_label(pace));
+        }
+        Some((status, pace))
+    }
+
+    /// The rehearsal status in the top-left corner, colored by pace.
+    fn draw_rehearsal_status(&self, ui: &egui::Ui, rect: egui::Rect, scale: f32) {
+        let Some((status, pace)) = self.rehearsal_status() else {
+            return;
+        };
+        let color = pace_color(pace);
+        let galley =
+            ui.painter()
+                .layout_no_wrap(status, egui::FontId::monospace(15.0 * scale), color);
+        let padding = egui::vec2(10.0, 6.0) * scale;
+        let pill = egui::Rect::from_min_size(
+            rect.left_top() + egui::vec2(12.0, 10.0) * scale,
+            galley.size() + padding * 2.0,
+        );
+        ui.painter().rect_filled(
+            pill,
+            pill.height() / 2.0,
+            egui::Color32::from_black_alpha(150),
+        );
+        ui.painter().galley(pill.min + padding, galley, color);
     }
 
     fn draw_grid(
@@ -1526,6 +2177,7 @@ impl PresentationApp {
         if clicked {
             if let Some(hi) = self.hover_slide {
                 // Click on a grid cell → zoom into that slide
+                self.search = None;
                 self.mode = AppMode::OverviewTransition {
                     selected: hi,
                     entering: false,
@@ -1572,7 +2224,7 @@ impl PresentationApp {
                 continue;
             }
 
-            let cell_scale = (cell_rect.width() / 1920.0).min(cell_rect.height() / 1080.0);
+            let cell_scale = self.aspect.scale(cell_rect);
 
             // Fill cell with theme background
             grid_child
@@ -1590,6 +2242,24 @@ impl PresentationApp {
             // Slide number badge overlay
             self.draw_slide_badge(&grid_child, cell_rect, i, scale, 1.0);
 
+            // While searching, matches stand out and the other slides fade
+            if let Some(search) = self.search.as_ref().filter(|s| !s.hits().is_empty()) {
+                if search.matches(i) {
+                    grid_child.painter().rect_stroke(
+                        cell_rect.expand(2.0 * scale),
+                        4.0 * scale,
+                        egui::Stroke::new(2.0 * scale, Theme::with_opacity(self.theme.accent, 0.6)),
+                        egui::StrokeKind::Outside,
+                    );
+                } else {
+                    grid_child.painter().rect_filled(
+                        cell_rect,
+                        4.0 * scale,
+                        Theme::with_opacity(self.theme.background, 0.75),
+                    );
+                }
+            }
+
             // Hover highlight (subtle glow, distinct from selection)
             if self.use_hover && self.hover_slide == Some(i) && i != selected {
                 let hover_color = Theme::with_opacity(self.theme.accent, 0.12);
@@ -1625,7 +2295,7 @@ impl PresentationApp {
         }
 
         // Navigation hint at bottom
-        let hint = "Arrows/Mouse: navigate  |  Enter/Click: select  |  Q: quit";
+        let hint = "Arrows/Mouse: navigate  |  Enter/Click: select  |  /: search  |  Q: quit";
         let hint_color = Theme::with_opacity(self.theme.foreground, 0.4);
         let hint_galley = ui.painter().layout_no_wrap(
             hint.to_string(),
@@ -1639,6 +2309,122 @@ impl PresentationApp {
         ui.painter().galley(hint_pos, hint_galley, hint_color);
     }
 
+    /// The search palette: the query, the best hits with the line that
+    /// matched, and key hints.
+    fn draw_search_palette(&self, ui: &egui::Ui, rect: egui::Rect, scale: f32) {
+        const ROWS: usize = 8;
+        let Some(search) = &self.search else {
+            return;
+        };
+        let padding = 16.0 * scale;
+        let row_height = 52.0 * scale;
+        let shown = search.hits().len().min(ROWS);
+        let width = (720.0 * scale).min(rect.width() - 2.0 * padding);
+        let height = padding * 2.0 + 36.0 * scale + shown as f32 * row_height + 28.0 * scale;
+        let panel = egui::Rect::from_min_size(
+            egui::pos2(rect.center().x - width / 2.0, rect.top() + 80.0 * scale),
+            egui::vec2(width, height),
+        );
+        let painter = ui.painter().with_clip_rect(panel);
+        painter.rect_filled(
+            panel,
+            12.0 * scale,
+            Theme::with_opacity(self.theme.code_background, 0.95),
+        );
+
+        let text_color = Theme::with_opacity(self.theme.foreground, 0.9);
+        let dim_color = Theme::with_opacity(self.theme.foreground, 0.5);
+        let accent = self.theme.accent;
+        let left = panel.left() + padding;
+        let mut y = panel.top() + padding;
+
+        // Query with a cursor, or a placeholder
+        let (query, query_color) = if search.query().is_empty() {
+            ("Search headings, text and notes", dim_color)
+        } else {
+            (search.query(), text_color)
+        };
+        let galley = painter.layout_no_wrap(
+            query.to_string(),
+            egui::FontId::proportional(22.0 * scale),
+            query_color,
+        );
+        let cursor_x = if search.query().is_empty() {
+            left
+        } else {
+            left + galley.rect.width() + 2.0 * scale
+        };
+        let query_height = galley.rect.height();
+        painter.galley(egui::pos2(left, y), galley, query_color);
+        painter.rect_filled(
+            egui::Rect::from_min_size(
+                egui::pos2(cursor_x, y),
+                egui::vec2(2.0 * scale, query_height),
+            ),
+            0.0,
+            accent,
+        );
+        y += 36.0 * scale;
+
+        // Hits, scrolled so the selected one is visible
+        let first = search.selected_index().saturating_sub(ROWS - 1);
+        for (n, hit) in search.hits().iter().enumerate().skip(first).take(ROWS) {
+            let row = egui::Rect::from_min_size(
+                egui::pos2(panel.left() + padding / 2.0, y),
+                egui::vec2(width - padding, row_height),
+            );
+            if n == search.selected_index() {
+                painter.rect_filled(row, 6.0 * scale, Theme::with_opacity(accent, 0.18));
+            }
+            let number = painter.layout_no_wrap(
+                format!("{}", hit.slide + 1),
+                egui::FontId::monospace(16.0 * scale),
+                accent,
+            );
+            painter.galley(egui::pos2(left, y + 6.0 * scale), number, accent);
+            let heading = match search.heading(hit.slide) {
+                "" => format!("Slide {}", hit.slide + 1),
+                heading => heading.to_string(),
+            };
+            let heading = painter.layout_no_wrap(
+                heading,
+                egui::FontId::proportional(17.0 * scale),
+                text_color,
+            );
+            let text_left = left + 48.0 * scale;
+            painter.galley(egui::pos2(text_left, y + 4.0 * scale), heading, text_color);
+            let detail = match hit.field {
+                search::Field::Heading => None,
+                search::Field::Text => Some(hit.snippet.clone()),
+                search::Field::Notes => Some(format!("Notes: {}", hit.snippet)),
+            };
+            if let Some(detail) = detail {
+                let detail = painter.layout_no_wrap(
+                    detail,
+                    egui::FontId::proportional(14.0 * scale),
+                    dim_color,
+                );
+                painter.galley(egui::pos2(text_left, y + 28.0 * scale), detail, dim_color);
+            }
+            y += row_height;
+        }
+
+        // Hints
+        let hint = if search.query().trim().is_empty() {
+            "Type to search  |  Esc: close".to_string()
+        } else if search.hits().is_empty() {
+            "No matching slides  |  Esc: close".to_string()
+        } else {
+            format!(
+                "{} matching  |  \u{2191}\u{2193}: select  |  Enter: go  |  Esc: close",
+                search.hits().len()
+            )
+        };
+        let hint =
+            painter.layout_no_wrap(hint, egui::FontId::proportional(13.0 * scale), dim_color);
+        painter.galley(egui::pos2(left, y + 6.0 * scale), hint, dim_color);
+    }
+
     fn draw_slide_badge(
         &self,
         ui: &egui::Ui,
@@ -1694,9 +2480,16 @@ impl PresentationApp {
         } else {
             selected
         };
+
+        // Fade the presentation's letterbox bars in or out
+        ui.painter().rect_filled(
+            rect,
+            0.0,
+            Theme::with_opacity(egui::Color32::BLACK, 1.0 - grid_amount),
+        );
         let hero_cell_rect = self.grid_cell_rect(hero_index, rect, scale, 0.0);
-        let hero_rect = lerp_rect(rect, hero_cell_rect, grid_amount);
-        let hero_scale = (hero_rect.width() / 1920.0).min(hero_rect.height() / 1080.0);
+        let hero_rect = lerp_rect(self.aspect.fit(rect), hero_cell_rect, grid_amount);
+        let hero_scale = self.aspect.scale(hero_rect);
 
         let count = self.slide_count();
 
@@ -1706,7 +2499,7 @@ impl PresentationApp {
                 continue;
             }
             let cell_rect = self.grid_cell_rect(i, rect, scale, 0.0);
-            let cell_scale = (cell_rect.width() / 1920.0).min(cell_rect.height() / 1080.0);
+            let cell_scale = self.aspect.scale(cell_rect);
 
             ui.painter()
                 .rect_filled(cell_rect, 4.0 * scale, self.theme.background);
@@ -1767,7 +2560,7 @@ impl PresentationApp {
             let title_pos = egui::pos2(rect.left() + padding, rect.top() + padding);
             ui.painter().galley(title_pos, title_galley, title_color);
 
-            let hint = "Arrows/Mouse: navigate  |  Enter/Click: select  |  Q: quit";
+            let hint = "Arrows/Mouse: navigate  |  Enter/Click: select  |  /: search  |  Q: quit";
             let hint_color = Theme::with_opacity(self.theme.foreground, 0.4 * grid_amount);
             let hint_galley = ui.painter().layout_no_wrap(
                 hint.to_string(),
@@ -1784,42 +2577,6 @@ impl PresentationApp {
         ctx.request_repaint();
     }
 
-    /// Pen color: cyan/blue tones
-    fn pen_color(&self, opacity: f32) -> egui::Color32 {
-        if self.theme.name == "dark" {
-            egui::Color32::from_rgba_unmultiplied(80, 200, 255, (opacity * 230.0) as u8)
-        } else {
-            egui::Color32::from_rgba_unmultiplied(30, 80, 200, (opacity * 230.0) as u8)
-        }
-    }
-
-    /// Pen outline color: darker cyan/blue
-    fn pen_outline_color(&self, opacity: f32) -> egui::Color32 {
-        if self.theme.name == "dark" {
-            egui::Color32::from_rgba_unmultiplied(30, 130, 180, (opacity * 140.0) as u8)
-        } else {
-            egui::Color32::from_rgba_unmultiplied(15, 40, 130, (opacity * 140.0) as u8)
-        }
-    }
-
-    /// Arrow color: yellow-orange / red tones
-    fn arrow_color(&self, opacity: f32) -> egui::Color32 {
-        if self.theme.name == "dark" {
-            egui::Color32::from_rgba_unmultiplied(255, 200, 50, (opacity * 230.0) as u8)
-        } else {
-            egui::Color32::from_rgba_unmultiplied(220, 40, 40, (opacity * 230.0) as u8)
-        }
-    }
-
-    /// Arrow outline color: darker orange / red
-    fn arrow_outline_color(&self, opacity: f32) -> egui::Color32 {
-        if self.theme.name == "dark" {
-            egui::Color32::from_rgba_unmultiplied(200, 140, 0, (opacity * 140.0) as u8)
-        } else {
-            egui::Color32::from_rgba_unmultiplied(150, 20, 20, (opacity * 140.0) as u8)
-        }
-    }
-
     /// Compute fade opacity for an annotation (1.0 for most of its life, fading in last 2s)
     fn annotation_opacity(start: Instant) -> f32 {
         let elapsed = start.elapsed().as_secs_f32();
@@ -1833,42 +2590,31 @@ impl PresentationApp {
         }
     }
 
-    /// Draw all pen strokes and arrow annotations for the current slide
+    /// Draw the saved annotations, pen strokes and arrows for the current slide
     fn draw_annotations(&self, ui: &egui::Ui, scale: f32) {
         let idx = self.current_slide;
-        let pen_width = 6.0 * scale;
-        let pen_outline_width = pen_width + 2.0 * scale;
-        let arrow_width = 5.0 * scale;
-        let arrow_outline_width = arrow_width + 2.0 * scale;
-        let arrow_size = 22.0 * scale;
-        let arrow_outline_size = arrow_size + 3.0 * scale;
+        let painter = ui.painter();
+
+        let scroll = self.scroll_offsets[idx];
+        let scrolled_rect = self.last_slide_rect.translate(egui::vec2(0.0, -scroll));
+        self.saved_annotations
+            .paint(painter, idx, scrolled_rect, &self.theme, scale);
 
         // Draw completed pen strokes
         for stroke in &self.pen_strokes {
-            if stroke.slide_index != idx || stroke.points.len() < 2 {
+            if stroke.slide_index != idx {
                 continue;
             }
             let opacity = Self::annotation_opacity(stroke.start);
             if opacity < 0.01 {
                 continue;
             }
-            let outline_color = self.pen_outline_color(opacity);
-            let color = self.pen_color(opacity);
-            let screen_points: Vec<egui::Pos2> = stroke
+            let points = stroke
                 .points
                 .iter()
                 .map(|p| self.local_to_screen(*p))
                 .collect();
-            // Outline pass
-            ui.painter().add(egui::Shape::line(
-                screen_points.clone(),
-                egui::Stroke::new(pen_outline_width, outline_color),
-            ));
-            // Main pass
-            ui.painter().add(egui::Shape::line(
-                screen_points,
-                egui::Stroke::new(pen_width, color),
-            ));
+            annotations::draw_pen_stroke(painter, points, &self.theme, opacity, scale);
         }
 
         // Draw completed arrows
@@ -1880,91 +2626,558 @@ impl PresentationApp {
             if opacity < 0.01 {
                 continue;
             }
-            let outline_color = self.arrow_outline_color(opacity);
-            let color = self.arrow_color(opacity);
             let from = self.local_to_screen(arrow.from);
             let to = self.local_to_screen(arrow.to);
-            // Outline pass
-            self.draw_arrow_shape(
-                ui,
-                from,
-                to,
-                arrow_outline_width,
-                arrow_outline_size,
-                outline_color,
-            );
-            // Main pass
-            self.draw_arrow_shape(ui, from, to, arrow_width, arrow_size, color);
+            annotations::draw_arrow(painter, from, to, &self.theme, opacity, scale);
         }
 
         // Draw active drawing in progress
+        if self.pointer_tool != PointerTool::Draw {
+            return;
+        }
         match &self.active_draw {
-            ActiveDraw::PenDrawing { points } if points.len() >= 2 => {
-                let outline_color = self.pen_outline_color(1.0);
-                let color = self.pen_color(1.0);
-                let screen_points: Vec<egui::Pos2> =
-                    points.iter().map(|p| self.local_to_screen(*p)).collect();
-                ui.painter().add(egui::Shape::line(
-                    screen_points.clone(),
-                    egui::Stroke::new(pen_outline_width, outline_color),
-                ));
-                ui.painter().add(egui::Shape::line(
-                    screen_points,
-                    egui::Stroke::new(pen_width, color),
-                ));
+            ActiveDraw::PenDrawing { points } => {
+                let points = points.iter().map(|p| self.local_to_screen(*p)).collect();
+                annotations::draw_pen_stroke(painter, points, &self.theme, 1.0, scale);
             }
             ActiveDraw::ArrowDrawing { from, current } => {
-                let outline_color = self.arrow_outline_color(1.0);
-                let color = self.arrow_color(1.0);
-                let screen_from = self.local_to_screen(*from);
-                let screen_to = self.local_to_screen(*current);
-                self.draw_arrow_shape(
-                    ui,
-                    screen_from,
-                    screen_to,
-                    arrow_outline_width,
-                    arrow_outline_size,
-                    outline_color,
-                );
-                self.draw_arrow_shape(ui, screen_from, screen_to, arrow_width, arrow_size, color);
+                let from = self.local_to_screen(*from);
+                let to = self.local_to_screen(*current);
+                annotations::draw_arrow(painter, from, to, &self.theme, 1.0, scale);
             }
             _ => {}
         }
     }
+}
 
-    /// Draw an arrow from `from` to `to` with a filled triangular arrowhead
-    fn draw_arrow_shape(
-        &self,
-        ui: &egui::Ui,
-        from: egui::Pos2,
-        to: egui::Pos2,
-        stroke_width: f32,
-        arrow_size: f32,
-        color: egui::Color32,
-    ) {
-        let delta = to - from;
-        let len = delta.length();
-        if len < 1.0 {
+impl PresentationApp {
+    /// Draw the laser pointer, spotlight or magnifier at the pointer
+    fn draw_pointer_tool(&self, ui: &mut egui::Ui, scale: f32) {
+        let rect = self.last_slide_rect;
+        let Some(pos) = self.pointer_pos.filter(|p| rect.contains(*p)) else {
+            return;
+        };
+        if self.transition.is_some() {
             return;
         }
-        let dir = delta / len;
-        let perp = egui::vec2(-dir.y, dir.x);
+        let painter = ui.painter();
+        match self.pointer_tool {
+            PointerTool::Draw => {}
+            PointerTool::Laser => {
+                // Trail: thinner and more transparent as it ages
+                for pair in self.laser_trail.windows(2) {
+                    let (from, _) = pair[0];
+                    let (to, start) = pair[1];
+                    let life = 1.0 - start.elapsed().as_secs_f32() / LASER_TRAIL_DURATION;
+                    if life <= 0.0 {
+                        continue;
+                    }
+                    let color =
+                        egui::Color32::from_rgba_unmultiplied(255, 40, 40, (life * 160.0) as u8);
+                    painter.line_segment(
+                        [from, to],
+                        egui::Stroke::new((2.0 + 6.0 * life) * scale, color),
+                    );
+                }
+                // Dot with a soft glow
+                let glow = egui::Color32::from_rgba_unmultiplied(255, 40, 40, 60);
+                painter.circle_filled(pos, 16.0 * scale, glow);
+                painter.circle_filled(pos, 8.0 * scale, egui::Color32::from_rgb(255, 30, 30));
+                painter.circle_filled(pos, 3.0 * scale, egui::Color32::from_rgb(255, 200, 200));
+            }
+            PointerTool::Spotlight => {
+                let radius = rect.height() * 0.15;
+                // Dim everything outside the circle, out past the slide corners
+                let cover = rect.width() + rect.height();
+                painter.add(ring_mesh(
+                    pos,
+                    radius,
+                    radius + cover,
+                    egui::Color32::from_black_alpha(180),
+                ));
+            }
+            PointerTool::Magnifier => {
+                let idx = self.current_slide;
+                let lens = egui::Rect::from_center_size(pos, rect.size() * 0.3);
+                let scroll = self.scroll_offsets[idx];
+                let slide_rect = rect.translate(egui::vec2(0.0, -scroll));
+                let zoomed = magnified_rect(slide_rect, pos, MAGNIFIER_ZOOM);
+                let mut lens_ui =
+                    ui.new_child(egui::UiBuilder::new().max_rect(lens).id_salt("magnifier"));
+                lens_ui.set_clip_rect(lens.intersect(ui.clip_rect()));
+                lens_ui
+                    .painter()
+                    .rect_filled(lens, 0.0, self.theme.background);
+                self.draw_slide(&lens_ui, idx, zoomed, 1.0, scale * MAGNIFIER_ZOOM);
+                ui.painter().rect_stroke(
+                    lens,
+                    0.0,
+                    egui::Stroke::new(3.0 * scale, self.theme.accent),
+                    egui::StrokeKind::Outside,
+                );
+            }
+        }
+    }
+}
 
-        // Arrowhead triangle points (wider spread)
-        let p1 = to - dir * arrow_size + perp * arrow_size * 0.45;
-        let p2 = to - dir * arrow_size - perp * arrow_size * 0.45;
+impl PresentationApp {
+    /// Show the presenter view in its own OS window. Navigation in either window
+    /// drives the same `current_slide` / `reveal_steps` state.
+    fn show_presenter_view(&mut self, ctx: &egui::Context) {
+        let title = format!("{} \u{2014} Presenter", self.display_title());
+        let builder = egui::ViewportBuilder::default()
+            .with_title(title)
+            .with_inner_size([1280.0, 800.0]);
+
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("mdeck_presenter"),
+            builder,
+            |ctx, class| {
+                self.handle_presenter_input(ctx);
+
+                if class == egui::ViewportClass::Embedded {
+                    // Backend without multi-viewport support: fall back to an in-app window
+                    egui::Window::new("Presenter")
+                        .default_size([960.0, 600.0])
+                        .show(ctx, |ui| {
+                            let (rect, _) = ui.allocate_exact_size(
+                                egui::vec2(960.0, 600.0),
+                                egui::Sense::hover(),
+                            );
+                            ui.painter().rect_filled(rect, 0.0, PRESENTER_BG);
+                            self.draw_presenter_view(ui, rect);
+                        });
+                } else {
+                    egui::CentralPanel::default()
+                        .frame(egui::Frame::new().fill(PRESENTER_BG).inner_margin(0.0))
+                        .show(ctx, |ui| {
+                            let rect = ui.max_rect();
+                            self.draw_presenter_view(ui, rect);
+                        });
+                }
 
-        // Shaft (stop further back from head to avoid blunt overlap)
-        ui.painter().line_segment(
-            [from, to - dir * arrow_size * 0.7],
-            egui::Stroke::new(stroke_width, color),
+                // Keep the timer and clock ticking
+                ctx.request_repaint_after(Duration::from_millis(500));
+            },
         );
-        // Arrowhead
-        ui.painter().add(egui::Shape::convex_polygon(
-            vec![to, p1, p2],
-            color,
-            egui::Stroke::NONE,
-        ));
+    }
+
+    fn handle_presenter_input(&mut self, ctx: &egui::Context) {
+        let mut quit = false;
+        ctx.input(|i| {
+            if i.viewport().close_requested()
+                || i.key_pressed(egui::Key::S)
+                || i.key_pressed(egui::Key::Escape)
+            {
+                self.presenter_open = false;
+                return;
+            }
+            if i.key_pressed(egui::Key::Q) {
+                quit = true;
+                return;
+            }
+            if i.key_pressed(egui::Key::Z) {
+                self.timer_start = Instant::now();
+                return;
+            }
+            if i.key_pressed(egui::Key::Period) {
+                self.blackout = !self.blackout;
+                return;
+            }
+
+            // Notes scrolling
+            if i.key_pressed(egui::Key::ArrowUp) {
+                self.notes_scroll = (self.notes_scroll - 60.0).max(0.0);
+            }
+            if i.key_pressed(egui::Key::ArrowDown) {
+                self.notes_scroll += 60.0;
+            }
+            self.notes_scroll = (self.notes_scroll - i.smooth_scroll_delta.y).max(0.0);
+
+            if self.blackout || !matches!(self.mode, AppMode::Presentation) {
+                return;
+            }
+            if i.key_pressed(egui::Key::ArrowRight)
+                || i.key_pressed(egui::Key::N)
+                || i.key_pressed(egui::Key::Space)
+                || i.key_pressed(egui::Key::PageDown)
+            {
+                self.navigate_forward();
+            }
+            if i.key_pressed(egui::Key::ArrowLeft)
+                || i.key_pressed(egui::Key::P)
+                || i.key_pressed(egui::Key::PageUp)
+            {
+                self.navigate_backward();
+            }
+            if i.key_pressed(egui::Key::Home) {
+                self.jump_to_slide(0);
+            }
+            if i.key_pressed(egui::Key::End) {
+                self.jump_to_slide(self.slide_count().saturating_sub(1));
+            }
+        });
+
+        if quit {
+            ctx.send_viewport_cmd_to(egui::ViewportId::ROOT, egui::ViewportCommand::Close);
+        }
+        // Wake the main window so it reflects navigation done here
+        ctx.request_repaint_of(egui::ViewportId::ROOT);
+    }
+
+    fn draw_presenter_view(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        let padding = 20.0;
+        let gap = 20.0;
+        let label_color = egui::Color32::from_gray(130);
+        let text_color = egui::Color32::from_gray(220);
+        let label_font = egui::FontId::proportional(13.0);
+
+        if self.notes_scroll_slide != self.current_slide {
+            self.notes_scroll_slide = self.current_slide;
+            self.notes_scroll = 0.0;
+            self.notes_blocks = None;
+        }
+
+        // Header: title on the left, position on the right
+        let title_galley = ui.painter().layout_no_wrap(
+            self.display_title(),
+            egui::FontId::proportional(18.0),
+            text_color,
+        );
+        ui.painter().galley(
+            egui::pos2(rect.left() + padding, rect.top() + padding),
+            title_galley,
+            text_color,
+        );
+
+        let idx = self.current_slide;
+        let reveal = self.reveal_steps.get(idx).copied().unwrap_or(0);
+        let max = self.max_steps.get(idx).copied().unwrap_or(0);
+        let mut position = if self.on_end_slide {
+            "End".to_string()
+        } else {
+            format!("Slide {} / {}", idx + 1, self.slide_count())
+        };
+        if max > 0 && !self.on_end_slide {
+            position.push_str(&format!("  \u{00b7}  Step {reveal} / {max}"));
+        }
+        if self.blackout {
+            position.push_str("  \u{00b7}  BLACKOUT");
+        }
+        if let Some((status, _)) = self.rehearsal_status() {
+            position = format!("REHEARSAL {status}  \u{00b7}  {position}");
+        }
+        let position_galley =
+            ui.painter()
+                .layout_no_wrap(position, egui::FontId::monospace(16.0), text_color);
+        ui.painter().galley(
+            egui::pos2(
+                rect.right() - padding - position_galley.rect.width(),
+                rect.top() + padding,
+            ),
+            position_galley,
+            text_color,
+        );
+
+        let body = egui::Rect::from_min_max(
+            egui::pos2(rect.left() + padding, rect.top() + padding + 40.0),
+            egui::pos2(rect.right() - padding, rect.bottom() - padding - 24.0),
+        );
+        let left_w = (body.width() - gap) * 0.6;
+        let right_w = body.width() - gap - left_w;
+        let timer_h = 110.0;
+
+        // Current slide (left, large)
+        let current_label_y = body.top();
+        draw_presenter_label(ui, "CURRENT", body.left(), current_label_y, &label_font);
+        let current_area = egui::Rect::from_min_size(
+            egui::pos2(body.left(), current_label_y + 20.0),
+            egui::vec2(left_w, (body.height() - 20.0 - timer_h - gap).max(0.0)),
+        );
+        let current_rect = fit_aspect(current_area, self.aspect.ratio());
+        if self.on_end_slide {
+            ui.painter()
+                .rect_filled(current_rect, 4.0, egui::Color32::BLACK);
+            draw_centered_text(ui, current_rect, "The End", 28.0, label_color);
+        } else {
+            self.draw_slide_preview(ui, idx, reveal, current_rect, "presenter_current");
+        }
+
+        // Timer block under the current slide
+        let timer_top = current_rect.bottom() + gap;
+        let elapsed = self.timer_start.elapsed();
+        let elapsed_galley = ui.painter().layout_no_wrap(
+            format_timer(elapsed),
+            egui::FontId::monospace(48.0),
+            text_color,
+        );
+        draw_presenter_label(ui, "ELAPSED", body.left(), timer_top, &label_font);
+        ui.painter().galley(
+            egui::pos2(body.left(), timer_top + 20.0),
+            elapsed_galley,
+            text_color,
+        );
+
+        if let Some(target) = self.target_duration {
+            let (remaining, overrun) = format_remaining(target, elapsed);
+            let color = if overrun {
+                egui::Color32::from_rgb(0xFF, 0x6B, 0x6B)
+            } else {
+                text_color
+            };
+            let x = body.left() + left_w * 0.36;
+            draw_presenter_label(ui, "REMAINING", x, timer_top, &label_font);
+            let galley =
+                ui.painter()
+                    .layout_no_wrap(remaining, egui::FontId::monospace(48.0), color);
+            ui.painter()
+                .galley(egui::pos2(x, timer_top + 20.0), galley, color);
+        }
+
+        let clock_x = body.left() + left_w * 0.72;
+        draw_presenter_label(ui, "CLOCK", clock_x, timer_top, &label_font);
+        let clock_galley = ui.painter().layout_no_wrap(
+            chrono::Local::now().format("%H:%M").to_string(),
+            egui::FontId::monospace(48.0),
+            text_color,
+        );
+        ui.painter().galley(
+            egui::pos2(clock_x, timer_top + 20.0),
+            clock_galley,
+            text_color,
+        );
+
+        // Next slide / next reveal step (right, top)
+        let right_left = body.left() + left_w + gap;
+        draw_presenter_label(ui, "NEXT", right_left, body.top(), &label_font);
+        // Capped at half the body so tall aspect ratios leave room for the notes
+        let next_area = fit_aspect(
+            egui::Rect::from_min_size(
+                egui::pos2(right_left, body.top() + 20.0),
+                egui::vec2(right_w, body.height() * 0.5),
+            ),
+            self.aspect.ratio(),
+        );
+        let next = if self.on_end_slide {
+            None
+        } else {
+            next_preview(idx, reveal, &self.max_steps)
+        };
+        match next {
+            Some((next_idx, next_step)) => {
+                self.draw_slide_preview(ui, next_idx, next_step, next_area, "presenter_next");
+            }
+            None => {
+                ui.painter()
+                    .rect_filled(next_area, 4.0, egui::Color32::BLACK);
+                draw_centered_text(ui, next_area, "End of presentation", 18.0, label_color);
+            }
+        }
+
+        // Speaker notes (right, below next)
+        let notes_top = next_area.bottom() + gap;
+        draw_presenter_label(ui, "NOTES", right_left, notes_top, &label_font);
+        let notes_rect = egui::Rect::from_min_max(
+            egui::pos2(right_left, notes_top + 20.0),
+            egui::pos2(body.right(), body.bottom()),
+        );
+        ui.painter()
+            .rect_filled(notes_rect, 4.0, egui::Color32::from_gray(28));
+        if self.notes_blocks.is_none() {
+            self.notes_blocks = self
+                .presentation
+                .slides
+                .get(idx)
+                .and_then(|s| s.notes.as_deref())
+                .map(parser::blocks::parse);
+        }
+        let notes = if self.on_end_slide {
+            None
+        } else {
+            self.notes_blocks.as_ref()
+        };
+        match notes {
+            Some(blocks) => {
+                let notes_theme = Theme::dark();
+                let notes_scale = 0.5;
+                let inner = notes_rect.shrink(12.0);
+                let child = ui.new_child(
+                    egui::UiBuilder::new()
+                        .max_rect(notes_rect.shrink(4.0))
+                        .id_salt("presenter_notes"),
+                );
+                let overflow = (text::measure_blocks_height(
+                    &child,
+                    blocks,
+                    &notes_theme,
+                    inner.width(),
+                    notes_scale,
+                ) - inner.height())
+                .max(0.0);
+                self.notes_scroll = self.notes_scroll.min(overflow);
+                text::draw_blocks(
+                    &child,
+                    blocks,
+                    &notes_theme,
+                    egui::pos2(inner.left(), inner.top() - self.notes_scroll),
+                    inner.width(),
+                    1.0,
+                    &self.image_cache,
+                    usize::MAX,
+                    notes_scale,
+                );
+            }
+            None => {
+                draw_centered_text(ui, notes_rect, "No notes for this slide", 15.0, label_color);
+            }
+        }
+
+        // Hint line
+        let hint = "\u{2190}/\u{2192}: navigate  |  \u{2191}/\u{2193}: scroll notes  |  Z: reset timer  |  .: blackout  |  S/Esc: close";
+        let hint_galley = ui.painter().layout_no_wrap(
+            hint.to_string(),
+            egui::FontId::proportional(12.0),
+            label_color,
+        );
+        ui.painter().galley(
+            egui::pos2(
+                rect.center().x - hint_galley.rect.width() / 2.0,
+                rect.bottom() - padding - 8.0,
+            ),
+            hint_galley,
+            label_color,
+        );
+    }
+
+    /// Draw a thumbnail of slide `index` at a given reveal step.
+    fn draw_slide_preview(
+        &self,
+        ui: &mut egui::Ui,
+        index: usize,
+        step: usize,
+        rect: egui::Rect,
+        id_salt: &str,
+    ) {
+        let Some(slide) = self.presentation.slides.get(index) else {
+            return;
+        };
+        ui.painter().rect_filled(rect, 4.0, self.theme.background);
+        let child = ui.new_child(egui::UiBuilder::new().max_rect(rect).id_salt(id_salt));
+        let scale = self.aspect.scale(rect);
+        render::render_slide(
+            &child,
+            slide,
+            &self.theme,
+            &self.custom_themes,
+            rect,
+            1.0,
+            &self.image_cache,
+            step,
+            None,
+            scale,
+            &self.footer_context(index),
+        );
+        ui.painter().rect_stroke(
+            rect,
+            4.0,
+            egui::Stroke::new(1.0, egui::Color32::from_gray(70)),
+            egui::StrokeKind::Outside,
+        );
+    }
+}
+
+fn draw_presenter_label(ui: &egui::Ui, label: &str, x: f32, y: f32, font: &egui::FontId) {
+    let color = egui::Color32::from_gray(130);
+    let galley = ui
+        .painter()
+        .layout_no_wrap(label.to_string(), font.clone(), color);
+    ui.painter().galley(egui::pos2(x, y), galley, color);
+}
+
+fn draw_centered_text(
+    ui: &egui::Ui,
+    rect: egui::Rect,
+    text: &str,
+    size: f32,
+    color: egui::Color32,
+) {
+    let galley =
+        ui.painter()
+            .layout_no_wrap(text.to_string(), egui::FontId::proportional(size), color);
+    let pos = rect.center() - galley.rect.size() / 2.0;
+    ui.painter().galley(pos, galley, color);
+}
+
+/// Largest rect with the given width/height ratio that fits in `area`, top-aligned
+/// and horizontally centered.
+fn fit_aspect(area: egui::Rect, aspect: f32) -> egui::Rect {
+    let width = area.width().min(area.height() * aspect);
+    let height = width / aspect;
+    egui::Rect::from_min_size(
+        egui::pos2(area.center().x - width / 2.0, area.top()),
+        egui::vec2(width, height),
+    )
+}
+
+/// What the next forward press will show: the same slide with one more reveal
+/// step, or the following slide from the top. `None` at the end of the deck.
+fn next_preview(current: usize, reveal: usize, max_steps: &[usize]) -> Option<(usize, usize)> {
+    let max = max_steps.get(current).copied()?;
+    if reveal < max {
+        Some((current, reveal + 1))
+    } else if current + 1 < max_steps.len() {
+        Some((current + 1, 0))
+    } else {
+        None
+    }
+}
+
+/// The address to open for an external link: URLs with a scheme as they
+/// are, other paths relative to the deck's directory as `file://` URLs.
+fn external_link(url: &str, deck: &Path) -> String {
+    let has_scheme = url.split_once(':').is_some_and(|(scheme, _)| {
+        // One letter is a Windows drive, not a scheme
+        scheme.len() > 1
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    if has_scheme {
+        return url.to_string();
+    }
+    let path = deck.parent().unwrap_or(Path::new(".")).join(url);
+    let path = path.canonicalize().unwrap_or(path);
+    format!("file://{}", path.display())
+}
+
+/// Format a duration as `MM:SS`, or `H:MM:SS` from one hour up.
+fn format_timer(d: Duration) -> String {
+    let secs = d.as_secs();
+    let (h, m, s) = (secs / 3600, (secs / 60) % 60, secs % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m:02}:{s:02}")
+    }
+}
+
+/// Rehearsal status color: green on pace or ahead, amber up to a minute
+/// behind, red beyond.
+fn pace_color(pace: Option<f64>) -> egui::Color32 {
+    match pace {
+        None => egui::Color32::from_gray(220),
+        Some(p) if p <= 0.0 => egui::Color32::from_rgb(0x6B, 0xD6, 0x8A),
+        Some(p) if p <= 60.0 => egui::Color32::from_rgb(0xFF, 0xC1, 0x4D),
+        Some(_) => egui::Color32::from_rgb(0xFF, 0x6B, 0x6B),
+    }
+}
+
+/// Remaining time against a target, and whether the talk has overrun.
+/// Overruns are shown with a leading `+`.
+fn format_remaining(target: Duration, elapsed: Duration) -> (String, bool) {
+    match target.checked_sub(elapsed) {
+        Some(remaining) => (format_timer(remaining), false),
+        None => (format!("+{}", format_timer(elapsed - target)), true),
     }
 }
 
@@ -2018,26 +3231,92 @@ fn draw_fade_gradient(ui: &egui::Ui, rect: egui::Rect, fade_h: f32, theme: &Them
     ui.painter().add(egui::Shape::mesh(mesh));
 }
 
+/// `rect` enlarged `zoom` times about `center`, so the point under `center`
+/// stays put.
+fn magnified_rect(rect: egui::Rect, center: egui::Pos2, zoom: f32) -> egui::Rect {
+    egui::Rect::from_min_size(center - (center - rect.min) * zoom, rect.size() * zoom)
+}
+
+/// A filled ring between `inner` and `outer` radius around `center`.
+fn ring_mesh(center: egui::Pos2, inner: f32, outer: f32, color: egui::Color32) -> egui::Mesh {
+    const SEGMENTS: u32 = 96;
+    let mut mesh = egui::Mesh::default();
+    for i in 0..SEGMENTS {
+        let angle = i as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+        let dir = egui::vec2(angle.cos(), angle.sin());
+        mesh.colored_vertex(center + dir * inner, color);
+        mesh.colored_vertex(center + dir * outer, color);
+    }
+    for i in 0..SEGMENTS {
+        let a = 2 * i;
+        let b = 2 * ((i + 1) % SEGMENTS);
+        mesh.add_triangle(a, a + 1, b);
+        mesh.add_triangle(a + 1, b + 1, b);
+    }
+    mesh
+}
+
+/// A message in a rounded box near the bottom of the screen.
+fn draw_toast(
+    ui: &egui::Ui,
+    theme: &Theme,
+    message: &str,
+    rect: egui::Rect,
+    scale: f32,
+    opacity: f32,
+) {
+    let toast_color = Theme::with_opacity(theme.foreground, opacity * 0.9);
+    let toast_bg = Theme::with_opacity(theme.code_background, opacity * 0.9);
+    let galley = ui.painter().layout_no_wrap(
+        message.to_string(),
+        egui::FontId::proportional(20.0 * scale),
+        toast_color,
+    );
+    let padding = 16.0 * scale;
+    let toast_rect = egui::Rect::from_min_size(
+        egui::pos2(
+            rect.center().x - galley.rect.width() / 2.0 - padding,
+            rect.bottom() - 80.0 * scale,
+        ),
+        egui::vec2(
+            galley.rect.width() + padding * 2.0,
+            galley.rect.height() + padding * 2.0,
+        ),
+    );
+    ui.painter().rect_filled(toast_rect, 8.0 * scale, toast_bg);
+    let text_pos = egui::pos2(toast_rect.left() + padding, toast_rect.top() + padding);
+    ui.painter().galley(text_pos, galley, toast_color);
+}
+
 fn draw_hud(ui: &egui::Ui, theme: &Theme, rect: egui::Rect, scale: f32) {
     let shortcuts = [
-        ("Space / N / \u{2192}", "Next slide / reveal"),
-        ("P / \u{2190}", "Previous slide / hide"),
+        ("Space / N / \u{2192} / PgDn", "Next slide / reveal"),
+        ("P / \u{2190} / PgUp", "Previous slide / hide"),
         ("\u{2191} / \u{2193} / Wheel", "Scroll slide content"),
         ("Left click", "Next slide"),
         ("Right click", "Previous slide"),
         ("Left drag", "Freehand pen (blue)"),
         ("Right drag", "Draw arrow (orange)"),
+        ("L", "Laser pointer"),
+        ("O", "Spotlight"),
+        ("M", "Magnifier"),
+        ("A", "Keep drawings (save to file)"),
         ("Esc", "Clear drawings / \u{00d7}2 exit"),
         ("G", "Grid view / overview"),
         ("T", "Cycle transition"),
         ("⇧T", "Cycle theme"),
         ("F", "Toggle fullscreen"),
         ("H", "Toggle this HUD"),
+        ("S", "Presenter view (notes, timer)"),
         (".", "Blackout screen"),
         ("R", "Debug overlay (L/R/off)"),
         ("Q", "Quit"),
         ("Home", "First slide"),
         ("End", "Last slide"),
+        ("/ or Ctrl+F", "Search slides"),
+        ("12 Enter", "Go to slide 12"),
+        ("Click link", "Open link / go to slide"),
+        ("Backspace", "Back from a slide link"),
     ];
 
     let bg = Theme::with_opacity(theme.code_background, 0.9);
@@ -2199,10 +3478,14 @@ fn load_app_icon() -> Option<egui::IconData> {
     })
 }
 
-/// Compute a hash of file content for change detection.
-fn hash_content(content: &str) -> u64 {
+/// Compute a hash of the deck content and its theme files for change detection.
+/// Unreadable theme files hash as empty.
+fn hash_sources(content: &str, theme_files: &[PathBuf]) -> u64 {
     let mut hasher = DefaultHasher::new();
     content.hash(&mut hasher);
+    for path in theme_files {
+        std::fs::read(path).unwrap_or_default().hash(&mut hasher);
+    }
     hasher.finish()
 }
 
@@ -2255,12 +3538,16 @@ fn spawn_file_watcher(
     Ok((rx, debouncer))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     file: PathBuf,
     windowed: bool,
     start_slide: Option<usize>,
     start_overview: bool,
+    presenter: bool,
     quiet: bool,
+    rehearsal: Option<Arc<Mutex<Rehearsal>>>,
+    remote: Option<Arc<Remote>>,
 ) -> anyhow::Result<()> {
     let file = file.canonicalize().unwrap_or(file);
 
@@ -2301,12 +3588,25 @@ pub fn run(
     for attempt in 0..=MAX_RETRIES {
         let content = std::fs::read_to_string(&file)?;
         let base_path = file.parent().unwrap_or(std::path::Path::new("."));
-        let presentation = parser::parse(&content, base_path);
+        let mut presentation = parser::parse(&content, base_path);
+        config.apply_deck_defaults(&mut presentation.meta);
 
         if presentation.slides.is_empty() {
             anyhow::bail!("No slides found in {}", file.display());
         }
 
+        let (custom_themes, mut theme_warnings) =
+            crate::theme::load_deck_themes(&presentation, base_path);
+        let (font_definitions, font_warnings) =
+            crate::fonts::load_deck_fonts(&presentation.meta, &custom_themes, base_path);
+        theme_warnings.extend(font_warnings);
+        if attempt == 0 && !quiet {
+            use colored::Colorize;
+            for w in &theme_warnings {
+                eprintln!("{} {}", "Warning:".yellow().bold(), w.message);
+            }
+        }
+
         // Warn about ungenerated AI images (first attempt only, not on hot-reload)
         if attempt == 0 && !quiet {
             let ungenerated = presentation
@@ -2354,8 +3654,10 @@ pub fn run(
         };
 
         let viewport = if windowed {
+            // 720 points tall, as wide as the deck's aspect ratio
+            let ratio = AspectRatio::for_deck(&presentation.meta).ratio();
             egui::ViewportBuilder::default()
-                .with_inner_size([1280.0, 720.0])
+                .with_inner_size([(720.0 * ratio).round(), 720.0])
                 .with_title(&title)
         } else {
             egui::ViewportBuilder::default()
@@ -2377,16 +3679,20 @@ pub fn run(
         let shared = shared_slide.clone();
         let file_clone = file.clone();
         let log_clone = incident_log.clone();
+        let rehearsal = rehearsal.clone();
+        let remote = remote.clone();
         let result = eframe::run_native(
             &title,
             options,
             Box::new(move |cc| {
-                let content_hash = hash_content(&content);
+                cc.egui_ctx.set_fonts(font_definitions);
+                let content_hash = hash_sources(&content, custom_themes.files());
                 let (watcher_rx, watcher) =
                     spawn_file_watcher(&file_clone, cc.egui_ctx.clone(), log_clone.clone())?;
                 let mut app = PresentationApp::new(
                     file_clone,
                     presentation,
+                    custom_themes,
                     windowed,
                     watcher_rx,
                     watcher,
@@ -2396,11 +3702,23 @@ pub fn run(
                 );
                 app.current_slide = initial_slide;
                 app.shared_slide = Some(shared);
+                app.presenter_open = presenter;
+                if let Some(rehearsal) = rehearsal {
+                    if let Some(target) = rehearsal.lock().unwrap().target() {
+                        app.target_duration = Some(target);
+                    }
+                    app.rehearsal = Some(rehearsal);
+                }
+                if let Some(remote) = remote {
+                    remote.set_repaint(cc.egui_ctx.clone());
+                    app.remote = Some(remote);
+                }
                 if initial_overview {
                     app.mode = AppMode::Grid {
                         selected: initial_slide,
                     };
                 }
+                app.watch_theme_files();
                 app.spawn_diagram_precache();
                 Ok(Box::new(app))
             }),
@@ -2466,9 +3784,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn magnifier_keeps_the_point_under_the_pointer() {
+        let rect = egui::Rect::from_min_size(egui::pos2(100.0, 0.0), egui::vec2(800.0, 450.0));
+        let pointer = egui::pos2(300.0, 150.0);
+        let zoomed = magnified_rect(rect, pointer, 2.0);
+        assert_eq!(zoomed.size(), egui::vec2(1600.0, 900.0));
+        // The pointer sits a quarter across and a third down in both
+        assert_eq!(zoomed.min, egui::pos2(-100.0, -150.0));
+        assert_eq!(magnified_rect(rect, rect.min, 3.0).min, rect.min);
+    }
+
     #[test]
     fn find_matching_slide_exact_match() {
-        let _slides = vec![slide("a"), slide("b"), slide("c")];
+        let _slides = [slide("a"), slide("b"), slide("c")];
         // Was at index 1 ("b"), new slides inserted "x" before it
         let new_slides = vec![slide("x"), slide("a"), slide("b"), slide("c")];
         assert_eq!(find_matching_slide(Some("b"), 1, &new_slides), 2);
@@ -2489,6 +3818,56 @@ mod tests {
         assert_eq!(find_matching_slide(Some("gone"), 5, &new_slides), 1);
     }
 
+    #[test]
+    fn external_links_resolve_relative_paths_against_the_deck() {
+        let deck = Path::new("/talks/deck.md");
+        assert_eq!(
+            external_link("https://example.com/a?b", deck),
+            "https://example.com/a?b"
+        );
+        assert_eq!(
+            external_link("mailto:me@example.com", deck),
+            "mailto:me@example.com"
+        );
+        assert_eq!(
+            external_link("handout.pdf", deck),
+            "file:///talks/handout.pdf"
+        );
+    }
+
+    #[test]
+    fn next_preview_reveals_next_step_first() {
+        assert_eq!(next_preview(0, 0, &[2, 0]), Some((0, 1)));
+        assert_eq!(next_preview(0, 1, &[2, 0]), Some((0, 2)));
+        assert_eq!(next_preview(0, 2, &[2, 0]), Some((1, 0)));
+    }
+
+    #[test]
+    fn next_preview_none_on_last_slide() {
+        assert_eq!(next_preview(1, 0, &[2, 0]), None);
+        assert_eq!(next_preview(5, 0, &[2, 0]), None);
+    }
+
+    #[test]
+    fn format_timer_minutes_and_hours() {
+        assert_eq!(format_timer(Duration::from_secs(0)), "00:00");
+        assert_eq!(format_timer(Duration::from_secs(754)), "12:34");
+        assert_eq!(format_timer(Duration::from_secs(3723)), "1:02:03");
+    }
+
+    #[test]
+    fn format_remaining_marks_overrun() {
+        let target = Duration::from_secs(600);
+        assert_eq!(
+            format_remaining(target, Duration::from_secs(90)),
+            ("08:30".to_string(), false)
+        );
+        assert_eq!(
+            format_remaining(target, Duration::from_secs(665)),
+            ("+01:05".to_string(), true)
+        );
+    }
+
     #[test]
     fn find_matching_slide_no_old_raw_returns_zero() {
         let new_slides = vec![slide("a"), slide("b")];
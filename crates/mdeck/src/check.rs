@@ -1,16 +1,25 @@
 use std::collections::BTreeSet;
 use std::fmt;
+use std::path::Path;
+
+use crate::parser::Slide;
 
 /// Category of a check warning, for grouping and filtering.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum CheckCategory {
     DiagramRouting,
+    Directive,
+    Font,
+    Theme,
 }
 
 impl fmt::Display for CheckCategory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             CheckCategory::DiagramRouting => write!(f, "architecture"),
+            CheckCategory::Directive => write!(f, "directive"),
+            CheckCategory::Font => write!(f, "font"),
+            CheckCategory::Theme => write!(f, "theme"),
         }
     }
 }
@@ -18,7 +27,7 @@ impl fmt::Display for CheckCategory {
 /// A single warning produced during presentation checking.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CheckWarning {
-    /// 1-indexed slide number.
+    /// 1-indexed slide number; 0 refers to the frontmatter.
     pub slide: usize,
     pub category: CheckCategory,
     pub message: String,
@@ -26,6 +35,9 @@ pub struct CheckWarning {
 
 impl fmt::Display for CheckWarning {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.slide == 0 {
+            return write!(f, "  frontmatter: [{}] {}", self.category, self.message);
+        }
         write!(
             f,
             "  slide {}: [{}] {}",
@@ -81,6 +93,80 @@ impl CheckReport {
     }
 }
 
+/// Slide-level directives the renderer understands.
+const SLIDE_DIRECTIVES: &[&str] = &[
+    "layout",
+    "theme",
+    "transition",
+    "background",
+    "footer",
+    "header",
+    "code-theme",
+    "class",
+];
+
+const LAYOUTS: &[&str] = &[
+    "title",
+    "section",
+    "image",
+    "gallery",
+    "quote",
+    "code",
+    "bullet",
+    "bullets",
+    "diagram",
+    "architecture",
+    "visualization",
+    "two-column",
+    "content",
+];
+
+/// Check a slide's `@name: value` directives for unknown names and invalid values.
+/// Relative `@background` image paths are resolved against `base_path`.
+/// `@theme` values are validated by `theme::load_deck_themes`.
+pub fn check_slide_directives(slide: &Slide, base_path: &Path) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for d in &slide.directives {
+        let value = d.value.as_str();
+        match d.name.as_str() {
+            "layout" if !LAYOUTS.contains(&value) => {
+                warnings.push(format!("Unknown layout '{value}', using content layout"));
+            }
+            "transition" if !matches!(value, "fade" | "slide" | "spatial" | "none") => {
+                warnings.push(format!("Unknown transition '{value}', using slide"));
+            }
+            "code-theme" if crate::render::syntax::find_code_theme(value).is_none() => {
+                warnings.push(format!(
+                    "Unknown code theme '{value}' (available: {})",
+                    crate::render::syntax::code_theme_names().join(", ")
+                ));
+            }
+            "background" => {
+                if value.starts_with('#') {
+                    if crate::theme::parse_hex_color(value).is_none() {
+                        warnings.push(format!("Invalid background color '{value}'"));
+                    }
+                } else if !base_path.join(value).exists() {
+                    warnings.push(format!("Background image not found: {value}"));
+                }
+            }
+            "footer" | "header" => {
+                for token in crate::render::footer::unknown_tokens(value) {
+                    warnings.push(format!("Unknown token {{{token}}} in @{}", d.name));
+                }
+            }
+            "aspect" => {
+                warnings.push("@aspect only applies in frontmatter; ignored on a slide".into());
+            }
+            name if !SLIDE_DIRECTIVES.contains(&name) => {
+                warnings.push(format!("Unknown directive @{name} (ignored)"));
+            }
+            _ => {}
+        }
+    }
+    warnings
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,10 +213,62 @@ mod tests {
         assert_eq!(report.warning_count(), 3);
     }
 
+    #[test]
+    fn frontmatter_warnings_display_without_slide_number() {
+        let w = CheckWarning {
+            slide: 0,
+            category: CheckCategory::Theme,
+            message: "Theme 'corp': theme file not found".to_string(),
+        };
+        assert_eq!(
+            w.to_string(),
+            "  frontmatter: [theme] Theme 'corp': theme file not found"
+        );
+    }
+
     #[test]
     fn empty_report_has_no_warnings() {
         let report = CheckReport::new();
         assert!(!report.has_warnings());
         assert_eq!(report.warning_count(), 0);
     }
+
+    fn directive_warnings(src: &str) -> Vec<String> {
+        let pres = crate::parser::parse(src, Path::new("."));
+        check_slide_directives(&pres.slides[0], Path::new("."))
+    }
+
+    #[test]
+    fn known_directives_pass() {
+        let warnings = directive_warnings(
+            "@theme: nord\n@transition: fade\n@background: #223344\n@footer: Hi {slide}/{total}\n@header: {title}\n@code-theme: InspiredGitHub\n@class: invert\n@layout: bullets\n# Ok",
+        );
+        assert!(warnings.is_empty(), "{warnings:?}");
+    }
+
+    #[test]
+    fn unknown_and_invalid_directives_warn() {
+        let warnings = directive_warnings(
+            "@colour: red\n@transition: zoom\n@background: #12\n@code-theme: nope\n@footer: {page}\n# Bad",
+        );
+        assert_eq!(warnings.len(), 5, "{warnings:?}");
+        assert!(warnings.iter().any(|w| w.contains("@colour")));
+        assert!(warnings.iter().any(|w| w.contains("zoom")));
+        assert!(warnings.iter().any(|w| w.contains("#12")));
+        assert!(warnings.iter().any(|w| w.contains("'nope'")));
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w == "Unknown token {page} in @footer")
+        );
+    }
+
+    #[test]
+    fn missing_background_image_warns() {
+        let warnings = directive_warnings("@background: no/such/image.png\n# Photo");
+        assert_eq!(
+            warnings,
+            vec!["Background image not found: no/such/image.png"]
+        );
+    }
 }
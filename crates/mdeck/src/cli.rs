@@ -9,6 +9,9 @@ use std::path::PathBuf;
     Examples:\n  \
     mdeck slides.md              Launch presentation (fullscreen)\n  \
     mdeck slides.md --windowed   Launch in a window\n  \
+    mdeck slides.md --presenter  Also open the presenter view\n  \
+    mdeck slides.md --remote 8080 --remote-lan  Control from a phone at port 8080\n  \
+    mdeck rehearse slides.md     Rehearse with per-slide timing\n  \
     mdeck spec                   Print format specification\n  \
     mdeck spec --short           Print quick reference card")]
 #[command(propagate_version = true)]
@@ -32,6 +35,20 @@ pub struct Cli {
     #[arg(long, global = false)]
     pub overview: bool,
 
+    /// Open the presenter view (notes, next slide, timer) in a second window
+    #[arg(long, global = false)]
+    pub presenter: bool,
+
+    /// Serve a remote control page (notes, next/previous, blackout) at this
+    /// port, on this machine only unless --remote-lan is given
+    #[arg(long, global = false, value_name = "PORT")]
+    pub remote: Option<u16>,
+
+    /// Accept remote control connections from the local network, so a phone
+    /// can connect
+    #[arg(long, global = false, requires = "remote")]
+    pub remote_lan: bool,
+
     /// Validate presentation and report problems without launching GUI
     #[arg(long, global = false)]
     pub check: bool,
@@ -70,23 +87,16 @@ pub enum Commands {
         shell: Shell,
     },
 
-    /// Export slides as PNG images
-    Export {
-        /// Markdown file to export
-        file: PathBuf,
-
-        /// Output directory for PNG files
-        #[arg(short, long, default_value = "export")]
-        output_dir: PathBuf,
+    /// Export slides as PNG or SVG images, a PDF document, a printable handout, a
+    /// standalone HTML page or a PowerPoint file
+    Export(ExportArgs),
 
-        /// Export width in pixels
-        #[arg(long, default_value = "1920")]
-        width: u32,
+    /// Convert a PowerPoint, Marp, reveal.js or remark deck into mdeck markdown
+    Import(ImportArgs),
 
-        /// Export height in pixels
-        #[arg(long, default_value = "1080")]
-        height: u32,
-    },
+    /// Rehearse a talk: time every slide and reveal step, show pacing against
+    /// the target duration and save a timing report to compare the next run with
+    Rehearse(RehearseArgs),
 
     /// Print the mdeck markdown format specification
     Spec {
@@ -146,6 +156,88 @@ pub enum AiCommands {
     },
 }
 
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Markdown file to export
+    pub file: PathBuf,
+
+    /// Output directory for the exported files
+    #[arg(short, long, default_value = "export")]
+    pub output_dir: PathBuf,
+
+    /// Output format: one PNG or SVG per slide, or a single PDF, handout PDF, HTML
+    /// page or PowerPoint file named after the file [default: png]
+    #[arg(short, long, value_enum)]
+    pub format: Option<ExportFormat>,
+
+    /// Export only the Nth diagram or chart in the deck (counting from 1) as SVG
+    #[arg(long, value_name = "N")]
+    pub block: Option<usize>,
+
+    /// Export every reveal step as its own image or PDF page
+    /// (slide-03-step-0.png, ...) instead of only the fully revealed slide
+    #[arg(long)]
+    pub steps: bool,
+
+    /// Export width in pixels [default: from the deck's @aspect, 1920 for 16:9]
+    #[arg(long)]
+    pub width: Option<u32>,
+
+    /// Export height in pixels [default: from the deck's @aspect, 1080]
+    #[arg(long)]
+    pub height: Option<u32>,
+
+    /// Slides per handout page
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u8).range(1..=8))]
+    pub per_page: u8,
+
+    /// Add ruled lines for writing beside each slide in the handout
+    #[arg(long)]
+    pub lines: bool,
+
+    /// Draw the annotations saved in <deck>.annotations.json onto the slides
+    /// (PNG, SVG, PDF and handout)
+    #[arg(long)]
+    pub annotations: bool,
+}
+
+#[derive(Args)]
+pub struct RehearseArgs {
+    /// Markdown file to rehearse
+    pub file: PathBuf,
+
+    /// Target talk length, e.g. 20m or 1h30m [default: the deck's @duration]
+    #[arg(long)]
+    pub duration: Option<String>,
+
+    /// Launch in a window instead of fullscreen
+    #[arg(long)]
+    pub windowed: bool,
+
+    /// Open the presenter view (notes, next slide, timer) in a second window
+    #[arg(long)]
+    pub presenter: bool,
+}
+
+#[derive(Args)]
+pub struct ImportArgs {
+    /// Deck to convert: a .pptx file, or Marp, reveal.js or remark markdown
+    /// (or the HTML page holding it)
+    pub file: PathBuf,
+
+    /// Markdown file to write [default: the input's name with a .md extension]
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Input format [default: detected from the extension and content]
+    #[arg(long, value_enum)]
+    pub from: Option<ImportFormat>,
+
+    /// Overwrite the output file if it exists
+    #[arg(long)]
+    pub force: bool,
+}
+
 #[derive(Args)]
 pub struct GenerateImageArgs {
     /// Image prompt
@@ -254,6 +346,24 @@ pub enum ConfigCommands {
     },
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Png,
+    Svg,
+    Pdf,
+    Handout,
+    Html,
+    Pptx,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    Pptx,
+    Marp,
+    Reveal,
+    Remark,
+}
+
 #[derive(Clone, ValueEnum)]
 pub enum Shell {
     Bash,
@@ -276,12 +386,9 @@ impl Cli {
                 crate::commands::completion::run(shell);
                 Ok(())
             }
-            Some(Commands::Export {
-                file,
-                output_dir,
-                width,
-                height,
-            }) => crate::commands::export::run(file, output_dir, width, height),
+            Some(Commands::Export(args)) => crate::commands::export::run(args),
+            Some(Commands::Import(args)) => crate::commands::import::run(args),
+            Some(Commands::Rehearse(args)) => crate::commands::rehearse::run(args, self.quiet),
             Some(Commands::Spec { short }) => {
                 crate::commands::spec::run(short);
                 Ok(())
@@ -298,7 +405,24 @@ impl Cli {
                     if self.check {
                         return crate::commands::check::run(file, self.verbose, self.quiet);
                     }
-                    crate::app::run(file, self.windowed, self.slide, self.overview, self.quiet)
+                    let remote = match self.remote {
+                        Some(port) => {
+                            let remote = crate::remote::Remote::start(port, self.remote_lan)?;
+                            eprintln!("Remote control: {}", remote.url());
+                            Some(std::sync::Arc::new(remote))
+                        }
+                        None => None,
+                    };
+                    crate::app::run(
+                        file,
+                        self.windowed,
+                        self.slide,
+                        self.overview,
+                        self.presenter,
+                        self.quiet,
+                        None,
+                        remote,
+                    )
                 } else {
                     use clap::CommandFactory;
                     let mut cmd = Self::command();
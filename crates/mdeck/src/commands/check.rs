@@ -7,7 +7,8 @@ use crate::render;
 pub fn run(file: PathBuf, _verbose: u8, quiet: bool) -> anyhow::Result<()> {
     let content = std::fs::read_to_string(&file)?;
     let base_path = file.parent().unwrap_or(std::path::Path::new("."));
-    let presentation = parser::parse(&content, base_path);
+    let mut presentation = parser::parse(&content, base_path);
+    crate::config::Config::load_or_default().apply_deck_defaults(&mut presentation.meta);
 
     if presentation.slides.is_empty() {
         anyhow::bail!("No slides found in {}", file.display());
@@ -31,11 +32,52 @@ pub fn run(file: PathBuf, _verbose: u8, quiet: bool) -> anyhow::Result<()> {
 
     let mut report = CheckReport::new();
 
+    if let Some(aspect) = presentation.meta.aspect.as_deref()
+        && render::aspect::AspectRatio::parse(aspect).is_none()
+    {
+        report.add(CheckWarning {
+            slide: 0,
+            category: CheckCategory::Directive,
+            message: format!("Invalid @aspect '{aspect}' (expected W:H or WxH), using 16:9"),
+        });
+    }
+
+    let (custom_themes, theme_warnings) = crate::theme::load_deck_themes(&presentation, base_path);
+    for warning in theme_warnings {
+        report.add(warning);
+    }
+    let (_, font_warnings) =
+        crate::fonts::load_deck_fonts(&presentation.meta, &custom_themes, base_path);
+    for warning in font_warnings {
+        report.add(warning);
+    }
+
+    let meta = &presentation.meta;
+    for (name, text) in [("footer", &meta.footer), ("header", &meta.header)] {
+        let Some(text) = text else { continue };
+        for token in render::footer::unknown_tokens(text) {
+            report.add(CheckWarning {
+                slide: 0,
+                category: CheckCategory::Directive,
+                message: format!("Unknown token {{{token}}} in @{name}"),
+            });
+        }
+    }
+
     for (i, slide) in presentation.slides.iter().enumerate() {
         let slide_num = i + 1;
+        for warning_msg in crate::check::check_slide_directives(slide, base_path) {
+            report.add(CheckWarning {
+                slide: slide_num,
+                category: CheckCategory::Directive,
+                message: warning_msg,
+            });
+        }
         for block in &slide.blocks {
-            if let parser::Block::Diagram { content } = block {
-                for warning_msg in render::diagram::check_diagram_routes(content) {
+            if let parser::Block::Diagram { kind, content } = block {
+                let key_warnings = render::diagram::check_diagram_keys(*kind, content);
+                let route_warnings = render::diagram::check_diagram_routes(*kind, content);
+                for warning_msg in key_warnings.into_iter().chain(route_warnings) {
                     report.add(CheckWarning {
                         slide: slide_num,
                         category: CheckCategory::DiagramRouting,
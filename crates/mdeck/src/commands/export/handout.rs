@@ -0,0 +1,335 @@
+//! Handout output: A4 pages with a column of slide thumbnails, each slide's
+//! speaker notes beside it, and optionally ruled lines for writing.
+
+use std::ops::Range;
+
+use eframe::egui::{self, Color32, Rect, Stroke};
+
+use crate::parser;
+use crate::render::text;
+use crate::theme::Theme;
+
+use super::Exporter;
+use super::pdf::PdfPage;
+
+/// A4 portrait in points.
+pub const PAGE_SIZE: egui::Vec2 = egui::vec2(595.28, 841.89);
+
+/// Rendered page size in pixels (A4 at 150 dpi), also the layout units.
+const PAGE_PIXELS: [usize; 2] = [1240, 1754];
+const MARGIN: f32 = 90.0;
+const ROW_GAP: f32 = 40.0;
+const COLUMN_GAP: f32 = 40.0;
+/// Body text size of the notes, in page pixels.
+const NOTES_BODY_SIZE: f32 = 22.0;
+const LINE_SPACING: f32 = 44.0;
+/// Gap `text::draw_blocks` leaves after each block, before scaling.
+const BLOCK_SPACING: f32 = 20.0;
+const RULE_COLOR: Color32 = Color32::from_gray(200);
+
+/// One slot of a handout page: a slide's thumbnail with (some of) its notes,
+/// or notes continued from the slot before.
+struct Slot {
+    slide: usize,
+    /// The notes blocks shown in this slot.
+    blocks: Range<usize>,
+    continued: bool,
+}
+
+/// Size of each slot and of the thumbnail in it, for `per_page` slots.
+fn slot_geometry(per_page: usize, reference: egui::Vec2) -> (f32, egui::Vec2) {
+    let content = Rect::from_min_size(
+        egui::Pos2::ZERO,
+        egui::vec2(PAGE_PIXELS[0] as f32, PAGE_PIXELS[1] as f32),
+    )
+    .shrink(MARGIN);
+    let row_height = (content.height() - ROW_GAP * (per_page - 1) as f32) / per_page as f32;
+    let thumb_width = (content.width() * 0.5).min(row_height * reference.x / reference.y);
+    let thumb_size = egui::vec2(thumb_width, thumb_width * reference.y / reference.x);
+    (row_height, thumb_size)
+}
+
+/// Split each slide's notes into slots of at most `row_height`: the slide's
+/// own slot, then continuation slots for notes that do not fit. A block
+/// taller than a whole slot gets a slot of its own and is cut off.
+fn plan_slots(heights: &[Vec<f32>], row_height: f32, spacing: f32) -> Vec<Slot> {
+    let mut slots = Vec::new();
+    for (slide, heights) in heights.iter().enumerate() {
+        let mut start = 0;
+        loop {
+            let mut end = start;
+            let mut used = 0.0;
+            while end < heights.len() {
+                let next = used + heights[end] + if end > start { spacing } else { 0.0 };
+                if next > row_height && end > start {
+                    break;
+                }
+                used = next;
+                end += 1;
+            }
+            slots.push(Slot {
+                slide,
+                blocks: start..end,
+                continued: start > 0,
+            });
+            if end >= heights.len() {
+                break;
+            }
+            start = end;
+        }
+    }
+    slots
+}
+
+/// Render the deck as handout pages with `per_page` slides each, fully
+/// revealed. With `lines`, the space under each slide's notes is ruled.
+/// Notes too long for their slot continue in the next one; a notes block
+/// that does not fit even a slot of its own is cut off with an ellipsis
+/// and a warning.
+pub fn pages(exporter: &mut Exporter, per_page: usize, lines: bool) -> Vec<PdfPage> {
+    let notes: Vec<Vec<parser::Block>> = exporter
+        .presentation
+        .slides
+        .iter()
+        .map(|s| {
+            s.notes
+                .as_deref()
+                .map(parser::blocks::parse)
+                .unwrap_or_default()
+        })
+        .collect();
+    let reveals: Vec<usize> = (0..exporter.slide_count())
+        .map(|idx| exporter.max_steps.get(idx).copied().unwrap_or(0))
+        .collect();
+    let notes_theme = Theme::light();
+    let notes_scale = NOTES_BODY_SIZE / notes_theme.body_size;
+    let (row_height, thumb_size) = slot_geometry(per_page, exporter.aspect.reference_size());
+    let notes_width = PAGE_PIXELS[0] as f32 - 2.0 * MARGIN - thumb_size.x - COLUMN_GAP;
+
+    let (renderer, deck) = exporter.split();
+
+    // Lay the notes out once, unseen, to know where they have to break
+    let mut heights: Vec<Vec<f32>> = Vec::with_capacity(notes.len());
+    renderer.render([1, 1], |ui| {
+        let child = ui.new_child(egui::UiBuilder::new().invisible());
+        heights = notes
+            .iter()
+            .map(|blocks| {
+                blocks
+                    .iter()
+                    .map(|block| {
+                        text::draw_block(
+                            &child,
+                            block,
+                            &notes_theme,
+                            egui::Pos2::ZERO,
+                            notes_width,
+                            1.0,
+                            deck.image_cache,
+                            usize::MAX,
+                            notes_scale,
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+    });
+    let slots = plan_slots(&heights, row_height, BLOCK_SPACING * notes_scale);
+    for slot in &slots {
+        let tallest = heights[slot.slide][slot.blocks.clone()]
+            .iter()
+            .copied()
+            .fold(0.0, f32::max);
+        if slot.blocks.len() == 1 && tallest > row_height {
+            eprintln!(
+                "Warning: slide {}: speaker notes are too long for a handout slot and are cut off",
+                slot.slide + 1
+            );
+        }
+    }
+
+    let page_count = slots.len().div_ceil(per_page);
+    let mut pages = Vec::with_capacity(page_count);
+    for (page_idx, chunk) in slots.chunks(per_page).enumerate() {
+        let frame = renderer.render(PAGE_PIXELS, |ui| {
+            let page = ui.max_rect();
+            ui.painter().rect_filled(page, 0.0, Color32::WHITE);
+
+            let content = page.shrink(MARGIN);
+            for (row, slot) in chunk.iter().enumerate() {
+                let idx = slot.slide;
+                let top = content.top() + row as f32 * (row_height + ROW_GAP);
+                let thumb = Rect::from_min_size(egui::pos2(content.left(), top), thumb_size);
+                if slot.continued {
+                    ui.painter().text(
+                        thumb.left_top(),
+                        egui::Align2::LEFT_TOP,
+                        format!("Slide {} (continued)", idx + 1),
+                        egui::FontId::proportional(NOTES_BODY_SIZE),
+                        Color32::from_gray(130),
+                    );
+                } else {
+                    deck.paint_slide(ui, thumb, idx, reveals[idx]);
+                    ui.painter().rect_stroke(
+                        thumb,
+                        0.0,
+                        Stroke::new(1.0, RULE_COLOR),
+                        egui::StrokeKind::Outside,
+                    );
+                }
+
+                let notes_rect = Rect::from_min_max(
+                    egui::pos2(thumb.right() + COLUMN_GAP, top),
+                    egui::pos2(content.right(), top + row_height),
+                );
+                let mut child =
+                    ui.new_child(egui::UiBuilder::new().max_rect(notes_rect).id_salt((
+                        "handout_notes",
+                        idx,
+                        slot.blocks.start,
+                    )));
+                child.set_clip_rect(notes_rect);
+                let blocks = &notes[idx][slot.blocks.clone()];
+                let used = text::draw_blocks(
+                    &child,
+                    blocks,
+                    &notes_theme,
+                    notes_rect.min,
+                    notes_rect.width(),
+                    1.0,
+                    deck.image_cache,
+                    usize::MAX,
+                    notes_scale,
+                );
+                if used - BLOCK_SPACING * notes_scale > notes_rect.height() {
+                    draw_cut_off_marker(ui, notes_rect);
+                }
+
+                if lines {
+                    let mut y = if blocks.is_empty() {
+                        notes_rect.top() + LINE_SPACING
+                    } else {
+                        notes_rect.top() + used + LINE_SPACING * 0.5
+                    };
+                    while y <= notes_rect.bottom() {
+                        ui.painter()
+                            .hline(notes_rect.x_range(), y, Stroke::new(1.0, RULE_COLOR));
+                        y += LINE_SPACING;
+                    }
+                }
+            }
+
+            let footer = match &deck.presentation.meta.title {
+                Some(title) => format!("{title} — {}/{page_count}", page_idx + 1),
+                None => format!("{}/{page_count}", page_idx + 1),
+            };
+            ui.painter().text(
+                egui::pos2(page.center().x, page.bottom() - MARGIN * 0.5),
+                egui::Align2::CENTER_CENTER,
+                footer,
+                egui::FontId::proportional(16.0),
+                Color32::from_gray(130),
+            );
+        });
+        pages.push(PdfPage::new(&frame.image, frame.glyphs));
+        eprintln!("  Rendered page {}", page_idx + 1);
+    }
+    pages
+}
+
+/// Cover the last line of a cut-off notes panel with an ellipsis.
+fn draw_cut_off_marker(ui: &egui::Ui, notes_rect: Rect) {
+    let band = Rect::from_min_max(
+        egui::pos2(notes_rect.left(), notes_rect.bottom() - LINE_SPACING),
+        notes_rect.right_bottom(),
+    );
+    ui.painter().rect_filled(band, 0.0, Color32::WHITE);
+    ui.painter().text(
+        band.left_center(),
+        egui::Align2::LEFT_CENTER,
+        "…",
+        egui::FontId::proportional(NOTES_BODY_SIZE),
+        Color32::from_gray(90),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::pdf;
+    use std::path::Path;
+
+    fn exporter(markdown: &str) -> Exporter {
+        let base = Path::new(".");
+        let presentation = parser::parse(markdown, base);
+        let (custom_themes, _) = crate::theme::load_deck_themes(&presentation, base);
+        let (fonts, _) = crate::fonts::load_deck_fonts(&presentation.meta, &custom_themes, base);
+        Exporter::new(presentation, custom_themes, fonts, base, (192, 108))
+    }
+
+    /// The text layer of each page, as a PDF reader extracts it, without
+    /// whitespace (Helvetica spacing differs from the painted fonts).
+    fn page_text(pages: &[PdfPage]) -> Vec<String> {
+        pages
+            .iter()
+            .map(|page| {
+                let bytes = pdf::write(&Default::default(), PAGE_SIZE, std::slice::from_ref(page));
+                pdf_extract::extract_text_from_mem(&bytes)
+                    .unwrap()
+                    .replace(char::is_whitespace, "")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pages_hold_thumbnails_and_formatted_notes() {
+        let mut exporter = exporter(
+            "# One\n\n???\nOpen with **the story**\n\n---\n\n# Two\n\n---\n\n# Three\n\n???\n- a point",
+        );
+        let text = page_text(&pages(&mut exporter, 2, true));
+        assert_eq!(text.len(), 2);
+        assert_eq!(text[0], "OneOpenwiththestoryTwo1/2");
+        assert_eq!(text[1], "Three•apoint2/2");
+    }
+
+    #[test]
+    fn slots_break_between_blocks() {
+        let slots = plan_slots(&[vec![40.0, 40.0, 40.0], vec![], vec![300.0]], 100.0, 10.0);
+        let plan: Vec<_> = slots
+            .iter()
+            .map(|s| (s.slide, s.blocks.clone(), s.continued))
+            .collect();
+        assert_eq!(
+            plan,
+            [
+                (0, 0..2, false),
+                (0, 2..3, true),
+                (1, 0..0, false),
+                (2, 0..1, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn long_notes_continue_in_the_next_slot() {
+        let paragraphs: Vec<String> = (1..=40).map(|i| format!("Point{i} of the talk.")).collect();
+        let markdown = format!("# One\n\n???\n{}\n\n---\n\n# Two", paragraphs.join("\n\n"));
+        let mut exporter = exporter(&markdown);
+        let text = page_text(&pages(&mut exporter, 3, false)).concat();
+        for i in 1..=40 {
+            assert!(text.contains(&format!("Point{i}ofthetalk.")), "{i}: {text}");
+        }
+        let continued = text.find("Slide1(continued)").expect("continuation slot");
+        assert!(continued < text.find("Two").unwrap(), "{text}");
+        assert!(!text.contains('…'), "{text}");
+    }
+
+    #[test]
+    fn notes_block_taller_than_a_slot_is_marked_cut_off() {
+        let items: Vec<String> = (1..=60).map(|i| format!("- item {i}")).collect();
+        let mut exporter = exporter(&format!("# One\n\n???\n{}", items.join("\n")));
+        let text = page_text(&pages(&mut exporter, 3, false)).concat();
+        assert!(text.contains('…'), "{text}");
+        assert!(!text.contains("item60"), "{text}");
+    }
+}
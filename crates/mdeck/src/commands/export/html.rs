@@ -0,0 +1,675 @@
+//! Standalone HTML output: the whole deck as one page with images, fonts and
+//! charts inlined, arrow-key navigation, incremental reveal and speaker notes
+//! behind the `N` key.
+//!
+//! Slides keep the reference canvas size in CSS pixels (1920×1080 for 16:9)
+//! and are scaled to the browser window, so theme sizes carry over unchanged.
+//! Diagrams and visualizations are drawn by the regular renderers and
+//! converted to SVG, one SVG per reveal step.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use base64::Engine;
+use eframe::egui::{self, Color32, FontDefinitions, FontFamily};
+
+use crate::fonts;
+use crate::parser::{self, Block, Inline, Layout, ListItem, ListMarker, Presentation, Slide};
+use crate::render::footer::{self, Band, FooterContext};
+use crate::render::layouts::visualization;
+use crate::render::svg::{BODY_FONT, HEADING_FONT, MONO_FONT};
+use crate::theme::{self, Theme};
+
+use super::{Exporter, visual};
+
+/// Write the deck as a single HTML document.
+pub fn write(exporter: &mut Exporter, font_definitions: &FontDefinitions) -> String {
+    let canvas = exporter.aspect.reference_size();
+    let meta = &exporter.presentation.meta;
+    let title = meta.title.clone().unwrap_or_else(|| "mdeck".to_string());
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    let _ = writeln!(html, "<title>{}</title>", escape(&title));
+    if let Some(author) = &meta.author {
+        let _ = writeln!(
+            html,
+            "<meta name=\"author\" content=\"{}\">",
+            escape(author)
+        );
+    }
+    html.push_str("<meta name=\"generator\" content=\"mdeck\">\n<style>\n");
+    html.push_str(&font_faces(font_definitions));
+    let _ = writeln!(
+        html,
+        "#deck {{ width: {}px; height: {}px; }}",
+        canvas.x, canvas.y
+    );
+    html.push_str(STYLE);
+    html.push_str("</style>\n</head>\n<body>\n<div id=\"deck\">\n");
+
+    for idx in 0..exporter.slide_count() {
+        let section = slide_html(exporter, idx, canvas);
+        html.push_str(&section);
+    }
+
+    html.push_str("</div>\n<div id=\"notes\" hidden></div>\n<script>\n");
+    html.push_str(SCRIPT);
+    html.push_str("</script>\n</body>\n</html>\n");
+    html
+}
+
+fn slide_html(exporter: &mut Exporter, idx: usize, canvas: egui::Vec2) -> String {
+    let slide = &exporter.presentation.slides[idx];
+    let theme = exporter
+        .theme
+        .for_slide(slide, &exporter.custom_themes)
+        .into_owned();
+    let steps = exporter.max_steps.get(idx).copied().unwrap_or(0);
+
+    let mut style = theme_vars(&theme);
+    if let Some(path) = slide
+        .directive("background")
+        .filter(|v| theme::parse_hex_color(v).is_none())
+        && let Some(uri) = image_uri(&exporter.base_path, path)
+    {
+        let _ = write!(style, " background-image: url('{uri}');");
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<section class=\"slide layout-{}\" data-steps=\"{steps}\" style=\"{style}\">",
+        layout_class(slide.layout)
+    );
+
+    let ctx = FooterContext {
+        meta: &exporter.presentation.meta,
+        number: idx + 1,
+        total: exporter.slide_count(),
+    };
+    for (band, class) in [(Band::Header, "header"), (Band::Footer, "footer")] {
+        if let Some(text) = footer::band_text(slide, band, &ctx) {
+            let _ = writeln!(out, "<div class=\"band {class}\">{}</div>", escape(&text));
+        }
+    }
+
+    let slide = slide.clone();
+    let visual_size = visual::visual_size(&slide, &theme, canvas);
+    let mut writer = BlockWriter {
+        exporter,
+        theme: &theme,
+        visual_size,
+        out: String::new(),
+    };
+    writer.content(&slide);
+    out.push_str(&writer.out);
+
+    if let Some(notes) = &slide.notes {
+        let blocks = parser::blocks::parse(notes);
+        let mut notes_writer = BlockWriter {
+            exporter: writer.exporter,
+            theme: &theme,
+            visual_size,
+            out: String::new(),
+        };
+        for block in &blocks {
+            notes_writer.block(block);
+        }
+        let _ = writeln!(out, "<aside class=\"notes\">{}</aside>", notes_writer.out);
+    }
+    out.push_str("</section>\n");
+    out
+}
+
+struct BlockWriter<'a> {
+    exporter: &'a mut Exporter,
+    theme: &'a Theme,
+    visual_size: egui::Vec2,
+    out: String,
+}
+
+impl BlockWriter<'_> {
+    fn content(&mut self, slide: &Slide) {
+        if slide.layout != Layout::TwoColumn {
+            self.out.push_str("<div class=\"content\">\n");
+            for block in &slide.blocks {
+                self.block(block);
+            }
+            self.out.push_str("</div>\n");
+            return;
+        }
+
+        // Leading headings span both columns, as in the two-column layout
+        let mut blocks = slide.blocks.iter().peekable();
+        self.out.push_str("<div class=\"content\">\n");
+        while let Some(block) = blocks.next_if(|b| {
+            matches!(
+                b,
+                Block::Heading { level: 1, .. } | Block::Heading { level: 2, .. }
+            )
+        }) {
+            self.block(block);
+        }
+        self.out
+            .push_str("<div class=\"columns\">\n<div class=\"column\">\n");
+        for block in blocks {
+            if matches!(block, Block::ColumnSeparator) {
+                self.out.push_str("</div>\n<div class=\"column\">\n");
+            } else {
+                self.block(block);
+            }
+        }
+        self.out.push_str("</div>\n</div>\n</div>\n");
+    }
+
+    fn block(&mut self, block: &Block) {
+        let presentation = &self.exporter.presentation;
+        match block {
+            Block::Heading { level, inlines } => {
+                let level = level.clamp(&1, &6);
+                let _ = writeln!(
+                    self.out,
+                    "<h{level}>{}</h{level}>",
+                    inlines_html(inlines, presentation)
+                );
+            }
+            Block::Paragraph { inlines } => {
+                let _ = writeln!(self.out, "<p>{}</p>", inlines_html(inlines, presentation));
+            }
+            Block::List { ordered, items } => {
+                let mut step = 0;
+                list_html(&mut self.out, items, *ordered, &mut step, presentation);
+            }
+            Block::Image {
+                alt,
+                path,
+                directives,
+            } => {
+                let mut style = String::new();
+                if let Some(w) = &directives.width {
+                    let _ = write!(style, "width: {};", css_length(w));
+                }
+                if let Some(h) = &directives.height {
+                    let _ = write!(style, "height: {};", css_length(h));
+                }
+                match image_uri(&self.exporter.base_path, path) {
+                    Some(uri) => {
+                        let _ = writeln!(
+                            self.out,
+                            "<img src=\"{}\" alt=\"{}\" style=\"{style}\">",
+                            escape(&uri),
+                            escape(alt)
+                        );
+                    }
+                    None => {
+                        let _ = writeln!(
+                            self.out,
+                            "<div class=\"missing-image\">{}</div>",
+                            escape(alt)
+                        );
+                    }
+                }
+            }
+            Block::CodeBlock {
+                language,
+                code,
+                highlight_lines,
+            } => self.code(code, language.as_deref(), highlight_lines),
+            Block::BlockQuote { inlines } => {
+                let _ = writeln!(
+                    self.out,
+                    "<blockquote>{}</blockquote>",
+                    inlines_html(inlines, presentation)
+                );
+            }
+            Block::Table { headers, rows } => {
+                self.out.push_str("<table>\n<thead><tr>");
+                for header in headers {
+                    let _ = write!(self.out, "<th>{}</th>", inlines_html(header, presentation));
+                }
+                self.out.push_str("</tr></thead>\n<tbody>\n");
+                for row in rows {
+                    self.out.push_str("<tr>");
+                    for cell in row {
+                        let _ = write!(self.out, "<td>{}</td>", inlines_html(cell, presentation));
+                    }
+                    self.out.push_str("</tr>\n");
+                }
+                self.out.push_str("</tbody>\n</table>\n");
+            }
+            Block::HorizontalRule => self.out.push_str("<hr>\n"),
+            Block::ColumnSeparator => {}
+            Block::Diagram { .. } => self.visual(block),
+            b if visualization::is_viz_block(b) => self.visual(block),
+            _ => {}
+        }
+    }
+
+    /// Syntax-highlighted code, colored the way the slide renderer colors it.
+    fn code(&mut self, code: &str, language: Option<&str>, highlight_lines: &[usize]) {
+        let lines: Vec<String> = super::highlighted_lines(code, language, self.theme)
+            .into_iter()
+            .map(|runs| {
+                let mut line = String::new();
+                for (text, color) in runs {
+                    let _ = write!(
+                        line,
+                        "<span style=\"color: {}\">{}</span>",
+                        css_color(color),
+                        escape(&text)
+                    );
+                }
+                line
+            })
+            .collect();
+
+        self.out.push_str("<pre class=\"code\"><code>");
+        for (i, line) in lines.iter().enumerate() {
+            let class = if highlight_lines.contains(&(i + 1)) {
+                "line highlight"
+            } else {
+                "line"
+            };
+            let _ = write!(self.out, "<span class=\"{class}\">{line}\n</span>");
+        }
+        self.out.push_str("</code></pre>\n");
+    }
+
+    /// A diagram or visualization as SVG, one per reveal step.
+    fn visual(&mut self, block: &Block) {
+        let steps = parser::compute_max_steps(std::slice::from_ref(block));
+        self.out.push_str("<div class=\"visual\">\n");
+        for step in 0..=steps {
+            let svg = visual::block_svg(self.exporter, block, self.theme, self.visual_size, step);
+            // Each step's drawing shows only while that step is current; the
+            // last one stays for the rest of the slide
+            let until = if step < steps {
+                format!(" data-until=\"{step}\"")
+            } else {
+                String::new()
+            };
+            let _ = writeln!(
+                self.out,
+                "<div class=\"visual-step\" data-step=\"{step}\"{until}>{svg}</div>"
+            );
+        }
+        self.out.push_str("</div>\n");
+    }
+}
+
+/// List items carry the reveal step they appear at, counted like the slide
+/// renderer does: each `+` item starts a new step, `*` items join it.
+fn list_html(
+    out: &mut String,
+    items: &[ListItem],
+    ordered: bool,
+    step: &mut usize,
+    presentation: &Presentation,
+) {
+    out.push_str(if ordered { "<ol>\n" } else { "<ul>\n" });
+    for item in items {
+        let item_step = match item.marker {
+            ListMarker::Static | ListMarker::Ordered => 0,
+            ListMarker::NextStep => {
+                *step += 1;
+                *step
+            }
+            ListMarker::WithPrev => *step,
+        };
+        if item_step > 0 {
+            let _ = write!(out, "<li data-step=\"{item_step}\">");
+        } else {
+            out.push_str("<li>");
+        }
+        out.push_str(&inlines_html(&item.inlines, presentation));
+        if !item.children.is_empty() {
+            let children_ordered = item
+                .children
+                .first()
+                .is_some_and(|c| c.marker == ListMarker::Ordered);
+            out.push('\n');
+            list_html(out, &item.children, children_ordered, step, presentation);
+        }
+        out.push_str("</li>\n");
+    }
+    out.push_str(if ordered { "</ol>\n" } else { "</ul>\n" });
+}
+
+/// Inline markup; links to slides of the deck become `#N` page links, other
+/// links open in a new tab.
+fn inlines_html(inlines: &[Inline], presentation: &Presentation) -> String {
+    let mut out = String::new();
+    for inline in inlines {
+        match inline {
+            Inline::Text(s) => out.push_str(&escape(s)),
+            Inline::Bold(children) => {
+                let _ = write!(
+                    out,
+                    "<strong>{}</strong>",
+                    inlines_html(children, presentation)
+                );
+            }
+            Inline::Italic(children) => {
+                let _ = write!(out, "<em>{}</em>", inlines_html(children, presentation));
+            }
+            Inline::Strikethrough(children) => {
+                let _ = write!(out, "<s>{}</s>", inlines_html(children, presentation));
+            }
+            Inline::Code(s) => {
+                let _ = write!(out, "<code>{}</code>", escape(s));
+            }
+            Inline::Link { text, url } => {
+                let text = inlines_html(text, presentation);
+                if let Some(idx) = presentation.link_slide(url) {
+                    let _ = write!(out, "<a href=\"#{}\">{text}</a>", idx + 1);
+                } else if url.starts_with('#') {
+                    let _ = write!(out, "<a href=\"{}\">{text}</a>", escape(url));
+                } else {
+                    let _ = write!(
+                        out,
+                        "<a href=\"{}\" target=\"_blank\" rel=\"noopener\">{text}</a>",
+                        escape(url)
+                    );
+                }
+            }
+        }
+    }
+    out
+}
+
+fn layout_class(layout: Layout) -> &'static str {
+    match layout {
+        Layout::Title => "title",
+        Layout::Section => "section",
+        Layout::Image => "image",
+        Layout::Gallery => "gallery",
+        Layout::Quote => "quote",
+        Layout::Code => "code",
+        Layout::Bullet => "bullet",
+        Layout::Diagram => "diagram",
+        Layout::Visualization => "visualization",
+        Layout::TwoColumn => "two-column",
+        Layout::Content => "content",
+    }
+}
+
+/// CSS custom properties for the slide's theme colors and sizes.
+fn theme_vars(theme: &Theme) -> String {
+    format!(
+        "--bg: {}; --fg: {}; --heading: {}; --accent: {}; --code-bg: {}; --code-fg: {}; \
+         --h1: {}px; --h2: {}px; --h3: {}px; --body: {}px; --code: {}px;",
+        css_color(theme.background),
+        css_color(theme.foreground),
+        css_color(theme.heading_color),
+        css_color(theme.accent),
+        css_color(theme.code_background),
+        css_color(theme.code_foreground),
+        theme.h1_size,
+        theme.h2_size,
+        theme.h3_size,
+        theme.body_size,
+        theme.code_size,
+    )
+}
+
+fn css_color(color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    if a == 255 {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    } else {
+        format!("rgba({r}, {g}, {b}, {:.3})", a as f32 / 255.0)
+    }
+}
+
+/// Image directive sizes are percentages or bare pixel counts.
+fn css_length(value: &str) -> String {
+    let value = value.trim();
+    if value.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        format!("{value}px")
+    } else {
+        escape(value)
+    }
+}
+
+/// A data URI for a local image, or the URL itself for remote ones.
+fn image_uri(base_path: &Path, path: &str) -> Option<String> {
+    if path.starts_with("http://") || path.starts_with("https://") || path.starts_with("data:") {
+        return Some(path.to_string());
+    }
+    let full = base_path.join(path);
+    let bytes = std::fs::read(&full).ok()?;
+    let ext = full
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let mime = match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        _ => return None,
+    };
+    Some(format!(
+        "data:{mime};base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// `@font-face` rules for the first font of the body, heading and code
+/// families, under the names SVG text refers to.
+fn font_faces(defs: &FontDefinitions) -> String {
+    let mut css = String::new();
+    for (family, name) in [
+        (FontFamily::Proportional, BODY_FONT),
+        (fonts::heading_family(), HEADING_FONT),
+        (FontFamily::Monospace, MONO_FONT),
+    ] {
+        let Some(data) = defs
+            .families
+            .get(&family)
+            .and_then(|names| names.first())
+            .and_then(|key| defs.font_data.get(key))
+        else {
+            continue;
+        };
+        let _ = writeln!(
+            css,
+            "@font-face {{ font-family: '{name}'; src: url(data:font/ttf;base64,{}); }}",
+            base64::engine::general_purpose::STANDARD.encode(&data.font)
+        );
+    }
+    css
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+const STYLE: &str = r#"html, body { margin: 0; height: 100%; background: #000; overflow: hidden; }
+#deck { position: absolute; left: 50%; top: 50%; transform-origin: 0 0; }
+.slide { position: absolute; inset: 0; display: none; flex-direction: column; justify-content: center;
+  box-sizing: border-box; padding: 80px 15%; overflow: hidden; background: var(--bg) center / cover no-repeat;
+  color: var(--fg); font-family: 'mdeck-body', sans-serif; font-size: var(--body); line-height: 1.3; }
+.slide.current { display: flex; }
+.slide h1, .slide h2, .slide h3, .slide h4, .slide h5, .slide h6 { color: var(--heading); font-family: 'mdeck-heading', 'mdeck-body', sans-serif; font-weight: normal; margin: 0 0 30px; line-height: 1.15; }
+.slide h1 { font-size: var(--h1); } .slide h2 { font-size: var(--h2); } .slide h3, .slide h4, .slide h5, .slide h6 { font-size: var(--h3); }
+.slide p, .slide ul, .slide ol, .slide table, .slide blockquote, .slide pre { margin: 0 0 16px; }
+.slide ul, .slide ol { padding-left: 45px; } .slide li { margin-bottom: 8px; }
+.slide ul ul, .slide ol ol, .slide ul ol, .slide ol ul { margin: 8px 0 0; }
+.slide a { color: #5294e2; }
+.slide code { font-family: 'mdeck-mono', monospace; font-size: 0.85em; background: rgba(128, 128, 128, 0.12); }
+.slide pre.code { background: var(--code-bg); color: var(--code-fg); border-radius: 8px; padding: 16px;
+  font-size: var(--code); white-space: pre-wrap; }
+.slide pre.code code { background: none; font-size: 1em; }
+.slide pre .line.highlight { display: inline-block; width: 100%; background: color-mix(in srgb, var(--accent) 15%, transparent); border-radius: 4px; }
+.slide blockquote { border-left: 4px solid var(--accent); padding-left: 16px; margin-left: 0; font-size: 1.1em; }
+.slide table { border-collapse: collapse; font-size: 0.85em; width: 100%; }
+.slide th { color: var(--heading); text-align: left; font-weight: normal; border-bottom: 1px solid var(--accent); padding: 12px; }
+.slide td { padding: 12px; }
+.slide hr { border: 0; border-top: 1px solid color-mix(in srgb, var(--accent) 50%, transparent); width: 100%; }
+.slide img { max-width: 100%; max-height: 70vh; object-fit: contain; }
+.missing-image { border: 2px dashed var(--fg); padding: 40px; opacity: 0.5; text-align: center; }
+.layout-title, .layout-section { text-align: center; padding: 80px; }
+.layout-title h1 { font-size: calc(var(--h1) * 1.1); }
+.layout-title h2, .layout-title p { font-size: calc(var(--h2) * 0.7); color: var(--fg); opacity: 0.8; }
+.layout-quote blockquote { font-size: 1.4em; }
+.layout-image .content, .layout-gallery .content { text-align: center; }
+.layout-two-column { padding: 80px 10%; }
+.columns { display: flex; gap: 40px; } .column { flex: 1; min-width: 0; }
+.layout-diagram, .layout-visualization { justify-content: flex-start; padding: 60px; }
+.layout-diagram .content, .layout-visualization .content { flex: 1; display: flex; flex-direction: column; min-height: 0; }
+.visual { position: relative; flex: 1; min-height: 0; }
+.visual-step { position: absolute; inset: 0; }
+.visual-step svg { width: 100%; height: 100%; }
+.band { position: absolute; left: 0; right: 0; text-align: center; font-size: 14px; opacity: 0.4; }
+.band.header { top: 14px; } .band.footer { bottom: 14px; }
+.notes { display: none; }
+[data-step].hidden { visibility: hidden; }
+#notes { position: fixed; left: 0; right: 0; bottom: 0; max-height: 40%; overflow: auto; box-sizing: border-box;
+  padding: 16px 24px; background: rgba(20, 20, 20, 0.92); color: #ddd; font: 16px/1.4 sans-serif; }
+#notes pre.code { font-size: 14px; }
+"#;
+
+const SCRIPT: &str = r#"const deck = document.getElementById('deck');
+const slides = [...deck.querySelectorAll('.slide')];
+const notes = document.getElementById('notes');
+let current = Math.min(Math.max(parseInt(location.hash.slice(1), 10) - 1 || 0, 0), slides.length - 1);
+let step = 0;
+
+function fit() {
+  const scale = Math.min(innerWidth / deck.offsetWidth, innerHeight / deck.offsetHeight);
+  deck.style.transform = `scale(${scale}) translate(-50%, -50%)`;
+}
+
+function show() {
+  slides.forEach((slide, i) => slide.classList.toggle('current', i === current));
+  const slide = slides[current];
+  slide.querySelectorAll('[data-step]').forEach(el => {
+    const from = +el.dataset.step;
+    const until = el.dataset.until === undefined ? Infinity : +el.dataset.until;
+    el.classList.toggle('hidden', step < from || step > until);
+  });
+  const slideNotes = slide.querySelector('.notes');
+  notes.innerHTML = slideNotes ? slideNotes.innerHTML : '<em>No notes for this slide.</em>';
+  history.replaceState(null, '', '#' + (current + 1));
+}
+
+function next() {
+  if (step < +slides[current].dataset.steps) step++;
+  else if (current < slides.length - 1) { current++; step = 0; }
+  show();
+}
+
+function prev() {
+  if (step > 0) step--;
+  else if (current > 0) { current--; step = +slides[current].dataset.steps; }
+  show();
+}
+
+document.addEventListener('keydown', e => {
+  if (e.ctrlKey || e.metaKey || e.altKey) return;
+  switch (e.key) {
+    case 'ArrowRight': case 'ArrowDown': case 'PageDown': case ' ': case 'Enter': next(); break;
+    case 'ArrowLeft': case 'ArrowUp': case 'PageUp': case 'Backspace': prev(); break;
+    case 'Home': current = 0; step = 0; show(); break;
+    case 'End': current = slides.length - 1; step = +slides[current].dataset.steps; show(); break;
+    case 'n': case 'N': notes.hidden = !notes.hidden; break;
+    default: return;
+  }
+  e.preventDefault();
+});
+deck.addEventListener('click', e => { if (!e.target.closest('a')) next(); });
+// Slide links and the browser's Back button change the hash
+addEventListener('hashchange', () => {
+  const target = parseInt(location.hash.slice(1), 10) - 1;
+  if (target >= 0 && target < slides.length && target !== current) { current = target; step = 0; show(); }
+});
+addEventListener('resize', fit);
+fit();
+show();
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn export(markdown: &str, dir: &Path) -> String {
+        let presentation = parser::parse(markdown, dir);
+        let (custom_themes, _) = theme::load_deck_themes(&presentation, dir);
+        let (fonts, _) = fonts::load_deck_fonts(&presentation.meta, &custom_themes, dir);
+        let mut exporter = Exporter::new(
+            presentation,
+            custom_themes,
+            fonts.clone(),
+            dir,
+            (1920, 1080),
+        );
+        write(&mut exporter, &fonts)
+    }
+
+    #[test]
+    fn writes_slides_reveal_steps_and_notes() {
+        let dir = TempDir::new("html-export");
+        dir.write("pixel.png", include_bytes!("../../../media/logo-small.png"));
+        let html = export(
+            "---\ntitle: Q&A <Night>\n---\n\n## Agenda\n\n- Always\n+ First *step*\n* Same step\n+ Second\n\n![Logo](pixel.png)\n\n???\nSay **hello**",
+            &dir,
+        );
+        assert!(html.contains("<title>Q&amp;A &lt;Night&gt;</title>"));
+        assert!(html.contains("data-steps=\"2\""));
+        assert!(html.contains("<li>Always</li>"));
+        assert!(html.contains("<li data-step=\"1\">First <em>step</em></li>"));
+        assert!(html.contains("<li data-step=\"1\">Same step</li>"));
+        assert!(html.contains("<li data-step=\"2\">Second</li>"));
+        assert!(html.contains("src=\"data:image/png;base64,"));
+        assert!(html.contains("<aside class=\"notes\"><p>Say <strong>hello</strong></p>"));
+        assert!(html.contains("@font-face { font-family: 'mdeck-body'"));
+    }
+
+    #[test]
+    fn slide_links_become_page_anchors() {
+        let dir = TempDir::new("html-export");
+        let html = export(
+            "# Intro\n\nSee [the results](#results), [slide two](#slide-2) or \
+             [the docs](https://example.com/?a=1&b=2).\n\n---\n\n# Results",
+            &dir,
+        );
+        assert!(html.contains("<a href=\"#2\">the results</a>"));
+        assert!(html.contains("<a href=\"#2\">slide two</a>"));
+        assert!(html.contains(
+            "<a href=\"https://example.com/?a=1&amp;b=2\" target=\"_blank\" rel=\"noopener\">the docs</a>"
+        ));
+        assert!(html.contains("addEventListener('hashchange'"));
+    }
+
+    #[test]
+    fn charts_become_svg_per_step_and_code_is_highlighted() {
+        let dir = TempDir::new("html-export");
+        let html = export(
+            "## Sales\n\n```@barchart\n- Q1: 10\n+ Q2: 20\n```\n\n---\n\n```rust\nfn main() {}\n```\n",
+            &dir,
+        );
+        assert!(html.contains("<div class=\"visual-step\" data-step=\"0\" data-until=\"0\"><svg"));
+        assert!(html.contains("<div class=\"visual-step\" data-step=\"1\"><svg"));
+        assert!(html.contains(">Q2</text>"));
+        assert!(
+            html.contains("<pre class=\"code\"><code><span class=\"line\"><span style=\"color: #")
+        );
+    }
+}
@@ -0,0 +1,527 @@
+mod handout;
+mod html;
+mod pdf;
+mod pptx;
+mod visual;
+
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+
+use crate::annotations::{self, Annotations};
+use crate::cli::{ExportArgs, ExportFormat};
+use crate::config::Config;
+use crate::parser::{self, Presentation};
+use crate::render;
+use crate::render::aspect::AspectRatio;
+use crate::render::footer::FooterContext;
+use crate::render::headless::{RenderedFrame, Renderer};
+use crate::render::image_cache::ImageCache;
+use crate::theme::{CustomThemes, Theme};
+
+use pdf::PdfPage;
+
+/// Renders a deck's slides without a window.
+struct Exporter {
+    presentation: Presentation,
+    theme: Theme,
+    custom_themes: CustomThemes,
+    aspect: AspectRatio,
+    image_cache: ImageCache,
+    base_path: PathBuf,
+    max_steps: Vec<usize>,
+    /// Saved annotations drawn over the slides, with `--annotations`.
+    annotations: Annotations,
+    renderer: Renderer,
+    size: [usize; 2],
+}
+
+impl Exporter {
+    fn new(
+        presentation: Presentation,
+        custom_themes: CustomThemes,
+        font_definitions: egui::FontDefinitions,
+        base_path: &Path,
+        (width, height): (u32, u32),
+    ) -> Self {
+        let theme = Theme::for_deck(&presentation.meta, &custom_themes);
+        let aspect = AspectRatio::for_deck(&presentation.meta);
+        let image_cache = ImageCache::new(base_path.to_path_buf());
+        let max_steps: Vec<usize> = presentation
+            .slides
+            .iter()
+            .map(|s| parser::compute_max_steps(&s.blocks))
+            .collect();
+        let ctx = egui::Context::default();
+        ctx.set_fonts(font_definitions);
+
+        Self {
+            presentation,
+            theme,
+            custom_themes,
+            aspect,
+            image_cache,
+            base_path: base_path.to_path_buf(),
+            max_steps,
+            annotations: Annotations::default(),
+            renderer: Renderer::new(ctx),
+            size: [width as usize, height as usize],
+        }
+    }
+
+    fn slide_count(&self) -> usize {
+        self.presentation.slides.len()
+    }
+
+    /// The reveal steps to export for slide `idx`: every step from 0 with
+    /// `all`, otherwise only the fully revealed one.
+    fn reveal_steps(&self, idx: usize, all: bool) -> RangeInclusive<usize> {
+        let max = self.max_steps.get(idx).copied().unwrap_or(0);
+        if all { 0..=max } else { max..=max }
+    }
+
+    /// Rasterize slide `idx` at the export size, revealed up to `reveal`.
+    fn render(&mut self, idx: usize, reveal: usize) -> RenderedFrame {
+        let size = self.size;
+        let (renderer, paint) = self.slide_painter(idx, reveal);
+        renderer.render(size, paint)
+    }
+
+    /// Slide `idx` at the export size, revealed up to `reveal`, as an SVG
+    /// document.
+    fn render_svg(&mut self, idx: usize, reveal: usize) -> String {
+        let size = egui::vec2(self.size[0] as f32, self.size[1] as f32);
+        let (renderer, paint) = self.slide_painter(idx, reveal);
+        renderer.render_svg(size, paint)
+    }
+
+    /// The renderer, and a paint function drawing slide `idx` revealed up to
+    /// `reveal` and letterboxed into the frame.
+    fn slide_painter(
+        &mut self,
+        idx: usize,
+        reveal: usize,
+    ) -> (&mut Renderer, impl FnMut(&mut egui::Ui) + '_) {
+        let (renderer, deck) = self.split();
+        let paint = move |ui: &mut egui::Ui| {
+            let window = ui.max_rect();
+            let rect = deck.aspect.fit(window);
+            if rect != window {
+                ui.painter().rect_filled(window, 0.0, egui::Color32::BLACK);
+            }
+            deck.paint_slide(ui, rect, idx, reveal);
+        };
+        (renderer, paint)
+    }
+
+    /// The renderer, and the deck to paint with it.
+    fn split(&mut self) -> (&mut Renderer, DeckView<'_>) {
+        let deck = DeckView {
+            presentation: &self.presentation,
+            theme: &self.theme,
+            custom_themes: &self.custom_themes,
+            aspect: &self.aspect,
+            image_cache: &self.image_cache,
+            annotations: &self.annotations,
+        };
+        (&mut self.renderer, deck)
+    }
+}
+
+/// The parts of an [`Exporter`] that painting a slide reads.
+struct DeckView<'a> {
+    presentation: &'a Presentation,
+    theme: &'a Theme,
+    custom_themes: &'a CustomThemes,
+    aspect: &'a AspectRatio,
+    image_cache: &'a ImageCache,
+    annotations: &'a Annotations,
+}
+
+impl DeckView<'_> {
+    /// Paint slide `idx` revealed up to `reveal` into `rect`, which has the
+    /// deck's aspect ratio, with its saved annotations on top.
+    fn paint_slide(&self, ui: &egui::Ui, rect: egui::Rect, idx: usize, reveal: usize) {
+        ui.painter().rect_filled(rect, 0.0, self.theme.background);
+        let Some(slide) = self.presentation.slides.get(idx) else {
+            return;
+        };
+        let scale = self.aspect.scale(rect);
+        render::render_slide(
+            ui,
+            slide,
+            self.theme,
+            self.custom_themes,
+            rect,
+            1.0,
+            self.image_cache,
+            reveal,
+            None, // no animation in export
+            scale,
+            &FooterContext {
+                meta: &self.presentation.meta,
+                number: idx + 1,
+                total: self.presentation.slides.len(),
+            },
+        );
+        self.annotations
+            .paint(ui.painter(), idx, rect, self.theme, scale);
+    }
+}
+
+/// Syntax-highlighted `code` as lines of colored runs, for formats that lay
+/// out code themselves.
+fn highlighted_lines(
+    code: &str,
+    language: Option<&str>,
+    theme: &Theme,
+) -> Vec<Vec<(String, egui::Color32)>> {
+    let job =
+        render::syntax::highlight_code(code, language, theme.code_size, 1.0, theme, f32::INFINITY);
+    let mut lines = vec![Vec::new()];
+    for section in &job.sections {
+        let text = &job.text[section.byte_range.clone()];
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(Vec::new());
+            }
+            if !part.is_empty() {
+                lines
+                    .last_mut()
+                    .unwrap()
+                    .push((part.to_string(), section.format.color));
+            }
+        }
+    }
+    if lines.len() > 1 && lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// `slide-03.png`, or `slide-03-step-0.png` for one reveal step.
+fn frame_name(prefix: &str, number: usize, step: Option<usize>, ext: &str) -> String {
+    match step {
+        Some(step) => format!("{prefix}-{number:02}-step-{step}.{ext}"),
+        None => format!("{prefix}-{number:02}.{ext}"),
+    }
+}
+
+fn save_color_image(image: &egui::ColorImage, path: &Path) {
+    let width = image.width() as u32;
+    let height = image.height() as u32;
+    let pixels: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|c| [c.r(), c.g(), c.b(), c.a()])
+        .collect();
+
+    image::save_buffer(path, &pixels, width, height, image::ColorType::Rgba8)
+        .unwrap_or_else(|e| eprintln!("Failed to save {}: {e}", path.display()));
+}
+
+/// Export every slide as PNG or SVG files, a single PDF, a handout PDF, a
+/// standalone HTML page or a PowerPoint file; with `block`, only that diagram or chart as SVG. With
+/// `steps`, every reveal step becomes its own image or page instead of only
+/// the fully revealed slide. Missing `width`/`height` follow the deck's
+/// `@aspect`.
+pub fn run(args: ExportArgs) -> anyhow::Result<()> {
+    let ExportArgs {
+        file,
+        output_dir,
+        format,
+        block,
+        steps,
+        width,
+        height,
+        per_page,
+        lines,
+        annotations,
+    } = args;
+
+    let content = std::fs::read_to_string(&file)?;
+    let base_path = file
+        .parent()
+        .unwrap_or(std::path::Path::new("."))
+        .to_path_buf();
+    let mut presentation = parser::parse(&content, &base_path);
+    Config::load_or_default().apply_deck_defaults(&mut presentation.meta);
+    let (custom_themes, mut warnings) = crate::theme::load_deck_themes(&presentation, &base_path);
+    let (font_definitions, font_warnings) =
+        crate::fonts::load_deck_fonts(&presentation.meta, &custom_themes, &base_path);
+    warnings.extend(font_warnings);
+    for w in warnings {
+        eprintln!("Warning: {}", w.message);
+    }
+
+    if presentation.slides.is_empty() {
+        anyhow::bail!("No slides found in {}", file.display());
+    }
+
+    std::fs::create_dir_all(&output_dir)?;
+    let aspect = AspectRatio::for_deck(&presentation.meta);
+    let (width, height) = aspect.export_size(width, height);
+    let stem = file
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let pdf_path = output_dir.join(format!("{stem}.pdf"));
+    let html_path = output_dir.join(format!("{stem}.html"));
+    let handout_path = output_dir.join(format!("{stem}-handout.pdf"));
+    let pptx_path = output_dir.join(format!("{stem}.pptx"));
+    let format = match (format, block) {
+        (Some(format), None) => format,
+        (None, None) => ExportFormat::Png,
+        (None | Some(ExportFormat::Svg), Some(_)) => ExportFormat::Svg,
+        (Some(_), Some(_)) => anyhow::bail!("--block exports SVG only; use --format svg"),
+    };
+
+    let meta = presentation.meta.clone();
+    let mut exporter = Exporter::new(
+        presentation,
+        custom_themes,
+        font_definitions.clone(),
+        &base_path,
+        (width, height),
+    );
+    if annotations {
+        let path = annotations::sidecar_path(&file);
+        if !path.exists() {
+            anyhow::bail!("No saved annotations: {} does not exist", path.display());
+        }
+        exporter.annotations = Annotations::load(&path)?;
+        if matches!(format, ExportFormat::Html | ExportFormat::Pptx) || block.is_some() {
+            eprintln!("Warning: annotations are only drawn in PNG, SVG, PDF and handout exports");
+        }
+    }
+
+    if let Some(n) = block {
+        let canvas = aspect.reference_size();
+        let blocks = visual::visual_blocks(&exporter.presentation);
+        let Some(&(idx, block)) = n.checked_sub(1).and_then(|i| blocks.get(i)) else {
+            anyhow::bail!(
+                "No block {n} in {}: it has {} diagrams and charts",
+                file.display(),
+                blocks.len()
+            );
+        };
+        let slide = &exporter.presentation.slides[idx];
+        let theme = exporter
+            .theme
+            .for_slide(slide, &exporter.custom_themes)
+            .into_owned();
+        let size = visual::visual_size(slide, &theme, canvas);
+        let block = block.clone();
+        eprintln!(
+            "Exporting block {n} from slide {} to {}",
+            idx + 1,
+            output_dir.display()
+        );
+        let max = parser::compute_max_steps(std::slice::from_ref(&block));
+        for reveal in if steps { 0..=max } else { max..=max } {
+            let filename = frame_name("block", n, steps.then_some(reveal), "svg");
+            let svg = visual::block_svg(&mut exporter, &block, &theme, size, reveal);
+            std::fs::write(output_dir.join(&filename), svg)?;
+            eprintln!("  Saved {filename}");
+        }
+        eprintln!("Export complete.");
+        return Ok(());
+    }
+
+    eprintln!(
+        "Exporting {} slides to {} ({}x{})",
+        exporter.slide_count(),
+        match format {
+            ExportFormat::Png | ExportFormat::Svg => output_dir.display(),
+            ExportFormat::Pdf => pdf_path.display(),
+            ExportFormat::Handout => handout_path.display(),
+            ExportFormat::Html => html_path.display(),
+            ExportFormat::Pptx => pptx_path.display(),
+        },
+        width,
+        height,
+    );
+
+    match format {
+        ExportFormat::Png => {
+            for idx in 0..exporter.slide_count() {
+                for reveal in exporter.reveal_steps(idx, steps) {
+                    let filename = frame_name("slide", idx + 1, steps.then_some(reveal), "png");
+                    let frame = exporter.render(idx, reveal);
+                    save_color_image(&frame.image, &output_dir.join(&filename));
+                    eprintln!("  Saved {filename}");
+                }
+            }
+        }
+        ExportFormat::Svg => {
+            for idx in 0..exporter.slide_count() {
+                for reveal in exporter.reveal_steps(idx, steps) {
+                    let filename = frame_name("slide", idx + 1, steps.then_some(reveal), "svg");
+                    std::fs::write(output_dir.join(&filename), exporter.render_svg(idx, reveal))?;
+                    eprintln!("  Saved {filename}");
+                }
+            }
+        }
+        ExportFormat::Pdf => {
+            // Slide links go to the first page of their slide
+            let first_pages: Vec<usize> = (0..exporter.slide_count())
+                .scan(0, |page, idx| {
+                    let first = *page;
+                    *page += exporter.reveal_steps(idx, steps).count();
+                    Some(first)
+                })
+                .collect();
+            let mut pages = Vec::new();
+            for idx in 0..exporter.slide_count() {
+                for reveal in exporter.reveal_steps(idx, steps) {
+                    let frame = exporter.render(idx, reveal);
+                    let links = pdf::page_links(&exporter.presentation, frame.links, &first_pages);
+                    pages.push(PdfPage::new(&frame.image, frame.glyphs).with_links(links));
+                }
+                eprintln!("  Rendered slide {}", idx + 1);
+            }
+            std::fs::write(
+                &pdf_path,
+                pdf::write(&meta, pdf::slide_page_size(aspect.reference_size()), &pages),
+            )?;
+            eprintln!("  Saved {}", pdf_path.display());
+        }
+        // Handouts show each slide once, fully revealed
+        ExportFormat::Handout => {
+            let pages = handout::pages(&mut exporter, per_page as usize, lines);
+            std::fs::write(&handout_path, pdf::write(&meta, handout::PAGE_SIZE, &pages))?;
+            eprintln!("  Saved {}", handout_path.display());
+        }
+        // The page keeps every reveal step, so `steps` changes nothing
+        ExportFormat::Html => {
+            std::fs::write(&html_path, html::write(&mut exporter, &font_definitions))?;
+            eprintln!("  Saved {}", html_path.display());
+        }
+        // Text stays editable, so slides are exported fully revealed
+        ExportFormat::Pptx => {
+            std::fs::write(&pptx_path, pptx::write(&mut exporter)?)?;
+            eprintln!("  Saved {}", pptx_path.display());
+        }
+    }
+
+    eprintln!("Export complete.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECK: &str = "---\ntitle: Golden\n@footer: {slide}/{total}\n---\n\n# Golden Deck\n\nRendered without a window\n\n---\n\n## Bullets\n\n- First point\n- Second *point*\n\n```rust\nfn main() {}\n```\n";
+
+    fn exporter(markdown: &str, size: (u32, u32)) -> Exporter {
+        let base = Path::new(".");
+        let presentation = parser::parse(markdown, base);
+        let (custom_themes, _) = crate::theme::load_deck_themes(&presentation, base);
+        let (fonts, _) = crate::fonts::load_deck_fonts(&presentation.meta, &custom_themes, base);
+        Exporter::new(presentation, custom_themes, fonts, base, size)
+    }
+
+    /// Compare against `tests/golden/<name>.png`, allowing a few pixels of
+    /// font rasterization noise. `MDECK_UPDATE_GOLDEN=1` rewrites the file.
+    fn assert_golden(name: &str, image: &egui::ColorImage) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(format!("{name}.png"));
+        if std::env::var_os("MDECK_UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            save_color_image(image, &path);
+            return;
+        }
+        let golden = image::open(&path)
+            .unwrap_or_else(|e| {
+                panic!("{}: {e} (MDECK_UPDATE_GOLDEN=1 creates it)", path.display())
+            })
+            .to_rgba8();
+        assert_eq!(
+            [golden.width() as usize, golden.height() as usize],
+            image.size,
+            "{name}"
+        );
+        let differing = golden
+            .pixels()
+            .zip(&image.pixels)
+            .filter(|(g, c)| (0..4).any(|i| g.0[i].abs_diff(c.to_srgba_unmultiplied()[i]) > 16))
+            .count();
+        assert!(
+            differing * 200 < image.pixels.len(),
+            "{name}: {differing} pixels differ from {}",
+            path.display()
+        );
+    }
+
+    #[test]
+    fn slides_match_golden_images() {
+        let mut exporter = exporter(DECK, (320, 180));
+        assert_golden("title", &exporter.render(0, 0).image);
+        assert_golden("bullets", &exporter.render(1, 0).image);
+    }
+
+    #[test]
+    fn letterboxes_other_aspect_ratios() {
+        let mut exporter = exporter("---\n@aspect: 4:3\n---\n\n# Square-ish", (160, 90));
+        let frame = exporter.render(0, 0);
+        let px = |x: usize, y: usize| frame.image.pixels[y * 160 + x];
+        assert_eq!(px(5, 45), egui::Color32::BLACK);
+        assert_eq!(px(154, 45), egui::Color32::BLACK);
+        assert_eq!(px(30, 5), exporter.theme.background);
+        assert!(frame.glyphs.iter().any(|g| g.chr == 'S'));
+    }
+
+    #[test]
+    fn slides_and_diagrams_export_as_svg() {
+        let mut exporter = exporter(
+            "## Flow\n\n```@architecture\n- Client (pos: 1,1)\n- Server (pos: 2,1)\n- Client -> Server: calls\n```\n",
+            (960, 540),
+        );
+        let slide = exporter.render_svg(0, 0);
+        assert!(slide.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(slide.contains(r#"viewBox="0 0 960 540""#));
+        assert!(slide.contains(">Flow</text>"));
+
+        let (_, block) = visual::visual_blocks(&exporter.presentation)[0];
+        let (block, theme) = (block.clone(), exporter.theme.clone());
+        let svg = visual::block_svg(&mut exporter, &block, &theme, egui::vec2(800.0, 400.0), 0);
+        assert!(svg.contains(r#"width="800" height="400""#));
+        assert!(svg.contains(">Client</text>"));
+        assert!(svg.contains(">calls</text>"));
+        // The routed edge between the nodes
+        assert!(svg.contains("<polyline points="));
+    }
+
+    #[test]
+    fn draws_saved_annotations_over_the_slide() {
+        let mut exporter = exporter("## Plain", (320, 180));
+        let px = |frame: &RenderedFrame| frame.image.pixels[150 * 320 + 160];
+        let background = px(&exporter.render(0, 0));
+        exporter
+            .annotations
+            .add_stroke(0, vec![[0.25, 0.8333], [0.75, 0.8333]]);
+        assert_ne!(px(&exporter.render(0, 0)), background);
+    }
+
+    #[test]
+    fn renders_each_reveal_step() {
+        let mut exporter = exporter("## Plan\n\n- Always\n+ Later\n* Along\n+ Last", (480, 270));
+        assert_eq!(exporter.reveal_steps(0, true), 0..=2);
+        assert_eq!(exporter.reveal_steps(0, false), 2..=2);
+        let text = |frame: RenderedFrame| frame.glyphs.iter().map(|g| g.chr).collect::<String>();
+        let first = text(exporter.render(0, 0));
+        assert!(first.contains("Always") && !first.contains("Later"));
+        let second = text(exporter.render(0, 1));
+        assert!(second.contains("Along") && !second.contains("Last"));
+        assert!(text(exporter.render(0, 2)).contains("Last"));
+        assert_eq!(
+            frame_name("slide", 3, Some(0), "png"),
+            "slide-03-step-0.png"
+        );
+        assert_eq!(frame_name("block", 12, None, "svg"), "block-12.svg");
+    }
+}
@@ -0,0 +1,353 @@
+//! PDF output: one page per rendered slide image, with an invisible text layer
+//! so slide text can be selected and searched, and link annotations over the
+//! slide's links.
+
+use eframe::egui;
+use pdf_writer::types::{ActionType, AnnotationType, TextRenderingMode};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+
+use crate::parser::{Presentation, PresentationMeta};
+use crate::render::headless::PlacedGlyph;
+use crate::render::links::LinkRegion;
+
+/// PDF points per reference canvas unit: a 16:9 deck becomes a 960×540 pt page,
+/// the usual widescreen slide size.
+const POINTS_PER_UNIT: f32 = 0.5;
+
+/// One rendered slide, ready to become a page.
+pub struct PdfPage {
+    width: u32,
+    height: u32,
+    /// Zlib-compressed RGB pixels.
+    pixels: Vec<u8>,
+    /// Slide text, in image pixels.
+    glyphs: Vec<PlacedGlyph>,
+    links: Vec<PdfLink>,
+}
+
+/// A clickable area of a page, in image pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfLink {
+    pub rect: egui::Rect,
+    pub target: LinkTarget,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkTarget {
+    Uri(String),
+    /// A page of the same document, counting from 0.
+    Page(usize),
+}
+
+impl PdfPage {
+    /// Compress a rendered slide; `glyphs` are the text painted in it.
+    pub fn new(image: &egui::ColorImage, glyphs: Vec<PlacedGlyph>) -> Self {
+        let rgb: Vec<u8> = image
+            .pixels
+            .iter()
+            .flat_map(|c| [c.r(), c.g(), c.b()])
+            .collect();
+        Self {
+            width: image.width() as u32,
+            height: image.height() as u32,
+            pixels: miniz_oxide::deflate::compress_to_vec_zlib(&rgb, 6),
+            glyphs,
+            links: Vec::new(),
+        }
+    }
+
+    pub fn with_links(mut self, links: Vec<PdfLink>) -> Self {
+        self.links = links;
+        self
+    }
+}
+
+/// The links painted on a slide as page links: intra-deck links go to the
+/// first page of their slide (`first_pages` has one per slide), links to
+/// slides that do not exist are dropped.
+pub fn page_links(
+    presentation: &Presentation,
+    regions: Vec<LinkRegion>,
+    first_pages: &[usize],
+) -> Vec<PdfLink> {
+    regions
+        .into_iter()
+        .filter_map(|region| {
+            let target = if region.url.starts_with('#') {
+                LinkTarget::Page(*first_pages.get(presentation.link_slide(&region.url)?)?)
+            } else {
+                LinkTarget::Uri(region.url)
+            };
+            Some(PdfLink {
+                rect: region.rect,
+                target,
+            })
+        })
+        .collect()
+}
+
+/// The PDF page size, in points, for slides on the reference `canvas`.
+pub fn slide_page_size(canvas: egui::Vec2) -> egui::Vec2 {
+    canvas * POINTS_PER_UNIT
+}
+
+/// Write `pages` as a PDF document with `page_size` pages (in points), whatever
+/// the pixel size of the images.
+pub fn write(meta: &PresentationMeta, page_size: egui::Vec2, pages: &[PdfPage]) -> Vec<u8> {
+    let (page_w, page_h) = (page_size.x, page_size.y);
+
+    let mut pdf = Pdf::new();
+    let mut next = 1;
+    let mut alloc = || {
+        next += 1;
+        Ref::new(next - 1)
+    };
+    let catalog_id = alloc();
+    let tree_id = alloc();
+    let info_id = alloc();
+    let font_id = alloc();
+    let page_ids: Vec<(Ref, Ref, Ref)> =
+        pages.iter().map(|_| (alloc(), alloc(), alloc())).collect();
+
+    pdf.catalog(catalog_id).pages(tree_id);
+    pdf.pages(tree_id)
+        .kids(page_ids.iter().map(|(page, _, _)| *page))
+        .count(pages.len() as i32);
+
+    let mut info = pdf.document_info(info_id);
+    if let Some(title) = &meta.title {
+        info.title(TextStr(title));
+    }
+    if let Some(author) = &meta.author {
+        info.author(TextStr(author));
+    }
+    info.creator(TextStr("mdeck"));
+    info.finish();
+
+    pdf.type1_font(font_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    for (page, &(page_id, image_id, content_id)) in pages.iter().zip(&page_ids) {
+        let mut image = pdf.image_xobject(image_id, &page.pixels);
+        image.filter(Filter::FlateDecode);
+        image.width(page.width as i32);
+        image.height(page.height as i32);
+        image.color_space().device_rgb();
+        image.bits_per_component(8);
+        image.finish();
+
+        let mut content = Content::new();
+        content.save_state();
+        content.transform([page_w, 0.0, 0.0, page_h, 0.0, 0.0]);
+        content.x_object(Name(b"Im"));
+        content.restore_state();
+        write_text_layer(
+            &mut content,
+            &page.glyphs,
+            page_w / page.width as f32,
+            page_h,
+        );
+        pdf.stream(content_id, &content.finish());
+
+        let mut pdf_page = pdf.page(page_id);
+        pdf_page
+            .parent(tree_id)
+            .media_box(Rect::new(0.0, 0.0, page_w, page_h))
+            .contents(content_id);
+        let mut resources = pdf_page.resources();
+        resources.x_objects().pair(Name(b"Im"), image_id);
+        resources.fonts().pair(Name(b"F"), font_id);
+        resources.finish();
+        if !page.links.is_empty() {
+            let unit = page_w / page.width as f32;
+            let mut annotations = pdf_page.annotations();
+            for link in &page.links {
+                let rect = link.rect;
+                let mut annotation = annotations.push();
+                annotation
+                    .subtype(AnnotationType::Link)
+                    .rect(Rect::new(
+                        rect.left() * unit,
+                        page_h - rect.bottom() * unit,
+                        rect.right() * unit,
+                        page_h - rect.top() * unit,
+                    ))
+                    .border(0.0, 0.0, 0.0, None);
+                let mut action = annotation.action();
+                match &link.target {
+                    LinkTarget::Uri(uri) => {
+                        action.action_type(ActionType::Uri).uri(Str(uri.as_bytes()));
+                    }
+                    LinkTarget::Page(index) => {
+                        let Some(&(target, _, _)) = page_ids.get(*index) else {
+                            continue;
+                        };
+                        action
+                            .action_type(ActionType::GoTo)
+                            .destination()
+                            .page(target)
+                            .fit();
+                    }
+                }
+            }
+        }
+    }
+
+    pdf.finish()
+}
+
+/// Invisible Helvetica text at each glyph's position, `unit` points per image
+/// pixel. Characters outside the WinAnsi encoding (e.g. CJK) are left out.
+fn write_text_layer(content: &mut Content, glyphs: &[PlacedGlyph], unit: f32, page_h: f32) {
+    if glyphs.is_empty() {
+        return;
+    }
+    content.begin_text();
+    content.set_text_rendering_mode(TextRenderingMode::Invisible);
+    let mut font_size = None;
+    for glyph in glyphs {
+        let Some(byte) = win_ansi(glyph.chr) else {
+            continue;
+        };
+        let size = (glyph.size * unit).max(1.0);
+        if font_size != Some(size) {
+            content.set_font(Name(b"F"), size);
+            font_size = Some(size);
+        }
+        // egui rotates clockwise with y down; PDF y points up
+        let (sin, cos) = (-glyph.angle).sin_cos();
+        let x = glyph.baseline.x * unit;
+        let y = page_h - glyph.baseline.y * unit;
+        content.set_text_matrix([cos, sin, -sin, cos, x, y]);
+        content.show(Str(&[byte]));
+    }
+    content.end_text();
+}
+
+/// The WinAnsiEncoding byte for `c`, if it has one.
+fn win_ansi(c: char) -> Option<u8> {
+    let byte = match c {
+        ' '..='~' | '\u{A0}'..='\u{FF}' => c as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        '„' => 0x84,
+        '…' => 0x85,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '™' => 0x99,
+        _ => return None,
+    };
+    Some(byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glyph(chr: char, x: f32) -> PlacedGlyph {
+        PlacedGlyph {
+            chr,
+            baseline: egui::pos2(x, 100.0),
+            size: 40.0,
+            angle: 0.0,
+        }
+    }
+
+    #[test]
+    fn win_ansi_covers_latin_and_typography() {
+        assert_eq!(win_ansi('A'), Some(b'A'));
+        assert_eq!(win_ansi('é'), Some(0xE9));
+        assert_eq!(win_ansi('—'), Some(0x97));
+        assert_eq!(win_ansi('漢'), None);
+    }
+
+    #[test]
+    fn writes_pages_metadata_and_text() {
+        let meta = PresentationMeta {
+            title: Some("Quarterly".to_string()),
+            author: Some("Ada".to_string()),
+            ..Default::default()
+        };
+        let image = egui::ColorImage::filled([960, 540], egui::Color32::RED);
+        let pages = vec![
+            PdfPage::new(&image, vec![glyph('H', 10.0), glyph('i', 30.0)]),
+            PdfPage::new(&image, Vec::new()),
+        ];
+        let bytes = write(&meta, slide_page_size(egui::vec2(1920.0, 1080.0)), &pages);
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.starts_with("%PDF-"));
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("/Title (Quarterly)"));
+        assert!(text.contains("/Author (Ada)"));
+        assert!(text.contains("/MediaBox [0 0 960 540]"));
+        assert!(text.contains("(H) Tj"));
+        assert!(text.contains("3 Tr"));
+    }
+
+    #[test]
+    fn writes_uri_and_page_link_annotations() {
+        let presentation =
+            crate::parser::parse("# Intro\n\n---\n\n# Results", std::path::Path::new("."));
+        let region = |url: &str| LinkRegion {
+            rect: egui::Rect::from_min_size(egui::pos2(100.0, 100.0), egui::vec2(200.0, 40.0)),
+            url: url.to_string(),
+        };
+        let links = page_links(
+            &presentation,
+            vec![
+                region("https://example.com"),
+                region("#results"),
+                region("#missing"),
+            ],
+            &[0, 3],
+        );
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[1].target, LinkTarget::Page(3));
+
+        let image = egui::ColorImage::filled([960, 540], egui::Color32::RED);
+        let pages = vec![
+            PdfPage::new(&image, Vec::new()).with_links(vec![
+                links[0].clone(),
+                PdfLink {
+                    rect: links[1].rect,
+                    target: LinkTarget::Page(1),
+                },
+            ]),
+            PdfPage::new(&image, Vec::new()),
+        ];
+        let bytes = write(
+            &PresentationMeta::default(),
+            slide_page_size(egui::vec2(1920.0, 1080.0)),
+            &pages,
+        );
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains("/Subtype /Link"));
+        // 960 px wide image on a 960 pt page: one point per pixel, y flipped
+        assert!(text.contains("/Rect [100 400 300 440]"), "{text}");
+        assert!(text.contains("/URI (https://example.com)"));
+        assert!(text.contains("/S /GoTo"));
+        assert!(text.contains("/D [8 0 R /Fit]"), "{text}");
+    }
+
+    #[test]
+    fn text_layer_extracts() {
+        let image = egui::ColorImage::filled([960, 540], egui::Color32::RED);
+        let pages = vec![PdfPage::new(
+            &image,
+            vec![glyph('H', 10.0), glyph('i', 40.0), glyph('é', 100.0)],
+        )];
+        let bytes = write(
+            &PresentationMeta::default(),
+            slide_page_size(egui::vec2(1920.0, 1080.0)),
+            &pages,
+        );
+        let text = pdf_extract::extract_text_from_mem(&bytes).unwrap();
+        assert_eq!(text.trim(), "Hi é");
+    }
+}
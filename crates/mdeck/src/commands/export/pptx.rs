@@ -0,0 +1,1892 @@
+//! PowerPoint output: an Office Open XML package in which titles, text,
+//! lists, code and tables are native, editable shapes, images are embedded,
+//! speaker notes fill the notes pane, and diagrams and visualizations are
+//! pictures drawn by the regular renderers.
+//!
+//! Shapes are placed on the reference canvas (1920×1080 for 16:9) at 6350
+//! EMU per unit, which makes a 16:9 deck PowerPoint's 13.33″ widescreen
+//! slide and turns theme sizes into points by halving them. Text heights are
+//! measured with the slide renderer's fonts, so boxes come out close to what
+//! the presenter shows.
+
+use std::fmt::Write as _;
+use std::io::{Cursor, Write as _};
+use std::path::Path;
+
+use eframe::egui::{self, Color32, Rect};
+
+use crate::parser::{self, Block, Inline, Layout, ListItem, ListMarker, Presentation, Slide};
+use crate::render::footer::{self, Band, FooterContext};
+use crate::render::layouts::{gallery, image_split};
+use crate::render::text;
+use crate::theme::{self, Theme};
+
+use super::{Exporter, visual};
+
+const EMU_PER_UNIT: f32 = 6350.0;
+/// Slide padding, as in the slide layouts.
+const PADDING: f32 = 80.0;
+/// Space between blocks, as in the slide renderer.
+const BLOCK_SPACING: f32 = 20.0;
+const CODE_PADDING: f32 = 16.0;
+const GALLERY_GAP: f32 = 16.0;
+/// Speaker notes body size in canvas units (12 pt).
+const NOTES_BODY_SIZE: f32 = 24.0;
+/// Portrait notes page, in EMU.
+const NOTES_SIZE: (i64, i64) = (6_858_000, 9_144_000);
+const MONO_TYPEFACE: &str = "Consolas";
+
+const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+const NAMESPACES: &str = concat!(
+    "xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" ",
+    "xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\" ",
+    "xmlns:p=\"http://schemas.openxmlformats.org/presentationml/2006/main\""
+);
+const CONTENT_TYPE_PREFIX: &str = "application/vnd.openxmlformats-officedocument.";
+
+/// Write the deck as a `.pptx` package.
+pub fn write(exporter: &mut Exporter) -> anyhow::Result<Vec<u8>> {
+    let canvas = exporter.aspect.reference_size();
+    let pixels_per_unit = exporter.size[0] as f32 / canvas.x;
+    let slide_count = exporter.slide_count();
+    let mut package = Package::default();
+    // For intra-deck links, while slides borrow the exporter
+    let deck = exporter.presentation.clone();
+
+    for idx in 0..slide_count {
+        let slide = exporter.presentation.slides[idx].clone();
+        let theme = exporter
+            .theme
+            .for_slide(&slide, &exporter.custom_themes)
+            .into_owned();
+        let number = idx + 1;
+        let layout = SlideLayout::for_slide(&slide);
+
+        let mut writer = SlideWriter {
+            exporter,
+            package: &mut package,
+            theme: &theme,
+            canvas,
+            pixels_per_unit,
+            shapes: String::new(),
+            next_id: 2,
+            rels: Rels::for_deck(&deck),
+        };
+        writer.rels.add(
+            "slideLayout",
+            format!("../slideLayouts/slideLayout{}.xml", layout.number()),
+        );
+        writer.rels.add(
+            "notesSlide",
+            format!("../notesSlides/notesSlide{number}.xml"),
+        );
+        let background = writer.background(&slide);
+        writer.content(&slide, layout);
+        writer.bands(&slide, idx);
+
+        let SlideWriter { shapes, rels, .. } = writer;
+        package.add(
+            format!("ppt/slides/slide{number}.xml"),
+            Some("presentationml.slide+xml"),
+            format!(
+                "{XML_DECLARATION}<p:sld {NAMESPACES}><p:cSld>{background}<p:spTree>{GROUP_PROPS}{shapes}</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sld>"
+            ),
+        );
+        package.add(
+            format!("ppt/slides/_rels/slide{number}.xml.rels"),
+            None,
+            rels.xml(),
+        );
+
+        let (notes, notes_rels) = notes_xml(&slide, number, &deck);
+        package.add(
+            format!("ppt/notesSlides/notesSlide{number}.xml"),
+            Some("presentationml.notesSlide+xml"),
+            notes,
+        );
+        package.add(
+            format!("ppt/notesSlides/_rels/notesSlide{number}.xml.rels"),
+            None,
+            notes_rels.xml(),
+        );
+        eprintln!("  Converted slide {number}");
+    }
+
+    add_deck_parts(&mut package, exporter, canvas, slide_count);
+    package.finish()
+}
+
+/// The slide layouts of the master, in order; slides pick one by their
+/// `parser::Layout`.
+#[derive(Clone, Copy, PartialEq)]
+enum SlideLayout {
+    Title,
+    Content,
+    Section,
+    TwoContent,
+    TitleOnly,
+    Blank,
+}
+
+const SLIDE_LAYOUTS: [SlideLayout; 6] = [
+    SlideLayout::Title,
+    SlideLayout::Content,
+    SlideLayout::Section,
+    SlideLayout::TwoContent,
+    SlideLayout::TitleOnly,
+    SlideLayout::Blank,
+];
+
+impl SlideLayout {
+    fn for_slide(slide: &Slide) -> Self {
+        match slide.layout {
+            Layout::Title => SlideLayout::Title,
+            Layout::Section => SlideLayout::Section,
+            Layout::TwoColumn => SlideLayout::TwoContent,
+            Layout::Bullet | Layout::Code | Layout::Quote | Layout::Content => SlideLayout::Content,
+            Layout::Image | Layout::Gallery | Layout::Diagram | Layout::Visualization => {
+                if matches!(slide.blocks.first(), Some(Block::Heading { .. })) {
+                    SlideLayout::TitleOnly
+                } else {
+                    SlideLayout::Blank
+                }
+            }
+        }
+    }
+
+    /// 1-based, as in `slideLayoutN.xml`.
+    fn number(self) -> usize {
+        SLIDE_LAYOUTS.iter().position(|&l| l == self).unwrap() + 1
+    }
+
+    fn type_name(self) -> &'static str {
+        match self {
+            SlideLayout::Title => "title",
+            SlideLayout::Content => "obj",
+            SlideLayout::Section => "secHead",
+            SlideLayout::TwoContent => "twoObj",
+            SlideLayout::TitleOnly => "titleOnly",
+            SlideLayout::Blank => "blank",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SlideLayout::Title => "Title Slide",
+            SlideLayout::Content => "Title and Content",
+            SlideLayout::Section => "Section Header",
+            SlideLayout::TwoContent => "Two Content",
+            SlideLayout::TitleOnly => "Title Only",
+            SlideLayout::Blank => "Blank",
+        }
+    }
+
+    /// The layout's placeholders and where they sit on `canvas`.
+    fn placeholders(self, canvas: egui::Vec2) -> Vec<(Placeholder, Rect)> {
+        let full = Rect::from_min_size(egui::Pos2::ZERO, canvas).shrink(PADDING);
+        let band = |top: f32, height: f32| {
+            Rect::from_min_size(
+                egui::pos2(full.left(), canvas.y * top),
+                egui::vec2(full.width(), canvas.y * height),
+            )
+        };
+        let title = Rect::from_min_size(full.min, egui::vec2(full.width(), 140.0));
+        let body = Rect::from_min_max(egui::pos2(full.left(), title.bottom() + 30.0), full.max);
+        match self {
+            SlideLayout::Title => vec![
+                (Placeholder::CenteredTitle, band(0.28, 0.26)),
+                (Placeholder::Subtitle, band(0.56, 0.2)),
+            ],
+            SlideLayout::Content => vec![(Placeholder::Title, title), (Placeholder::Body(1), body)],
+            SlideLayout::Section => vec![
+                (Placeholder::Title, band(0.3, 0.26)),
+                (Placeholder::Body(1), band(0.58, 0.16)),
+            ],
+            SlideLayout::TwoContent => {
+                let (left, right) = columns(body);
+                vec![
+                    (Placeholder::Title, title),
+                    (Placeholder::Body(1), left),
+                    (Placeholder::Body(2), right),
+                ]
+            }
+            SlideLayout::TitleOnly => vec![(Placeholder::Title, title)],
+            SlideLayout::Blank => Vec::new(),
+        }
+    }
+}
+
+/// The two halves of `rect` with a gap between them.
+fn columns(rect: Rect) -> (Rect, Rect) {
+    let gap = rect.width() * 0.05;
+    let width = (rect.width() - gap) / 2.0;
+    (
+        Rect::from_min_size(rect.min, egui::vec2(width, rect.height())),
+        Rect::from_min_size(
+            egui::pos2(rect.right() - width, rect.top()),
+            egui::vec2(width, rect.height()),
+        ),
+    )
+}
+
+#[derive(Clone, Copy)]
+enum Placeholder {
+    Title,
+    CenteredTitle,
+    Subtitle,
+    Body(u32),
+}
+
+impl Placeholder {
+    fn xml(self) -> String {
+        match self {
+            Placeholder::Title => "<p:ph type=\"title\"/>".to_string(),
+            Placeholder::CenteredTitle => "<p:ph type=\"ctrTitle\"/>".to_string(),
+            Placeholder::Subtitle => "<p:ph type=\"subTitle\" idx=\"1\"/>".to_string(),
+            Placeholder::Body(idx) => format!("<p:ph idx=\"{idx}\"/>"),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Placeholder::Title | Placeholder::CenteredTitle => "Title",
+            Placeholder::Subtitle => "Subtitle",
+            Placeholder::Body(_) => "Content Placeholder",
+        }
+    }
+}
+
+/// How a text box lines up its paragraphs.
+#[derive(Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Center,
+}
+
+struct SlideWriter<'a, 'd> {
+    exporter: &'a mut Exporter,
+    package: &'a mut Package,
+    theme: &'a Theme,
+    canvas: egui::Vec2,
+    pixels_per_unit: f32,
+    shapes: String,
+    next_id: u32,
+    rels: Rels<'d>,
+}
+
+/// A run of blocks that shares one shape.
+enum Segment<'b> {
+    Text(Vec<&'b Block>),
+    Code(&'b Block),
+    Table(&'b Block),
+    Picture(&'b Block),
+}
+
+impl SlideWriter<'_, '_> {
+    /// `<p:bg>` for a slide theme background color or `@background` image.
+    fn background(&mut self, slide: &Slide) -> String {
+        let image = slide
+            .directive("background")
+            .filter(|v| theme::parse_hex_color(v).is_none())
+            .and_then(|path| load_image(&self.exporter.base_path, path));
+        let fill = match image {
+            Some(media) => {
+                // Crop to cover the slide, as the renderer does
+                let image_aspect = media.size[0] as f32 / media.size[1] as f32;
+                let slide_aspect = self.canvas.x / self.canvas.y;
+                let crop = if image_aspect > slide_aspect {
+                    let side = ((1.0 - slide_aspect / image_aspect) * 50_000.0).round();
+                    format!("<a:srcRect l=\"{side}\" r=\"{side}\"/>")
+                } else {
+                    let side = ((1.0 - image_aspect / slide_aspect) * 50_000.0).round();
+                    format!("<a:srcRect t=\"{side}\" b=\"{side}\"/>")
+                };
+                let id = self.embed(media);
+                format!(
+                    "<a:blipFill dpi=\"0\" rotWithShape=\"1\"><a:blip r:embed=\"{id}\"/>{crop}<a:stretch><a:fillRect/></a:stretch></a:blipFill>"
+                )
+            }
+            None => solid_fill(self.theme.background),
+        };
+        format!("<p:bg><p:bgPr>{fill}<a:effectLst/></p:bgPr></p:bg>")
+    }
+
+    fn content(&mut self, slide: &Slide, layout: SlideLayout) {
+        let mut blocks: Vec<&Block> = slide.blocks.iter().collect();
+
+        match layout {
+            // Both have a title and a subtitle placeholder, in that order
+            SlideLayout::Title | SlideLayout::Section => {
+                let placeholders = layout.placeholders(self.canvas);
+                let (title_ph, title_rect) = placeholders[0];
+                let (body_ph, body_rect) = placeholders[1];
+                let title_size = self.theme.h1_size
+                    * if layout == SlideLayout::Title {
+                        1.1
+                    } else {
+                        1.2
+                    };
+                let subtitle_size = self.theme.h2_size * 0.7;
+                let heading = blocks
+                    .iter()
+                    .position(|b| matches!(b, Block::Heading { level: 1, .. }))
+                    .or_else(|| {
+                        blocks
+                            .iter()
+                            .position(|b| matches!(b, Block::Heading { .. }))
+                    })
+                    .map(|i| blocks.remove(i));
+                if let Some(Block::Heading { inlines, .. }) = heading {
+                    self.title(inlines, title_size, title_ph, title_rect, Align::Center);
+                }
+                // Subtitles keep the layout's smaller size instead of their
+                // heading level's
+                let mut paragraphs = String::new();
+                let mut rest = Vec::new();
+                for block in blocks {
+                    match block {
+                        Block::Heading { inlines, .. } | Block::Paragraph { inlines } => {
+                            let style = RunStyle {
+                                size: subtitle_size,
+                                color: Some(Theme::with_opacity(self.theme.foreground, 0.8)),
+                                ..RunStyle::default()
+                            };
+                            paragraphs.push_str(&paragraph(
+                                &paragraph_props(Align::Center, 0, None, 0.0),
+                                inlines,
+                                style,
+                                &mut self.rels,
+                            ));
+                        }
+                        _ => rest.push(block),
+                    }
+                }
+                if !paragraphs.is_empty() {
+                    self.text_shape(Some(body_ph), body_rect, None, 0.0, "t", &paragraphs);
+                }
+                if !rest.is_empty() {
+                    let below = Rect::from_min_max(
+                        egui::pos2(body_rect.left(), body_rect.bottom() + BLOCK_SPACING),
+                        egui::pos2(body_rect.right(), self.canvas.y - PADDING),
+                    );
+                    self.flow(&rest, below, None, Align::Center);
+                }
+            }
+            _ => {
+                let mut body = Rect::from_min_max(
+                    egui::pos2(PADDING, PADDING),
+                    (self.canvas - egui::vec2(PADDING, PADDING)).to_pos2(),
+                );
+                if let Some(Block::Heading { level, inlines }) = blocks.first().copied() {
+                    blocks.remove(0);
+                    let size = self.theme.heading_size(*level);
+                    let height = size * 1.5;
+                    let rect = Rect::from_min_size(body.min, egui::vec2(body.width(), height));
+                    self.title(inlines, size, Placeholder::Title, rect, Align::Left);
+                    body.min.y = rect.bottom() + BLOCK_SPACING;
+                }
+                self.body(slide, layout, &blocks, body);
+            }
+        }
+    }
+
+    /// Everything under the title of a non-title slide.
+    fn body(&mut self, slide: &Slide, layout: SlideLayout, blocks: &[&Block], rect: Rect) {
+        let placeholder = (layout == SlideLayout::Content).then_some(Placeholder::Body(1));
+        match slide.layout {
+            Layout::TwoColumn => {
+                let (left, right) = columns(rect);
+                let split = blocks
+                    .iter()
+                    .position(|b| matches!(b, Block::ColumnSeparator))
+                    .unwrap_or(blocks.len());
+                let (first, second) = blocks.split_at(split);
+                self.flow(first, left, Some(Placeholder::Body(1)), Align::Left);
+                self.flow(
+                    second.get(1..).unwrap_or_default(),
+                    right,
+                    Some(Placeholder::Body(2)),
+                    Align::Left,
+                );
+            }
+            Layout::Gallery => {
+                let images: Vec<&Block> = blocks
+                    .iter()
+                    .copied()
+                    .filter(|b| matches!(b, Block::Image { .. }))
+                    .collect();
+                let cells =
+                    gallery::compute_grid(images.len(), rect.width(), rect.height(), GALLERY_GAP);
+                for (block, cell) in images.iter().zip(cells) {
+                    let cell = Rect::from_min_size(
+                        rect.min + egui::vec2(cell.x, cell.y),
+                        egui::vec2(cell.w, cell.h),
+                    );
+                    self.picture(block, cell);
+                }
+            }
+            Layout::Bullet | Layout::Code | Layout::Quote | Layout::Content
+                if image_split::has_image(&slide.blocks) =>
+            {
+                let (left, right) = image_split::image_split_rects(rect);
+                let mut image = None;
+                let mut content = Vec::new();
+                for &block in blocks {
+                    if image.is_none() && matches!(block, Block::Image { .. }) {
+                        image = Some(block);
+                    } else {
+                        content.push(block);
+                    }
+                }
+                self.flow(&content, left, placeholder, Align::Left);
+                if let Some(image) = image {
+                    self.picture(image, right);
+                }
+            }
+            _ => {
+                // Bullet and code slides narrow their text column
+                let width = match slide.layout {
+                    Layout::Bullet => self.canvas.x * 0.7,
+                    Layout::Code => self.canvas.x * 0.75,
+                    _ => rect.width(),
+                };
+                let rect = Rect::from_center_size(
+                    rect.center(),
+                    egui::vec2(width.min(rect.width()), rect.height()),
+                );
+                self.flow(blocks, rect, placeholder, Align::Left);
+            }
+        }
+    }
+
+    /// Lay `blocks` out top to bottom in `rect`. Consecutive text blocks
+    /// share one text box, the `placeholder` if given; code and tables get
+    /// their own shapes; images and visuals split the height that is left.
+    fn flow(
+        &mut self,
+        blocks: &[&Block],
+        rect: Rect,
+        mut placeholder: Option<Placeholder>,
+        align: Align,
+    ) {
+        let mut segments: Vec<Segment> = Vec::new();
+        for &block in blocks {
+            match block {
+                Block::Heading { .. }
+                | Block::Paragraph { .. }
+                | Block::List { .. }
+                | Block::BlockQuote { .. }
+                | Block::HorizontalRule => match segments.last_mut() {
+                    Some(Segment::Text(run)) => run.push(block),
+                    _ => segments.push(Segment::Text(vec![block])),
+                },
+                Block::CodeBlock { .. } => segments.push(Segment::Code(block)),
+                Block::Table { .. } => segments.push(Segment::Table(block)),
+                Block::Image { .. } => segments.push(Segment::Picture(block)),
+                b if visual::is_visual(b) => segments.push(Segment::Picture(block)),
+                _ => {}
+            }
+        }
+        if segments.is_empty() {
+            return;
+        }
+
+        let heights: Vec<Option<f32>> = segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(run) => Some(self.measure(run, rect.width())),
+                Segment::Code(block) | Segment::Table(block) => {
+                    Some(self.measure(&[block], rect.width()))
+                }
+                Segment::Picture(_) => None,
+            })
+            .collect();
+        let gaps = BLOCK_SPACING * (segments.len() - 1) as f32;
+        let fixed: f32 = heights.iter().flatten().sum::<f32>() + gaps;
+        let pictures = heights.iter().filter(|h| h.is_none()).count();
+        let picture_height = if pictures > 0 {
+            ((rect.height() - fixed) / pictures as f32).max(rect.height() * 0.3)
+        } else {
+            0.0
+        };
+        // Without pictures the content is centered vertically, as the slide
+        // layouts do
+        let mut y = if pictures == 0 && fixed < rect.height() {
+            rect.top() + (rect.height() - fixed) / 2.0
+        } else {
+            rect.top()
+        };
+
+        for (segment, height) in segments.iter().zip(heights) {
+            let height = height.unwrap_or(picture_height);
+            let area =
+                Rect::from_min_size(egui::pos2(rect.left(), y), egui::vec2(rect.width(), height));
+            match segment {
+                Segment::Text(run) => {
+                    let mut paragraphs = String::new();
+                    for (i, block) in run.iter().enumerate() {
+                        let space = if i == 0 { 0.0 } else { BLOCK_SPACING };
+                        paragraphs.push_str(&block_paragraphs(
+                            block,
+                            self.theme,
+                            1.0,
+                            align,
+                            space,
+                            &mut self.rels,
+                        ));
+                    }
+                    // A little slack for fonts wider than the renderer's
+                    let area = area.with_max_y(area.bottom() + (height * 0.1).min(BLOCK_SPACING));
+                    self.text_shape(placeholder.take(), area, None, 0.0, "t", &paragraphs);
+                }
+                Segment::Code(Block::CodeBlock {
+                    language,
+                    code,
+                    highlight_lines,
+                }) => {
+                    let paragraphs =
+                        code_paragraphs(code, language.as_deref(), highlight_lines, self.theme);
+                    self.text_shape(
+                        None,
+                        area,
+                        Some(self.theme.code_background),
+                        CODE_PADDING,
+                        "t",
+                        &paragraphs,
+                    );
+                }
+                Segment::Table(Block::Table { headers, rows }) => {
+                    self.table(headers, rows, area);
+                }
+                Segment::Picture(block) => self.picture(block, area),
+                _ => {}
+            }
+            y += height + BLOCK_SPACING;
+        }
+    }
+
+    /// Height of `blocks` laid out `width` wide by the slide renderer.
+    fn measure(&mut self, blocks: &[&Block], width: f32) -> f32 {
+        let owned: Vec<Block> = blocks.iter().map(|b| (*b).clone()).collect();
+        let theme = self.theme;
+        let mut height = 0.0;
+        self.exporter.renderer.render([1, 1], |ui| {
+            height = text::measure_blocks_height(ui, &owned, theme, width, 1.0);
+        });
+        height
+    }
+
+    /// A title placeholder holding `inlines` in the heading font.
+    fn title(
+        &mut self,
+        inlines: &[Inline],
+        size: f32,
+        placeholder: Placeholder,
+        rect: Rect,
+        align: Align,
+    ) {
+        let style = RunStyle {
+            size,
+            color: Some(self.theme.heading_color),
+            heading: true,
+            ..RunStyle::default()
+        };
+        let paragraphs = paragraph(
+            &paragraph_props(align, 0, None, 0.0),
+            inlines,
+            style,
+            &mut self.rels,
+        );
+        self.text_shape(Some(placeholder), rect, None, 0.0, "b", &paragraphs);
+    }
+
+    /// An image scaled to fit `area`, or a diagram or visualization rendered
+    /// to fill it.
+    fn picture(&mut self, block: &Block, area: Rect) {
+        match block {
+            Block::Image { alt, path, .. } => {
+                let Some(media) = load_image(&self.exporter.base_path, path) else {
+                    // Keep the alt text where the image would be
+                    let style = RunStyle {
+                        size: self.theme.body_size,
+                        color: Some(Theme::with_opacity(self.theme.foreground, 0.6)),
+                        italic: true,
+                        ..RunStyle::default()
+                    };
+                    let text = format!("[{}]", if alt.is_empty() { path } else { alt });
+                    let paragraphs = paragraph(
+                        &paragraph_props(Align::Center, 0, None, 0.0),
+                        &[Inline::Text(text)],
+                        style,
+                        &mut self.rels,
+                    );
+                    self.text_shape(None, area, None, 0.0, "ctr", &paragraphs);
+                    return;
+                };
+                let size = egui::vec2(media.size[0] as f32, media.size[1] as f32);
+                let scale = (area.width() / size.x).min(area.height() / size.y);
+                let rect = Rect::from_center_size(area.center(), size * scale);
+                let id = self.embed(media);
+                self.pic_shape(&id, rect, alt);
+            }
+            _ => {
+                let image = visual::block_image(
+                    self.exporter,
+                    block,
+                    self.theme,
+                    area.size(),
+                    self.pixels_per_unit,
+                );
+                let media = Media {
+                    bytes: encode_png(&image),
+                    extension: "png",
+                    size: [image.width() as u32, image.height() as u32],
+                };
+                let id = self.embed(media);
+                self.pic_shape(&id, area, "");
+            }
+        }
+    }
+
+    fn table(&mut self, headers: &[Vec<Inline>], rows: &[Vec<Vec<Inline>>], area: Rect) {
+        let columns = headers
+            .len()
+            .max(rows.iter().map(Vec::len).max().unwrap_or(0))
+            .max(1);
+        let column_width = emu(area.width() / columns as f32);
+        let row_height = emu(area.height() / (rows.len() + 1) as f32);
+        let size = self.theme.body_size * 0.85;
+
+        let mut xml = String::new();
+        let _ = write!(xml, "<a:tbl><a:tblPr firstRow=\"1\"/><a:tblGrid>");
+        for _ in 0..columns {
+            let _ = write!(xml, "<a:gridCol w=\"{column_width}\"/>");
+        }
+        xml.push_str("</a:tblGrid>");
+        let empty = Vec::new();
+        let all_rows =
+            std::iter::once((headers, true)).chain(rows.iter().map(|r| (r.as_slice(), false)));
+        for (cells, header) in all_rows {
+            let _ = write!(xml, "<a:tr h=\"{row_height}\">");
+            for col in 0..columns {
+                let inlines = cells.get(col).unwrap_or(&empty);
+                let style = RunStyle {
+                    size,
+                    color: Some(if header {
+                        self.theme.heading_color
+                    } else {
+                        self.theme.foreground
+                    }),
+                    bold: header,
+                    ..RunStyle::default()
+                };
+                let text = paragraph(
+                    &paragraph_props(Align::Left, 0, None, 0.0),
+                    inlines,
+                    style,
+                    &mut self.rels,
+                );
+                let rule = Theme::with_opacity(
+                    if header {
+                        self.theme.accent
+                    } else {
+                        self.theme.foreground
+                    },
+                    if header { 1.0 } else { 0.2 },
+                );
+                let _ = write!(
+                    xml,
+                    "<a:tc><a:txBody><a:bodyPr/><a:lstStyle/>{text}</a:txBody><a:tcPr anchor=\"ctr\"><a:lnL w=\"0\"><a:noFill/></a:lnL><a:lnR w=\"0\"><a:noFill/></a:lnR><a:lnT w=\"0\"><a:noFill/></a:lnT><a:lnB w=\"12700\">{}</a:lnB><a:noFill/></a:tcPr></a:tc>",
+                    solid_fill(rule)
+                );
+            }
+            xml.push_str("</a:tr>");
+        }
+        xml.push_str("</a:tbl>");
+
+        let id = self.next_id();
+        let _ = write!(
+            self.shapes,
+            "<p:graphicFrame><p:nvGraphicFramePr><p:cNvPr id=\"{id}\" name=\"Table {id}\"/><p:cNvGraphicFramePr><a:graphicFrameLocks noGrp=\"1\"/></p:cNvGraphicFramePr><p:nvPr/></p:nvGraphicFramePr><p:xfrm>{}</p:xfrm><a:graphic><a:graphicData uri=\"http://schemas.openxmlformats.org/drawingml/2006/table\">{xml}</a:graphicData></a:graphic></p:graphicFrame>",
+            offset_extent(area)
+        );
+    }
+
+    /// Header and footer bands as small centered text boxes.
+    fn bands(&mut self, slide: &Slide, idx: usize) {
+        let ctx = FooterContext {
+            meta: &self.exporter.presentation.meta,
+            number: idx + 1,
+            total: self.exporter.slide_count(),
+        };
+        let bands: Vec<(Band, String)> = [Band::Header, Band::Footer]
+            .into_iter()
+            .filter_map(|band| footer::band_text(slide, band, &ctx).map(|t| (band, t)))
+            .collect();
+        for (band, text) in bands {
+            let size = 14.0;
+            let top = match band {
+                Band::Header => 14.0,
+                Band::Footer => self.canvas.y - 30.0,
+            };
+            let rect = Rect::from_min_size(
+                egui::pos2(PADDING, top),
+                egui::vec2(self.canvas.x - PADDING * 2.0, size * 1.4),
+            );
+            let style = RunStyle {
+                size,
+                color: Some(Theme::with_opacity(self.theme.foreground, 0.4)),
+                ..RunStyle::default()
+            };
+            let paragraphs = paragraph(
+                &paragraph_props(Align::Center, 0, None, 0.0),
+                &[Inline::Text(text)],
+                style,
+                &mut self.rels,
+            );
+            self.text_shape(None, rect, None, 0.0, "t", &paragraphs);
+        }
+    }
+
+    fn next_id(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Add `media` to the package and return its relationship id.
+    fn embed(&mut self, media: Media) -> String {
+        let target = self.package.add_media(media);
+        self.rels.add("image", format!("../media/{target}"))
+    }
+
+    fn text_shape(
+        &mut self,
+        placeholder: Option<Placeholder>,
+        rect: Rect,
+        fill: Option<Color32>,
+        inset: f32,
+        anchor: &str,
+        paragraphs: &str,
+    ) {
+        let id = self.next_id();
+        let (name, properties) = match placeholder {
+            Some(ph) => (
+                ph.name(),
+                format!(
+                    "<p:cNvSpPr><a:spLocks noGrp=\"1\"/></p:cNvSpPr><p:nvPr>{}</p:nvPr>",
+                    ph.xml()
+                ),
+            ),
+            None => ("TextBox", "<p:cNvSpPr txBox=\"1\"/><p:nvPr/>".to_string()),
+        };
+        let fill = fill.map_or("<a:noFill/>".to_string(), solid_fill);
+        let inset = emu(inset);
+        let paragraphs = if paragraphs.is_empty() {
+            "<a:p/>"
+        } else {
+            paragraphs
+        };
+        let _ = write!(
+            self.shapes,
+            "<p:sp><p:nvSpPr><p:cNvPr id=\"{id}\" name=\"{name} {id}\"/>{properties}</p:nvSpPr><p:spPr><a:xfrm>{}</a:xfrm><a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom>{fill}</p:spPr><p:txBody><a:bodyPr wrap=\"square\" lIns=\"{inset}\" tIns=\"{inset}\" rIns=\"{inset}\" bIns=\"{inset}\" anchor=\"{anchor}\"><a:normAutofit/></a:bodyPr><a:lstStyle/>{paragraphs}</p:txBody></p:sp>",
+            offset_extent(rect)
+        );
+    }
+
+    fn pic_shape(&mut self, relationship: &str, rect: Rect, description: &str) {
+        let id = self.next_id();
+        let _ = write!(
+            self.shapes,
+            "<p:pic><p:nvPicPr><p:cNvPr id=\"{id}\" name=\"Picture {id}\" descr=\"{}\"/><p:cNvPicPr><a:picLocks noChangeAspect=\"1\"/></p:cNvPicPr><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed=\"{relationship}\"/><a:stretch><a:fillRect/></a:stretch></p:blipFill><p:spPr><a:xfrm>{}</a:xfrm><a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom></p:spPr></p:pic>",
+            escape(description),
+            offset_extent(rect)
+        );
+    }
+}
+
+/// Character formatting of a text run; `size` is in canvas units.
+#[derive(Clone, Copy)]
+struct RunStyle {
+    size: f32,
+    /// `None` inherits the placeholder's color, as in the notes pane.
+    color: Option<Color32>,
+    bold: bool,
+    italic: bool,
+    strike: bool,
+    mono: bool,
+    heading: bool,
+}
+
+impl Default for RunStyle {
+    fn default() -> Self {
+        Self {
+            size: NOTES_BODY_SIZE,
+            color: None,
+            bold: false,
+            italic: false,
+            strike: false,
+            mono: false,
+            heading: false,
+        }
+    }
+}
+
+/// `<a:pPr>` with optional bullet (`buChar` or `buAutoNum`), indented by
+/// `level` and preceded by `space_before` canvas units.
+fn paragraph_props(
+    align: Align,
+    level: u8,
+    bullet: Option<(&str, f32)>,
+    space_before: f32,
+) -> String {
+    let mut xml = String::from("<a:pPr");
+    // DrawingML has nine outline levels
+    let level = level.min(8);
+    if let Some((_, indent)) = bullet {
+        let indent = emu(indent);
+        let _ = write!(
+            xml,
+            " marL=\"{}\" indent=\"-{indent}\" lvl=\"{level}\"",
+            indent * (level as i64 + 1)
+        );
+    } else {
+        xml.push_str(" marL=\"0\" indent=\"0\"");
+    }
+    if align == Align::Center {
+        xml.push_str(" algn=\"ctr\"");
+    }
+    xml.push('>');
+    if space_before > 0.0 {
+        let _ = write!(
+            xml,
+            "<a:spcBef><a:spcPts val=\"{}\"/></a:spcBef>",
+            hundredths(space_before)
+        );
+    }
+    match bullet {
+        Some((bullet, _)) => xml.push_str(bullet),
+        None => xml.push_str("<a:buNone/>"),
+    }
+    xml.push_str("</a:pPr>");
+    xml
+}
+
+/// One `<a:p>` of `inlines`.
+fn paragraph(props: &str, inlines: &[Inline], style: RunStyle, rels: &mut Rels) -> String {
+    let mut xml = format!("<a:p>{props}");
+    runs(&mut xml, inlines, style, None, rels);
+    let _ = write!(
+        xml,
+        "<a:endParaRPr lang=\"en-US\" sz=\"{}\"/></a:p>",
+        hundredths(style.size)
+    );
+    xml
+}
+
+fn runs(
+    out: &mut String,
+    inlines: &[Inline],
+    style: RunStyle,
+    link: Option<&str>,
+    rels: &mut Rels,
+) {
+    for inline in inlines {
+        match inline {
+            Inline::Text(text) => run(out, text, style, link),
+            Inline::Bold(children) => {
+                runs(
+                    out,
+                    children,
+                    RunStyle {
+                        bold: true,
+                        ..style
+                    },
+                    link,
+                    rels,
+                );
+            }
+            Inline::Italic(children) => {
+                runs(
+                    out,
+                    children,
+                    RunStyle {
+                        italic: true,
+                        ..style
+                    },
+                    link,
+                    rels,
+                );
+            }
+            Inline::Strikethrough(children) => {
+                runs(
+                    out,
+                    children,
+                    RunStyle {
+                        strike: true,
+                        ..style
+                    },
+                    link,
+                    rels,
+                );
+            }
+            Inline::Code(text) => run(
+                out,
+                text,
+                RunStyle {
+                    mono: true,
+                    ..style
+                },
+                link,
+            ),
+            Inline::Link { text, url } => match rels.add_hyperlink(url) {
+                Some(hlink) => runs(out, text, style, Some(&hlink), rels),
+                // An anchor matching no slide stays plain text
+                None => runs(out, text, style, link, rels),
+            },
+        }
+    }
+}
+
+fn run(out: &mut String, text: &str, style: RunStyle, link: Option<&str>) {
+    let _ = write!(
+        out,
+        "<a:r><a:rPr lang=\"en-US\" sz=\"{}\"",
+        hundredths(style.size)
+    );
+    if style.bold {
+        out.push_str(" b=\"1\"");
+    }
+    if style.italic {
+        out.push_str(" i=\"1\"");
+    }
+    if style.strike {
+        out.push_str(" strike=\"sngStrike\"");
+    }
+    out.push_str(" dirty=\"0\">");
+    if let Some(color) = style.color {
+        out.push_str(&solid_fill(color));
+    }
+    if style.mono {
+        let _ = write!(out, "<a:latin typeface=\"{MONO_TYPEFACE}\"/>");
+    } else if style.heading {
+        out.push_str("<a:latin typeface=\"+mj-lt\"/>");
+    }
+    if let Some(hlink) = link {
+        out.push_str(hlink);
+    }
+    let _ = write!(
+        out,
+        "</a:rPr><a:t>{}</a:t></a:r>",
+        escape(&text.replace('\n', " "))
+    );
+}
+
+/// Paragraphs for a text block, with sizes multiplied by `scale`.
+fn block_paragraphs(
+    block: &Block,
+    theme: &Theme,
+    scale: f32,
+    align: Align,
+    space_before: f32,
+    rels: &mut Rels,
+) -> String {
+    let body = RunStyle {
+        size: theme.body_size * scale,
+        color: Some(theme.foreground),
+        ..RunStyle::default()
+    };
+    match block {
+        Block::Heading { level, inlines } => paragraph(
+            &paragraph_props(align, 0, None, space_before),
+            inlines,
+            RunStyle {
+                size: theme.heading_size(*level) * scale,
+                color: Some(theme.heading_color),
+                heading: true,
+                ..body
+            },
+            rels,
+        ),
+        Block::Paragraph { inlines } => paragraph(
+            &paragraph_props(align, 0, None, space_before),
+            inlines,
+            body,
+            rels,
+        ),
+        Block::BlockQuote { inlines } => paragraph(
+            &paragraph_props(align, 0, None, space_before),
+            inlines,
+            RunStyle {
+                italic: true,
+                ..body
+            },
+            rels,
+        ),
+        Block::List { ordered, items } => {
+            let mut xml = String::new();
+            list_paragraphs(&mut xml, items, *ordered, 0, body, space_before, rels);
+            xml
+        }
+        Block::HorizontalRule => paragraph(
+            &paragraph_props(Align::Center, 0, None, space_before),
+            &[Inline::Text("―――".to_string())],
+            RunStyle {
+                color: Some(Theme::with_opacity(theme.foreground, 0.4)),
+                ..body
+            },
+            rels,
+        ),
+        _ => String::new(),
+    }
+}
+
+/// List items as bulleted or numbered paragraphs, fully revealed. Nested
+/// lists indent one level per depth.
+fn list_paragraphs(
+    out: &mut String,
+    items: &[ListItem],
+    ordered: bool,
+    level: u8,
+    style: RunStyle,
+    space_before: f32,
+    rels: &mut Rels,
+) {
+    let bullet = if ordered {
+        "<a:buFont typeface=\"+mj-lt\"/><a:buAutoNum type=\"arabicPeriod\"/>"
+    } else if level == 0 {
+        "<a:buFont typeface=\"Arial\"/><a:buChar char=\"•\"/>"
+    } else {
+        "<a:buFont typeface=\"Arial\"/><a:buChar char=\"–\"/>"
+    };
+    for (i, item) in items.iter().enumerate() {
+        let space = if i == 0 && level == 0 {
+            space_before
+        } else {
+            style.size * 0.3
+        };
+        let props = paragraph_props(Align::Left, level, Some((bullet, style.size * 1.2)), space);
+        out.push_str(&paragraph(&props, &item.inlines, style, rels));
+        if !item.children.is_empty() {
+            let children_ordered = item
+                .children
+                .first()
+                .is_some_and(|c| c.marker == ListMarker::Ordered);
+            list_paragraphs(
+                out,
+                &item.children,
+                children_ordered,
+                level + 1,
+                style,
+                0.0,
+                rels,
+            );
+        }
+    }
+}
+
+/// A paragraph per line of syntax-highlighted code; highlighted lines are
+/// bold.
+fn code_paragraphs(
+    code: &str,
+    language: Option<&str>,
+    highlight_lines: &[usize],
+    theme: &Theme,
+) -> String {
+    let mut xml = String::new();
+    let size = hundredths(theme.code_size);
+    for (i, line) in super::highlighted_lines(code, language, theme)
+        .into_iter()
+        .enumerate()
+    {
+        xml.push_str("<a:p><a:pPr marL=\"0\" indent=\"0\"><a:buNone/></a:pPr>");
+        let style = RunStyle {
+            size: theme.code_size,
+            mono: true,
+            bold: highlight_lines.contains(&(i + 1)),
+            ..RunStyle::default()
+        };
+        for (text, color) in line {
+            run(
+                &mut xml,
+                &text,
+                RunStyle {
+                    color: Some(color),
+                    ..style
+                },
+                None,
+            );
+        }
+        let _ = write!(xml, "<a:endParaRPr lang=\"en-US\" sz=\"{size}\"/></a:p>");
+    }
+    xml
+}
+
+/// The notes slide for slide `number`: a slide image and the speaker notes
+/// as formatted text.
+fn notes_xml<'a>(slide: &Slide, number: usize, deck: &'a Presentation) -> (String, Rels<'a>) {
+    let mut rels = Rels::for_deck(deck);
+    rels.add("notesMaster", "../notesMasters/notesMaster1.xml");
+    rels.add("slide", format!("../slides/slide{number}.xml"));
+
+    let theme = Theme::light();
+    let scale = NOTES_BODY_SIZE / theme.body_size;
+    let mut paragraphs = String::new();
+    let blocks = slide
+        .notes
+        .as_deref()
+        .map(parser::blocks::parse)
+        .unwrap_or_default();
+    for (i, block) in blocks.iter().enumerate() {
+        let space = if i == 0 { 0.0 } else { BLOCK_SPACING * scale };
+        match block {
+            Block::CodeBlock {
+                language,
+                code,
+                highlight_lines,
+            } => {
+                let mut code_theme = theme.clone();
+                code_theme.code_size *= scale;
+                paragraphs.push_str(&code_paragraphs(
+                    code,
+                    language.as_deref(),
+                    highlight_lines,
+                    &code_theme,
+                ));
+            }
+            _ => paragraphs.push_str(&block_paragraphs(
+                block,
+                &theme,
+                scale,
+                Align::Left,
+                space,
+                &mut rels,
+            )),
+        }
+    }
+    if paragraphs.is_empty() {
+        paragraphs.push_str("<a:p/>");
+    }
+
+    let xml = format!(
+        "{XML_DECLARATION}<p:notes {NAMESPACES}><p:cSld><p:spTree>{GROUP_PROPS}\
+         <p:sp><p:nvSpPr><p:cNvPr id=\"2\" name=\"Slide Image Placeholder 1\"/><p:cNvSpPr><a:spLocks noGrp=\"1\" noRot=\"1\" noChangeAspect=\"1\"/></p:cNvSpPr><p:nvPr><p:ph type=\"sldImg\"/></p:nvPr></p:nvSpPr><p:spPr/></p:sp>\
+         <p:sp><p:nvSpPr><p:cNvPr id=\"3\" name=\"Notes Placeholder 2\"/><p:cNvSpPr><a:spLocks noGrp=\"1\"/></p:cNvSpPr><p:nvPr><p:ph type=\"body\" idx=\"1\"/></p:nvPr></p:nvSpPr><p:spPr/><p:txBody><a:bodyPr/><a:lstStyle/>{paragraphs}</p:txBody></p:sp>\
+         </p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:notes>"
+    );
+    (xml, rels)
+}
+
+/// The parts every deck has: presentation, master, layouts, themes, notes
+/// master, properties and document metadata.
+fn add_deck_parts(
+    package: &mut Package,
+    exporter: &Exporter,
+    canvas: egui::Vec2,
+    slide_count: usize,
+) {
+    let theme = &exporter.theme;
+    let meta = &exporter.presentation.meta;
+
+    // Presentation, its relationships and properties
+    let mut rels = Rels::default();
+    let master_rel = rels.add("slideMaster", "slideMasters/slideMaster1.xml");
+    let notes_master_rel = rels.add("notesMaster", "notesMasters/notesMaster1.xml");
+    rels.add("theme", "theme/theme1.xml");
+    rels.add("presProps", "presProps.xml");
+    rels.add("viewProps", "viewProps.xml");
+    rels.add("tableStyles", "tableStyles.xml");
+    let mut slide_ids = String::new();
+    for number in 1..=slide_count {
+        let id = rels.add("slide", format!("slides/slide{number}.xml"));
+        let _ = write!(
+            slide_ids,
+            "<p:sldId id=\"{}\" r:id=\"{id}\"/>",
+            255 + number
+        );
+    }
+    package.add(
+        "ppt/presentation.xml".to_string(),
+        Some("presentationml.presentation.main+xml"),
+        format!(
+            "{XML_DECLARATION}<p:presentation {NAMESPACES} saveSubsetFonts=\"1\"><p:sldMasterIdLst><p:sldMasterId id=\"2147483648\" r:id=\"{master_rel}\"/></p:sldMasterIdLst><p:notesMasterIdLst><p:notesMasterId r:id=\"{notes_master_rel}\"/></p:notesMasterIdLst><p:sldIdLst>{slide_ids}</p:sldIdLst><p:sldSz cx=\"{}\" cy=\"{}\"/><p:notesSz cx=\"{}\" cy=\"{}\"/></p:presentation>",
+            emu(canvas.x),
+            emu(canvas.y),
+            NOTES_SIZE.0,
+            NOTES_SIZE.1,
+        ),
+    );
+    package.add(
+        "ppt/_rels/presentation.xml.rels".to_string(),
+        None,
+        rels.xml(),
+    );
+    package.add(
+        "ppt/presProps.xml".to_string(),
+        Some("presentationml.presProps+xml"),
+        format!("{XML_DECLARATION}<p:presentationPr {NAMESPACES}/>"),
+    );
+    package.add(
+        "ppt/viewProps.xml".to_string(),
+        Some("presentationml.viewProps+xml"),
+        format!("{XML_DECLARATION}<p:viewPr {NAMESPACES}/>"),
+    );
+    package.add(
+        "ppt/tableStyles.xml".to_string(),
+        Some("presentationml.tableStyles+xml"),
+        format!(
+            "{XML_DECLARATION}<a:tblStyleLst xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" def=\"{{5C22544A-7EE6-4342-B048-85BDC9FD1C3A}}\"/>"
+        ),
+    );
+
+    // Slide master and layouts
+    let mut master_rels = Rels::default();
+    let mut layout_ids = String::new();
+    for layout in SLIDE_LAYOUTS {
+        let number = layout.number();
+        let id = master_rels.add(
+            "slideLayout",
+            format!("../slideLayouts/slideLayout{number}.xml"),
+        );
+        let _ = write!(
+            layout_ids,
+            "<p:sldLayoutId id=\"{}\" r:id=\"{id}\"/>",
+            2147483648 + number
+        );
+        let shapes = placeholder_shapes(&layout.placeholders(canvas));
+        package.add(
+            format!("ppt/slideLayouts/slideLayout{number}.xml"),
+            Some("presentationml.slideLayout+xml"),
+            format!(
+                "{XML_DECLARATION}<p:sldLayout {NAMESPACES} type=\"{}\" preserve=\"1\"><p:cSld name=\"{}\"><p:spTree>{GROUP_PROPS}{shapes}</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sldLayout>",
+                layout.type_name(),
+                layout.name()
+            ),
+        );
+        let mut layout_rels = Rels::default();
+        layout_rels.add("slideMaster", "../slideMasters/slideMaster1.xml");
+        package.add(
+            format!("ppt/slideLayouts/_rels/slideLayout{number}.xml.rels"),
+            None,
+            layout_rels.xml(),
+        );
+    }
+    master_rels.add("theme", "../theme/theme1.xml");
+    let master_shapes = placeholder_shapes(&SlideLayout::Content.placeholders(canvas));
+    let level = |size: f32, color: &str, font: &str, level: u8| {
+        format!(
+            "<a:lvl{n}pPr marL=\"0\" indent=\"0\"><a:spcBef><a:spcPts val=\"{space}\"/></a:spcBef><a:buNone/><a:defRPr sz=\"{sz}\"><a:solidFill><a:schemeClr val=\"{color}\"/></a:solidFill><a:latin typeface=\"{font}\"/></a:defRPr></a:lvl{n}pPr>",
+            n = level + 1,
+            space = hundredths(BLOCK_SPACING),
+            sz = hundredths(size),
+        )
+    };
+    let title_style = level(theme.h2_size, "tx2", "+mj-lt", 0);
+    let body_style: String = (0..3)
+        .map(|l| level(theme.body_size, "tx1", "+mn-lt", l))
+        .collect();
+    package.add(
+        "ppt/slideMasters/slideMaster1.xml".to_string(),
+        Some("presentationml.slideMaster+xml"),
+        format!(
+            "{XML_DECLARATION}<p:sldMaster {NAMESPACES}><p:cSld><p:bg><p:bgRef idx=\"1001\"><a:schemeClr val=\"bg1\"/></p:bgRef></p:bg><p:spTree>{GROUP_PROPS}{master_shapes}</p:spTree></p:cSld>{COLOR_MAP}<p:sldLayoutIdLst>{layout_ids}</p:sldLayoutIdLst><p:txStyles><p:titleStyle>{title_style}</p:titleStyle><p:bodyStyle>{body_style}</p:bodyStyle><p:otherStyle><a:defPPr><a:defRPr lang=\"en-US\"/></a:defPPr></p:otherStyle></p:txStyles></p:sldMaster>"
+        ),
+    );
+    package.add(
+        "ppt/slideMasters/_rels/slideMaster1.xml.rels".to_string(),
+        None,
+        master_rels.xml(),
+    );
+    package.add(
+        "ppt/theme/theme1.xml".to_string(),
+        Some("theme+xml"),
+        theme_xml(theme),
+    );
+
+    // Notes master with its own light theme, so notes print dark on white
+    let (w, h) = NOTES_SIZE;
+    let notes_shapes = format!(
+        "<p:sp><p:nvSpPr><p:cNvPr id=\"2\" name=\"Slide Image Placeholder 1\"/><p:cNvSpPr><a:spLocks noGrp=\"1\" noRot=\"1\" noChangeAspect=\"1\"/></p:cNvSpPr><p:nvPr><p:ph type=\"sldImg\" idx=\"2\"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x=\"{x}\" y=\"{y1}\"/><a:ext cx=\"{cx}\" cy=\"{cy1}\"/></a:xfrm><a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom><a:noFill/></p:spPr></p:sp>\
+         <p:sp><p:nvSpPr><p:cNvPr id=\"3\" name=\"Notes Placeholder 2\"/><p:cNvSpPr><a:spLocks noGrp=\"1\"/></p:cNvSpPr><p:nvPr><p:ph type=\"body\" idx=\"1\"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x=\"{x}\" y=\"{y2}\"/><a:ext cx=\"{cx}\" cy=\"{cy2}\"/></a:xfrm><a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom></p:spPr><p:txBody><a:bodyPr/><a:lstStyle/><a:p/></p:txBody></p:sp>",
+        x = w / 10,
+        cx = w * 8 / 10,
+        y1 = h / 12,
+        cy1 = ((w * 8 / 10) as f32 * canvas.y / canvas.x) as i64,
+        y2 = h * 11 / 24,
+        cy2 = h * 11 / 24,
+    );
+    package.add(
+        "ppt/notesMasters/notesMaster1.xml".to_string(),
+        Some("presentationml.notesMaster+xml"),
+        format!(
+            "{XML_DECLARATION}<p:notesMaster {NAMESPACES}><p:cSld><p:bg><p:bgRef idx=\"1001\"><a:schemeClr val=\"bg1\"/></p:bgRef></p:bg><p:spTree>{GROUP_PROPS}{notes_shapes}</p:spTree></p:cSld>{COLOR_MAP}<p:notesStyle>{}</p:notesStyle></p:notesMaster>",
+            level(NOTES_BODY_SIZE, "tx1", "+mn-lt", 0)
+        ),
+    );
+    let mut notes_master_rels = Rels::default();
+    notes_master_rels.add("theme", "../theme/theme2.xml");
+    package.add(
+        "ppt/notesMasters/_rels/notesMaster1.xml.rels".to_string(),
+        None,
+        notes_master_rels.xml(),
+    );
+    package.add(
+        "ppt/theme/theme2.xml".to_string(),
+        Some("theme+xml"),
+        theme_xml(&Theme::light()),
+    );
+
+    // Document metadata
+    let mut core = String::new();
+    if let Some(title) = &meta.title {
+        let _ = write!(core, "<dc:title>{}</dc:title>", escape(title));
+    }
+    if let Some(author) = &meta.author {
+        let _ = write!(core, "<dc:creator>{}</dc:creator>", escape(author));
+    }
+    package.add(
+        "docProps/core.xml".to_string(),
+        Some("core"),
+        format!(
+            "{XML_DECLARATION}<cp:coreProperties xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\" xmlns:dcterms=\"http://purl.org/dc/terms/\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">{core}</cp:coreProperties>"
+        ),
+    );
+    package.add(
+        "docProps/app.xml".to_string(),
+        Some("extended-properties+xml"),
+        format!(
+            "{XML_DECLARATION}<Properties xmlns=\"http://schemas.openxmlformats.org/officeDocument/2006/extended-properties\"><Application>mdeck</Application><Slides>{slide_count}</Slides></Properties>"
+        ),
+    );
+}
+
+/// Layout placeholders, sized so new slides match exported ones.
+fn placeholder_shapes(placeholders: &[(Placeholder, Rect)]) -> String {
+    let mut xml = String::new();
+    for (i, (placeholder, rect)) in placeholders.iter().enumerate() {
+        let id = i + 2;
+        let _ = write!(
+            xml,
+            "<p:sp><p:nvSpPr><p:cNvPr id=\"{id}\" name=\"{} {}\"/><p:cNvSpPr><a:spLocks noGrp=\"1\"/></p:cNvSpPr><p:nvPr>{}</p:nvPr></p:nvSpPr><p:spPr><a:xfrm>{}</a:xfrm></p:spPr><p:txBody><a:bodyPr/><a:lstStyle/><a:p/></p:txBody></p:sp>",
+            placeholder.name(),
+            i + 1,
+            placeholder.xml(),
+            offset_extent(*rect)
+        );
+    }
+    xml
+}
+
+/// A theme part whose color scheme carries the deck theme, so text typed
+/// into a new slide matches the exported ones.
+fn theme_xml(theme: &Theme) -> String {
+    let hex = |c: Color32| format!("{:02X}{:02X}{:02X}", c.r(), c.g(), c.b());
+    let palette = theme.diagram_colors.clone().unwrap_or_default();
+    let accent = |i: usize| palette.get(i).copied().unwrap_or(theme.accent);
+    let mut colors = String::new();
+    for (name, color) in [
+        ("dk1", theme.foreground),
+        ("lt1", theme.background),
+        ("dk2", theme.heading_color),
+        ("lt2", theme.code_background),
+        ("accent1", theme.accent),
+        ("accent2", accent(1)),
+        ("accent3", accent(2)),
+        ("accent4", accent(3)),
+        ("accent5", accent(4)),
+        ("accent6", accent(5)),
+        ("hlink", theme.accent),
+        ("folHlink", theme.accent),
+    ] {
+        let _ = write!(
+            colors,
+            "<a:{name}><a:srgbClr val=\"{}\"/></a:{name}>",
+            hex(color)
+        );
+    }
+    let font = "<a:latin typeface=\"Calibri\"/><a:ea typeface=\"\"/><a:cs typeface=\"\"/>";
+    let fill = "<a:solidFill><a:schemeClr val=\"phClr\"/></a:solidFill>";
+    let line = format!("<a:ln w=\"6350\">{fill}</a:ln>");
+    let effect = "<a:effectStyle><a:effectLst/></a:effectStyle>";
+    format!(
+        "{XML_DECLARATION}<a:theme xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" name=\"{name}\"><a:themeElements><a:clrScheme name=\"{name}\">{colors}</a:clrScheme><a:fontScheme name=\"{name}\"><a:majorFont>{font}</a:majorFont><a:minorFont>{font}</a:minorFont></a:fontScheme><a:fmtScheme name=\"{name}\"><a:fillStyleLst>{fill}{fill}{fill}</a:fillStyleLst><a:lnStyleLst>{line}{line}{line}</a:lnStyleLst><a:effectStyleLst>{effect}{effect}{effect}</a:effectStyleLst><a:bgFillStyleLst>{fill}{fill}{fill}</a:bgFillStyleLst></a:fmtScheme></a:themeElements><a:objectDefaults/><a:extraClrSchemeLst/></a:theme>",
+        name = escape(&theme.name),
+    )
+}
+
+/// The empty group properties every shape tree starts with.
+const GROUP_PROPS: &str = "<p:nvGrpSpPr><p:cNvPr id=\"1\" name=\"\"/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x=\"0\" y=\"0\"/><a:ext cx=\"0\" cy=\"0\"/><a:chOff x=\"0\" y=\"0\"/><a:chExt cx=\"0\" cy=\"0\"/></a:xfrm></p:grpSpPr>";
+
+const COLOR_MAP: &str = "<p:clrMap bg1=\"lt1\" tx1=\"dk1\" bg2=\"lt2\" tx2=\"dk2\" accent1=\"accent1\" accent2=\"accent2\" accent3=\"accent3\" accent4=\"accent4\" accent5=\"accent5\" accent6=\"accent6\" hlink=\"hlink\" folHlink=\"folHlink\"/>";
+
+/// A part's relationships, numbered `rId1`, `rId2`, … in the order added.
+/// Parts of a slide know the deck, to turn `#anchor` links into slide jumps.
+#[derive(Default)]
+struct Rels<'a> {
+    rels: Vec<(&'static str, String, bool)>,
+    deck: Option<&'a Presentation>,
+}
+
+impl<'a> Rels<'a> {
+    fn for_deck(deck: &'a Presentation) -> Self {
+        Self {
+            rels: Vec::new(),
+            deck: Some(deck),
+        }
+    }
+
+    /// Relate to the part at `target` by an `officeDocument` relationship
+    /// `kind` and return the id.
+    fn add(&mut self, kind: &'static str, target: impl Into<String>) -> String {
+        self.rels.push((kind, target.into(), false));
+        format!("rId{}", self.rels.len())
+    }
+
+    /// Like [`Rels::add`], for a target outside the package such as a URL.
+    fn add_external(&mut self, kind: &'static str, target: &str) -> String {
+        self.rels.push((kind, target.to_string(), true));
+        format!("rId{}", self.rels.len())
+    }
+
+    /// The `<a:hlinkClick>` for a link to `url`: a jump to the slide an
+    /// intra-deck `#anchor` points to, or a hyperlink to any other URL.
+    /// `None` for an anchor that matches no slide.
+    fn add_hyperlink(&mut self, url: &str) -> Option<String> {
+        if !url.starts_with('#') {
+            let id = self.add_external("hyperlink", url);
+            return Some(format!("<a:hlinkClick r:id=\"{id}\"/>"));
+        }
+        let idx = self.deck?.link_slide(url)?;
+        let id = self.add("slide", format!("../slides/slide{}.xml", idx + 1));
+        Some(format!(
+            "<a:hlinkClick r:id=\"{id}\" action=\"ppaction://hlinksldjump\"/>"
+        ))
+    }
+
+    fn xml(&self) -> String {
+        let mut xml = format!(
+            "{XML_DECLARATION}<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">"
+        );
+        for (i, (kind, target, external)) in self.rels.iter().enumerate() {
+            let _ = write!(
+                xml,
+                "<Relationship Id=\"rId{}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/{kind}\" Target=\"{}\"{}/>",
+                i + 1,
+                escape(target),
+                if *external {
+                    " TargetMode=\"External\""
+                } else {
+                    ""
+                }
+            );
+        }
+        xml.push_str("</Relationships>");
+        xml
+    }
+}
+
+/// The package parts collected so far, with the content types to declare.
+#[derive(Default)]
+struct Package {
+    parts: Vec<(String, Vec<u8>)>,
+    /// `(part name, content type)` overrides for `[Content_Types].xml`.
+    overrides: Vec<(String, String)>,
+    media: usize,
+}
+
+impl Package {
+    /// Add an XML part. `content_type` is the suffix after
+    /// `application/vnd.openxmlformats-officedocument.`, except for `core`
+    /// properties; relationship parts have none.
+    fn add(&mut self, name: String, content_type: Option<&str>, xml: String) {
+        if let Some(content_type) = content_type {
+            let content_type = match content_type {
+                "core" => "application/vnd.openxmlformats-package.core-properties+xml".to_string(),
+                suffix => format!("{CONTENT_TYPE_PREFIX}{suffix}"),
+            };
+            self.overrides.push((format!("/{name}"), content_type));
+        }
+        self.parts.push((name, xml.into_bytes()));
+    }
+
+    /// Add an image under `ppt/media` and return its file name.
+    fn add_media(&mut self, media: Media) -> String {
+        self.media += 1;
+        let name = format!("image{}.{}", self.media, media.extension);
+        self.parts.push((format!("ppt/media/{name}"), media.bytes));
+        name
+    }
+
+    /// Zip the parts, content types first.
+    fn finish(self) -> anyhow::Result<Vec<u8>> {
+        let mut types = format!(
+            "{XML_DECLARATION}<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+             <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+             <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+             <Default Extension=\"png\" ContentType=\"image/png\"/>\
+             <Default Extension=\"jpeg\" ContentType=\"image/jpeg\"/>"
+        );
+        for (part, content_type) in &self.overrides {
+            let _ = write!(
+                types,
+                "<Override PartName=\"{part}\" ContentType=\"{content_type}\"/>"
+            );
+        }
+        types.push_str("</Types>");
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file("[Content_Types].xml", options)?;
+        zip.write_all(types.as_bytes())?;
+        // Package root relationships
+        zip.start_file("_rels/.rels", options)?;
+        zip.write_all(ROOT_RELS.as_bytes())?;
+        for (name, bytes) in &self.parts {
+            zip.start_file(name.as_str(), options)?;
+            zip.write_all(bytes)?;
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+}
+
+const ROOT_RELS: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+    "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+    "<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"ppt/presentation.xml\"/>",
+    "<Relationship Id=\"rId2\" Type=\"http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties\" Target=\"docProps/core.xml\"/>",
+    "<Relationship Id=\"rId3\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/extended-properties\" Target=\"docProps/app.xml\"/>",
+    "</Relationships>"
+);
+
+/// An image file to embed, with its size in pixels.
+struct Media {
+    bytes: Vec<u8>,
+    extension: &'static str,
+    size: [u32; 2],
+}
+
+/// A local image as PNG or JPEG bytes; other formats are converted to PNG.
+fn load_image(base_path: &Path, path: &str) -> Option<Media> {
+    let bytes = std::fs::read(base_path.join(path)).ok()?;
+    let reader = image::ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .ok()?;
+    let extension = match reader.format()? {
+        image::ImageFormat::Png => "png",
+        image::ImageFormat::Jpeg => "jpeg",
+        _ => {
+            let image = reader.decode().ok()?.to_rgba8();
+            let size = [image.width(), image.height()];
+            let mut png = Vec::new();
+            image
+                .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+                .ok()?;
+            return Some(Media {
+                bytes: png,
+                extension: "png",
+                size,
+            });
+        }
+    };
+    let (width, height) = reader.into_dimensions().ok()?;
+    Some(Media {
+        bytes,
+        extension,
+        size: [width, height],
+    })
+}
+
+fn encode_png(image: &egui::ColorImage) -> Vec<u8> {
+    let pixels: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|c| c.to_srgba_unmultiplied())
+        .collect();
+    let mut png = Vec::new();
+    image::RgbaImage::from_raw(image.width() as u32, image.height() as u32, pixels)
+        .expect("pixel count matches the image size")
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .expect("encoding PNG in memory");
+    png
+}
+
+fn solid_fill(color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    if a == 255 {
+        format!("<a:solidFill><a:srgbClr val=\"{r:02X}{g:02X}{b:02X}\"/></a:solidFill>")
+    } else {
+        format!(
+            "<a:solidFill><a:srgbClr val=\"{r:02X}{g:02X}{b:02X}\"><a:alpha val=\"{}\"/></a:srgbClr></a:solidFill>",
+            a as u32 * 100_000 / 255
+        )
+    }
+}
+
+/// Canvas units to EMU.
+fn emu(units: f32) -> i64 {
+    (units * EMU_PER_UNIT).round() as i64
+}
+
+/// Canvas units to hundredths of a point, for font sizes and spacing.
+fn hundredths(units: f32) -> i64 {
+    (units * 50.0).round() as i64
+}
+
+/// `<a:off>` and `<a:ext>` placing a shape at `rect`.
+fn offset_extent(rect: Rect) -> String {
+    format!(
+        "<a:off x=\"{}\" y=\"{}\"/><a:ext cx=\"{}\" cy=\"{}\"/>",
+        emu(rect.left()),
+        emu(rect.top()),
+        emu(rect.width()),
+        emu(rect.height())
+    )
+}
+
+/// Escape text for XML, dropping control characters XML 1.0 cannot hold.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' => out.push('\t'),
+            c if c.is_control() && c != '\n' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use std::io::Read;
+
+    fn export(markdown: &str, dir: &Path) -> zip::ZipArchive<Cursor<Vec<u8>>> {
+        let presentation = parser::parse(markdown, dir);
+        let (custom_themes, _) = theme::load_deck_themes(&presentation, dir);
+        let (fonts, _) = crate::fonts::load_deck_fonts(&presentation.meta, &custom_themes, dir);
+        let mut exporter = Exporter::new(presentation, custom_themes, fonts, dir, (960, 540));
+        zip::ZipArchive::new(Cursor::new(write(&mut exporter).unwrap())).unwrap()
+    }
+
+    fn part(package: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+        let mut xml = String::new();
+        package
+            .by_name(name)
+            .unwrap_or_else(|_| panic!("missing {name}"))
+            .read_to_string(&mut xml)
+            .unwrap();
+        xml
+    }
+
+    #[test]
+    fn writes_editable_text_images_and_notes() {
+        let dir = TempDir::new("pptx-export");
+        dir.write("logo.png", include_bytes!("../../../media/logo-small.png"));
+        let mut package = export(
+            "---\ntitle: Q&A\n---\n\n# Q&A Night\n\nAll the answers\n\n---\n\n## Agenda\n\n- See [docs](https://example.com)\n  1. Nested **bold**\n+ Later\n\n![Logo](logo.png)\n\n???\nSay *hello*\n\n---\n\n```rust\nfn main() {}\n```\n",
+            &dir,
+        );
+
+        let title = part(&mut package, "ppt/slides/slide1.xml");
+        assert!(title.contains("<p:ph type=\"ctrTitle\"/>"));
+        assert!(title.contains("<a:t>Q&amp;A Night</a:t>"));
+        assert!(title.contains("<p:ph type=\"subTitle\" idx=\"1\"/>"));
+        assert!(
+            part(&mut package, "ppt/slides/_rels/slide1.xml.rels")
+                .contains("Target=\"../slideLayouts/slideLayout1.xml\"")
+        );
+
+        let agenda = part(&mut package, "ppt/slides/slide2.xml");
+        assert!(agenda.contains("<p:ph type=\"title\"/>"));
+        assert!(agenda.contains("<a:buChar char=\"•\"/>"));
+        assert!(agenda.contains("lvl=\"1\">"));
+        assert!(agenda.contains("<a:buAutoNum type=\"arabicPeriod\"/>"));
+        assert!(agenda.contains(" b=\"1\" dirty=\"0\">"));
+        assert!(agenda.contains("<a:t>Later</a:t>"));
+        assert!(agenda.contains("<a:hlinkClick r:id=\"rId3\"/>"));
+        assert!(agenda.contains("<a:blip r:embed=\"rId4\"/>"));
+        let rels = part(&mut package, "ppt/slides/_rels/slide2.xml.rels");
+        assert!(rels.contains("Target=\"https://example.com\" TargetMode=\"External\""));
+        assert!(rels.contains("Target=\"../media/image1.png\""));
+        assert!(package.by_name("ppt/media/image1.png").is_ok());
+
+        let notes = part(&mut package, "ppt/notesSlides/notesSlide2.xml");
+        assert!(notes.contains("<p:ph type=\"body\" idx=\"1\"/>"));
+        assert!(notes.contains(" i=\"1\" dirty=\"0\"><a:solidFill><a:srgbClr val=\"1A1A2E\"/></a:solidFill></a:rPr><a:t>hello</a:t>"));
+
+        let code = part(&mut package, "ppt/slides/slide3.xml");
+        assert!(code.contains("<a:latin typeface=\"Consolas\"/></a:rPr><a:t>fn</a:t>"));
+
+        let types = part(&mut package, "[Content_Types].xml");
+        assert!(types.contains("<Override PartName=\"/ppt/slides/slide3.xml\""));
+        assert!(types.contains("<Default Extension=\"png\" ContentType=\"image/png\"/>"));
+        let presentation = part(&mut package, "ppt/presentation.xml");
+        // 1920×1080 canvas units at 6350 EMU each: PowerPoint's widescreen size
+        assert!(presentation.contains("<p:sldSz cx=\"12192000\" cy=\"6858000\"/>"));
+        assert_eq!(presentation.matches("<p:sldId ").count(), 3);
+        assert!(part(&mut package, "docProps/core.xml").contains("<dc:title>Q&amp;A</dc:title>"));
+    }
+
+    #[test]
+    fn charts_and_tables_map_to_pictures_and_native_tables() {
+        let dir = TempDir::new("pptx-export");
+        let mut package = export(
+            "## Sales\n\n```@barchart\n- Q1: 10\n+ Q2: 20\n```\n\n---\n\n## Keys\n\n| Key | Action |\n|-----|--------|\n| N | Next |\n\n---\n\n## Left\n\nOne\n\n+++\n\nTwo\n",
+            &dir,
+        );
+
+        let chart = part(&mut package, "ppt/slides/slide1.xml");
+        assert!(chart.contains("<p:pic>"));
+        assert!(
+            part(&mut package, "ppt/slides/_rels/slide1.xml.rels")
+                .contains("Target=\"../slideLayouts/slideLayout5.xml\"")
+        );
+        let image = image::load_from_memory(&{
+            let mut bytes = Vec::new();
+            package
+                .by_name("ppt/media/image1.png")
+                .unwrap()
+                .read_to_end(&mut bytes)
+                .unwrap();
+            bytes
+        })
+        .unwrap();
+        // Rendered at the export width's pixels per canvas unit (960 / 1920)
+        assert_eq!(image.width(), 880);
+
+        let table = part(&mut package, "ppt/slides/slide2.xml");
+        assert!(table.contains("<a:tbl><a:tblPr firstRow=\"1\"/><a:tblGrid><a:gridCol"));
+        assert_eq!(table.matches("<a:tr ").count(), 2);
+        assert!(table.contains("<a:t>Next</a:t>"));
+
+        let columns = part(&mut package, "ppt/slides/slide3.xml");
+        assert!(columns.contains("<p:ph idx=\"1\"/>"));
+        assert!(columns.contains("<p:ph idx=\"2\"/>"));
+        assert!(
+            part(&mut package, "ppt/slides/_rels/slide3.xml.rels")
+                .contains("Target=\"../slideLayouts/slideLayout4.xml\"")
+        );
+    }
+
+    #[test]
+    fn intra_deck_links_jump_to_slides() {
+        let dir = TempDir::new("pptx-export");
+        let mut package = export(
+            "# Intro\n\nSee [results](#results), [two](#slide-2) and [gone](#nowhere)\n\n???\nBack to [intro](#slide-1)\n\n---\n\n## Results\n\n| Go |\n|----|\n| [top](#intro) |\n",
+            &dir,
+        );
+
+        let intro = part(&mut package, "ppt/slides/slide1.xml");
+        let jump = |id: &str| {
+            format!("<a:hlinkClick r:id=\"{id}\" action=\"ppaction://hlinksldjump\"/></a:rPr><a:t>")
+        };
+        assert!(intro.contains(&(jump("rId3") + "results</a:t>")));
+        assert!(intro.contains(&(jump("rId4") + "two</a:t>")));
+        // No slide has that anchor, so the text is not linked
+        assert!(intro.contains("<a:t>gone</a:t>"));
+        assert_eq!(intro.matches("<a:hlinkClick ").count(), 2);
+        let rels = part(&mut package, "ppt/slides/_rels/slide1.xml.rels");
+        assert!(rels.contains("Id=\"rId3\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide\" Target=\"../slides/slide2.xml\"/>"));
+        assert!(rels.contains("Id=\"rId4\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide\" Target=\"../slides/slide2.xml\"/>"));
+        assert!(!rels.contains("TargetMode=\"External\""));
+
+        let notes = part(&mut package, "ppt/notesSlides/notesSlide1.xml");
+        assert!(notes.contains(&(jump("rId3") + "intro</a:t>")));
+        assert!(
+            part(&mut package, "ppt/notesSlides/_rels/notesSlide1.xml.rels")
+                .contains("Id=\"rId3\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide\" Target=\"../slides/slide1.xml\"/>")
+        );
+
+        let results = part(&mut package, "ppt/slides/slide2.xml");
+        assert!(results.contains(&(jump("rId3") + "top</a:t>")));
+        assert!(
+            part(&mut package, "ppt/slides/_rels/slide2.xml.rels")
+                .contains("Id=\"rId3\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide\" Target=\"../slides/slide1.xml\"/>")
+        );
+    }
+
+    #[test]
+    fn notes_keep_lists_code_and_links() {
+        let dir = TempDir::new("pptx-export");
+        let mut package = export(
+            "## Talk\n\nBody\n\n???\n- First **point**\n- See [site](https://example.com)\n\n```rust\nlet x = 1;\n```\n\n---\n\n## Quiet\n\nNo notes\n",
+            &dir,
+        );
+
+        let notes = part(&mut package, "ppt/notesSlides/notesSlide1.xml");
+        assert!(notes.contains("<p:ph type=\"sldImg\"/>"));
+        assert_eq!(notes.matches("<a:buChar char=\"•\"/>").count(), 2);
+        assert!(notes.contains(" b=\"1\" dirty=\"0\">"));
+        assert!(notes.contains("<a:hlinkClick r:id=\"rId3\"/></a:rPr><a:t>site</a:t>"));
+        assert!(notes.contains("<a:latin typeface=\"Consolas\"/></a:rPr><a:t>let</a:t>"));
+        let rels = part(&mut package, "ppt/notesSlides/_rels/notesSlide1.xml.rels");
+        assert!(rels.contains("Target=\"../notesMasters/notesMaster1.xml\""));
+        assert!(rels.contains("Target=\"../slides/slide1.xml\""));
+        assert!(rels.contains("Target=\"https://example.com\" TargetMode=\"External\""));
+
+        // A slide without notes still gets an empty notes body
+        let quiet = part(&mut package, "ppt/notesSlides/notesSlide2.xml");
+        assert!(quiet.contains("<a:lstStyle/><a:p/></p:txBody>"));
+    }
+
+    #[test]
+    fn images_embed_once_per_use_and_keep_alt_text() {
+        let dir = TempDir::new("pptx-export");
+        dir.write("logo.png", include_bytes!("../../../media/logo-small.png"));
+        let mut package = export(
+            "## Logos\n\n![First logo](logo.png)\n\n---\n\n## Again\n\n![Second & last](logo.png)\n\n---\n\n## Missing\n\n![Ghost](nowhere.png)\n",
+            &dir,
+        );
+
+        let first = part(&mut package, "ppt/slides/slide1.xml");
+        assert!(first.contains("descr=\"First logo\""));
+        assert!(first.contains("<a:blip r:embed=\"rId3\"/>"));
+        assert!(first.contains("<a:picLocks noChangeAspect=\"1\"/>"));
+        assert!(
+            part(&mut package, "ppt/slides/_rels/slide1.xml.rels")
+                .contains("Id=\"rId3\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/image\" Target=\"../media/image1.png\"/>")
+        );
+
+        let second = part(&mut package, "ppt/slides/slide2.xml");
+        assert!(second.contains("descr=\"Second &amp; last\""));
+        assert!(
+            part(&mut package, "ppt/slides/_rels/slide2.xml.rels")
+                .contains("Target=\"../media/image2.png\"")
+        );
+
+        // A missing image leaves its alt text in place
+        let missing = part(&mut package, "ppt/slides/slide3.xml");
+        assert!(!missing.contains("<p:pic>"));
+        assert!(missing.contains(" i=\"1\" dirty=\"0\">"));
+        assert!(missing.contains("<a:t>[Ghost]</a:t>"));
+        assert!(package.by_name("ppt/media/image3.png").is_err());
+    }
+
+    #[test]
+    fn tables_fill_ragged_rows_and_keep_inline_formatting() {
+        let dir = TempDir::new("pptx-export");
+        let mut package = export(
+            "## Plan\n\n| Step | Owner | Due |\n|------|-------|-----|\n| **Ship** | `ops` | Fri |\n| Rest |\n",
+            &dir,
+        );
+
+        let table = part(&mut package, "ppt/slides/slide1.xml");
+        assert!(table.contains(
+            "<a:graphicData uri=\"http://schemas.openxmlformats.org/drawingml/2006/table\"><a:tbl>"
+        ));
+        assert_eq!(table.matches("<a:gridCol ").count(), 3);
+        assert_eq!(table.matches("<a:tr ").count(), 3);
+        // Every row has a cell per column, empty where the row is short
+        assert_eq!(table.matches("<a:tc>").count(), 9);
+        assert!(table.contains(" b=\"1\" dirty=\"0\"><a:solidFill>"));
+        assert!(table.contains("<a:latin typeface=\"Consolas\"/></a:rPr><a:t>ops</a:t>"));
+        assert!(table.contains("<a:t>Rest</a:t>"));
+    }
+}
@@ -0,0 +1,153 @@
+//! Diagrams and visualizations on their own, outside a slide: the regular
+//! renderers paint into a headless frame, which becomes SVG (routed edges
+//! keep the waypoints the slide shows) or an image.
+
+use eframe::egui;
+
+use crate::parser::{self, Block, Presentation, Slide};
+use crate::render::diagram;
+use crate::render::image_cache::ImageCache;
+use crate::render::layouts::visualization;
+use crate::theme::Theme;
+
+use super::Exporter;
+
+/// Padding around diagrams and visualizations, as in their slide layouts.
+const VISUAL_PADDING: f32 = 60.0;
+
+/// Whether `block` is a diagram or a visualization.
+pub fn is_visual(block: &Block) -> bool {
+    matches!(block, Block::Diagram { .. }) || visualization::is_viz_block(block)
+}
+
+/// The deck's diagrams and visualizations in order, with their slide index.
+/// `--block N` counts from 1 in this order.
+pub fn visual_blocks(presentation: &Presentation) -> Vec<(usize, &Block)> {
+    presentation
+        .slides
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, slide)| {
+            slide
+                .blocks
+                .iter()
+                .filter(|b| is_visual(b))
+                .map(move |b| (idx, b))
+        })
+        .collect()
+}
+
+/// Size of the area a diagram or visualization fills on `slide`: the
+/// reference canvas minus padding and whatever sits above it.
+pub fn visual_size(slide: &Slide, theme: &Theme, canvas: egui::Vec2) -> egui::Vec2 {
+    let mut used = 0.0;
+    for block in &slide.blocks {
+        match block {
+            b if is_visual(b) => break,
+            Block::Heading { level, .. } => used += theme.heading_size(*level) * 1.3 + 30.0,
+            _ => used += theme.body_size * 2.0,
+        }
+    }
+    let width = canvas.x - VISUAL_PADDING * 2.0;
+    let height = (canvas.y - VISUAL_PADDING * 2.0 - used).max(canvas.y * 0.3);
+    egui::vec2(width, height)
+}
+
+/// Draw a diagram or visualization block at `size`, revealed up to `step`,
+/// as an SVG document.
+pub fn block_svg(
+    exporter: &mut Exporter,
+    block: &Block,
+    theme: &Theme,
+    size: egui::Vec2,
+    step: usize,
+) -> String {
+    let Exporter {
+        renderer,
+        image_cache,
+        ..
+    } = exporter;
+    renderer.render_svg(size, |ui| {
+        paint_block(ui, block, theme, image_cache, size, step, 1.0)
+    })
+}
+
+/// Rasterize a diagram or visualization block, fully revealed, on the theme
+/// background at `size` reference units and `pixels_per_unit`.
+pub fn block_image(
+    exporter: &mut Exporter,
+    block: &Block,
+    theme: &Theme,
+    size: egui::Vec2,
+    pixels_per_unit: f32,
+) -> egui::ColorImage {
+    let step = parser::compute_max_steps(std::slice::from_ref(block));
+    let Exporter {
+        renderer,
+        image_cache,
+        ..
+    } = exporter;
+    let pixels = (size * pixels_per_unit).round();
+    renderer
+        .render([pixels.x as usize, pixels.y as usize], |ui| {
+            ui.painter()
+                .rect_filled(ui.max_rect(), 0.0, theme.background);
+            paint_block(ui, block, theme, image_cache, pixels, step, pixels_per_unit);
+        })
+        .image
+}
+
+/// Paint `block` at the origin filling `size`, with `scale` pixels per
+/// reference unit.
+fn paint_block(
+    ui: &egui::Ui,
+    block: &Block,
+    theme: &Theme,
+    image_cache: &ImageCache,
+    size: egui::Vec2,
+    step: usize,
+    scale: f32,
+) {
+    let pos = egui::Pos2::ZERO;
+    match block {
+        Block::Diagram { kind, content } => {
+            diagram::draw_diagram_sized(
+                ui,
+                *kind,
+                content,
+                theme,
+                pos,
+                size.x,
+                size.y,
+                1.0,
+                image_cache,
+                step,
+                None,
+                scale,
+            );
+        }
+        _ => {
+            visualization::draw_viz_block(
+                ui, block, theme, pos, size.x, size.y, 1.0, step, None, scale,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    #[test]
+    fn numbers_visual_blocks_across_slides() {
+        let presentation = parser::parse(
+            "# Intro\n\n---\n\n```@piechart\n- A: 1\n- B: 2\n```\n\n---\n\n## Flow\n\n```@architecture\n- A (pos: 1,1)\n- B (pos: 2,1)\n- A -> B\n```\n\n```rust\nfn main() {}\n```\n",
+            std::path::Path::new("."),
+        );
+        let blocks = visual_blocks(&presentation);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].0, 1);
+        assert!(matches!(blocks[1], (2, Block::Diagram { .. })));
+    }
+}
@@ -0,0 +1,1030 @@
+//! Marp, reveal.js and remark import. All three are markdown already; what
+//! changes is how slides are separated, where speaker notes go, how
+//! directives and slide properties are written, and which list items are
+//! revealed step by step.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::{Deck, Slide};
+use crate::cli::ImportFormat;
+
+/// Marp directives that map onto mdeck frontmatter or slide directives.
+const MARP_DIRECTIVES: &[&str] = &[
+    "paginate",
+    "header",
+    "footer",
+    "class",
+    "backgroundColor",
+    "backgroundImage",
+    "theme",
+    "size",
+    "title",
+    "author",
+    "description",
+    "headingDivider",
+    "marp",
+];
+
+/// Other Marp directives, recognized so they are not read as notes.
+const MARP_IGNORED: &[&str] = &[
+    "style",
+    "math",
+    "image",
+    "keywords",
+    "url",
+    "lang",
+    "backgroundPosition",
+    "backgroundRepeat",
+    "backgroundSize",
+    "color",
+];
+
+/// Properties remark reads at the top of a slide.
+const REMARK_PROPERTIES: &[&str] = &[
+    "name",
+    "class",
+    "layout",
+    "template",
+    "exclude",
+    "count",
+    "background-image",
+    "background-position",
+    "background-repeat",
+    "background-size",
+];
+
+/// reveal.js themes with light text on a dark background.
+const REVEAL_DARK_THEMES: &[&str] = &["black", "night", "league", "blood", "moon", "dracula"];
+
+static IMAGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"!\[([^\]]*)\]\(([^)\s]+)(\s+"[^"]*")?\)"#).unwrap());
+static BULLET: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\s*)([-*+]) ").unwrap());
+static CSS_URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"url\(\s*['"]?([^'")]+)['"]?\s*\)"#).unwrap());
+static REVEAL_COMMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<!--\s*\.(slide|element):?(.*?)-->").unwrap());
+static HTML_ATTR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([\w-]+)\s*=\s*["']([^"']*)["']"#).unwrap());
+static REVEAL_NOTES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^\s*notes?:").unwrap());
+static REMARK_PROPERTY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([a-zA-Z][\w-]*):\s*(.*)$").unwrap());
+static REMARK_SOURCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<textarea[^>]*\bid\s*=\s*["']source["'][^>]*>(.*?)</textarea>"#).unwrap()
+});
+static REVEAL_SECTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<section[^>]*\bdata-markdown\b[^>]*>(.*?)</section>").unwrap()
+});
+static REVEAL_TEMPLATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<(textarea[^>]*data-template|script[^>]*text/template)[^>]*>(.*?)</(textarea|script)>"#)
+        .unwrap()
+});
+
+/// Guess which tool `source` was written for from its frontmatter and
+/// markers, or `None` when nothing gives it away.
+pub fn detect(source: &str) -> Option<ImportFormat> {
+    let (front, body) = frontmatter(source);
+    let has_key = |key: &str| front.as_ref().is_some_and(|f| f.contains_key(key));
+    let lines = outside_fences(body);
+    let has_line = |pred: &dyn Fn(&str) -> bool| lines.iter().any(|l| pred(l.trim_end()));
+
+    if front
+        .as_ref()
+        .and_then(|f| f.get("marp"))
+        .is_some_and(|v| v.as_bool() == Some(true))
+        || has_line(&|l| {
+            l.contains("<!-- _class:") || l.contains("<!-- paginate:") || l.contains("![bg")
+        })
+    {
+        Some(ImportFormat::Marp)
+    } else if has_key("revealOptions")
+        || has_line(&|l| REVEAL_COMMENT.is_match(l) || REVEAL_NOTES.is_match(l))
+    {
+        Some(ImportFormat::Reveal)
+    } else if has_line(&|l| {
+        l == "???" || l == "--" || l.starts_with("class: ") || l == "layout: true"
+    }) {
+        Some(ImportFormat::Remark)
+    } else {
+        None
+    }
+}
+
+/// Which tool an HTML page presents its markdown with.
+pub fn detect_html(html: &str) -> Option<ImportFormat> {
+    if REMARK_SOURCE.is_match(html) {
+        Some(ImportFormat::Remark)
+    } else if REVEAL_SECTION.is_match(html) {
+        Some(ImportFormat::Reveal)
+    } else {
+        None
+    }
+}
+
+/// The markdown inside a remark page's `<textarea id="source">`, or inside
+/// a reveal.js page's `data-markdown` sections, one after another.
+pub fn from_html(html: &str) -> String {
+    if let Some(source) = REMARK_SOURCE.captures(html) {
+        return dedent(&super::xml::unescape(&source[1]));
+    }
+    let sections: Vec<String> = REVEAL_SECTION
+        .captures_iter(html)
+        .filter_map(|section| {
+            let template = REVEAL_TEMPLATE.captures(&section[1])?;
+            Some(dedent(&super::xml::unescape(&template[2])))
+        })
+        .collect();
+    if sections.is_empty() {
+        html.to_string()
+    } else {
+        sections.join("\n\n---\n\n")
+    }
+}
+
+/// Convert a Marp deck: `---` rulers (and `headingDivider`) separate slides,
+/// HTML comments are directives or else speaker notes, `![bg](…)` images
+/// become backgrounds and `*` lists are revealed item by item.
+pub fn marp(source: &str) -> Deck {
+    let (front, body) = frontmatter(source);
+    let mut deck = Deck::default();
+    let mut ignored = BTreeSet::new();
+
+    let mut state: HashMap<String, String> = HashMap::new();
+    let mut globals: HashMap<String, String> = HashMap::new();
+    for (key, value) in front.iter().flatten() {
+        let (Some(key), Some(value)) = (key.as_str(), yaml_text(value)) else {
+            continue;
+        };
+        marp_directive(key, &value, &mut state, &mut globals, &mut ignored);
+    }
+
+    let divider = globals
+        .get("headingDivider")
+        .and_then(|d| d.parse::<usize>().ok());
+    let mut chunks = Vec::new();
+    for chunk in split_slides(body, |line, prev_blank, _| prev_blank && is_ruler(line)) {
+        match divider {
+            Some(level) => chunks.extend(split_before_headings(&chunk, level)),
+            None => chunks.push(chunk),
+        }
+    }
+
+    // Each slide's text, inherited directives, directives with its spot
+    // (`_`) ones applied, and notes. Deck-wide settings are what the first
+    // slide inherits.
+    let mut slides_state = Vec::new();
+    for chunk in &chunks {
+        let (text, comments) = take_comments(chunk);
+        let mut spot = HashMap::new();
+        let mut notes = Vec::new();
+        for comment in comments {
+            match marp_directives_in(&comment) {
+                Some(directives) => {
+                    for (key, value) in directives {
+                        match key.strip_prefix('_') {
+                            Some(key) => {
+                                spot.insert(key.to_string(), value);
+                            }
+                            None => {
+                                marp_directive(&key, &value, &mut state, &mut globals, &mut ignored)
+                            }
+                        }
+                    }
+                }
+                None if comment == "fit" || comment.is_empty() => {}
+                None => notes.push(comment),
+            }
+        }
+        let mut effective = state.clone();
+        effective.extend(spot);
+        slides_state.push((text, state.clone(), effective, notes.join("\n\n")));
+    }
+
+    for key in ["title", "author", "date"] {
+        if let Some(value) = globals.get(key) {
+            deck.meta.push((key.to_string(), value.clone()));
+        }
+    }
+    let first = slides_state
+        .first()
+        .map(|(_, inherited, _, _)| inherited.clone())
+        .unwrap_or_default();
+    let deck_invert = marp_invert(&first);
+    if deck_invert {
+        deck.meta.push(("@theme".to_string(), "dark".to_string()));
+    }
+    if let Some(size) = globals.get("size").filter(|s| *s == "4:3") {
+        deck.meta.push(("@aspect".to_string(), size.clone()));
+    }
+    let deck_header = first.get("header").cloned().filter(|h| !h.is_empty());
+    let deck_footer = marp_footer(&first);
+    if let Some(header) = &deck_header {
+        deck.meta.push(("@header".to_string(), header.clone()));
+    }
+    if let Some(footer) = &deck_footer {
+        deck.meta.push(("@footer".to_string(), footer.clone()));
+    }
+
+    for (number, (text, _, state, notes)) in slides_state.into_iter().enumerate() {
+        let mut slide = Slide {
+            notes,
+            ..Default::default()
+        };
+        let header = state.get("header").cloned().filter(|h| !h.is_empty());
+        if header != deck_header {
+            slide.directives.push((
+                "header".to_string(),
+                header.unwrap_or_else(|| "none".to_string()),
+            ));
+        }
+        let footer = marp_footer(&state);
+        if footer != deck_footer {
+            slide.directives.push((
+                "footer".to_string(),
+                footer.unwrap_or_else(|| "none".to_string()),
+            ));
+        }
+        if marp_invert(&state) != deck_invert {
+            slide
+                .directives
+                .push(("class".to_string(), "invert".to_string()));
+        }
+
+        let mut background = None;
+        // `bg left`/`bg right` pictures sit beside the content: an mdeck
+        // image split, written after the text.
+        let mut split_images = Vec::new();
+        let mut content = Vec::new();
+        for (line, fenced) in lines_with_fences(&text) {
+            if fenced {
+                content.push(line.to_string());
+                continue;
+            }
+            let raw = line;
+            let line = IMAGE.replace_all(raw, |caps: &regex::Captures| {
+                let words: Vec<&str> = caps[1].split_whitespace().collect();
+                if words.first() == Some(&"bg") {
+                    let split = words
+                        .iter()
+                        .any(|w| w.starts_with("left") || w.starts_with("right"));
+                    if split {
+                        split_images.push(format!("![]({})", &caps[2]));
+                        return String::new();
+                    }
+                    if background.is_none() {
+                        background = Some(caps[2].to_string());
+                    } else {
+                        deck.warnings.push(format!(
+                            "Slide {}: only the first background image is imported",
+                            number + 1
+                        ));
+                    }
+                    return String::new();
+                }
+                let alt: Vec<String> = words.iter().filter_map(|w| marp_image_word(w)).collect();
+                format!("![{}]({})", alt.join(" "), &caps[2])
+            });
+            if line.trim().is_empty() && !raw.trim().is_empty() {
+                continue;
+            }
+            content.push(convert_bullet(&line, |marker| match marker {
+                "*" => "+",
+                _ => "-",
+            }));
+        }
+        let background = background.or_else(|| {
+            let color = state.get("backgroundColor");
+            let image = state
+                .get("backgroundImage")
+                .and_then(|image| CSS_URL.captures(image))
+                .map(|caps| caps[1].to_string());
+            image.or_else(|| color.filter(|c| c.starts_with('#')).cloned())
+        });
+        if let Some(background) = background {
+            slide
+                .directives
+                .push(("background".to_string(), background));
+        } else if let Some(color) = state.get("backgroundColor").filter(|c| !c.starts_with('#')) {
+            deck.warnings.push(format!(
+                "Slide {}: background color `{color}` is not a hex color and was not imported",
+                number + 1
+            ));
+        }
+        slide.content = content.join("\n");
+        for image in split_images {
+            slide.content.push_str("\n\n");
+            slide.content.push_str(&image);
+        }
+        deck.slides.push(slide);
+    }
+
+    if !ignored.is_empty() {
+        let names: Vec<_> = ignored.into_iter().collect();
+        deck.warnings.push(format!(
+            "Marp directives not imported: {}",
+            names.join(", ")
+        ));
+    }
+    deck
+}
+
+/// Apply one Marp directive to the inherited slide `state`, or to the
+/// deck-wide `globals`.
+fn marp_directive(
+    key: &str,
+    value: &str,
+    state: &mut HashMap<String, String>,
+    globals: &mut HashMap<String, String>,
+    ignored: &mut BTreeSet<String>,
+) {
+    match key {
+        "paginate" | "header" | "footer" | "class" | "backgroundColor" | "backgroundImage" => {
+            state.insert(key.to_string(), value.to_string());
+        }
+        "theme" if matches!(value, "default" | "gaia" | "uncover") => {}
+        "marp" | "description" => {}
+        "title" | "author" | "date" | "size" | "headingDivider" => {
+            globals.insert(key.to_string(), value.to_string());
+        }
+        key => {
+            ignored.insert(key.to_string());
+        }
+    }
+}
+
+/// The directives a Marp HTML comment sets, or `None` when it is a note.
+fn marp_directives_in(comment: &str) -> Option<Vec<(String, String)>> {
+    let mapping: serde_yaml::Mapping = serde_yaml::from_str(comment).ok()?;
+    let directives: Vec<(String, String)> = mapping
+        .iter()
+        .filter_map(|(key, value)| Some((key.as_str()?.to_string(), yaml_text(value)?)))
+        .collect();
+    let known = |key: &str| {
+        let key = key.strip_prefix('_').unwrap_or(key);
+        MARP_DIRECTIVES.contains(&key) || MARP_IGNORED.contains(&key)
+    };
+    (!directives.is_empty() && directives.iter().all(|(key, _)| known(key))).then_some(directives)
+}
+
+fn marp_invert(state: &HashMap<String, String>) -> bool {
+    state
+        .get("class")
+        .is_some_and(|class| class.split_whitespace().any(|c| c == "invert"))
+}
+
+/// The footer for a Marp slide: its `footer`, with the page number added
+/// when `paginate` is on.
+fn marp_footer(state: &HashMap<String, String>) -> Option<String> {
+    let footer = state.get("footer").filter(|f| !f.is_empty());
+    let paginate = state.get("paginate").is_some_and(|p| p == "true");
+    match (footer, paginate) {
+        (Some(footer), true) => Some(format!("{footer} · {{slide}}")),
+        (Some(footer), false) => Some(footer.clone()),
+        (None, true) => Some("{slide}".to_string()),
+        (None, false) => None,
+    }
+}
+
+/// A word of Marp image alt text in mdeck terms: sizes become `@width` and
+/// `@height`, filters are dropped and other words stay alt text.
+fn marp_image_word(word: &str) -> Option<String> {
+    const FILTERS: &[&str] = &[
+        "blur",
+        "brightness",
+        "contrast",
+        "drop-shadow",
+        "grayscale",
+        "hue-rotate",
+        "invert",
+        "opacity",
+        "saturate",
+        "sepia",
+    ];
+    let (key, value) = word.split_once(':').unwrap_or((word, ""));
+    let size = |value: &str| {
+        if value.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            format!("{value}px")
+        } else {
+            value.to_string()
+        }
+    };
+    match key {
+        "w" | "width" if !value.is_empty() => Some(format!("@width:{}", size(value))),
+        "h" | "height" if !value.is_empty() => Some(format!("@height:{}", size(value))),
+        key if FILTERS.contains(&key) => None,
+        _ => Some(word.to_string()),
+    }
+}
+
+/// Convert a reveal.js markdown deck: `---` separates slides (vertical
+/// `--` and `----` stacks are flattened), `Note:` starts speaker notes,
+/// `<!-- .slide: -->` attributes become directives and list items marked
+/// `<!-- .element: class="fragment" -->` are revealed one by one.
+pub fn reveal(source: &str) -> Deck {
+    let (front, body) = frontmatter(source);
+    let mut deck = Deck::default();
+    if let Some(front) = &front {
+        if let Some(title) = front.get("title").and_then(yaml_text) {
+            deck.meta.push(("title".to_string(), title.to_string()));
+        }
+        if let Some(theme) = front.get("theme").and_then(yaml_text) {
+            let dark = REVEAL_DARK_THEMES.contains(&theme.trim_end_matches(".css"));
+            if dark {
+                deck.meta.push(("@theme".to_string(), "dark".to_string()));
+            }
+        }
+        let transition = front
+            .get("revealOptions")
+            .and_then(|options| options.get("transition"))
+            .and_then(yaml_text)
+            .and_then(|t| transition(&t));
+        if let Some(transition) = transition {
+            deck.meta
+                .push(("@transition".to_string(), transition.to_string()));
+        }
+    }
+
+    let chunks = split_slides(body, |line, prev_blank, next_blank| {
+        prev_blank && next_blank && matches!(line.trim(), "---" | "--" | "----")
+    });
+    for (number, chunk) in chunks.iter().enumerate() {
+        let mut slide = Slide::default();
+        let mut content = Vec::new();
+        let mut notes: Option<Vec<String>> = None;
+        for (line, fenced) in lines_with_fences(chunk) {
+            if let Some(notes) = &mut notes {
+                notes.push(line.to_string());
+                continue;
+            }
+            if fenced {
+                content.push(line.to_string());
+                continue;
+            }
+            if REVEAL_NOTES.is_match(line) {
+                let rest = REVEAL_NOTES.replace(line, "");
+                notes = Some(vec![rest.trim().to_string()]);
+                continue;
+            }
+
+            let mut fragment = false;
+            for caps in REVEAL_COMMENT.captures_iter(line) {
+                let attrs: HashMap<&str, &str> = HTML_ATTR
+                    .captures_iter(caps.get(2).map_or("", |m| m.as_str()))
+                    .map(|a| {
+                        let (_, [key, value]) = a.extract();
+                        (key, value)
+                    })
+                    .collect();
+                if &caps[1] == "element" {
+                    fragment |= attrs
+                        .get("class")
+                        .is_some_and(|c| c.split_whitespace().any(|c| c == "fragment"));
+                    continue;
+                }
+                for (key, value) in attrs {
+                    match key {
+                        "data-background" | "data-background-color" | "data-background-image" => {
+                            slide
+                                .directives
+                                .push(("background".to_string(), value.to_string()));
+                        }
+                        "data-transition" => match transition(value) {
+                            Some(t) => slide
+                                .directives
+                                .push(("transition".to_string(), t.to_string())),
+                            None => deck.warnings.push(format!(
+                                "Slide {}: transition `{value}` has no mdeck equivalent",
+                                number + 1
+                            )),
+                        },
+                        _ => {}
+                    }
+                }
+            }
+            let stripped = REVEAL_COMMENT.replace_all(line, "");
+            if stripped.trim().is_empty() && !line.trim().is_empty() {
+                continue;
+            }
+            content.push(convert_bullet(stripped.trim_end(), |_| {
+                if fragment { "+" } else { "-" }
+            }));
+        }
+        let (content, _) = take_comments(&content.join("\n"));
+        slide.content = content;
+        slide.notes = notes.map(|n| n.join("\n")).unwrap_or_default();
+        deck.slides.push(slide);
+    }
+    deck
+}
+
+/// An mdeck transition for a reveal.js one.
+fn transition(name: &str) -> Option<&'static str> {
+    match name.split('-').next()? {
+        "none" => Some("none"),
+        "fade" => Some("fade"),
+        "slide" => Some("slide"),
+        _ => None,
+    }
+}
+
+/// Convert a remark deck: `---` separates slides, `--` continues a slide
+/// with the next items revealed, `???` starts notes, slide properties
+/// (`class`, `background-image`, `layout`, `exclude`) become directives, and
+/// `.class[…]` wrappers are unwrapped, `.left[…]`/`.right[…]` into columns.
+pub fn remark(source: &str) -> Deck {
+    let mut deck = Deck::default();
+    let mut layout: HashMap<String, String> = HashMap::new();
+    let mut ignored = BTreeSet::new();
+
+    let chunks = split_slides(source, |line, _, _| line.trim_end() == "---");
+    for chunk in &chunks {
+        let (properties, text) = remark_properties(chunk);
+        if properties.get("exclude").is_some_and(|v| v == "true") {
+            continue;
+        }
+        if properties.get("layout").is_some_and(|v| v == "true") {
+            if !text.trim().is_empty() {
+                deck.warnings
+                    .push("Content of remark layout slides is not imported".to_string());
+            }
+            layout = properties;
+            layout.remove("layout");
+            continue;
+        }
+        let mut properties_with_layout = layout.clone();
+        properties_with_layout.extend(properties);
+
+        let mut slide = Slide::default();
+        for (key, value) in &properties_with_layout {
+            match key.as_str() {
+                "class" => {
+                    if value.split([',', ' ']).any(|c| c == "inverse") {
+                        slide
+                            .directives
+                            .push(("class".to_string(), "invert".to_string()));
+                    }
+                }
+                "background-image" => {
+                    if let Some(caps) = CSS_URL.captures(value) {
+                        slide
+                            .directives
+                            .push(("background".to_string(), caps[1].to_string()));
+                    }
+                }
+                "name" | "count" => {}
+                other => {
+                    ignored.insert(other.to_string());
+                }
+            }
+        }
+
+        let mut content = Vec::new();
+        let mut notes = Vec::new();
+        for (step, part) in split_slides(&text, |line, _, _| line.trim_end() == "--")
+            .into_iter()
+            .enumerate()
+        {
+            let (part, part_notes) = match lines_with_fences(&part)
+                .iter()
+                .position(|(line, fenced)| !fenced && line.trim() == "???")
+            {
+                Some(at) => {
+                    let lines: Vec<&str> = part.lines().collect();
+                    (lines[..at].join("\n"), lines[at + 1..].join("\n"))
+                }
+                None => (part.clone(), String::new()),
+            };
+            if !part_notes.trim().is_empty() {
+                notes.push(part_notes.trim().to_string());
+            }
+            let mut first = true;
+            for (line, fenced) in lines_with_fences(&part) {
+                if fenced {
+                    content.push(line.to_string());
+                    continue;
+                }
+                let top_level = !line.starts_with(char::is_whitespace);
+                content.push(convert_bullet(line, |_| {
+                    if step == 0 || !top_level {
+                        "-"
+                    } else if std::mem::take(&mut first) {
+                        "+"
+                    } else {
+                        "*"
+                    }
+                }));
+            }
+            content.push(String::new());
+        }
+        slide.content = unwrap_classes(&content.join("\n"));
+        slide.notes = notes.join("\n\n");
+        deck.slides.push(slide);
+    }
+
+    if !ignored.is_empty() {
+        let names: Vec<_> = ignored.into_iter().collect();
+        deck.warnings.push(format!(
+            "remark slide properties not imported: {}",
+            names.join(", ")
+        ));
+    }
+    deck
+}
+
+/// The `key: value` properties at the top of a remark slide, and the rest.
+fn remark_properties(chunk: &str) -> (HashMap<String, String>, String) {
+    let mut properties = HashMap::new();
+    let lines: Vec<&str> = chunk.trim_start_matches('\n').lines().collect();
+    let mut count = 0;
+    for line in &lines {
+        let Some(caps) = REMARK_PROPERTY
+            .captures(line)
+            .filter(|caps| REMARK_PROPERTIES.contains(&&caps[1]))
+        else {
+            break;
+        };
+        properties.insert(caps[1].to_string(), caps[2].trim().to_string());
+        count += 1;
+    }
+    (properties, lines[count..].join("\n"))
+}
+
+/// Replace remark's `.name[content]` wrappers with their content, putting a
+/// column break before a `.right…[` block that follows a `.left…[` one.
+fn unwrap_classes(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut left_column = false;
+    let mut in_fence = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let line_start = i == 0 || chars[i - 1] == '\n';
+        if line_start {
+            let line: String = chars[i..].iter().take_while(|&&c| c != '\n').collect();
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+            }
+        }
+        let name_len =
+            if !in_fence && chars[i] == '.' && (i == 0 || !chars[i - 1].is_alphanumeric()) {
+                chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '-' || **c == '_')
+                    .count()
+            } else {
+                0
+            };
+        let open = i + 1 + name_len;
+        if name_len > 0 && chars.get(open) == Some(&'[') {
+            let mut depth = 0;
+            let close = (open..chars.len()).find(|&j| {
+                match chars[j] {
+                    '[' => depth += 1,
+                    ']' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            });
+            if let Some(close) = close.filter(|&c| chars.get(c + 1) != Some(&'(')) {
+                let name: String = chars[i + 1..open].iter().collect();
+                let inner: String = chars[open + 1..close].iter().collect();
+                if line_start && name.starts_with("left") {
+                    left_column = true;
+                } else if line_start && name.starts_with("right") && left_column {
+                    out.push_str("+++\n\n");
+                    left_column = false;
+                }
+                out.push_str(&unwrap_classes(&inner));
+                i = close + 1;
+                continue;
+            }
+        }
+        out.push(chars[i]);
+        i += 1;
+    }
+    out
+}
+
+/// Split `text` into frontmatter and body. The frontmatter is `None` when
+/// absent or not a YAML mapping.
+fn frontmatter(text: &str) -> (Option<serde_yaml::Mapping>, &str) {
+    let Some(rest) = text.strip_prefix("---\n").or(text.strip_prefix("---\r\n")) else {
+        return (None, text);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (None, text);
+    };
+    match serde_yaml::from_str(&rest[..end]) {
+        Ok(mapping) => {
+            let body = rest[end + 4..].trim_start_matches(['\r', '\n']);
+            (Some(mapping), body)
+        }
+        Err(_) => (None, text),
+    }
+}
+
+/// A YAML scalar as text.
+fn yaml_text(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Each line of `text` with whether it belongs to a fenced code block
+/// (fences included).
+fn lines_with_fences(text: &str) -> Vec<(&str, bool)> {
+    let mut fence: Option<String> = None;
+    text.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            match &fence {
+                Some(open) => {
+                    if trimmed.starts_with(open.as_str()) {
+                        fence = None;
+                    }
+                    (line, true)
+                }
+                None if trimmed.starts_with("```") || trimmed.starts_with("~~~") => {
+                    fence = Some(trimmed[..3].to_string());
+                    (line, true)
+                }
+                None => (line, false),
+            }
+        })
+        .collect()
+}
+
+fn outside_fences(text: &str) -> Vec<&str> {
+    lines_with_fences(text)
+        .into_iter()
+        .filter(|(_, fenced)| !fenced)
+        .map(|(line, _)| line)
+        .collect()
+}
+
+/// Split `text` at separator lines outside code fences. `separator` gets
+/// the line and whether the lines before and after it are blank.
+fn split_slides(text: &str, separator: impl Fn(&str, bool, bool) -> bool) -> Vec<String> {
+    let lines = lines_with_fences(text);
+    let mut slides = vec![String::new()];
+    for (i, (line, fenced)) in lines.iter().enumerate() {
+        let blank = |j: Option<usize>| {
+            j.and_then(|j| lines.get(j))
+                .is_none_or(|(l, _)| l.trim().is_empty())
+        };
+        if !fenced && separator(line, blank(i.checked_sub(1)), blank(Some(i + 1))) {
+            slides.push(String::new());
+            continue;
+        }
+        let slide = slides.last_mut().unwrap();
+        slide.push_str(line);
+        slide.push('\n');
+    }
+    slides
+        .into_iter()
+        .filter(|s| !s.trim().is_empty())
+        .collect()
+}
+
+/// Split a Marp slide before each heading at `level` or above that follows
+/// other content, as `headingDivider` does.
+fn split_before_headings(text: &str, level: usize) -> Vec<String> {
+    let mut slides = vec![String::new()];
+    for (line, fenced) in lines_with_fences(text) {
+        let hashes = line.chars().take_while(|&c| c == '#').count();
+        let heading = !fenced && (1..=level).contains(&hashes) && line[hashes..].starts_with(' ');
+        if heading && !slides.last().unwrap().trim().is_empty() {
+            slides.push(String::new());
+        }
+        let slide = slides.last_mut().unwrap();
+        slide.push_str(line);
+        slide.push('\n');
+    }
+    slides
+}
+
+/// Whether `line` is a thematic break (`---`, `***`, `___`, `- - -`).
+fn is_ruler(line: &str) -> bool {
+    let chars: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    chars.len() >= 3 && matches!(chars[0], '-' | '*' | '_') && chars.iter().all(|&c| c == chars[0])
+}
+
+/// Remove HTML comments outside code fences, returning the text and the
+/// comments' trimmed contents.
+fn take_comments(text: &str) -> (String, Vec<String>) {
+    let mut out = Vec::new();
+    let mut comments = Vec::new();
+    let mut comment: Option<String> = None;
+    for (line, fenced) in lines_with_fences(text) {
+        if fenced && comment.is_none() {
+            out.push(line.to_string());
+            continue;
+        }
+        let mut kept = String::new();
+        let mut rest = line;
+        loop {
+            match &mut comment {
+                Some(body) => match rest.find("-->") {
+                    Some(end) => {
+                        body.push_str(&rest[..end]);
+                        comments.push(body.trim().to_string());
+                        comment = None;
+                        rest = &rest[end + 3..];
+                    }
+                    None => {
+                        body.push_str(rest);
+                        body.push('\n');
+                        break;
+                    }
+                },
+                None => match rest.find("<!--") {
+                    Some(start) => {
+                        kept.push_str(&rest[..start]);
+                        comment = Some(String::new());
+                        rest = &rest[start + 4..];
+                    }
+                    None => {
+                        kept.push_str(rest);
+                        break;
+                    }
+                },
+            }
+        }
+        if !kept.trim().is_empty() || line.trim().is_empty() {
+            out.push(kept.trim_end().to_string());
+        }
+    }
+    (out.join("\n"), comments)
+}
+
+/// `line` with its bullet marker replaced by what `marker` returns for it;
+/// other lines unchanged.
+fn convert_bullet<'a>(line: &str, mut marker: impl FnMut(&str) -> &'a str) -> String {
+    match BULLET.captures(line) {
+        Some(caps) if !is_ruler(line) => {
+            let end = caps.get(0).unwrap().end();
+            format!("{}{} {}", &caps[1], marker(&caps[2]), &line[end..])
+        }
+        _ => line.to_string(),
+    }
+}
+
+/// `text` without the indentation all its lines share.
+fn dedent(text: &str) -> String {
+    let indent = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    text.lines()
+        .map(|l| l.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_matches('\n')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::import::tidy;
+    use crate::parser;
+    use std::path::Path;
+
+    fn content(slide: &Slide) -> String {
+        tidy(&slide.content)
+    }
+
+    fn directives(slide: &Slide) -> Vec<(&str, &str)> {
+        slide
+            .directives
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn marp_directives_notes_backgrounds_and_fragments() {
+        let source = "---\nmarp: true\ntitle: Launch\npaginate: true\nfooter: ACME\nstyle: |\n  h1 { color: red }\n---\n\n<!-- _class: invert -->\n# Launch\n\n![bg](cover.jpg)\n\n<!-- Welcome everyone -->\n\n---\n\n<!-- footer: Q3 -->\n## Plan\n\n![bg left](side.png)\n\n* one\n* two\n+ plain\n\n![w:200 Chart](chart.png)\n\n***\n\n```html\n<!-- kept -->\n---\n```\n";
+        assert_eq!(detect(source), Some(ImportFormat::Marp));
+        let deck = marp(source);
+
+        assert_eq!(
+            deck.meta,
+            [
+                ("title".to_string(), "Launch".to_string()),
+                ("@footer".to_string(), "ACME · {slide}".to_string()),
+            ]
+        );
+        assert_eq!(deck.slides.len(), 3);
+        assert_eq!(
+            directives(&deck.slides[0]),
+            [("class", "invert"), ("background", "cover.jpg")]
+        );
+        assert_eq!(content(&deck.slides[0]), "# Launch");
+        assert_eq!(deck.slides[0].notes, "Welcome everyone");
+
+        assert_eq!(directives(&deck.slides[1]), [("footer", "Q3 · {slide}")]);
+        assert_eq!(
+            content(&deck.slides[1]),
+            "## Plan\n\n+ one\n+ two\n- plain\n\n![@width:200px Chart](chart.png)\n\n![](side.png)"
+        );
+        assert_eq!(directives(&deck.slides[2]), [("footer", "Q3 · {slide}")]);
+        assert_eq!(content(&deck.slides[2]), "```html\n<!-- kept -->\n---\n```");
+        assert_eq!(deck.warnings, ["Marp directives not imported: style"]);
+
+        let presentation = parser::parse(&deck.to_markdown(), Path::new("."));
+        assert_eq!(presentation.slides.len(), 3);
+        assert_eq!(parser::compute_max_steps(&presentation.slides[1].blocks), 2);
+    }
+
+    #[test]
+    fn marp_heading_divider_splits_slides() {
+        let deck =
+            marp("---\nmarp: true\nheadingDivider: 2\n---\n\n# A\n\ntext\n\n## B\n\n### C\n");
+        let contents: Vec<&str> = deck.slides.iter().map(|s| s.content.trim()).collect();
+        assert_eq!(contents, ["# A\n\ntext", "## B\n\n### C"]);
+    }
+
+    #[test]
+    fn reveal_separators_notes_and_fragments() {
+        let source = "---\ntitle: Deep Dive\ntheme: night\nrevealOptions:\n  transition: fade\n---\n\n# Deep Dive\n\nNote: Start slow.\nMention the demo.\n\n---\n\n<!-- .slide: data-background=\"#ff0000\" data-transition=\"zoom\" -->\n## Points\n\n* always\n- step <!-- .element: class=\"fragment\" -->\n\n--\n\n## Vertical\n\n<!-- a plain comment -->\nText\n";
+        assert_eq!(detect(source), Some(ImportFormat::Reveal));
+        let deck = reveal(source);
+
+        assert_eq!(
+            deck.meta,
+            [
+                ("title".to_string(), "Deep Dive".to_string()),
+                ("@theme".to_string(), "dark".to_string()),
+                ("@transition".to_string(), "fade".to_string()),
+            ]
+        );
+        assert_eq!(deck.slides.len(), 3);
+        assert_eq!(content(&deck.slides[0]), "# Deep Dive");
+        assert_eq!(
+            tidy(&deck.slides[0].notes),
+            "Start slow.\nMention the demo."
+        );
+        assert_eq!(directives(&deck.slides[1]), [("background", "#ff0000")]);
+        assert_eq!(content(&deck.slides[1]), "## Points\n\n- always\n+ step");
+        assert_eq!(content(&deck.slides[2]), "## Vertical\n\nText");
+        assert_eq!(
+            deck.warnings,
+            ["Slide 2: transition `zoom` has no mdeck equivalent"]
+        );
+    }
+
+    #[test]
+    fn remark_properties_increments_notes_and_columns() {
+        let source = "layout: true\nclass: inverse\n\n.footer[ACME]\n\n---\n\nname: intro\n\n# Welcome\n\n???\nSmile.\n\n---\n\nexclude: true\n\n# Draft\n\n---\n\nbackground-image: url(bg.png)\n\n# Steps\n\n- first\n\n--\n\n- second\n- third\n\n--\n\n- fourth\n\n???\nPause here.\n\n---\n\n.left-column[\n- a .red[b]\n]\n.right-column[\nRight side\n]\n";
+        assert_eq!(detect(source), Some(ImportFormat::Remark));
+        let deck = remark(source);
+
+        assert_eq!(deck.slides.len(), 3);
+        assert_eq!(directives(&deck.slides[0]), [("class", "invert")]);
+        assert_eq!(content(&deck.slides[0]), "# Welcome");
+        assert_eq!(deck.slides[0].notes, "Smile.");
+
+        let steps = &deck.slides[1];
+        let mut steps_directives = directives(steps);
+        steps_directives.sort();
+        assert_eq!(
+            steps_directives,
+            [("background", "bg.png"), ("class", "invert")]
+        );
+        assert_eq!(
+            content(steps),
+            "# Steps\n\n- first\n\n+ second\n* third\n\n+ fourth"
+        );
+        assert_eq!(steps.notes, "Pause here.");
+        assert_eq!(content(&deck.slides[2]), "- a b\n\n+++\n\nRight side");
+        assert_eq!(
+            deck.warnings,
+            ["Content of remark layout slides is not imported"]
+        );
+
+        let presentation = parser::parse(&deck.to_markdown(), Path::new("."));
+        assert_eq!(presentation.slides.len(), 3);
+        assert_eq!(parser::compute_max_steps(&presentation.slides[1].blocks), 2);
+    }
+
+    #[test]
+    fn extracts_markdown_from_html_pages() {
+        let remark_page = "<html><body><textarea id=\"source\">\n    # Title\n\n    a &lt; b\n\n    ---\n\n    # Next\n    </textarea></body></html>";
+        assert_eq!(detect_html(remark_page), Some(ImportFormat::Remark));
+        assert_eq!(from_html(remark_page), "# Title\n\na < b\n\n---\n\n# Next");
+
+        let reveal_page = "<div class=\"slides\"><section data-markdown><textarea data-template>\n  ## One\n</textarea></section><section data-markdown>\n<script type=\"text/template\">\n  ## Two\n</script></section></div>";
+        assert_eq!(detect_html(reveal_page), Some(ImportFormat::Reveal));
+        assert_eq!(from_html(reveal_page), "## One\n\n---\n\n## Two");
+        assert_eq!(detect("# Just markdown\n\n---\n\nMore"), None);
+    }
+}
@@ -0,0 +1,351 @@
+//! `mdeck import` — convert a PowerPoint, Marp, reveal.js or remark deck into
+//! mdeck markdown.
+//!
+//! Each importer produces a [`Deck`]: frontmatter fields plus slides with
+//! their directives, content and speaker notes. The deck is written with
+//! explicit `---` separators, so slide boundaries survive as they were.
+
+mod markdown;
+mod pptx;
+mod xml;
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+use crate::cli::{ImportArgs, ImportFormat};
+
+/// An imported deck, ready to write as mdeck markdown.
+#[derive(Debug, Default)]
+struct Deck {
+    /// Frontmatter fields in output order, e.g. `("title", "Roadmap")`.
+    meta: Vec<(String, String)>,
+    slides: Vec<Slide>,
+    /// What could not be carried over, reported after the import.
+    warnings: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Slide {
+    /// Slide directives, e.g. `("background", "#1e1e2e")`.
+    directives: Vec<(String, String)>,
+    content: String,
+    notes: String,
+}
+
+impl Deck {
+    fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let slide_level = self.slide_level();
+        if !self.meta.is_empty() || slide_level.is_some() {
+            out.push_str("---\n");
+            for (key, value) in &self.meta {
+                let _ = writeln!(out, "{key}: {}", yaml_string(value));
+            }
+            if let Some(level) = slide_level {
+                let _ = writeln!(out, "@slide-level: {level}");
+            }
+            out.push_str("---\n\n");
+        }
+        let slides: Vec<String> = self.slides.iter().map(Slide::to_markdown).collect();
+        out.push_str(&slides.join("\n\n---\n\n"));
+        out.push('\n');
+        out
+    }
+
+    /// The `@slide-level` that keeps headings inside a slide from starting
+    /// new slides, when the inferred level would split them.
+    fn slide_level(&self) -> Option<u8> {
+        let inner = self
+            .slides
+            .iter()
+            .filter_map(|slide| inner_heading_level(&slide.to_markdown()))
+            .min()?;
+        (inner <= 2).then(|| inner - 1)
+    }
+}
+
+impl Slide {
+    fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        for (key, value) in &self.directives {
+            let _ = writeln!(out, "@{key}: {value}");
+        }
+        let content = tidy(&self.content);
+        if !content.is_empty() {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&content);
+        }
+        let notes = tidy(&self.notes);
+        if !notes.is_empty() {
+            out.push_str("\n\n???\n\n");
+            out.push_str(&notes);
+        }
+        out.trim().to_string()
+    }
+}
+
+/// Trim `text` and collapse blank-line runs outside code fences, so a gap
+/// never reads as a slide break.
+fn tidy(text: &str) -> String {
+    let mut out: Vec<&str> = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in text.trim().lines() {
+        let trimmed = line.trim();
+        match fence {
+            Some(open) if trimmed.starts_with(open) => fence = None,
+            Some(_) => {}
+            None if trimmed.starts_with("```") || trimmed.starts_with("~~~") => {
+                fence = Some(&trimmed[..3]);
+            }
+            None if trimmed.is_empty() && out.last().is_some_and(|l| l.trim().is_empty()) => {
+                continue;
+            }
+            None => {}
+        }
+        out.push(line.trim_end());
+    }
+    out.join("\n")
+}
+
+/// The smallest level of a heading that follows other content in `text`:
+/// one the splitter would start a new slide at.
+fn inner_heading_level(text: &str) -> Option<u8> {
+    let mut has_content = false;
+    let mut in_fence = false;
+    let mut level = None;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            has_content = true;
+            continue;
+        }
+        if in_fence || trimmed.is_empty() || (trimmed.starts_with('@') && trimmed.contains(':')) {
+            continue;
+        }
+        let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+        if has_content && (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            let hashes = hashes as u8;
+            level = Some(level.map_or(hashes, |l: u8| l.min(hashes)));
+        }
+        has_content = true;
+    }
+    level
+}
+
+/// A frontmatter value, double-quoted when YAML would read it as something
+/// other than the same string. (Frontmatter with `@` keys is read line by
+/// line, which strips the quotes but does not unescape.)
+fn yaml_string(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value.trim() == value
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.starts_with(|c| "!&*[]{}|>'\"%@`#,?-:".contains(c));
+    if plain {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// Convert `file` into an mdeck deck. The format is detected from the
+/// extension and content unless given; PowerPoint images are extracted to a
+/// `<deck>-images` folder next to the output.
+pub fn run(args: ImportArgs) -> Result<()> {
+    let ImportArgs {
+        file,
+        output,
+        from,
+        force,
+    } = args;
+
+    if !file.exists() {
+        anyhow::bail!("File not found: {}", file.display());
+    }
+    let output = output.unwrap_or_else(|| default_output(&file));
+    if output.exists() && !force {
+        anyhow::bail!(
+            "{} already exists; use --force to overwrite it",
+            output.display()
+        );
+    }
+
+    let format = match from {
+        Some(format) => format,
+        None => detect(&file)?,
+    };
+
+    let deck = match format {
+        ImportFormat::Pptx => {
+            let stem = output
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            let images = format!("{stem}-images");
+            let images_dir = output.parent().unwrap_or(Path::new(".")).join(&images);
+            pptx::import(&file, &images_dir, &images)?
+        }
+        ImportFormat::Marp => markdown::marp(&read_source(&file)?),
+        ImportFormat::Reveal => markdown::reveal(&read_source(&file)?),
+        ImportFormat::Remark => markdown::remark(&read_source(&file)?),
+    };
+
+    for warning in &deck.warnings {
+        eprintln!("  {} {warning}", "!".yellow().bold());
+    }
+    if deck.slides.is_empty() {
+        anyhow::bail!("No slides found in {}", file.display());
+    }
+    std::fs::write(&output, deck.to_markdown())
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    eprintln!(
+        "Imported {} slides from {} to {}",
+        deck.slides.len(),
+        file.display(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// `<stem>.md` next to the input, or `<stem>-mdeck.md` when the input is
+/// that file already.
+fn default_output(file: &Path) -> PathBuf {
+    let output = file.with_extension("md");
+    if output == file {
+        let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+        file.with_file_name(format!("{stem}-mdeck.md"))
+    } else {
+        output
+    }
+}
+
+/// The kind of deck `file` holds, from its extension and content.
+fn detect(file: &Path) -> Result<ImportFormat> {
+    if has_extension(file, &["pptx"]) {
+        return Ok(ImportFormat::Pptx);
+    }
+    let text = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let format = if is_html(file) {
+        markdown::detect_html(&text)
+    } else {
+        markdown::detect(&text)
+    };
+    format.with_context(|| {
+        format!(
+            "Could not tell which kind of deck {} is; pass --from marp, reveal or remark",
+            file.display()
+        )
+    })
+}
+
+/// The markdown of a Marp, reveal.js or remark deck, taken out of its HTML
+/// page when `file` is one.
+fn read_source(file: &Path) -> Result<String> {
+    let text = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    Ok(if is_html(file) {
+        markdown::from_html(&text)
+    } else {
+        text
+    })
+}
+
+fn is_html(file: &Path) -> bool {
+    has_extension(file, &["html", "htm"])
+}
+
+fn has_extension(file: &Path, extensions: &[&str]) -> bool {
+    file.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| extensions.iter().any(|x| e.eq_ignore_ascii_case(x)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    fn slide(content: &str, notes: &str) -> Slide {
+        Slide {
+            content: content.to_string(),
+            notes: notes.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn writes_frontmatter_directives_separators_and_notes() {
+        let deck = Deck {
+            meta: vec![
+                ("title".into(), "Q3: Roadmap".into()),
+                ("@aspect".into(), "4:3".into()),
+            ],
+            slides: vec![
+                Slide {
+                    directives: vec![("background".into(), "#102030".into())],
+                    ..slide("# Roadmap\n\n\n\n\nNext year", "Say hi")
+                },
+                slide("- a\n- b", ""),
+            ],
+            warnings: Vec::new(),
+        };
+        let markdown = deck.to_markdown();
+        assert_eq!(
+            markdown,
+            "---\ntitle: \"Q3: Roadmap\"\n@aspect: 4:3\n---\n\n@background: #102030\n\n# Roadmap\n\nNext year\n\n???\n\nSay hi\n\n---\n\n- a\n- b\n"
+        );
+
+        let presentation = parser::parse(&markdown, Path::new("."));
+        assert_eq!(presentation.meta.title.as_deref(), Some("Q3: Roadmap"));
+        assert_eq!(presentation.slides.len(), 2);
+        assert_eq!(presentation.slides[0].notes.as_deref(), Some("Say hi"));
+    }
+
+    #[test]
+    fn headings_inside_slides_lower_the_slide_level() {
+        let deck = Deck {
+            slides: vec![
+                slide("# One\n\n```\n# not a heading\n```", ""),
+                slide("## Two\n\ntext\n\n## Inner", ""),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(deck.slide_level(), Some(1));
+        let presentation = parser::parse(&deck.to_markdown(), Path::new("."));
+        assert_eq!(presentation.slides.len(), 2);
+
+        let deck = Deck {
+            slides: vec![slide("Intro\n\n# Inner", "")],
+            ..Default::default()
+        };
+        assert_eq!(deck.slide_level(), Some(0));
+        let presentation = parser::parse(&deck.to_markdown(), Path::new("."));
+        assert_eq!(presentation.slides.len(), 1);
+
+        let deck = Deck {
+            slides: vec![slide("# One\n\n### Minor", "")],
+            ..Default::default()
+        };
+        assert_eq!(deck.slide_level(), None);
+    }
+
+    #[test]
+    fn default_output_avoids_the_input() {
+        assert_eq!(
+            default_output(Path::new("talks/deck.pptx")),
+            Path::new("talks/deck.md")
+        );
+        assert_eq!(
+            default_output(Path::new("talks/deck.md")),
+            Path::new("talks/deck-mdeck.md")
+        );
+    }
+}
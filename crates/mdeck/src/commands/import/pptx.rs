@@ -0,0 +1,917 @@
+//! PowerPoint import. Slide titles become headings, text becomes paragraphs
+//! and nested lists with bold, italic, code and links kept, tables become
+//! markdown tables, pictures are extracted to an images folder, and the
+//! notes pane becomes speaker notes. Two-content layouts become columns.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use super::xml::{self, Element};
+use super::{Deck, Slide};
+use crate::theme::{self, Theme};
+
+/// Typefaces whose runs are read as code.
+const MONO_TYPEFACES: &[&str] = &["mono", "consolas", "courier", "menlo", "monaco"];
+
+/// Placeholders that repeat the deck's footer, date and slide number.
+const FOOTER_PLACEHOLDERS: &[&str] = &["dt", "ftr", "sldNum", "hdr", "sldImg"];
+
+/// Layouts whose two body placeholders sit side by side.
+const TWO_COLUMN_LAYOUTS: &[&str] = &["twoObj", "twoTxTwoObj", "twoColTx", "twoObjAndTx"];
+
+/// Convert the deck at `path`, writing its pictures to `images_dir`, which
+/// the markdown links to as `images_link`.
+pub fn import(path: &Path, images_dir: &Path, images_link: &str) -> Result<Deck> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open PowerPoint file: {}", path.display()))?;
+    let archive = zip::ZipArchive::new(file)
+        .with_context(|| format!("Failed to read PowerPoint file as ZIP: {}", path.display()))?;
+    let mut importer = Importer::new(archive, images_link);
+    let deck = importer
+        .deck()
+        .with_context(|| format!("Not a PowerPoint presentation: {}", path.display()))?;
+
+    if !importer.media.is_empty() {
+        std::fs::create_dir_all(images_dir)
+            .with_context(|| format!("Failed to create {}", images_dir.display()))?;
+        for (name, bytes) in &importer.media {
+            let path = images_dir.join(name);
+            std::fs::write(&path, bytes)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+    }
+    Ok(deck)
+}
+
+/// A relationship from one package part to another, or to a URL.
+struct Rel {
+    kind: String,
+    target: String,
+    external: bool,
+}
+
+struct Importer<R> {
+    archive: zip::ZipArchive<R>,
+    images_link: String,
+    /// Extracted pictures as (file name, bytes).
+    media: Vec<(String, Vec<u8>)>,
+    /// Media part → markdown link, so a picture used twice is written once.
+    links: HashMap<String, String>,
+    warnings: Vec<String>,
+}
+
+impl<R: Read + Seek> Importer<R> {
+    fn new(archive: zip::ZipArchive<R>, images_link: &str) -> Self {
+        Self {
+            archive,
+            images_link: images_link.to_string(),
+            media: Vec::new(),
+            links: HashMap::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn bytes(&mut self, part: &str) -> Option<Vec<u8>> {
+        let mut entry = self.archive.by_name(part).ok()?;
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).ok()?;
+        Some(bytes)
+    }
+
+    fn xml(&mut self, part: &str) -> Option<Element> {
+        let bytes = self.bytes(part)?;
+        xml::parse(&String::from_utf8_lossy(&bytes))
+    }
+
+    /// The relationships of `part`, by id.
+    fn rels(&mut self, part: &str) -> HashMap<String, Rel> {
+        let (dir, name) = part.rsplit_once('/').unwrap_or(("", part));
+        let rels_part = match dir {
+            "" => format!("_rels/{name}.rels"),
+            dir => format!("{dir}/_rels/{name}.rels"),
+        };
+        let Some(root) = self.xml(&rels_part) else {
+            return HashMap::new();
+        };
+        let mut found = Vec::new();
+        root.find_all("Relationship", &mut found);
+        found
+            .into_iter()
+            .filter_map(|rel| {
+                let id = rel.attr("Id")?.to_string();
+                let target = rel.attr("Target")?;
+                let external = rel.attr("TargetMode") == Some("External");
+                let kind = rel.attr("Type")?.rsplit('/').next()?.to_string();
+                let target = if external {
+                    target.to_string()
+                } else {
+                    resolve(dir, target)
+                };
+                Some((
+                    id,
+                    Rel {
+                        kind,
+                        target,
+                        external,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// The part `part` relates to with a relationship of `kind`.
+    fn related(&mut self, part: &str, kind: &str) -> Option<String> {
+        self.rels(part)
+            .into_values()
+            .find(|rel| rel.kind == kind && !rel.external)
+            .map(|rel| rel.target)
+    }
+
+    fn deck(&mut self) -> Option<Deck> {
+        let presentation_part = self
+            .related("", "officeDocument")
+            .unwrap_or_else(|| "ppt/presentation.xml".to_string());
+        let presentation = self.xml(&presentation_part)?;
+        let rels = self.rels(&presentation_part);
+
+        let mut deck = Deck::default();
+        if let Some(core) = self
+            .related("", "core-properties")
+            .and_then(|part| self.xml(&part))
+        {
+            for (field, key) in [("dc:title", "title"), ("dc:creator", "author")] {
+                let value = core.child(field).map(|el| el.text()).unwrap_or_default();
+                if !value.trim().is_empty() {
+                    deck.meta.push((key.to_string(), value.trim().to_string()));
+                }
+            }
+        }
+        let size = presentation.child("p:sldSz").and_then(|sz| {
+            Some((
+                sz.attr("cx")?.parse::<i64>().ok()?,
+                sz.attr("cy")?.parse::<i64>().ok()?,
+            ))
+        });
+        if let Some(aspect) = size.and_then(|(cx, cy)| aspect(cx, cy)) {
+            deck.meta.push(("@aspect".to_string(), aspect));
+        }
+
+        let slide_parts: Vec<String> = presentation
+            .child("p:sldIdLst")
+            .into_iter()
+            .flat_map(|list| list.elements())
+            .filter_map(|id| rels.get(id.attr("r:id")?))
+            .map(|rel| rel.target.clone())
+            .collect();
+        let width = size.map_or(0, |(cx, _)| cx);
+        for (idx, part) in slide_parts.iter().enumerate() {
+            if let Some(slide) = self.slide(part, idx + 1, width) {
+                deck.slides.push(slide);
+            }
+        }
+        hoist_background(&mut deck);
+        deck.warnings = std::mem::take(&mut self.warnings);
+        Some(deck)
+    }
+
+    fn slide(&mut self, part: &str, number: usize, width: i64) -> Option<Slide> {
+        let root = self.xml(part)?;
+        let rels = self.rels(part);
+        let layout_part = self.related(part, "slideLayout");
+        let layout = layout_part.as_deref().and_then(|p| self.xml(p));
+        let layout_type = layout
+            .as_ref()
+            .and_then(|l| l.attr("type"))
+            .unwrap_or("obj")
+            .to_string();
+        let mut positions = Vec::new();
+        if let Some(layout_part) = &layout_part {
+            if let Some(layout) = &layout {
+                placeholder_positions(layout, &mut positions);
+            }
+            if let Some(master) = self
+                .related(layout_part, "slideMaster")
+                .and_then(|p| self.xml(&p))
+            {
+                placeholder_positions(&master, &mut positions);
+            }
+        }
+
+        let mut slide = Slide::default();
+        let c_sld = root.child("p:cSld")?;
+        if let Some(bg) = c_sld.path(&["p:bg", "p:bgPr"]) {
+            let color = bg
+                .path(&["a:solidFill", "a:srgbClr"])
+                .and_then(|c| c.attr("val"));
+            let image = bg
+                .path(&["a:blipFill", "a:blip"])
+                .and_then(|b| b.attr("r:embed"));
+            if let Some(color) = color {
+                slide
+                    .directives
+                    .push(("background".to_string(), format!("#{color}")));
+            } else if let Some(link) = image.and_then(|id| self.image(&rels, id, number)) {
+                slide.directives.push(("background".to_string(), link));
+            }
+        }
+
+        let mut shapes = Vec::new();
+        if let Some(tree) = c_sld.child("p:spTree") {
+            self.shapes(tree, &rels, &positions, number, &mut shapes);
+        }
+        let mut content = Vec::new();
+        let (titles, mut body): (Vec<Shape>, Vec<Shape>) =
+            shapes.into_iter().partition(|s| s.kind == ShapeKind::Title);
+        let mut titles = titles.into_iter();
+        if let Some(title) = titles.next() {
+            let centered = title.centered || matches!(layout_type.as_str(), "title" | "secHead");
+            let hashes = if centered { "#" } else { "##" };
+            content.push(format!("{hashes} {}", title.markdown));
+        }
+        body.extend(titles);
+        body.sort_by_key(|s| (s.y, s.x));
+
+        let (left, right): (Vec<&Shape>, Vec<&Shape>) = body.iter().partition(|s| s.x < width / 2);
+        if TWO_COLUMN_LAYOUTS.contains(&layout_type.as_str())
+            && !left.is_empty()
+            && !right.is_empty()
+        {
+            content.extend(left.iter().map(|s| s.markdown.clone()));
+            content.push("+++".to_string());
+            content.extend(right.iter().map(|s| s.markdown.clone()));
+        } else {
+            content.extend(body.iter().map(|s| s.markdown.clone()));
+        }
+        slide.content = content.join("\n\n");
+
+        if let Some(notes) = self
+            .related(part, "notesSlide")
+            .and_then(|p| Some((self.xml(&p)?, self.rels(&p))))
+        {
+            let (notes, rels) = notes;
+            let mut bodies = Vec::new();
+            notes.find_all("p:sp", &mut bodies);
+            let text: Vec<String> = bodies
+                .into_iter()
+                .filter(|sp| placeholder(sp).is_some_and(|(kind, _)| kind == "body"))
+                .filter_map(|sp| sp.child("p:txBody"))
+                .map(|body| text_body(body, &rels, false))
+                .collect();
+            slide.notes = text.join("\n\n");
+        }
+        Some(slide)
+    }
+
+    /// Collect the shapes under `tree`, descending into groups.
+    fn shapes(
+        &mut self,
+        tree: &Element,
+        rels: &HashMap<String, Rel>,
+        positions: &[Position],
+        number: usize,
+        shapes: &mut Vec<Shape>,
+    ) {
+        for el in tree.elements() {
+            let offset = el
+                .find("a:off")
+                .and_then(|off| Some((off.attr("y")?.parse().ok()?, off.attr("x")?.parse().ok()?)));
+            match el.name.as_str() {
+                "p:grpSp" => self.shapes(el, rels, positions, number, shapes),
+                "p:sp" => {
+                    let ph = placeholder(el);
+                    if ph.is_some_and(|(kind, _)| FOOTER_PLACEHOLDERS.contains(&kind)) {
+                        continue;
+                    }
+                    let Some(body) = el.child("p:txBody") else {
+                        continue;
+                    };
+                    let (y, x) = offset
+                        .or_else(|| ph.and_then(|ph| inherited_position(positions, ph)))
+                        .unwrap_or_default();
+                    let kind = match ph {
+                        Some(("title" | "ctrTitle", _)) => ShapeKind::Title,
+                        Some(("subTitle", _)) | None => ShapeKind::Text,
+                        Some(_) => ShapeKind::Body,
+                    };
+                    let markdown = match kind {
+                        ShapeKind::Title => paragraphs(body)
+                            .map(|p| runs(p, rels))
+                            .filter(|t| !t.is_empty())
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                        ShapeKind::Text if is_code(body) => code_block(body),
+                        _ => text_body(body, rels, kind == ShapeKind::Body),
+                    };
+                    if !markdown.is_empty() {
+                        shapes.push(Shape {
+                            kind,
+                            centered: ph.is_some_and(|(kind, _)| kind == "ctrTitle"),
+                            x,
+                            y,
+                            markdown,
+                        });
+                    }
+                }
+                "p:pic" => {
+                    let alt = el
+                        .path(&["p:nvPicPr", "p:cNvPr"])
+                        .and_then(|pr| pr.attr("descr"))
+                        .unwrap_or_default()
+                        .replace(['\n', '[', ']'], " ");
+                    let link = el
+                        .path(&["p:blipFill", "a:blip"])
+                        .and_then(|blip| blip.attr("r:embed"))
+                        .and_then(|id| self.image(rels, id, number));
+                    let (y, x) = offset.unwrap_or_default();
+                    if let Some(link) = link {
+                        shapes.push(Shape {
+                            kind: ShapeKind::Body,
+                            centered: false,
+                            x,
+                            y,
+                            markdown: format!("![{}]({link})", alt.trim()),
+                        });
+                    }
+                }
+                "p:graphicFrame" => {
+                    let (y, x) = offset.unwrap_or_default();
+                    if let Some(table) = el.find("a:tbl") {
+                        shapes.push(Shape {
+                            kind: ShapeKind::Body,
+                            centered: false,
+                            x,
+                            y,
+                            markdown: table_markdown(table, rels),
+                        });
+                    } else if el.find("c:chart").is_some() {
+                        self.warnings
+                            .push(format!("Slide {number}: charts are not imported"));
+                    } else {
+                        self.warnings.push(format!(
+                            "Slide {number}: SmartArt and embedded objects are not imported"
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Extract the picture relationship `id` points at, returning its link.
+    fn image(&mut self, rels: &HashMap<String, Rel>, id: &str, number: usize) -> Option<String> {
+        let rel = rels.get(id).filter(|rel| !rel.external)?;
+        if let Some(link) = self.links.get(&rel.target) {
+            return Some(link.clone());
+        }
+        let bytes = self.bytes(&rel.target)?;
+        let name = rel.target.rsplit('/').next()?.to_string();
+        let extension = name.rsplit('.').next().unwrap_or_default().to_lowercase();
+        if !matches!(
+            extension.as_str(),
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg" | "bmp"
+        ) {
+            self.warnings.push(format!(
+                "Slide {number}: {name} is in a format mdeck cannot show; convert it to PNG"
+            ));
+        }
+        let link = format!("{}/{name}", self.images_link);
+        self.media.push((name, bytes));
+        self.links.insert(rel.target.clone(), link.clone());
+        Some(link)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ShapeKind {
+    Title,
+    /// A body or content placeholder: paragraphs are bulleted by default.
+    Body,
+    /// A subtitle, text box or other shape with text.
+    Text,
+}
+
+struct Shape {
+    kind: ShapeKind,
+    centered: bool,
+    x: i64,
+    y: i64,
+    markdown: String,
+}
+
+/// Drop a solid background color every slide shares, setting the dark
+/// theme when it is dark: mdeck has no deck-wide background.
+fn hoist_background(deck: &mut Deck) {
+    let background = |slide: &Slide| {
+        slide
+            .directives
+            .iter()
+            .find(|(key, _)| key == "background")
+            .map(|(_, value)| value.clone())
+    };
+    let Some(shared) = deck.slides.first().and_then(background) else {
+        return;
+    };
+    let Some(color) = theme::parse_hex_color(&shared) else {
+        return;
+    };
+    if deck
+        .slides
+        .iter()
+        .any(|s| background(s).as_ref() != Some(&shared))
+    {
+        return;
+    }
+    for slide in &mut deck.slides {
+        slide.directives.retain(|(key, _)| key != "background");
+    }
+    let theme = Theme {
+        background: color,
+        ..Theme::light()
+    };
+    if !theme.is_light() {
+        deck.meta.push(("@theme".to_string(), "dark".to_string()));
+    }
+}
+
+/// A layout or master placeholder's type, index and (y, x) offset.
+type Position = (String, Option<String>, (i64, i64));
+
+/// The type (`obj` when unset) and index of the placeholder `shape` fills.
+fn placeholder(shape: &Element) -> Option<(&str, Option<&str>)> {
+    let ph = shape
+        .elements()
+        .find(|el| el.name.starts_with("p:nv"))?
+        .path(&["p:nvPr", "p:ph"])?;
+    Some((ph.attr("type").unwrap_or("obj"), ph.attr("idx")))
+}
+
+fn placeholder_positions(root: &Element, positions: &mut Vec<Position>) {
+    let mut shapes = Vec::new();
+    root.find_all("p:sp", &mut shapes);
+    for shape in shapes {
+        let Some((kind, idx)) = placeholder(shape) else {
+            continue;
+        };
+        let offset = shape
+            .find("a:off")
+            .and_then(|off| Some((off.attr("y")?.parse().ok()?, off.attr("x")?.parse().ok()?)));
+        if let Some(offset) = offset {
+            positions.push((kind.to_string(), idx.map(String::from), offset));
+        }
+    }
+}
+
+/// Where a placeholder without its own offset sits: the layout's (or
+/// master's) placeholder with the same index, or else the same type.
+fn inherited_position(
+    positions: &[Position],
+    (kind, idx): (&str, Option<&str>),
+) -> Option<(i64, i64)> {
+    let same_type = |k: &str| k == kind || (k == "title" && kind == "ctrTitle");
+    idx.and_then(|idx| positions.iter().find(|(_, i, _)| i.as_deref() == Some(idx)))
+        .or_else(|| positions.iter().find(|(k, _, _)| same_type(k)))
+        .map(|(_, _, offset)| *offset)
+}
+
+fn paragraphs(body: &Element) -> impl Iterator<Item = &Element> {
+    body.elements().filter(|el| el.name == "a:p")
+}
+
+/// Text paragraphs as markdown: bulleted and numbered paragraphs become
+/// list items nested by their level, the rest become paragraphs.
+fn text_body(body: &Element, rels: &HashMap<String, Rel>, bulleted: bool) -> String {
+    let mut out = String::new();
+    let mut in_list = false;
+    let mut numbers = [0usize; 9];
+    for p in paragraphs(body) {
+        let text = runs(p, rels);
+        if text.is_empty() {
+            in_list = false;
+            continue;
+        }
+        let props = p.child("a:pPr");
+        let level = props
+            .and_then(|pr| pr.attr("lvl"))
+            .and_then(|l| l.parse::<usize>().ok())
+            .unwrap_or(0)
+            .min(numbers.len() - 1);
+        let has = |name: &str| props.is_some_and(|pr| pr.child(name).is_some());
+        let numbered = has("a:buAutoNum");
+        let bullet = !has("a:buNone") && (bulleted || numbered || has("a:buChar"));
+
+        if bullet {
+            if !in_list && !out.is_empty() {
+                out.push_str("\n\n");
+            } else if in_list {
+                out.push('\n');
+            }
+            numbers[level + 1..].fill(0);
+            let marker = if numbered {
+                numbers[level] += 1;
+                format!("{}.", numbers[level])
+            } else {
+                numbers[level] = 0;
+                "-".to_string()
+            };
+            out.push_str(&format!("{}{marker} {text}", "  ".repeat(level)));
+        } else {
+            if !out.is_empty() {
+                out.push_str("\n\n");
+            }
+            numbers = [0; 9];
+            out.push_str(&text);
+        }
+        in_list = bullet;
+    }
+    out
+}
+
+/// Whether every run with text is set in a monospaced typeface, as code
+/// exported to PowerPoint is.
+fn is_code(body: &Element) -> bool {
+    let mut runs = Vec::new();
+    body.find_all("a:r", &mut runs);
+    let mut texts = runs
+        .iter()
+        .filter(|r| !r.text().trim().is_empty())
+        .peekable();
+    texts.peek().is_some() && texts.all(|r| run_style(r).code)
+}
+
+fn code_block(body: &Element) -> String {
+    let lines: Vec<String> = paragraphs(body).map(|p| p.text()).collect();
+    format!("```\n{}\n```", lines.join("\n").trim_end())
+}
+
+fn table_markdown(table: &Element, rels: &HashMap<String, Rel>) -> String {
+    let rows: Vec<Vec<String>> = table
+        .elements()
+        .filter(|el| el.name == "a:tr")
+        .map(|row| {
+            row.elements()
+                .filter(|el| el.name == "a:tc")
+                .map(|cell| {
+                    let text: Vec<String> = cell
+                        .child("a:txBody")
+                        .into_iter()
+                        .flat_map(paragraphs)
+                        .map(|p| runs(p, rels))
+                        .filter(|t| !t.is_empty())
+                        .collect();
+                    text.join(" ").replace('|', "/")
+                })
+                .collect()
+        })
+        .collect();
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let line = |cells: &[String]| {
+        let cells: Vec<&str> = (0..columns)
+            .map(|i| cells.get(i).map_or("", String::as_str))
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let mut out = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        out.push(line(row));
+        if i == 0 {
+            out.push(format!("|{}", " --- |".repeat(columns)));
+        }
+    }
+    out.join("\n")
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+struct RunStyle {
+    bold: bool,
+    italic: bool,
+    strike: bool,
+    code: bool,
+}
+
+fn run_style(run: &Element) -> RunStyle {
+    let Some(props) = run.child("a:rPr") else {
+        return RunStyle::default();
+    };
+    let on = |name: &str| matches!(props.attr(name), Some("1" | "true"));
+    RunStyle {
+        bold: on("b"),
+        italic: on("i"),
+        strike: props.attr("strike").is_some_and(|s| s != "noStrike"),
+        code: props
+            .child("a:latin")
+            .and_then(|latin| latin.attr("typeface"))
+            .is_some_and(|face| {
+                let face = face.to_lowercase();
+                MONO_TYPEFACES.iter().any(|mono| face.contains(mono))
+            }),
+    }
+}
+
+/// A paragraph's runs as inline markdown, adjacent runs with the same style
+/// merged.
+fn runs(p: &Element, rels: &HashMap<String, Rel>) -> String {
+    let mut spans: Vec<(String, RunStyle, Option<String>)> = Vec::new();
+    for el in p.elements() {
+        let (text, style, link) = match el.name.as_str() {
+            "a:r" | "a:fld" => {
+                let link = el
+                    .path(&["a:rPr", "a:hlinkClick"])
+                    .and_then(|h| rels.get(h.attr("r:id")?))
+                    .filter(|rel| rel.external)
+                    .map(|rel| rel.target.clone());
+                let text = el.child("a:t").map(|t| t.text()).unwrap_or_default();
+                (text, run_style(el), link)
+            }
+            "a:br" => (" ".to_string(), RunStyle::default(), None),
+            _ => continue,
+        };
+        match spans.last_mut() {
+            Some((last, s, l)) if *s == style && *l == link => last.push_str(&text),
+            _ => spans.push((text, style, link)),
+        }
+    }
+
+    let mut out = String::new();
+    for (text, style, link) in spans {
+        let core = text.trim();
+        if core.is_empty() {
+            out.push_str(&text);
+            continue;
+        }
+        let start = text.len() - text.trim_start().len();
+        let mut inline = if style.code {
+            format!("`{core}`")
+        } else {
+            let mut s = core.to_string();
+            if style.strike {
+                s = format!("~~{s}~~");
+            }
+            if style.italic {
+                s = format!("*{s}*");
+            }
+            if style.bold {
+                s = format!("**{s}**");
+            }
+            s
+        };
+        if let Some(url) = link {
+            inline = format!("[{inline}]({url})");
+        }
+        out.push_str(&text[..start]);
+        out.push_str(&inline);
+        out.push_str(&text[start + core.len()..]);
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `target` relative to the package directory `dir`, with `..` resolved.
+fn resolve(dir: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = match target.strip_prefix('/') {
+        Some(_) => Vec::new(),
+        None => dir.split('/').filter(|p| !p.is_empty()).collect(),
+    };
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// The `@aspect` for a slide size in EMU, or `None` for the default 16:9.
+fn aspect(cx: i64, cy: i64) -> Option<String> {
+    if cx <= 0 || cy <= 0 {
+        return None;
+    }
+    let ratio = cx as f64 / cy as f64;
+    let known = [(16, 9), (4, 3), (16, 10)]
+        .into_iter()
+        .find(|(w, h)| (ratio - *w as f64 / *h as f64).abs() < 0.01);
+    match known {
+        Some((16, 9)) => None,
+        Some((w, h)) => Some(format!("{w}:{h}")),
+        None => {
+            let gcd = gcd(cx, cy);
+            Some(format!("{}:{}", cx / gcd, cy / gcd))
+        }
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+
+    const NS: &str = r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main""#;
+    const REL: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+    fn rels(entries: &[(&str, &str, &str)]) -> String {
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+        for (id, kind, target) in entries {
+            let mode = if target.starts_with("http") {
+                r#" TargetMode="External""#
+            } else {
+                ""
+            };
+            xml.push_str(&format!(
+                r#"<Relationship Id="{id}" Type="{REL}/{kind}" Target="{target}"{mode}/>"#
+            ));
+        }
+        xml + "</Relationships>"
+    }
+
+    fn placeholder_shape(ph: &str, off: Option<(i64, i64)>, paragraphs: &str) -> String {
+        let xfrm = off.map_or(String::new(), |(x, y)| {
+            format!(r#"<a:xfrm><a:off x="{x}" y="{y}"/><a:ext cx="1" cy="1"/></a:xfrm>"#)
+        });
+        format!(
+            r#"<p:sp><p:nvSpPr><p:cNvPr id="2" name="s"/><p:cNvSpPr/><p:nvPr>{ph}</p:nvPr></p:nvSpPr><p:spPr>{xfrm}</p:spPr><p:txBody><a:bodyPr/>{paragraphs}</p:txBody></p:sp>"#
+        )
+    }
+
+    fn slide_xml(shapes: &str) -> String {
+        format!(
+            r#"<p:sld {NS}><p:cSld><p:bg><p:bgPr><a:solidFill><a:srgbClr val="102030"/></a:solidFill></p:bgPr></p:bg><p:spTree>{shapes}</p:spTree></p:cSld></p:sld>"#
+        )
+    }
+
+    /// A two-slide deck: a title slide, and a two-content slide with
+    /// bullets, a link, code, a picture, a table and notes.
+    fn sample_pptx() -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let mut add = |name: &str, content: &[u8]| {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content).unwrap();
+        };
+        add(
+            "_rels/.rels",
+            rels(&[
+                ("rId1", "officeDocument", "ppt/presentation.xml"),
+                ("rId2", "metadata/core-properties", "docProps/core.xml"),
+            ])
+            .as_bytes(),
+        );
+        add(
+            "docProps/core.xml",
+            br#"<cp:coreProperties xmlns:cp="cp" xmlns:dc="dc"><dc:title>Quarterly Review</dc:title><dc:creator>Ana</dc:creator></cp:coreProperties>"#,
+        );
+        add(
+            "ppt/presentation.xml",
+            format!(
+                r#"<p:presentation {NS}><p:sldIdLst><p:sldId id="257" r:id="rId3"/><p:sldId id="256" r:id="rId2"/></p:sldIdLst><p:sldSz cx="9144000" cy="6858000"/></p:presentation>"#
+            )
+            .as_bytes(),
+        );
+        add(
+            "ppt/_rels/presentation.xml.rels",
+            rels(&[
+                ("rId2", "slide", "slides/slide2.xml"),
+                ("rId3", "slide", "slides/slide1.xml"),
+            ])
+            .as_bytes(),
+        );
+        add(
+            "ppt/slides/slide1.xml",
+            slide_xml(&format!(
+                "{}{}",
+                placeholder_shape(
+                    r#"<p:ph type="ctrTitle"/>"#,
+                    None,
+                    "<a:p><a:r><a:t>Quarterly</a:t></a:r><a:br/><a:r><a:t>Review</a:t></a:r></a:p>"
+                ),
+                placeholder_shape(
+                    r#"<p:ph type="subTitle" idx="1"/>"#,
+                    None,
+                    "<a:p><a:r><a:t>Q3 2026</a:t></a:r></a:p>"
+                ),
+            ))
+            .as_bytes(),
+        );
+        add(
+            "ppt/slides/_rels/slide1.xml.rels",
+            rels(&[("rId1", "slideLayout", "../slideLayouts/slideLayout1.xml")]).as_bytes(),
+        );
+        add(
+            "ppt/slideLayouts/slideLayout1.xml",
+            format!(r#"<p:sldLayout {NS} type="title"/>"#).as_bytes(),
+        );
+        add(
+            "ppt/slides/slide2.xml",
+            slide_xml(&format!(
+                r#"{}{}{}<p:sp><p:nvSpPr><p:cNvPr id="5" name="code"/><p:cNvSpPr txBox="1"/><p:nvPr/></p:nvSpPr><p:spPr><a:xfrm><a:off x="100" y="5000000"/></a:xfrm></p:spPr><p:txBody><a:p><a:r><a:rPr><a:latin typeface="Consolas"/></a:rPr><a:t>fn main() {{</a:t></a:r></a:p><a:p><a:r><a:rPr><a:latin typeface="Consolas"/></a:rPr><a:t>    run();</a:t></a:r></a:p><a:p><a:r><a:rPr><a:latin typeface="Consolas"/></a:rPr><a:t>}}</a:t></a:r></a:p></p:txBody></p:sp><p:pic><p:nvPicPr><p:cNvPr id="6" name="Picture" descr="Revenue chart"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rId3"/></p:blipFill><p:spPr><a:xfrm><a:off x="5000000" y="3000000"/></a:xfrm></p:spPr></p:pic><p:graphicFrame><p:xfrm><a:off x="5000000" y="4000000"/></p:xfrm><a:graphic><a:graphicData><a:tbl><a:tr><a:tc><a:txBody><a:p><a:r><a:t>Region</a:t></a:r></a:p></a:txBody></a:tc><a:tc><a:txBody><a:p><a:r><a:t>Sales</a:t></a:r></a:p></a:txBody></a:tc></a:tr><a:tr><a:tc><a:txBody><a:p><a:r><a:t>EU</a:t></a:r></a:p></a:txBody></a:tc><a:tc><a:txBody><a:p><a:r><a:t>12</a:t></a:r></a:p></a:txBody></a:tc></a:tr></a:tbl></a:graphicData></a:graphic></p:graphicFrame>"#,
+                placeholder_shape(
+                    r#"<p:ph type="title"/>"#,
+                    None,
+                    "<a:p><a:r><a:t>Results</a:t></a:r></a:p>"
+                ),
+                placeholder_shape(
+                    r#"<p:ph idx="1"/>"#,
+                    None,
+                    r#"<a:p><a:r><a:rPr b="1"/><a:t>Growth </a:t></a:r><a:r><a:t>everywhere</a:t></a:r></a:p><a:p><a:pPr lvl="1"/><a:r><a:t>see </a:t></a:r><a:r><a:rPr><a:hlinkClick r:id="rId4"/></a:rPr><a:t>the report</a:t></a:r></a:p><a:p><a:pPr><a:buNone/></a:pPr><a:r><a:rPr i="1"/><a:t>Plain text</a:t></a:r></a:p><a:p><a:pPr><a:buAutoNum type="arabicPeriod"/></a:pPr><a:r><a:t>first</a:t></a:r></a:p><a:p><a:pPr><a:buAutoNum type="arabicPeriod"/></a:pPr><a:r><a:t>second</a:t></a:r></a:p>"#
+                ),
+                placeholder_shape(
+                    r#"<p:ph type="sldNum" idx="12"/>"#,
+                    None,
+                    "<a:p><a:r><a:t>2</a:t></a:r></a:p>"
+                ),
+            ))
+            .as_bytes(),
+        );
+        add(
+            "ppt/slides/_rels/slide2.xml.rels",
+            rels(&[
+                ("rId1", "slideLayout", "../slideLayouts/slideLayout2.xml"),
+                ("rId2", "notesSlide", "../notesSlides/notesSlide2.xml"),
+                ("rId3", "image", "../media/image1.png"),
+                ("rId4", "hyperlink", "https://example.com/report"),
+            ])
+            .as_bytes(),
+        );
+        add(
+            "ppt/slideLayouts/slideLayout2.xml",
+            format!(
+                r#"<p:sldLayout {NS} type="twoObj"><p:cSld><p:spTree>{}{}</p:spTree></p:cSld></p:sldLayout>"#,
+                placeholder_shape(r#"<p:ph type="title"/>"#, Some((100, 100)), ""),
+                placeholder_shape(r#"<p:ph idx="1"/>"#, Some((100, 2000000)), ""),
+            )
+            .as_bytes(),
+        );
+        add(
+            "ppt/notesSlides/notesSlide2.xml",
+            format!(
+                r#"<p:notes {NS}><p:cSld><p:spTree>{}{}</p:spTree></p:cSld></p:notes>"#,
+                placeholder_shape(r#"<p:ph type="sldImg"/>"#, None, ""),
+                placeholder_shape(
+                    r#"<p:ph type="body" idx="1"/>"#,
+                    None,
+                    "<a:p><a:r><a:t>Mention </a:t></a:r><a:r><a:rPr b=\"1\"/><a:t>EU</a:t></a:r></a:p>"
+                ),
+            )
+            .as_bytes(),
+        );
+        add("ppt/media/image1.png", b"not really a png");
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn converts_slides_text_pictures_tables_and_notes() {
+        let archive = zip::ZipArchive::new(Cursor::new(sample_pptx())).unwrap();
+        let mut importer = Importer::new(archive, "deck-images");
+        let deck = importer.deck().unwrap();
+
+        assert_eq!(
+            deck.meta,
+            [
+                ("title".to_string(), "Quarterly Review".to_string()),
+                ("author".to_string(), "Ana".to_string()),
+                ("@aspect".to_string(), "4:3".to_string()),
+                ("@theme".to_string(), "dark".to_string()),
+            ]
+        );
+        assert_eq!(deck.slides.len(), 2);
+        assert_eq!(deck.slides[0].content, "# Quarterly Review\n\nQ3 2026");
+        assert!(deck.slides[0].directives.is_empty());
+
+        let results = &deck.slides[1];
+        assert_eq!(
+            results.content,
+            "## Results\n\n\
+             - **Growth** everywhere\n  - see [the report](https://example.com/report)\n\n\
+             *Plain text*\n\n1. first\n2. second\n\n\
+             ```\nfn main() {\n    run();\n}\n```\n\n\
+             +++\n\n\
+             ![Revenue chart](deck-images/image1.png)\n\n\
+             | Region | Sales |\n| --- | --- |\n| EU | 12 |"
+        );
+        assert_eq!(results.notes, "Mention **EU**");
+        assert_eq!(
+            importer.media,
+            [("image1.png".to_string(), b"not really a png".to_vec())]
+        );
+        assert!(deck.warnings.is_empty());
+    }
+
+    #[test]
+    fn resolves_parts_and_aspect_ratios() {
+        assert_eq!(resolve("ppt/slides", "../media/a.png"), "ppt/media/a.png");
+        assert_eq!(resolve("", "ppt/presentation.xml"), "ppt/presentation.xml");
+        assert_eq!(resolve("ppt/slides", "/ppt/media/b.png"), "ppt/media/b.png");
+        assert_eq!(aspect(12192000, 6858000), None);
+        assert_eq!(aspect(9144000, 6858000).as_deref(), Some("4:3"));
+        assert_eq!(aspect(10692000, 7560000).as_deref(), Some("99:70"));
+    }
+}
@@ -0,0 +1,233 @@
+//! A minimal XML reader for the parts of a `.pptx`: elements, attributes and
+//! text, with entities decoded. Prefixed names are kept as written
+//! (`p:sp`, `r:embed`); there are no namespaces, DTDs or validation.
+
+#[derive(Debug, Default)]
+pub struct Element {
+    pub name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Child elements, in document order.
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(el) => Some(el),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// The first child element called `name`.
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|el| el.name == name)
+    }
+
+    /// The element reached by following `path` through first children.
+    pub fn path(&self, path: &[&str]) -> Option<&Element> {
+        path.iter().try_fold(self, |el, name| el.child(name))
+    }
+
+    /// The first element called `name` anywhere below this one.
+    pub fn find(&self, name: &str) -> Option<&Element> {
+        self.elements().find_map(|el| {
+            if el.name == name {
+                Some(el)
+            } else {
+                el.find(name)
+            }
+        })
+    }
+
+    /// Every element called `name` below this one, in document order.
+    pub fn find_all<'a>(&'a self, name: &str, found: &mut Vec<&'a Element>) {
+        for el in self.elements() {
+            if el.name == name {
+                found.push(el);
+            }
+            el.find_all(name, found);
+        }
+    }
+
+    /// The concatenated text content.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.children {
+            match node {
+                Node::Element(el) => text.push_str(&el.text()),
+                Node::Text(t) => text.push_str(t),
+            }
+        }
+        text
+    }
+}
+
+/// Parse a document into its root element, or `None` when it is not
+/// well-formed enough to read.
+pub fn parse(xml: &str) -> Option<Element> {
+    let mut stack = vec![Element::new("")];
+    let mut rest = xml;
+    while !rest.is_empty() {
+        let Some(after) = rest.strip_prefix('<') else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = unescape(&rest[..end]);
+            stack.last_mut()?.children.push(Node::Text(text));
+            rest = &rest[end..];
+            continue;
+        };
+        if let Some(comment) = after.strip_prefix("!--") {
+            rest = &comment[comment.find("-->")? + 3..];
+        } else if let Some(cdata) = after.strip_prefix("![CDATA[") {
+            let end = cdata.find("]]>")?;
+            let text = cdata[..end].to_string();
+            stack.last_mut()?.children.push(Node::Text(text));
+            rest = &cdata[end + 3..];
+        } else if after.starts_with('?') || after.starts_with('!') {
+            rest = &after[after.find('>')? + 1..];
+        } else {
+            let end = tag_end(after)?;
+            let tag = &after[..end];
+            rest = &after[end + 1..];
+            if tag.starts_with('/') {
+                let el = stack.pop()?;
+                stack.last_mut()?.children.push(Node::Element(el));
+            } else if let Some(tag) = tag.strip_suffix('/') {
+                let el = parse_tag(tag);
+                stack.last_mut()?.children.push(Node::Element(el));
+            } else {
+                stack.push(parse_tag(tag));
+            }
+        }
+    }
+    let document = stack.pop()?;
+    if !stack.is_empty() {
+        return None;
+    }
+    document.children.into_iter().find_map(|node| match node {
+        Node::Element(el) => Some(el),
+        Node::Text(_) => None,
+    })
+}
+
+/// The index of the `>` closing a tag, skipping quoted attribute values.
+fn tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_tag(tag: &str) -> Element {
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let mut el = Element::new(&tag[..name_end]);
+    let mut rest = tag[name_end..].trim_start();
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().to_string();
+        let value = rest[eq + 1..].trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(len) = value[1..].find(quote) else {
+            break;
+        };
+        el.attrs.push((key, unescape(&value[1..1 + len])));
+        rest = value[len + 2..].trim_start();
+    }
+    el
+}
+
+/// Decode the predefined and numeric character entities.
+pub fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').and_then(|semi| {
+            let entity = &rest[1..semi];
+            let c = match entity {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                _ => {
+                    let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => entity.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, semi + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_elements_attributes_and_text() {
+        let root = parse(
+            r#"<?xml version="1.0"?>
+<!-- generated -->
+<p:sld xmlns:p="urn:p"><a:p lvl='1' title="a > b"><a:t>Fish &amp; chips &#8212;&#x20;now</a:t><a:br/><a:t><![CDATA[<raw>]]></a:t></a:p></p:sld>"#,
+        )
+        .unwrap();
+        assert_eq!(root.name, "p:sld");
+        let p = root.child("a:p").unwrap();
+        assert_eq!(p.attr("lvl"), Some("1"));
+        assert_eq!(p.attr("title"), Some("a > b"));
+        assert_eq!(p.text(), "Fish & chips — now<raw>");
+        let names: Vec<&str> = p.elements().map(|el| el.name.as_str()).collect();
+        assert_eq!(names, ["a:t", "a:br", "a:t"]);
+        assert!(root.find("a:br").is_some());
+    }
+
+    #[test]
+    fn rejects_unclosed_documents() {
+        assert!(parse("<a><b></b>").is_none());
+        assert_eq!(unescape("a &unknown; & b"), "a &unknown; & b");
+    }
+}
@@ -5,5 +5,7 @@ pub mod config;
 pub mod create;
 pub mod export;
 pub mod generate;
+pub mod import;
+pub mod rehearse;
 pub mod skill;
 pub mod spec;
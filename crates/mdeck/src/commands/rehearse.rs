@@ -0,0 +1,83 @@
+//! `mdeck rehearse` — present a deck while timing every slide and reveal
+//! step, then print and save the timing report.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+use crate::cli::RehearseArgs;
+use crate::parser::{self, Presentation};
+use crate::rehearsal::{self, Rehearsal, Report};
+
+pub fn run(args: RehearseArgs, quiet: bool) -> Result<()> {
+    let RehearseArgs {
+        file,
+        duration,
+        windowed,
+        presenter,
+    } = args;
+
+    if !file.exists() {
+        anyhow::bail!("File not found: {}", file.display());
+    }
+    let target = match duration {
+        Some(d) => Some(
+            parser::frontmatter::parse_duration(&d)
+                .with_context(|| format!("Invalid duration '{d}'; use e.g. 20m, 1h30m or 90s"))?,
+        ),
+        None => load(&file)?
+            .meta
+            .duration
+            .as_deref()
+            .and_then(parser::frontmatter::parse_duration),
+    };
+    if target.is_none() && !quiet {
+        eprintln!(
+            "{} No @duration in the deck; pass --duration to see pacing and overruns.",
+            "Note:".yellow().bold()
+        );
+    }
+
+    let report_path = rehearsal::report_path(&file);
+    let previous = Report::load(&report_path);
+    let rehearsal = Arc::new(Mutex::new(Rehearsal::new(target, previous)));
+    let result = crate::app::run(
+        file.clone(),
+        windowed,
+        None,
+        false,
+        presenter,
+        quiet,
+        Some(rehearsal.clone()),
+        None,
+    );
+
+    let mut rehearsal = rehearsal.lock().unwrap();
+    rehearsal.finish();
+    if rehearsal.elapsed().is_zero() {
+        return result;
+    }
+    // Titles as the deck reads now, after any edits during the rehearsal
+    let titles: Vec<String> = load(&file)?
+        .slides
+        .iter()
+        .map(parser::Slide::title)
+        .collect();
+    let report = rehearsal.report(&titles);
+    eprintln!();
+    report.print(rehearsal.previous());
+    report.save(&report_path)?;
+    eprintln!("\nTiming report saved to {}", report_path.display());
+    result
+}
+
+fn load(file: &Path) -> Result<Presentation> {
+    let content = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let base_path = file.parent().unwrap_or(Path::new("."));
+    let mut presentation = parser::parse(&content, base_path);
+    crate::config::Config::load_or_default().apply_deck_defaults(&mut presentation.meta);
+    Ok(presentation)
+}
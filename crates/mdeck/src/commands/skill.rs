@@ -69,8 +69,11 @@ types, and incremental reveal that you need to write correct presentations.
 
 - `mdeck <file.md>` — launch presentation
 - `mdeck <file.md> --check` — validate without launching
+- `mdeck <file.md> --remote 8080` — control the presentation from a phone on the same network
 - `mdeck ai generate <file.md>` — generate AI images
-- `mdeck export <file.md>` — export slides as PNG images
+- `mdeck export <file.md>` — export slides as PNG images (`--format svg|pdf|handout|html|pptx` for other formats)
+- `mdeck import <file>` — convert a PowerPoint, Marp, reveal.js or remark deck into mdeck markdown
+- `mdeck rehearse <file.md>` — present while timing each slide, with pacing against `@duration`
 - `mdeck spec` — print format specification
 - `mdeck spec --short` — print quick reference card
 - `mdeck ai status` — show AI configuration status
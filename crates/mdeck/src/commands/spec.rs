@@ -22,8 +22,18 @@ FRONTMATTER (YAML at top of file)
   title, author, date     Standard metadata
   @theme: dark|light      Global theme
   @transition: slide|fade|spatial|none
-  @aspect: 16:9|4:3|16:10
-  @footer: "text"         Footer on every slide
+  @aspect: 16:9|4:3|16:10  Also any W:H, or a pixel size like 1024x768
+  @footer: "text"         Footer on every slide (also @header); none hides it
+                          Tokens: {{slide}} {{total}} {{title}} {{author}} {{date}}
+  @duration: 20m          Target talk length (presenter view timer)
+  @code-theme: name       Syntax theme for code blocks
+  @font: fonts/Inter.ttf  Body font (also @font-heading, @font-mono)
+  @font-fallback: [a.otf] Fonts for missing glyphs (CJK, emoji)
+
+SLIDE DIRECTIVES (lines at the top of a slide; override frontmatter)
+  @theme  @transition  @footer  @header  @code-theme  @layout
+  @background: #1E293B | images/bg.jpg
+  @class: invert          Swap background and text colors
 
 LAYOUTS (auto-inferred, override with @layout: name)
   title        H1 + optional subtitle
@@ -52,19 +62,22 @@ KEYBOARD SHORTCUTS
   D              Toggle theme     F           Toggle fullscreen
   H              Show/hide HUD    Esc x2      Exit
   Ctrl+C x2      Exit             Q           Quit
+  / or Ctrl+F    Search slides    12 Enter    Go to slide 12
 
 MOUSE CONTROLS
   Left click     Next slide       Right click Previous slide
   Left drag      Freehand pen     Right drag  Draw arrow
   Scroll wheel   Scroll content
-  Drawings fade out after 8 seconds
+  Click a link   Open URL, or go to a #heading-anchor / #slide-N link (Backspace: back)
+  Drawings fade out after 8 seconds; A keeps them in <deck>.annotations.json
+  L / O / M      Laser pointer, spotlight, magnifier (again: back to pen)
 
 COLUMN SEPARATOR
   +++   Separates left and right columns in two-column layout
 
 SPEAKER NOTES
   ???   Notes separator (3+ question marks)
-        Everything after ??? is presenter-only notes (not rendered)
+        Everything after ??? is presenter-only notes (press S for presenter view)
         Supports full markdown formatting in notes content
 
 VISUALIZATIONS (fenced code blocks with @ language tag)
@@ -84,6 +97,14 @@ VISUALIZATIONS (fenced code blocks with @ language tag)
   @orgchart      Org chart (- Name (parent: Parent))
   @gantt         Gantt chart (- Task: date, duration, after Dep; # labels: inside)
   @gitgraph      Git branch graph (- branch X from Y, - merge X -> Y: label)
+  @architecture  Components (- A -> B, (icon: x, pos: c,r, label: x,
+                 style: primary|secondary|muted, color: #hex,
+                 shape: box|circle|cylinder|cloud),
+                 # Group: Name (style: dashed) ... # End group,
+                 @layout: grid|layered [LR|TB]|radial)
+  @flowchart     Flowchart (- A -> B, - Ok? -> C: yes, (shape: start|process|decision|io))
+  @architecture sequence  Sequence diagram (- A (icon: x, label: x), - A -> B: msg,
+                         activate B, note over A: text, alt/else/opt/loop ... end)
 
 GANTT CHART DURATION FORMATS
   Nd             Calendar days (e.g. 10d)
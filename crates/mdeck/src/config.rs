@@ -25,6 +25,10 @@ fn default_one() -> f64 {
     1.0
 }
 
+fn default_group_border() -> f64 {
+    2.0
+}
+
 /// Configuration for routing cost weights.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoutingWeightsConfig {
@@ -36,6 +40,8 @@ pub struct RoutingWeightsConfig {
     pub lane_change: f64,
     #[serde(default = "default_one")]
     pub crossing: f64,
+    #[serde(default = "default_group_border")]
+    pub group_border: f64,
 }
 
 impl Default for RoutingWeightsConfig {
@@ -45,6 +51,7 @@ impl Default for RoutingWeightsConfig {
             turn: 1.0,
             lane_change: 1.0,
             crossing: 1.0,
+            group_border: 2.0,
         }
     }
 }
@@ -57,6 +64,7 @@ impl RoutingWeightsConfig {
             turn: self.turn,
             lane_change: self.lane_change,
             crossing: self.crossing,
+            group_border: self.group_border,
         }
     }
 }
@@ -232,14 +240,32 @@ impl Config {
         crate::prompt::DEFAULT_ICON_STYLE
     }
 
+    /// Fill deck-level settings the frontmatter leaves unset from `defaults`.
+    /// Frontmatter always wins; slide directives are resolved later, at render time.
+    pub fn apply_deck_defaults(&self, meta: &mut crate::parser::PresentationMeta) {
+        let Some(ref defaults) = self.defaults else {
+            return;
+        };
+        if meta.theme.is_none() {
+            meta.theme = defaults.theme.clone();
+        }
+        if meta.transition.is_none() {
+            meta.transition = defaults.transition.clone();
+        }
+        if meta.aspect.is_none() {
+            meta.aspect = defaults.aspect.clone();
+        }
+    }
+
     pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
         match key {
             "defaults.theme" => {
-                match value {
-                    "light" | "dark" | "nord" => {}
-                    _ => {
-                        anyhow::bail!("Invalid theme: {value}. Must be 'light', 'dark', or 'nord'.")
-                    }
+                let custom = crate::theme::custom::config_themes_dir()
+                    .is_some_and(|dir| dir.join(format!("{value}.yaml")).is_file());
+                if !crate::theme::BUILTIN_THEMES.contains(&value) && !custom {
+                    anyhow::bail!(
+                        "Invalid theme: {value}. Must be 'light', 'dark', 'nord', or a theme in the config themes directory."
+                    )
                 }
                 self.defaults
                     .get_or_insert_with(DefaultsConfig::default)
@@ -257,11 +283,10 @@ impl Config {
                     .transition = Some(value.to_string());
             }
             "defaults.aspect" => {
-                match value {
-                    "16:9" | "4:3" | "16:10" => {}
-                    _ => anyhow::bail!(
-                        "Invalid aspect ratio: {value}. Must be '16:9', '4:3', or '16:10'."
-                    ),
+                if crate::render::aspect::AspectRatio::parse(value).is_none() {
+                    anyhow::bail!(
+                        "Invalid aspect ratio: {value}. Must be a ratio such as '16:9', '4:3' or '16:10', or a size such as '1024x768'."
+                    );
                 }
                 self.defaults
                     .get_or_insert_with(DefaultsConfig::default)
@@ -284,3 +309,34 @@ impl Config {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::PresentationMeta;
+
+    #[test]
+    fn apply_deck_defaults_only_fills_unset_fields() {
+        let mut config = Config::default();
+        config.set("defaults.theme", "nord").unwrap();
+        config.set("defaults.transition", "fade").unwrap();
+
+        let mut meta = PresentationMeta {
+            theme: Some("dark".to_string()),
+            ..Default::default()
+        };
+        config.apply_deck_defaults(&mut meta);
+        assert_eq!(meta.theme.as_deref(), Some("dark"));
+        assert_eq!(meta.transition.as_deref(), Some("fade"));
+        assert_eq!(meta.aspect, None);
+    }
+
+    #[test]
+    fn set_aspect_accepts_custom_ratios_and_sizes() {
+        let mut config = Config::default();
+        config.set("defaults.aspect", "21:9").unwrap();
+        config.set("defaults.aspect", "1024x768").unwrap();
+        assert!(config.set("defaults.aspect", "wide").is_err());
+        assert_eq!(config.defaults.unwrap().aspect.as_deref(), Some("1024x768"));
+    }
+}
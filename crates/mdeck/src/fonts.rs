@@ -0,0 +1,249 @@
+//! Deck fonts: TTF/OTF files named by the frontmatter or the deck theme,
+//! installed into the egui context in place of the built-in fonts.
+//!
+//! Frontmatter (`@font`, `@font-heading`, `@font-mono`, `@font-fallback`) wins
+//! over the theme (`font-family`, `font-family-heading`, `font-family-mono`,
+//! `font-fallback`). Frontmatter paths are relative to the presentation, theme
+//! paths to the theme file. Fallback fonts are consulted for glyphs the primary
+//! font lacks (CJK, emoji), before egui's own bundled fonts.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use eframe::egui::{self, FontData, FontDefinitions, FontFamily};
+
+use crate::check::{CheckCategory, CheckWarning};
+use crate::parser::PresentationMeta;
+use crate::theme::{CustomThemes, Theme};
+
+/// Name of the font family used for headings and titles.
+const HEADING_FAMILY: &str = "heading";
+
+/// The font family headings are drawn with. Same fonts as
+/// [`FontFamily::Proportional`] unless the deck sets a heading font.
+pub fn heading_family() -> FontFamily {
+    FontFamily::Name(HEADING_FAMILY.into())
+}
+
+/// Font files requested by a deck, resolved from frontmatter and theme.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeckFonts {
+    pub body: Option<PathBuf>,
+    /// Heading font; falls back to `body` when unset.
+    pub heading: Option<PathBuf>,
+    pub mono: Option<PathBuf>,
+    pub fallback: Vec<PathBuf>,
+}
+
+impl DeckFonts {
+    /// Frontmatter settings override the theme's, one role at a time.
+    /// Frontmatter paths are joined to `base_dir`; theme paths are already
+    /// resolved against their theme file.
+    pub fn resolve(meta: &PresentationMeta, theme: &Theme, base_dir: &Path) -> Self {
+        let deck = |path: &Option<String>| path.as_ref().map(|p| base_dir.join(p));
+        let body = deck(&meta.font).or_else(|| theme.font_family.clone());
+        let heading = deck(&meta.font_heading)
+            .or_else(|| theme.font_family_heading.clone())
+            .or_else(|| body.clone());
+        let mono = deck(&meta.font_mono).or_else(|| theme.font_family_mono.clone());
+        let fallback = if meta.font_fallback.is_empty() {
+            theme.font_fallback.clone()
+        } else {
+            meta.font_fallback
+                .iter()
+                .map(|p| base_dir.join(p))
+                .collect()
+        };
+        Self {
+            body,
+            heading,
+            mono,
+            fallback,
+        }
+    }
+
+    /// Build egui font definitions. Files that are missing or not valid fonts
+    /// are skipped with a warning, leaving egui's built-in font in their place.
+    /// Warnings show paths relative to `base_dir` where possible.
+    pub fn load(&self, base_dir: &Path) -> (FontDefinitions, Vec<CheckWarning>) {
+        let mut defs = FontDefinitions::default();
+        let mut warnings = Vec::new();
+
+        let mut load = |path: &PathBuf| -> Option<String> {
+            let shown = path.strip_prefix(base_dir).unwrap_or(path).display();
+            let key = format!("deck:{shown}");
+            if defs.font_data.contains_key(&key) {
+                return Some(key);
+            }
+            match read_font(path) {
+                Ok(bytes) => {
+                    defs.font_data
+                        .insert(key.clone(), Arc::new(FontData::from_owned(bytes)));
+                    Some(key)
+                }
+                Err(reason) => {
+                    warnings.push(CheckWarning {
+                        slide: 0,
+                        category: CheckCategory::Font,
+                        message: format!("{reason}: {shown}"),
+                    });
+                    None
+                }
+            }
+        };
+
+        let body = self.body.as_ref().and_then(&mut load);
+        let heading = self.heading.as_ref().and_then(&mut load);
+        let mono = self.mono.as_ref().and_then(&mut load);
+        let fallback: Vec<String> = self.fallback.iter().filter_map(&mut load).collect();
+
+        let proportional = defs.families[&FontFamily::Proportional].clone();
+        let monospace = defs.families[&FontFamily::Monospace].clone();
+        defs.families.insert(
+            FontFamily::Proportional,
+            font_chain(body.clone(), &proportional, &fallback),
+        );
+        defs.families.insert(
+            heading_family(),
+            font_chain(heading.or(body), &proportional, &fallback),
+        );
+        defs.families.insert(
+            FontFamily::Monospace,
+            font_chain(mono, &monospace, &fallback),
+        );
+        (defs, warnings)
+    }
+}
+
+/// Resolve and load the fonts for a deck, taking the deck theme from
+/// `custom_themes` when it is a custom one.
+pub fn load_deck_fonts(
+    meta: &PresentationMeta,
+    custom_themes: &CustomThemes,
+    base_dir: &Path,
+) -> (FontDefinitions, Vec<CheckWarning>) {
+    DeckFonts::resolve(meta, &Theme::for_deck(meta, custom_themes), base_dir).load(base_dir)
+}
+
+/// Load the deck's fonts and install them into `ctx`.
+pub fn install_deck_fonts(
+    ctx: &egui::Context,
+    meta: &PresentationMeta,
+    custom_themes: &CustomThemes,
+    base_dir: &Path,
+) -> Vec<CheckWarning> {
+    let (defs, warnings) = load_deck_fonts(meta, custom_themes, base_dir);
+    ctx.set_fonts(defs);
+    warnings
+}
+
+/// Primary font, then the deck fallbacks, then egui's remaining built-in fonts.
+/// Without a custom primary, egui's own primary keeps its place at the front.
+fn font_chain(primary: Option<String>, builtin: &[String], fallback: &[String]) -> Vec<String> {
+    let (head, rest) = match primary {
+        Some(font) => (vec![font], builtin),
+        None => (builtin[..1].to_vec(), &builtin[1..]),
+    };
+    let mut chain = head;
+    for font in fallback.iter().chain(rest) {
+        if !chain.contains(font) {
+            chain.push(font.clone());
+        }
+    }
+    chain
+}
+
+/// Read a font file and check it parses, since egui panics on invalid font data.
+fn read_font(path: &Path) -> Result<Vec<u8>, &'static str> {
+    if !path.is_file() {
+        return Err("font file not found");
+    }
+    let bytes = std::fs::read(path).map_err(|_| "cannot read font file")?;
+    ab_glyph::FontRef::try_from_slice(&bytes).map_err(|_| "not a valid TTF/OTF font")?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    /// Write one of egui's bundled fonts to disk so tests have a real font file.
+    fn write_builtin_font(dir: &TempDir, file: &str) {
+        let defs = FontDefinitions::default();
+        let data = defs.font_data.get("Hack").expect("egui bundles Hack");
+        dir.write(file, &data.font[..]);
+    }
+
+    #[test]
+    fn frontmatter_overrides_theme_per_role() {
+        let mut theme = Theme::light();
+        theme.font_family = Some("/themes/body.ttf".into());
+        theme.font_family_mono = Some("/themes/mono.ttf".into());
+        theme.font_fallback = vec!["/themes/cjk.otf".into()];
+        let meta = PresentationMeta {
+            font_mono: Some("deck-mono.ttf".to_string()),
+            ..Default::default()
+        };
+        let fonts = DeckFonts::resolve(&meta, &theme, Path::new("/deck"));
+        assert_eq!(fonts.body, Some("/themes/body.ttf".into()));
+        assert_eq!(fonts.heading, Some("/themes/body.ttf".into()));
+        assert_eq!(fonts.mono, Some("/deck/deck-mono.ttf".into()));
+        assert_eq!(fonts.fallback, vec![PathBuf::from("/themes/cjk.otf")]);
+    }
+
+    #[test]
+    fn font_chain_orders_primary_fallbacks_builtins() {
+        let builtin = vec!["Ubuntu".to_string(), "Emoji".to_string()];
+        let fallback = vec!["CJK".to_string()];
+        assert_eq!(
+            font_chain(Some("Inter".to_string()), &builtin, &fallback),
+            vec!["Inter", "CJK", "Ubuntu", "Emoji"]
+        );
+        assert_eq!(
+            font_chain(None, &builtin, &fallback),
+            vec!["Ubuntu", "CJK", "Emoji"]
+        );
+    }
+
+    #[test]
+    fn load_installs_fonts_and_heading_family() {
+        let dir = TempDir::new("fonts-load");
+        write_builtin_font(&dir, "body.ttf");
+        let fonts = DeckFonts {
+            body: Some(dir.join("body.ttf")),
+            heading: Some(dir.join("body.ttf")),
+            ..Default::default()
+        };
+        let (defs, warnings) = fonts.load(&dir);
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(defs.families[&FontFamily::Proportional][0], "deck:body.ttf");
+        assert_eq!(defs.families[&heading_family()][0], "deck:body.ttf");
+        assert_eq!(defs.families[&FontFamily::Monospace][0], "Hack");
+    }
+
+    #[test]
+    fn load_warns_and_skips_bad_files() {
+        let dir = TempDir::new("fonts-bad");
+        dir.write("broken.ttf", "not a font");
+        let fonts = DeckFonts {
+            body: Some(dir.join("missing.ttf")),
+            mono: Some(dir.join("broken.ttf")),
+            ..Default::default()
+        };
+        let (defs, warnings) = fonts.load(&dir);
+        let messages: Vec<&str> = warnings.iter().map(|w| w.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "font file not found: missing.ttf",
+                "not a valid TTF/OTF font: broken.ttf"
+            ]
+        );
+        assert_eq!(
+            defs.families[&FontFamily::Proportional],
+            FontDefinitions::default().families[&FontFamily::Proportional]
+        );
+        assert!(defs.families.contains_key(&heading_family()));
+    }
+}
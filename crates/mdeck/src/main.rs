@@ -1,15 +1,23 @@
+mod annotations;
 mod app;
 mod banner;
 mod check;
 mod cli;
 mod commands;
 mod config;
+mod fonts;
 mod incident_log;
 mod parser;
 mod prompt;
+mod rehearsal;
+mod remote;
 mod render;
+mod search;
 mod theme;
 
+#[cfg(test)]
+mod test_util;
+
 use clap::{CommandFactory, Parser};
 use colored::Colorize;
 
@@ -1,4 +1,4 @@
-use super::{Block, Directive, ImageDirectives, Inline, ListItem, ListMarker};
+use super::{Block, DiagramKind, Directive, ImageDirectives, Inline, ListItem, ListMarker};
 
 /// Extract @ directives from the beginning of a slide's raw text.
 /// Returns (directives, remaining content).
@@ -216,7 +216,10 @@ fn parse_code_block(lines: &[&str], start: usize, fence_char: char) -> (Block, u
     let code = code_lines.join("\n");
 
     let block = match viz_kind {
-        VizKind::Diagram => Block::Diagram { content: code },
+        VizKind::Diagram(kind) => Block::Diagram {
+            kind,
+            content: code,
+        },
         VizKind::WordCloud => Block::WordCloud { content: code },
         VizKind::Timeline => Block::Timeline { content: code },
         VizKind::PieChart => Block::PieChart { content: code },
@@ -246,7 +249,7 @@ fn parse_code_block(lines: &[&str], start: usize, fence_char: char) -> (Block, u
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum VizKind {
     None,
-    Diagram,
+    Diagram(DiagramKind),
     WordCloud,
     Timeline,
     PieChart,
@@ -271,8 +274,16 @@ fn parse_code_info(info: &str) -> (Option<String>, Vec<usize>, VizKind) {
     }
 
     // Check for visualization language tags
-    if info.starts_with("@architecture") {
-        return (None, vec![], VizKind::Diagram);
+    if let Some(qualifier) = info.strip_prefix("@architecture") {
+        let kind = match qualifier.trim() {
+            "sequence" => DiagramKind::Sequence,
+            "flowchart" => DiagramKind::Flowchart,
+            _ => DiagramKind::Architecture,
+        };
+        return (None, vec![], VizKind::Diagram(kind));
+    }
+    if info.starts_with("@flowchart") {
+        return (None, vec![], VizKind::Diagram(DiagramKind::Flowchart));
     }
     if info.starts_with("@wordcloud") {
         return (None, vec![], VizKind::WordCloud);
@@ -736,7 +747,33 @@ mod tests {
     fn test_parse_diagram_block() {
         let blocks = parse("```@architecture\n- A -> B: hello\n```");
         assert_eq!(blocks.len(), 1);
-        assert!(matches!(&blocks[0], Block::Diagram { .. }));
+        assert!(matches!(
+            &blocks[0],
+            Block::Diagram {
+                kind: DiagramKind::Architecture,
+                ..
+            }
+        ));
+
+        let blocks = parse("```@architecture sequence\n- A -> B: hello\n```");
+        assert!(matches!(
+            &blocks[0],
+            Block::Diagram {
+                kind: DiagramKind::Sequence,
+                ..
+            }
+        ));
+
+        for info in ["@architecture flowchart", "@flowchart"] {
+            let blocks = parse(&format!("```{info}\n- A -> B\n```"));
+            assert!(matches!(
+                &blocks[0],
+                Block::Diagram {
+                    kind: DiagramKind::Flowchart,
+                    ..
+                }
+            ));
+        }
     }
 
     #[test]
@@ -1,5 +1,6 @@
 use super::PresentationMeta;
 use std::collections::HashMap;
+use std::time::Duration;
 
 pub fn extract(content: &str) -> (PresentationMeta, String) {
     let trimmed = content.trim_start_matches('\u{feff}'); // strip BOM
@@ -75,9 +76,22 @@ fn parse_frontmatter(yaml_str: &str) -> PresentationMeta {
         aspect: get_string(&map, "@aspect"),
         code_theme: get_string(&map, "@code-theme"),
         footer: get_string(&map, "@footer"),
+        header: get_string(&map, "@header"),
         image_style: get_string(&map, "@image-style"),
         icon_style: get_string(&map, "@icon-style"),
         slide_level: get_u8(&map, "@slide-level"),
+        duration: map.get("@duration").and_then(|v| match v {
+            serde_yaml::Value::String(s) => Some(s.clone()),
+            serde_yaml::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }),
+        font: get_string(&map, "@font"),
+        font_heading: get_string(&map, "@font-heading"),
+        font_mono: get_string(&map, "@font-mono"),
+        font_fallback: map
+            .get("@font-fallback")
+            .map(get_string_list)
+            .unwrap_or_default(),
     }
 }
 
@@ -88,6 +102,30 @@ fn get_string(map: &HashMap<String, serde_yaml::Value>, key: &str) -> Option<Str
     })
 }
 
+/// A YAML list of strings, or a single comma-separated string.
+fn get_string_list(value: &serde_yaml::Value) -> Vec<String> {
+    match value {
+        serde_yaml::Value::Sequence(items) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        serde_yaml::Value::String(s) => split_list(s),
+        _ => Vec::new(),
+    }
+}
+
+fn split_list(s: &str) -> Vec<String> {
+    let s = s.trim();
+    let s = s.strip_prefix('[').unwrap_or(s);
+    let s = s.strip_suffix(']').unwrap_or(s);
+    s.split(',')
+        .map(|p| p.trim().trim_matches('"').to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
 fn get_u8(map: &HashMap<String, serde_yaml::Value>, key: &str) -> Option<u8> {
     map.get(key).and_then(|v| match v {
         serde_yaml::Value::Number(n) => n.as_u64().and_then(|n| u8::try_from(n).ok()),
@@ -113,9 +151,15 @@ fn parse_frontmatter_manual(yaml_str: &str) -> PresentationMeta {
                 "@aspect" => meta.aspect = Some(value.to_string()),
                 "@code-theme" => meta.code_theme = Some(value.to_string()),
                 "@footer" => meta.footer = Some(value.to_string()),
+                "@header" => meta.header = Some(value.to_string()),
                 "@image-style" => meta.image_style = Some(value.to_string()),
                 "@icon-style" => meta.icon_style = Some(value.to_string()),
                 "@slide-level" => meta.slide_level = value.parse().ok(),
+                "@duration" => meta.duration = Some(value.to_string()),
+                "@font" => meta.font = Some(value.to_string()),
+                "@font-heading" => meta.font_heading = Some(value.to_string()),
+                "@font-mono" => meta.font_mono = Some(value.to_string()),
+                "@font-fallback" => meta.font_fallback = split_list(value),
                 _ => {}
             }
         }
@@ -123,6 +167,52 @@ fn parse_frontmatter_manual(yaml_str: &str) -> PresentationMeta {
     meta
 }
 
+/// Parse a talk duration such as `20m`, `1h30m`, `90s` or `45` (bare numbers are minutes).
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim().to_ascii_lowercase();
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(minutes) = s.parse::<f64>() {
+        return (minutes > 0.0).then(|| Duration::from_secs_f64(minutes * 60.0));
+    }
+
+    let mut total = 0.0;
+    let mut number = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut unit = c.to_string();
+        while let Some(&next) = chars.peek() {
+            if next.is_ascii_alphabetic() {
+                unit.push(next);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let value: f64 = number.parse().ok()?;
+        number.clear();
+        total += value
+            * match unit.as_str() {
+                "h" | "hr" | "hrs" | "hour" | "hours" => 3600.0,
+                "m" | "min" | "mins" | "minute" | "minutes" => 60.0,
+                "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+                _ => return None,
+            };
+    }
+    if !number.is_empty() || total <= 0.0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(total))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,4 +263,48 @@ mod tests {
         let (meta, _body) = extract(content);
         assert!(meta.date.is_some());
     }
+
+    #[test]
+    fn test_frontmatter_duration() {
+        let content = "---\ntitle: \"Test\"\n@duration: 20m\n---\nBody";
+        let (meta, _body) = extract(content);
+        assert_eq!(meta.duration.as_deref(), Some("20m"));
+
+        let content = "---\n@duration: 45\n---\nBody";
+        let (meta, _body) = extract(content);
+        assert_eq!(meta.duration.as_deref(), Some("45"));
+    }
+
+    #[test]
+    fn test_frontmatter_fonts() {
+        let content = "---\n@font: fonts/Inter.ttf\n@font-heading: fonts/Inter-Bold.ttf\n@font-mono: fonts/JetBrainsMono.ttf\n@font-fallback: [fonts/NotoSansCJK.otf, fonts/NotoEmoji.ttf]\n---\nBody";
+        let (meta, _body) = extract(content);
+        assert_eq!(meta.font.as_deref(), Some("fonts/Inter.ttf"));
+        assert_eq!(meta.font_heading.as_deref(), Some("fonts/Inter-Bold.ttf"));
+        assert_eq!(meta.font_mono.as_deref(), Some("fonts/JetBrainsMono.ttf"));
+        assert_eq!(
+            meta.font_fallback,
+            vec!["fonts/NotoSansCJK.otf", "fonts/NotoEmoji.ttf"]
+        );
+
+        let content = "---\n@font-fallback: a.otf, b.ttf\n---\nBody";
+        let (meta, _body) = extract(content);
+        assert_eq!(meta.font_fallback, vec!["a.otf", "b.ttf"]);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("20m"), Some(Duration::from_secs(20 * 60)));
+        assert_eq!(parse_duration("45"), Some(Duration::from_secs(45 * 60)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(90 * 60)));
+        assert_eq!(
+            parse_duration("1h 15min"),
+            Some(Duration::from_secs(75 * 60))
+        );
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("abc"), None);
+        assert_eq!(parse_duration("20x"), None);
+        assert_eq!(parse_duration("0"), None);
+        assert_eq!(parse_duration(""), None);
+    }
 }
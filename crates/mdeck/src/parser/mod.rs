@@ -11,6 +11,48 @@ pub struct Presentation {
     pub slides: Vec<Slide>,
 }
 
+impl Presentation {
+    /// The slide an intra-deck link such as `#results` or `#slide-12` points
+    /// to: `#slide-N` counts from 1, any other anchor matches a heading on
+    /// a slide. `None` for external URLs and anchors that match nothing.
+    pub fn link_slide(&self, url: &str) -> Option<usize> {
+        let anchor = url.strip_prefix('#')?;
+        if let Some(number) = anchor.strip_prefix("slide-") {
+            if let Ok(number) = number.parse::<usize>() {
+                return (1..=self.slides.len())
+                    .contains(&number)
+                    .then(|| number - 1);
+            }
+        }
+        let anchor = heading_anchor(anchor);
+        self.slides.iter().position(|slide| {
+            slide.blocks.iter().any(|block| match block {
+                Block::Heading { inlines, .. } => {
+                    heading_anchor(&inlines_to_text(inlines)) == anchor
+                }
+                _ => false,
+            })
+        })
+    }
+}
+
+/// The anchor of a heading, as GitHub derives it: lowercase, with runs of
+/// other characters collapsed to a single `-`.
+pub fn heading_anchor(text: &str) -> String {
+    let mut anchor = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            anchor.extend(c.to_lowercase());
+        } else if !anchor.is_empty() && !anchor.ends_with('-') {
+            anchor.push('-');
+        }
+    }
+    if anchor.ends_with('-') {
+        anchor.pop();
+    }
+    anchor
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct PresentationMeta {
     pub title: Option<String>,
@@ -21,24 +63,68 @@ pub struct PresentationMeta {
     pub aspect: Option<String>,
     pub code_theme: Option<String>,
     pub footer: Option<String>,
+    /// Text along the top edge of every slide (`@header`).
+    pub header: Option<String>,
     pub image_style: Option<String>,
     pub icon_style: Option<String>,
     pub slide_level: Option<u8>,
+    pub duration: Option<String>,
+    /// Body (and heading) font file, relative to the deck (`@font`).
+    pub font: Option<String>,
+    /// Heading font file (`@font-heading`).
+    pub font_heading: Option<String>,
+    /// Code font file (`@font-mono`).
+    pub font_mono: Option<String>,
+    /// Fonts searched for glyphs the primary fonts lack (`@font-fallback`).
+    pub font_fallback: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Slide {
-    #[allow(dead_code)]
     pub directives: Vec<Directive>,
     pub blocks: Vec<Block>,
     pub layout: Layout,
     /// The original raw markdown source text for this slide.
     pub raw_source: String,
     /// Speaker notes for this slide (content after `???` separator).
-    #[allow(dead_code)]
     pub notes: Option<String>,
 }
 
+impl Slide {
+    /// Value of the `@name:` directive on this slide, if present.
+    /// When a directive is repeated, the last occurrence wins.
+    pub fn directive(&self, name: &str) -> Option<&str> {
+        self.directives
+            .iter()
+            .rev()
+            .find(|d| d.name == name)
+            .map(|d| d.value.as_str())
+    }
+
+    /// Class names from the `@class` directive (space- or comma-separated).
+    pub fn classes(&self) -> impl Iterator<Item = &str> {
+        self.directive("class")
+            .unwrap_or("")
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|c| !c.is_empty())
+    }
+
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes().any(|c| c == class)
+    }
+
+    /// The text of the slide's first heading, if it has one.
+    pub fn title(&self) -> String {
+        self.blocks
+            .iter()
+            .find_map(|block| match block {
+                Block::Heading { inlines, .. } => Some(inlines_to_text(inlines)),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Directive {
     pub name: String,
@@ -78,6 +164,7 @@ pub enum Block {
     },
     HorizontalRule,
     Diagram {
+        kind: DiagramKind,
         content: String,
     },
     WordCloud {
@@ -148,11 +235,7 @@ pub enum Inline {
     Italic(Vec<Inline>),
     Strikethrough(Vec<Inline>),
     Code(String),
-    Link {
-        text: Vec<Inline>,
-        #[allow(dead_code)]
-        url: String,
-    },
+    Link { text: Vec<Inline>, url: String },
 }
 
 #[derive(Debug, Clone)]
@@ -170,6 +253,18 @@ pub enum ListMarker {
     Ordered,
 }
 
+/// The diagram type named after `@architecture`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DiagramKind {
+    /// Components and the relationships between them (no qualifier).
+    #[default]
+    Architecture,
+    /// `sequence`: participants exchanging messages, top to bottom.
+    Sequence,
+    /// `flowchart` (or `@flowchart`): steps and decisions, top to bottom.
+    Flowchart,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Layout {
     Title,
@@ -426,7 +521,7 @@ pub fn compute_max_steps(blocks: &[Block]) -> usize {
         .iter()
         .map(|b| match b {
             Block::List { items, .. } => count_next_steps(items),
-            Block::Diagram { content } => crate::render::diagram::count_diagram_steps(content),
+            Block::Diagram { content, .. } => crate::render::diagram::count_diagram_steps(content),
             Block::WordCloud { content }
             | Block::Timeline { content }
             | Block::PieChart { content }
@@ -812,4 +907,44 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_directives_sample_parses() {
+        let content = include_str!("../../../../samples/features/directives.md");
+        let pres = parse(content, Path::new("."));
+        assert_eq!(pres.slides.len(), 6);
+        assert_eq!(pres.slides[1].directive("theme"), Some("dark"));
+        assert_eq!(pres.slides[1].directive("transition"), Some("fade"));
+        assert_eq!(pres.slides[2].directive("background"), Some("#1E3A5F"));
+        assert!(pres.slides[5].has_class("invert"));
+    }
+
+    #[test]
+    fn test_slide_directive_lookup() {
+        let content = "@theme: dark\n@class: invert, wide\n@theme: nord\n# Styled\n\nBody";
+        let pres = parse(content, Path::new("."));
+        let slide = &pres.slides[0];
+        assert_eq!(
+            slide.directive("theme"),
+            Some("nord"),
+            "last occurrence wins"
+        );
+        assert_eq!(slide.directive("footer"), None);
+        assert_eq!(slide.classes().collect::<Vec<_>>(), vec!["invert", "wide"]);
+        assert!(slide.has_class("invert"));
+        assert!(!slide.has_class("inv"));
+    }
+
+    #[test]
+    fn test_link_slide_resolves_anchors_and_numbers() {
+        let content = "# Intro\n\n---\n\n# Q3 Results & Outlook\n\n---\n\n## Next steps";
+        let pres = parse(content, Path::new("."));
+        assert_eq!(heading_anchor("Q3 Results & Outlook"), "q3-results-outlook");
+        assert_eq!(pres.link_slide("#q3-results-outlook"), Some(1));
+        assert_eq!(pres.link_slide("#Next-Steps"), Some(2));
+        assert_eq!(pres.link_slide("#slide-1"), Some(0));
+        assert_eq!(pres.link_slide("#slide-4"), None);
+        assert_eq!(pres.link_slide("#missing"), None);
+        assert_eq!(pres.link_slide("https://example.com"), None);
+    }
 }
@@ -7,7 +7,7 @@
 ///
 /// The `slide_level` parameter controls which heading level triggers splits:
 /// - `Some(n)` — explicitly set via `@slide-level: n` in frontmatter; headings at
-///   level 1..=n all split slides.
+///   level 1..=n all split slides, and `0` turns heading splits off.
 /// - `None` — inferred: if there is exactly one H1, both H1 and H2 split (level 2);
 ///   if there are multiple H1s, only H1 splits (level 1).
 pub fn split(body: &str, slide_level: Option<u8>) -> Vec<String> {
@@ -332,6 +332,14 @@ mod tests {
         assert_eq!(slides.len(), 1);
     }
 
+    #[test]
+    fn test_explicit_slide_level_0() {
+        // Explicit @slide-level: 0 — headings never split, only --- and blank lines
+        let body = "Intro\n\n# Title\n\n## Section\n\n---\n\n# Next";
+        let slides = split(body, Some(0));
+        assert_eq!(slides.len(), 2);
+    }
+
     #[test]
     fn test_heading_inference_first_heading() {
         // First heading shouldn't split (no prior content)
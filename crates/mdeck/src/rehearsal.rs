@@ -0,0 +1,399 @@
+//! Rehearsal timing for `mdeck rehearse`.
+//!
+//! The presentation reports which slide and reveal step is on screen every
+//! frame; [`Rehearsal`] adds up the time spent on each. Against a target
+//! length (`@duration` or `--duration`) every slide gets a time budget, split
+//! like the previous run when there is one, and evenly otherwise. At the end
+//! the times are written to `<deck>.rehearsal.json`, which the next run
+//! compares against.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+/// Time spent on each slide and reveal step during one rehearsal.
+#[derive(Debug, Default)]
+pub struct Rehearsal {
+    target: Option<Duration>,
+    /// The report of the previous rehearsal, compared against.
+    previous: Option<Report>,
+    /// Seconds spent on each slide, per reveal step.
+    seconds: Vec<Vec<f64>>,
+    /// Slide and reveal step on screen since `since`.
+    shown: Option<(usize, usize)>,
+    since: Option<Instant>,
+    started: Option<Instant>,
+}
+
+/// Timing report written after a rehearsal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    /// When the rehearsal took place, as `YYYY-MM-DD HH:MM`.
+    pub date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_seconds: Option<f64>,
+    /// Time spent on slides, not counting the end slide.
+    pub total_seconds: f64,
+    pub slides: Vec<SlideTiming>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlideTiming {
+    /// Slide number, counting from 1.
+    pub slide: usize,
+    #[serde(default)]
+    pub title: String,
+    pub seconds: f64,
+    /// Seconds per reveal step, for slides with more than one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_seconds: Option<f64>,
+    /// Seconds over the budget, when the slide ran long.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overrun_seconds: Option<f64>,
+}
+
+/// `talk.rehearsal.json` for `talk.md`.
+pub fn report_path(deck: &Path) -> PathBuf {
+    deck.with_extension("rehearsal.json")
+}
+
+impl Rehearsal {
+    pub fn new(target: Option<Duration>, previous: Option<Report>) -> Self {
+        Self {
+            target,
+            previous,
+            ..Default::default()
+        }
+    }
+
+    pub fn target(&self) -> Option<Duration> {
+        self.target
+    }
+
+    pub fn previous(&self) -> Option<&Report> {
+        self.previous.as_ref()
+    }
+
+    /// Record that `shown` (slide and reveal step, or nothing for the end
+    /// slide) is on screen now. Time counts from the first call.
+    pub fn observe(&mut self, shown: Option<(usize, usize)>) {
+        let now = Instant::now();
+        self.started.get_or_insert(now);
+        if self.since.is_some() && shown == self.shown {
+            return;
+        }
+        self.flush(now);
+        self.shown = shown;
+    }
+
+    /// Stop the clock on the slide on screen.
+    pub fn finish(&mut self) {
+        self.flush(Instant::now());
+        self.shown = None;
+    }
+
+    /// Add the time since `since` to the slide and step on screen.
+    fn flush(&mut self, now: Instant) {
+        if let (Some((slide, step)), Some(since)) = (self.shown, self.since) {
+            self.add(slide, step, now.duration_since(since).as_secs_f64());
+        }
+        self.since = Some(now);
+    }
+
+    fn add(&mut self, slide: usize, step: usize, seconds: f64) {
+        if self.seconds.len() <= slide {
+            self.seconds.resize(slide + 1, Vec::new());
+        }
+        let steps = &mut self.seconds[slide];
+        if steps.len() <= step {
+            steps.resize(step + 1, 0.0);
+        }
+        steps[step] += seconds;
+    }
+
+    /// Time since the rehearsal started.
+    pub fn elapsed(&self) -> Duration {
+        self.started.map(|s| s.elapsed()).unwrap_or_default()
+    }
+
+    /// Seconds spent on `slide` so far, including the current visit.
+    pub fn slide_seconds(&self, slide: usize) -> f64 {
+        let recorded: f64 = self.seconds.get(slide).map_or(0.0, |s| s.iter().sum());
+        let running = match (self.shown, self.since) {
+            (Some((shown, _)), Some(since)) if shown == slide => since.elapsed().as_secs_f64(),
+            _ => 0.0,
+        };
+        recorded + running
+    }
+
+    /// Seconds budgeted for each of `slide_count` slides: the target split
+    /// like the previous run when it had the same slides, otherwise evenly.
+    pub fn budgets(&self, slide_count: usize) -> Option<Vec<f64>> {
+        let target = self.target?.as_secs_f64();
+        if slide_count == 0 {
+            return Some(Vec::new());
+        }
+        let previous = self
+            .previous
+            .as_ref()
+            .filter(|p| p.slides.len() == slide_count && p.total_seconds > 0.0);
+        Some(match previous {
+            Some(previous) => previous
+                .slides
+                .iter()
+                .map(|s| target * s.seconds / previous.total_seconds)
+                .collect(),
+            None => vec![target / slide_count as f64; slide_count],
+        })
+    }
+
+    /// Seconds behind (positive) or ahead of (negative) the budget while on
+    /// `slide`: behind once the time runs past the slide's budget window,
+    /// ahead when it is still before it.
+    pub fn pace(&self, slide: usize, slide_count: usize) -> Option<f64> {
+        let budgets = self.budgets(slide_count)?;
+        let start: f64 = budgets.iter().take(slide).sum();
+        let end = start + budgets.get(slide).copied().unwrap_or(0.0);
+        let elapsed = self.elapsed().as_secs_f64();
+        Some(if elapsed > end {
+            elapsed - end
+        } else if elapsed < start {
+            elapsed - start
+        } else {
+            0.0
+        })
+    }
+
+    /// The timing report for a deck whose slides have `titles`.
+    pub fn report(&self, titles: &[String]) -> Report {
+        let round = |s: f64| (s * 10.0).round() / 10.0;
+        let slide_count = titles.len().max(self.seconds.len());
+        let budgets = self.budgets(slide_count);
+        let slides: Vec<SlideTiming> = (0..slide_count)
+            .map(|i| {
+                let steps = self.seconds.get(i).cloned().unwrap_or_default();
+                let seconds = round(steps.iter().sum());
+                let budget = budgets.as_ref().map(|b| round(b[i]));
+                SlideTiming {
+                    slide: i + 1,
+                    title: titles.get(i).cloned().unwrap_or_default(),
+                    seconds,
+                    steps: if steps.len() > 1 {
+                        steps.into_iter().map(round).collect()
+                    } else {
+                        Vec::new()
+                    },
+                    budget_seconds: budget,
+                    overrun_seconds: budget.filter(|&b| seconds > b).map(|b| round(seconds - b)),
+                }
+            })
+            .collect();
+        Report {
+            date: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+            target_seconds: self.target.map(|t| t.as_secs_f64()),
+            total_seconds: round(slides.iter().map(|s| s.seconds).sum()),
+            slides,
+        }
+    }
+}
+
+impl Report {
+    /// The report saved at `path`, if there is a readable one.
+    pub fn load(path: &Path) -> Option<Self> {
+        let json = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Print the per-slide times, budgets and overruns, with the change
+    /// from `previous` when given.
+    pub fn print(&self, previous: Option<&Report>) {
+        let total = format_seconds(self.total_seconds);
+        match self.target_seconds {
+            Some(target) => {
+                let diff = self.total_seconds - target;
+                let verdict = if diff > 0.0 {
+                    format!("{} over", format_seconds(diff)).red().to_string()
+                } else {
+                    format!("{} to spare", format_seconds(-diff))
+                        .green()
+                        .to_string()
+                };
+                eprintln!(
+                    "Rehearsal: {} of {} ({verdict})",
+                    total.bold(),
+                    format_seconds(target)
+                );
+            }
+            None => eprintln!("Rehearsal: {}", total.bold()),
+        }
+        if let Some(previous) = previous {
+            eprintln!(
+                "  Last run {}: {}",
+                previous.date,
+                format_seconds(previous.total_seconds)
+            );
+        }
+        eprintln!();
+        eprintln!(
+            "  {:>3}  {:<32} {:>6} {:>7}  {:<14}",
+            "#", "Slide", "Time", "Budget", "Last run"
+        );
+        for slide in &self.slides {
+            let budget = slide.budget_seconds.map(format_seconds).unwrap_or_default();
+            let last = previous
+                .and_then(|p| p.slides.iter().find(|s| s.slide == slide.slide))
+                .map(|last| {
+                    format!(
+                        "{} ({})",
+                        format_seconds(last.seconds),
+                        format_delta(slide.seconds - last.seconds)
+                    )
+                })
+                .unwrap_or_default();
+            let mut title: String = slide.title.chars().take(32).collect();
+            if title.is_empty() {
+                title = "(untitled)".to_string();
+            }
+            let time = format!("{:>6}", format_seconds(slide.seconds));
+            let time = if slide.overrun_seconds.is_some() {
+                time.red().to_string()
+            } else {
+                time
+            };
+            let mut line = format!(
+                "  {:>3}  {title:<32} {time} {budget:>7}  {last:<14}",
+                slide.slide
+            );
+            if let Some(overrun) = slide.overrun_seconds {
+                line.push_str(&format!(
+                    "  {}",
+                    format!("{} over", format_seconds(overrun)).red()
+                ));
+            }
+            eprintln!("{}", line.trim_end());
+        }
+    }
+}
+
+/// `m:ss`, or `h:mm:ss` from one hour up.
+pub fn format_seconds(seconds: f64) -> String {
+    let secs = seconds.max(0.0).round() as u64;
+    let (h, m, s) = (secs / 3600, (secs / 60) % 60, secs % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
+
+/// `on pace`, `0:42 behind` or `1:05 ahead`.
+pub fn pace_label(seconds: f64) -> String {
+    if seconds.abs() < 1.0 {
+        "on pace".to_string()
+    } else if seconds > 0.0 {
+        format!("{} behind", format_seconds(seconds))
+    } else {
+        format!("{} ahead", format_seconds(-seconds))
+    }
+}
+
+/// A signed difference in seconds: `+0:42` or `-1:05`.
+pub fn format_delta(seconds: f64) -> String {
+    let sign = if seconds < 0.0 { '-' } else { '+' };
+    format!("{sign}{}", format_seconds(seconds.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rehearsal(target_secs: Option<u64>, seconds: &[&[f64]]) -> Rehearsal {
+        let mut rehearsal = Rehearsal::new(target_secs.map(Duration::from_secs), None);
+        for (slide, steps) in seconds.iter().enumerate() {
+            for (step, s) in steps.iter().enumerate() {
+                rehearsal.add(slide, step, *s);
+            }
+        }
+        rehearsal
+    }
+
+    #[test]
+    fn accounts_time_to_the_slide_and_step_on_screen() {
+        let mut rehearsal = Rehearsal::new(None, None);
+        rehearsal.observe(Some((0, 0)));
+        std::thread::sleep(Duration::from_millis(20));
+        rehearsal.observe(Some((0, 1)));
+        rehearsal.observe(Some((0, 1)));
+        std::thread::sleep(Duration::from_millis(20));
+        rehearsal.observe(None);
+        std::thread::sleep(Duration::from_millis(20));
+        rehearsal.finish();
+        assert_eq!(rehearsal.seconds[0].len(), 2);
+        assert!(rehearsal.seconds[0].iter().all(|&s| s >= 0.02));
+        let on_slide = rehearsal.slide_seconds(0);
+        assert!(rehearsal.elapsed().as_secs_f64() > on_slide);
+    }
+
+    #[test]
+    fn budgets_follow_the_previous_run() {
+        let even = rehearsal(Some(600), &[]);
+        assert_eq!(even.budgets(4), Some(vec![150.0; 4]));
+        assert_eq!(rehearsal(None, &[]).budgets(4), None);
+
+        let previous = rehearsal(Some(100), &[&[10.0], &[30.0]]).report(&[]);
+        let next = Rehearsal::new(Some(Duration::from_secs(200)), Some(previous));
+        assert_eq!(next.budgets(2), Some(vec![50.0, 150.0]));
+        // Different slides: back to an even split
+        assert_eq!(next.budgets(4), Some(vec![50.0; 4]));
+    }
+
+    #[test]
+    fn paces_against_the_slide_budget() {
+        let mut rehearsal = rehearsal(Some(600), &[]);
+        rehearsal.started = Some(Instant::now() - Duration::from_secs(200));
+        let pace = |slide| rehearsal.pace(slide, 4).unwrap().round();
+        assert_eq!(pace(0), 50.0);
+        assert_eq!(pace(1), 0.0);
+        assert_eq!(pace(3), -250.0);
+    }
+
+    #[test]
+    fn reports_overruns_and_steps() {
+        let titles = vec!["Intro".to_string(), "Deep dive".to_string()];
+        let report = rehearsal(Some(120), &[&[30.04], &[20.0, 70.0]]).report(&titles);
+        assert_eq!(report.total_seconds, 120.0);
+        assert_eq!(report.target_seconds, Some(120.0));
+        let intro = &report.slides[0];
+        assert_eq!((intro.seconds, intro.overrun_seconds), (30.0, None));
+        assert!(intro.steps.is_empty());
+        let deep = &report.slides[1];
+        assert_eq!(deep.title, "Deep dive");
+        assert_eq!(deep.steps, [20.0, 70.0]);
+        assert_eq!(deep.budget_seconds, Some(60.0));
+        assert_eq!(deep.overrun_seconds, Some(30.0));
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<Report>(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn formats_times() {
+        assert_eq!(format_seconds(42.4), "0:42");
+        assert_eq!(format_seconds(3725.0), "1:02:05");
+        assert_eq!(format_delta(-65.0), "-1:05");
+        assert_eq!(format_delta(42.0), "+0:42");
+        assert_eq!(pace_label(0.4), "on pace");
+        assert_eq!(pace_label(-65.0), "1:05 ahead");
+    }
+}
@@ -0,0 +1,727 @@
+//! Remote control over the local network (`mdeck slides.md --remote 8080`).
+//!
+//! A small HTTP server serves a mobile page with the speaker notes and
+//! next/previous/blackout buttons. The page talks to the presentation over a
+//! WebSocket at `/ws`: it sends commands as text (`next`, `previous`,
+//! `goto 3`, `blackout`) and receives the current slide state as JSON
+//! whenever it changes. The same commands are accepted as `POST /next`,
+//! `POST /goto/3`, …, and `GET /state` returns the state, for scripting.
+//!
+//! The server only listens on localhost unless started for the LAN
+//! (`--remote-lan`). Every request but the page itself needs the random
+//! session token from the printed address, as `?token=…` or an
+//! `Authorization: Bearer …` header, and requests whose `Origin` is another
+//! site are refused, so neither the network nor a web page open in the
+//! presenter's browser can drive the deck or read the notes.
+//!
+//! Commands are queued for the presentation window, which applies them on
+//! its next frame through the same navigation as the keyboard.
+
+mod websocket;
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use eframe::egui;
+use serde::Serialize;
+
+use websocket::Frame;
+
+/// How long a connection may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest request line accepted, in bytes.
+const MAX_REQUEST_LINE: u64 = 8 * 1024;
+/// Most header bytes accepted in one request.
+const MAX_HEADER_BYTES: u64 = 16 * 1024;
+/// Most connections served at once; further ones are closed right away.
+const MAX_CONNECTIONS: usize = 16;
+
+const PAGE: &str = include_str!("page.html");
+
+/// What the remote page shows, sent whenever it changes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RemoteState {
+    /// Deck title from the frontmatter.
+    pub title: String,
+    /// Current slide, counting from 1.
+    pub slide: usize,
+    pub total: usize,
+    /// Reveal steps shown on the current slide, out of `steps`.
+    pub step: usize,
+    pub steps: usize,
+    /// Whether the "The End" slide is showing.
+    pub end: bool,
+    pub blackout: bool,
+    /// The current slide's first heading.
+    pub heading: String,
+    /// Speaker notes of the current slide, as written.
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RemoteCommand {
+    Next,
+    Previous,
+    /// Jump to a slide, counting from 1.
+    Goto(usize),
+    /// Toggle blackout.
+    Blackout,
+}
+
+impl RemoteCommand {
+    /// Parse a WebSocket message (`goto 3`) or request path (`/goto/3`).
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().trim_start_matches('/').to_ascii_lowercase();
+        let mut words = text.split([' ', '/']).filter(|w| !w.is_empty());
+        let command = match (words.next()?, words.next()) {
+            ("next", None) => Self::Next,
+            ("previous" | "prev", None) => Self::Previous,
+            ("blackout", None) => Self::Blackout,
+            ("goto", Some(n)) => Self::Goto(n.parse().ok().filter(|&n| n > 0)?),
+            _ => return None,
+        };
+        words.next().is_none().then_some(command)
+    }
+}
+
+/// State shared between the presentation and the connection threads.
+struct Shared {
+    /// The latest state with a version bumped on every change.
+    state: Mutex<(u64, RemoteState)>,
+    changed: Condvar,
+    /// Woken when a command arrives, so it is applied without waiting for input.
+    repaint: Mutex<Option<egui::Context>>,
+    /// The session token requests must carry.
+    token: String,
+    /// Connections being served.
+    connections: AtomicUsize,
+}
+
+impl Shared {
+    fn send(&self, commands: &Sender<RemoteCommand>, command: RemoteCommand) {
+        if commands.send(command).is_ok() {
+            if let Some(ctx) = self.repaint.lock().unwrap().as_ref() {
+                ctx.request_repaint();
+            }
+        }
+    }
+}
+
+/// One of the [`MAX_CONNECTIONS`] connection slots, freed when dropped.
+struct ConnectionSlot(Arc<Shared>);
+
+impl ConnectionSlot {
+    fn take(shared: &Arc<Shared>) -> Option<Self> {
+        shared
+            .connections
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < MAX_CONNECTIONS).then_some(n + 1)
+            })
+            .ok()?;
+        Some(Self(shared.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A running remote control server.
+pub struct Remote {
+    shared: Arc<Shared>,
+    commands: Mutex<Receiver<RemoteCommand>>,
+    addr: SocketAddr,
+}
+
+impl Remote {
+    /// Listen on `port` on localhost, or on all interfaces with `lan` so a
+    /// phone on the same network can connect. Port 0 picks a free port.
+    pub fn start(port: u16, lan: bool) -> Result<Self> {
+        let host = if lan {
+            Ipv4Addr::UNSPECIFIED
+        } else {
+            Ipv4Addr::LOCALHOST
+        };
+        let listener = TcpListener::bind((host, port))
+            .with_context(|| format!("Failed to start the remote control on port {port}"))?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            state: Mutex::new((0, RemoteState::default())),
+            changed: Condvar::new(),
+            repaint: Mutex::new(None),
+            token: session_token()?,
+            connections: AtomicUsize::new(0),
+        });
+        let (sender, commands) = mpsc::channel();
+
+        let accept_shared = shared.clone();
+        std::thread::Builder::new()
+            .name("mdeck-remote".into())
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    // Past the limit, close the connection instead of
+                    // starting another thread for it
+                    let Some(slot) = ConnectionSlot::take(&accept_shared) else {
+                        continue;
+                    };
+                    let sender = sender.clone();
+                    let _ = std::thread::Builder::new()
+                        .name("mdeck-remote-conn".into())
+                        .spawn(move || {
+                            let _ = handle_connection(stream, &slot.0, &sender);
+                        });
+                }
+            })?;
+
+        Ok(Self {
+            shared,
+            commands: Mutex::new(commands),
+            addr,
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// The address to open, with the session token: this machine's LAN
+    /// address when serving the network, else (or without a network)
+    /// localhost.
+    pub fn url(&self) -> String {
+        let host = if self.addr.ip().is_loopback() {
+            None
+        } else {
+            lan_ip()
+        };
+        format!(
+            "http://{}:{}/?token={}",
+            host.as_deref().unwrap_or("localhost"),
+            self.port(),
+            self.shared.token
+        )
+    }
+
+    /// Repaint `ctx` whenever a command arrives.
+    pub fn set_repaint(&self, ctx: egui::Context) {
+        *self.shared.repaint.lock().unwrap() = Some(ctx);
+    }
+
+    /// The next queued command, if any.
+    pub fn try_recv(&self) -> Option<RemoteCommand> {
+        self.commands.lock().unwrap().try_recv().ok()
+    }
+
+    /// Make `state` the current state, pushing it to connected pages if it changed.
+    pub fn publish(&self, state: RemoteState) {
+        let mut current = self.shared.state.lock().unwrap();
+        if current.1 != state {
+            current.0 += 1;
+            current.1 = state;
+            self.shared.changed.notify_all();
+        }
+    }
+}
+
+/// A random token for this session's address, as 32 hex digits.
+fn session_token() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes)
+        .map_err(|e| anyhow::anyhow!("No random source for the remote control token: {e}"))?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// The address other machines on the network reach this one at. Connecting
+/// a UDP socket sends nothing; it only picks the outgoing interface.
+fn lan_ip() -> Option<String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_unspecified()).then(|| ip.to_string())
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    shared: &Arc<Shared>,
+    commands: &Sender<RemoteCommand>,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let Some(request_line) = read_line_within(&mut reader, MAX_REQUEST_LINE)? else {
+        return refuse(
+            stream,
+            reader,
+            "414 URI Too Long",
+            "Request line too long\n",
+        );
+    };
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut headers = HashMap::new();
+    let mut header_bytes = 0;
+    loop {
+        let Some(line) = read_line_within(&mut reader, MAX_HEADER_BYTES - header_bytes)? else {
+            return refuse(
+                stream,
+                reader,
+                "431 Request Header Fields Too Large",
+                "Request headers too large\n",
+            );
+        };
+        if line.is_empty() {
+            break;
+        }
+        header_bytes += line.len() as u64;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    // The page holds nothing secret; it reads the token from its own address
+    if (method, path) == ("GET", "/") {
+        return respond(&mut stream, "200 OK", "text/html; charset=utf-8", PAGE);
+    }
+    if let Some(origin) = headers.get("origin")
+        && headers
+            .get("host")
+            .is_none_or(|host| *origin != format!("http://{host}"))
+    {
+        return respond(
+            &mut stream,
+            "403 Forbidden",
+            "text/plain",
+            "Requests from other sites are not allowed\n",
+        );
+    }
+    if !authorized(query, &headers, &shared.token) {
+        return respond(
+            &mut stream,
+            "401 Unauthorized",
+            "text/plain",
+            "Open the remote control address mdeck printed, with its token\n",
+        );
+    }
+
+    match (method, path) {
+        ("GET", "/state") => {
+            let json = serde_json::to_string(&shared.state.lock().unwrap().1)?;
+            respond(&mut stream, "200 OK", "application/json", &json)
+        }
+        ("GET", "/ws") => match headers.get("sec-websocket-key") {
+            Some(key) => websocket_session(stream, reader, key, shared, commands),
+            None => respond(
+                &mut stream,
+                "400 Bad Request",
+                "text/plain",
+                "Expected a WebSocket upgrade\n",
+            ),
+        },
+        (method, path) => match RemoteCommand::parse(path) {
+            Some(command) if method == "POST" => {
+                // Drain any body so closing the connection does not reset it
+                let length = headers
+                    .get("content-length")
+                    .and_then(|l| l.parse().ok())
+                    .unwrap_or(0);
+                io::copy(&mut reader.by_ref().take(length), &mut io::sink())?;
+                shared.send(commands, command);
+                respond(&mut stream, "204 No Content", "text/plain", "")
+            }
+            Some(_) => respond(
+                &mut stream,
+                "405 Method Not Allowed",
+                "text/plain",
+                "Commands are sent with POST\n",
+            ),
+            None => respond(&mut stream, "404 Not Found", "text/plain", "Not found\n"),
+        },
+    }
+}
+
+/// The next line if it fits in `limit` bytes, `None` if it is longer. At
+/// the end of the stream the line is empty.
+fn read_line_within(reader: &mut impl BufRead, limit: u64) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    let read = reader.take(limit).read_line(&mut line)?;
+    Ok((line.ends_with('\n') || (read as u64) < limit).then_some(line))
+}
+
+/// Whether the request carries the session token, in the query or as a
+/// bearer token.
+fn authorized(query: &str, headers: &HashMap<String, String>, token: &str) -> bool {
+    let given = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .or_else(|| {
+            headers
+                .get("authorization")
+                .and_then(|value| value.strip_prefix("Bearer "))
+        });
+    // Compare every byte, so timing does not tell how much of a guess is right
+    given.is_some_and(|given| {
+        let given = given.trim().as_bytes();
+        given.len() == token.len()
+            && given
+                .iter()
+                .zip(token.as_bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    })
+}
+
+/// Answer a request that is cut short. The rest of it is read and dropped
+/// after the answer, as closing with unread data would reset the connection
+/// before the client sees the answer.
+fn refuse(
+    mut stream: TcpStream,
+    reader: BufReader<TcpStream>,
+    status: &str,
+    body: &str,
+) -> io::Result<()> {
+    respond(&mut stream, status, "text/plain", body)?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+    io::copy(&mut reader.take(MAX_HEADER_BYTES), &mut io::sink())?;
+    Ok(())
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: {content_type}\r\n\
+         Content-Length: {}\r\n\
+         Cache-Control: no-store\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Answer the WebSocket handshake, then apply incoming commands while a
+/// second thread pushes every state change to the page.
+fn websocket_session(
+    mut stream: TcpStream,
+    mut reader: BufReader<TcpStream>,
+    key: &str,
+    shared: &Arc<Shared>,
+    commands: &Sender<RemoteCommand>,
+) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        websocket::accept_key(key)
+    )?;
+    stream.flush()?;
+    stream.set_read_timeout(None)?;
+
+    let writer = Arc::new(Mutex::new(stream));
+    let closed = Arc::new(AtomicBool::new(false));
+    let pusher = {
+        let (shared, writer, closed) = (shared.clone(), writer.clone(), closed.clone());
+        std::thread::spawn(move || push_states(&shared, &writer, &closed))
+    };
+
+    let result = loop {
+        match websocket::read_frame(&mut reader) {
+            Ok(Frame::Text(text)) => {
+                if let Some(command) = RemoteCommand::parse(&text) {
+                    shared.send(commands, command);
+                }
+            }
+            Ok(Frame::Ping(payload)) => {
+                if let Err(e) = websocket::write_pong(&mut *writer.lock().unwrap(), &payload) {
+                    break Err(e);
+                }
+            }
+            Ok(Frame::Close) => {
+                let _ = websocket::write_close(&mut *writer.lock().unwrap());
+                break Ok(());
+            }
+            Ok(Frame::Other) => {}
+            Err(e) => break Err(e),
+        }
+    };
+
+    closed.store(true, Ordering::Relaxed);
+    shared.changed.notify_all();
+    let _ = pusher.join();
+    result
+}
+
+/// Send the current state, then each new one, until the session closes.
+fn push_states(shared: &Shared, writer: &Mutex<TcpStream>, closed: &AtomicBool) {
+    let mut sent = None;
+    loop {
+        let json = {
+            let mut state = shared.state.lock().unwrap();
+            // Time out now and then to notice a closed session
+            while sent == Some(state.0) && !closed.load(Ordering::Relaxed) {
+                state = shared
+                    .changed
+                    .wait_timeout(state, Duration::from_secs(1))
+                    .unwrap()
+                    .0;
+            }
+            if closed.load(Ordering::Relaxed) {
+                return;
+            }
+            sent = Some(state.0);
+            serde_json::to_string(&state.1)
+        };
+        let Ok(json) = json else {
+            return;
+        };
+        if websocket::write_text(&mut *writer.lock().unwrap(), &json).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn state(slide: usize) -> RemoteState {
+        RemoteState {
+            title: "Talk".into(),
+            slide,
+            total: 5,
+            notes: format!("Notes for slide {slide}"),
+            ..Default::default()
+        }
+    }
+
+    fn connect(remote: &Remote) -> TcpStream {
+        let stream = TcpStream::connect(("127.0.0.1", remote.port())).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        stream
+    }
+
+    /// The response to the raw `request`.
+    fn send(remote: &Remote, request: &str) -> String {
+        let mut stream = connect(remote);
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    /// The response to `method path`, with the session token.
+    fn request(remote: &Remote, method: &str, path: &str) -> String {
+        send(
+            remote,
+            &format!(
+                "{method} {path}?token={} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+                remote.shared.token
+            ),
+        )
+    }
+
+    /// The next command, waiting for connection threads to deliver it.
+    fn next_command(remote: &Remote) -> RemoteCommand {
+        let start = Instant::now();
+        loop {
+            if let Some(command) = remote.try_recv() {
+                return command;
+            }
+            assert!(start.elapsed() < Duration::from_secs(5), "no command");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn parses_commands_from_messages_and_paths() {
+        assert_eq!(RemoteCommand::parse("next"), Some(RemoteCommand::Next));
+        assert_eq!(RemoteCommand::parse("/prev"), Some(RemoteCommand::Previous));
+        assert_eq!(RemoteCommand::parse("goto 3"), Some(RemoteCommand::Goto(3)));
+        assert_eq!(
+            RemoteCommand::parse("/goto/12"),
+            Some(RemoteCommand::Goto(12))
+        );
+        assert_eq!(
+            RemoteCommand::parse("Blackout\n"),
+            Some(RemoteCommand::Blackout)
+        );
+        assert_eq!(RemoteCommand::parse("goto 0"), None);
+        assert_eq!(RemoteCommand::parse("goto"), None);
+        assert_eq!(RemoteCommand::parse("next 2"), None);
+        assert_eq!(RemoteCommand::parse("/state"), None);
+    }
+
+    #[test]
+    fn serves_state_page_and_commands_over_http() {
+        let remote = Remote::start(0, false).unwrap();
+        remote.publish(state(2));
+
+        let response = request(&remote, "GET", "/state");
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.contains("\"slide\":2,\"total\":5"), "{response}");
+        assert!(
+            response.contains("\"notes\":\"Notes for slide 2\""),
+            "{response}"
+        );
+
+        let page = request(&remote, "GET", "/");
+        assert!(page.contains("text/html"), "{page}");
+        assert!(page.contains("new WebSocket"));
+
+        let response = request(&remote, "POST", "/goto/4");
+        assert!(response.starts_with("HTTP/1.1 204"), "{response}");
+        assert_eq!(next_command(&remote), RemoteCommand::Goto(4));
+        request(&remote, "POST", "/next");
+        assert_eq!(next_command(&remote), RemoteCommand::Next);
+
+        assert!(request(&remote, "GET", "/next").starts_with("HTTP/1.1 405"));
+        assert!(request(&remote, "GET", "/missing").starts_with("HTTP/1.1 404"));
+        assert!(request(&remote, "GET", "/ws").starts_with("HTTP/1.1 400"));
+        assert!(remote.try_recv().is_none());
+    }
+
+    #[test]
+    fn websocket_receives_state_changes_and_sends_commands() {
+        let remote = Remote::start(0, false).unwrap();
+        remote.publish(state(1));
+
+        let mut stream = connect(&remote);
+        write!(
+            stream,
+            "GET /ws?token={} HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\n\
+             Connection: Upgrade\r\nSec-WebSocket-Version: 13\r\n\
+             Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+            remote.shared.token
+        )
+        .unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut head = String::new();
+        while !head.ends_with("\r\n\r\n") {
+            reader.read_line(&mut head).unwrap();
+        }
+        assert!(head.starts_with("HTTP/1.1 101"), "{head}");
+        assert!(head.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+
+        let Frame::Text(json) = websocket::read_frame(&mut reader).unwrap() else {
+            panic!("expected the current state");
+        };
+        assert!(json.contains("\"slide\":1"), "{json}");
+
+        stream
+            .write_all(&websocket::client_frame(websocket::OP_TEXT, b"previous"))
+            .unwrap();
+        assert_eq!(next_command(&remote), RemoteCommand::Previous);
+
+        remote.publish(state(3));
+        let Frame::Text(json) = websocket::read_frame(&mut reader).unwrap() else {
+            panic!("expected the new state");
+        };
+        assert!(json.contains("\"slide\":3"), "{json}");
+
+        stream
+            .write_all(&websocket::client_frame(websocket::OP_CLOSE, b""))
+            .unwrap();
+        assert_eq!(websocket::read_frame(&mut reader).unwrap(), Frame::Close);
+    }
+
+    #[test]
+    fn requires_the_token_and_the_same_origin() {
+        let remote = Remote::start(0, false).unwrap();
+        assert!(remote.addr.ip().is_loopback());
+        let token = remote.shared.token.clone();
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, Remote::start(0, false).unwrap().shared.token);
+        assert!(
+            remote
+                .url()
+                .ends_with(&format!(":{}/?token={token}", remote.port()))
+        );
+
+        // The page itself is public, everything else needs the token
+        assert!(send(&remote, "GET / HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 200"));
+        for request in [
+            "GET /state HTTP/1.1\r\n\r\n".to_string(),
+            "GET /state?token=0123 HTTP/1.1\r\n\r\n".to_string(),
+            "POST /next HTTP/1.1\r\n\r\n".to_string(),
+            format!("POST /next?token={}x HTTP/1.1\r\n\r\n", token),
+            "GET /ws HTTP/1.1\r\nUpgrade: websocket\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n"
+                .to_string(),
+        ] {
+            let response = send(&remote, &request);
+            assert!(response.starts_with("HTTP/1.1 401"), "{request}: {response}");
+        }
+        let response = send(
+            &remote,
+            &format!("GET /state HTTP/1.1\r\nAuthorization: Bearer {token}\r\n\r\n"),
+        );
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+        // A page from another site cannot send commands, even with the token
+        let host = format!("localhost:{}", remote.port());
+        let from = |origin: &str| {
+            send(
+                &remote,
+                &format!(
+                    "POST /next?token={token} HTTP/1.1\r\nHost: {host}\r\nOrigin: {origin}\r\n\r\n"
+                ),
+            )
+        };
+        assert!(from("http://evil.example").starts_with("HTTP/1.1 403"));
+        assert!(remote.try_recv().is_none());
+        assert!(from(&format!("http://{host}")).starts_with("HTTP/1.1 204"));
+        assert_eq!(next_command(&remote), RemoteCommand::Next);
+    }
+
+    #[test]
+    fn limits_request_size_and_connections() {
+        let remote = Remote::start(0, false).unwrap();
+        let long_path = "a".repeat(MAX_REQUEST_LINE as usize);
+        let response = send(&remote, &format!("GET /{long_path} HTTP/1.1\r\n\r\n"));
+        assert!(response.starts_with("HTTP/1.1 414"), "{response}");
+        let header = format!("X-Padding: {}\r\n", "a".repeat(1000));
+        let response = send(
+            &remote,
+            &format!("GET / HTTP/1.1\r\n{}\r\n", header.repeat(20)),
+        );
+        assert!(response.starts_with("HTTP/1.1 431"), "{response}");
+
+        // Idle connections fill every slot, so the next one is closed
+        let idle: Vec<TcpStream> = (0..MAX_CONNECTIONS).map(|_| connect(&remote)).collect();
+        let start = Instant::now();
+        while remote.shared.connections.load(Ordering::SeqCst) < MAX_CONNECTIONS {
+            assert!(start.elapsed() < Duration::from_secs(5), "slots not taken");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let mut extra = connect(&remote);
+        let mut response = String::new();
+        let _ = extra.read_to_string(&mut response);
+        assert_eq!(response, "");
+
+        // Closing them frees the slots again
+        drop(idle);
+        let start = Instant::now();
+        while remote.shared.connections.load(Ordering::SeqCst) > 0 {
+            assert!(start.elapsed() < Duration::from_secs(5), "slots not freed");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(request(&remote, "GET", "/state").starts_with("HTTP/1.1 200"));
+    }
+}
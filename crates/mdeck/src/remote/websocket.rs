@@ -0,0 +1,154 @@
+//! The parts of WebSocket (RFC 6455) the remote page needs: the opening
+//! handshake and unfragmented text, ping and close frames.
+
+use std::io::{self, Read, Write};
+
+use base64::Engine;
+use sha1::{Digest, Sha1};
+
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// Commands are a few bytes; anything larger is not from the remote page.
+const MAX_PAYLOAD: u64 = 64 * 1024;
+
+pub const OP_TEXT: u8 = 0x1;
+pub const OP_CLOSE: u8 = 0x8;
+pub const OP_PING: u8 = 0x9;
+pub const OP_PONG: u8 = 0xA;
+
+#[derive(Debug, PartialEq)]
+pub enum Frame {
+    Text(String),
+    Ping(Vec<u8>),
+    Close,
+    /// Binary, pong and continuation frames, which the remote ignores.
+    Other,
+}
+
+/// The `Sec-WebSocket-Accept` value answering a client's `Sec-WebSocket-Key`.
+pub fn accept_key(key: &str) -> String {
+    let mut sha = Sha1::new();
+    sha.update(key.trim().as_bytes());
+    sha.update(GUID.as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(sha.finalize())
+}
+
+/// Read one frame, unmasking client payloads.
+pub fn read_frame(r: &mut impl Read) -> io::Result<Frame> {
+    let mut head = [0u8; 2];
+    r.read_exact(&mut head)?;
+    let opcode = head[0] & 0x0F;
+    let masked = head[1] & 0x80 != 0;
+    let len = match head[1] & 0x7F {
+        126 => {
+            let mut ext = [0u8; 2];
+            r.read_exact(&mut ext)?;
+            u16::from_be_bytes(ext) as u64
+        }
+        127 => {
+            let mut ext = [0u8; 8];
+            r.read_exact(&mut ext)?;
+            u64::from_be_bytes(ext)
+        }
+        n => n as u64,
+    };
+    if len > MAX_PAYLOAD {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "WebSocket frame too large",
+        ));
+    }
+    let mut mask = [0u8; 4];
+    if masked {
+        r.read_exact(&mut mask)?;
+    }
+    let mut payload = vec![0u8; len as usize];
+    r.read_exact(&mut payload)?;
+    if masked {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+    }
+    Ok(match opcode {
+        OP_TEXT => Frame::Text(String::from_utf8_lossy(&payload).into_owned()),
+        OP_PING => Frame::Ping(payload),
+        OP_CLOSE => Frame::Close,
+        _ => Frame::Other,
+    })
+}
+
+/// Write an unmasked text frame, as servers send them.
+pub fn write_text(w: &mut impl Write, text: &str) -> io::Result<()> {
+    write_frame(w, OP_TEXT, text.as_bytes())
+}
+
+pub fn write_pong(w: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    write_frame(w, OP_PONG, payload)
+}
+
+pub fn write_close(w: &mut impl Write) -> io::Result<()> {
+    write_frame(w, OP_CLOSE, &[])
+}
+
+fn write_frame(w: &mut impl Write, opcode: u8, payload: &[u8]) -> io::Result<()> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        n if n < 126 => frame.push(n as u8),
+        n if n <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            frame.push(127);
+            frame.extend_from_slice(&(n as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    w.write_all(&frame)?;
+    w.flush()
+}
+
+/// A masked client frame, for tests.
+#[cfg(test)]
+pub fn client_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mask = [0x12, 0x34, 0x56, 0x78];
+    let mut frame = vec![0x80 | opcode, 0x80 | payload.len() as u8];
+    frame.extend_from_slice(&mask);
+    frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_the_rfc_handshake_example() {
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn reads_masked_frames_and_writes_long_ones() {
+        let mut input = client_frame(OP_TEXT, b"goto 3");
+        input.extend(client_frame(OP_PING, b"hi"));
+        input.extend(client_frame(OP_CLOSE, b""));
+        let mut reader = input.as_slice();
+        assert_eq!(
+            read_frame(&mut reader).unwrap(),
+            Frame::Text("goto 3".into())
+        );
+        assert_eq!(
+            read_frame(&mut reader).unwrap(),
+            Frame::Ping(b"hi".to_vec())
+        );
+        assert_eq!(read_frame(&mut reader).unwrap(), Frame::Close);
+
+        let text = "x".repeat(300);
+        let mut out = Vec::new();
+        write_text(&mut out, &text).unwrap();
+        assert_eq!(&out[..4], &[0x81, 126, 0x01, 0x2C]);
+        assert_eq!(read_frame(&mut out.as_slice()).unwrap(), Frame::Text(text));
+    }
+}
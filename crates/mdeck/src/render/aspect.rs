@@ -0,0 +1,154 @@
+//! Slide aspect ratio (`@aspect`) and the reference canvas slides are laid out on.
+//!
+//! Slides are designed on a canvas 1080 units tall whose width follows the
+//! aspect ratio (1920×1080 for 16:9, 1440×1080 for 4:3). On screen the canvas
+//! is letterboxed into the window and scaled uniformly.
+
+use eframe::egui;
+
+use crate::parser::PresentationMeta;
+
+/// Height of the reference canvas; theme sizes are in these units.
+pub const REFERENCE_HEIGHT: f32 = 1080.0;
+
+/// A slide aspect ratio, optionally with an explicit pixel size (`1024x768`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AspectRatio {
+    ratio: f32,
+    size: Option<(u32, u32)>,
+}
+
+impl Default for AspectRatio {
+    fn default() -> Self {
+        Self {
+            ratio: 16.0 / 9.0,
+            size: None,
+        }
+    }
+}
+
+impl AspectRatio {
+    /// Parse `W:H` (`16:9`, `4:3`, `16:10`, `2.39:1`) or a pixel size `WxH`
+    /// (`1024x768`). Returns `None` for anything else.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Some((w, h)) = value.split_once(':') {
+            let (w, h) = (w.trim().parse::<f32>().ok()?, h.trim().parse::<f32>().ok()?);
+            if !(w.is_finite() && h.is_finite() && w > 0.0 && h > 0.0) {
+                return None;
+            }
+            return Some(Self {
+                ratio: w / h,
+                size: None,
+            });
+        }
+        let (w, h) = value.split_once(['x', 'X', '×'])?;
+        let (w, h) = (w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?);
+        if w == 0 || h == 0 {
+            return None;
+        }
+        Some(Self {
+            ratio: w as f32 / h as f32,
+            size: Some((w, h)),
+        })
+    }
+
+    /// The deck's aspect ratio from `@aspect`; unset or invalid values give 16:9.
+    pub fn for_deck(meta: &PresentationMeta) -> Self {
+        meta.aspect
+            .as_deref()
+            .and_then(Self::parse)
+            .unwrap_or_default()
+    }
+
+    /// Width divided by height.
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Size of the reference canvas.
+    pub fn reference_size(&self) -> egui::Vec2 {
+        egui::vec2(REFERENCE_HEIGHT * self.ratio, REFERENCE_HEIGHT)
+    }
+
+    /// Scale from reference units to `rect`, fitting the whole canvas.
+    pub fn scale(&self, rect: egui::Rect) -> f32 {
+        let reference = self.reference_size();
+        (rect.width() / reference.x).min(rect.height() / reference.y)
+    }
+
+    /// The largest rect of this aspect ratio centered in `area` (letterboxing).
+    pub fn fit(&self, area: egui::Rect) -> egui::Rect {
+        let width = area.width().min(area.height() * self.ratio);
+        let height = width / self.ratio;
+        egui::Rect::from_center_size(area.center(), egui::vec2(width, height))
+    }
+
+    /// Export image size. Explicit dimensions win; a missing one follows the
+    /// ratio. With neither, a `WxH` aspect gives its own size and a ratio gives
+    /// the reference canvas.
+    pub fn export_size(&self, width: Option<u32>, height: Option<u32>) -> (u32, u32) {
+        match (width, height) {
+            (Some(w), Some(h)) => (w, h),
+            (Some(w), None) => (w, (w as f32 / self.ratio).round().max(1.0) as u32),
+            (None, Some(h)) => ((h as f32 * self.ratio).round().max(1.0) as u32, h),
+            (None, None) => self.size.unwrap_or_else(|| {
+                let size = self.reference_size();
+                (size.x.round() as u32, size.y as u32)
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ratios_and_sizes() {
+        assert_eq!(AspectRatio::parse("4:3").unwrap().ratio(), 4.0 / 3.0);
+        assert_eq!(AspectRatio::parse(" 16 : 10 ").unwrap().ratio(), 1.6);
+        assert_eq!(AspectRatio::parse("2.39:1").unwrap().ratio(), 2.39);
+        let size = AspectRatio::parse("1024x768").unwrap();
+        assert_eq!(size.ratio(), 4.0 / 3.0);
+        assert_eq!(size.export_size(None, None), (1024, 768));
+        for bad in ["", "16", "16:0", "-4:3", "wide", "0x768", "1024x", "nan:1"] {
+            assert_eq!(AspectRatio::parse(bad), None, "{bad}");
+        }
+    }
+
+    #[test]
+    fn invalid_or_missing_aspect_is_widescreen() {
+        let mut meta = PresentationMeta::default();
+        assert_eq!(AspectRatio::for_deck(&meta), AspectRatio::default());
+        meta.aspect = Some("square".to_string());
+        assert_eq!(AspectRatio::for_deck(&meta).ratio(), 16.0 / 9.0);
+    }
+
+    #[test]
+    fn export_size_follows_ratio() {
+        let standard = AspectRatio::parse("4:3").unwrap();
+        assert_eq!(standard.export_size(None, None), (1440, 1080));
+        assert_eq!(standard.export_size(Some(1024), None), (1024, 768));
+        assert_eq!(standard.export_size(None, Some(600)), (800, 600));
+        assert_eq!(standard.export_size(Some(100), Some(100)), (100, 100));
+        let wide = AspectRatio::default();
+        assert_eq!(wide.export_size(None, None), (1920, 1080));
+        let sixteen_ten = AspectRatio::parse("16:10").unwrap();
+        assert_eq!(sixteen_ten.export_size(None, None), (1728, 1080));
+    }
+
+    #[test]
+    fn fit_letterboxes_and_scale_matches_reference() {
+        let window = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(1920.0, 1080.0));
+        let standard = AspectRatio::parse("4:3").unwrap();
+        let slide = standard.fit(window);
+        assert_eq!(
+            slide,
+            egui::Rect::from_min_max(egui::pos2(240.0, 0.0), egui::pos2(1680.0, 1080.0))
+        );
+        assert_eq!(standard.scale(slide), 1.0);
+        assert_eq!(standard.scale(window), 1.0);
+        assert_eq!(AspectRatio::default().fit(window), window);
+    }
+}
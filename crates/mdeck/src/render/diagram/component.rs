@@ -0,0 +1,233 @@
+//! Component nodes in architecture diagrams: the outline chosen with
+//! `shape:` (box, circle, cylinder, cloud) and the emphasis set by `style:`
+//! (primary, secondary, muted) and `color:`.
+
+use eframe::egui::{self, Color32, Pos2, Stroke};
+
+use super::{DiagramNode, NodeLayout};
+
+/// Values of the `shape:` key for component nodes.
+pub(super) const SHAPES: &[&str] = &["box", "circle", "cylinder", "cloud"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum NodeShape {
+    /// A rounded box (the default).
+    Box,
+    /// A circle as wide as the node is tall.
+    Circle,
+    /// A database-style cylinder.
+    Cylinder,
+    /// A scalloped cloud.
+    Cloud,
+}
+
+/// The shape of `node` from its `shape:` key; unknown values draw a box.
+pub(super) fn shape_of(node: &DiagramNode) -> NodeShape {
+    match node.shape.as_str() {
+        "circle" => NodeShape::Circle,
+        "cylinder" => NodeShape::Cylinder,
+        "cloud" => NodeShape::Cloud,
+        _ => NodeShape::Box,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum NodeStyle {
+    /// Accent border (the default).
+    Primary,
+    /// Thin, neutral border.
+    Secondary,
+    /// Faded: for components in the background of the story.
+    Muted,
+}
+
+/// The emphasis of `node` from its `style:` key; unknown values are primary.
+pub(super) fn style_of(node: &DiagramNode) -> NodeStyle {
+    match node.style.as_str() {
+        "secondary" => NodeStyle::Secondary,
+        "muted" => NodeStyle::Muted,
+        _ => NodeStyle::Primary,
+    }
+}
+
+/// Circles are as wide as they are tall; the other shapes fill the cell's
+/// node box.
+pub(super) fn fit_shape(shape: NodeShape, layout: &mut NodeLayout) {
+    if shape == NodeShape::Circle {
+        let size = layout.width.min(layout.height);
+        layout.width = size;
+        layout.height = size;
+    }
+}
+
+/// Where the icon and label go inside a `shape` node, as fractions of its
+/// height: icon size, icon center and label top, both measured from the
+/// node's center. Round shapes pull them in from the narrow top and bottom.
+pub(super) fn content_placement(shape: NodeShape) -> (f32, f32, f32) {
+    match shape {
+        NodeShape::Box => (0.5, -0.12, 0.25),
+        NodeShape::Circle | NodeShape::Cloud => (0.4, -0.16, 0.02),
+        NodeShape::Cylinder => (0.4, -0.04, 0.2),
+    }
+}
+
+/// Height of a cylinder's elliptical caps.
+fn cap_height(rect: &egui::Rect) -> f32 {
+    (rect.height() * 0.24).min(rect.width() * 0.3)
+}
+
+/// Points on the ellipse centered at `center` with radii `r`, from angle
+/// `from` to `to` (degrees, clockwise from +x on screen).
+fn arc(center: Pos2, r: egui::Vec2, from: f32, to: f32, steps: usize) -> Vec<Pos2> {
+    (0..=steps)
+        .map(|k| {
+            let a = (from + (to - from) * k as f32 / steps as f32).to_radians();
+            center + egui::vec2(r.x * a.cos(), r.y * a.sin())
+        })
+        .collect()
+}
+
+/// The closed outline of a cloud filling `rect`: an ellipse with eight
+/// bumps around it.
+fn cloud_outline(rect: egui::Rect) -> Vec<Pos2> {
+    let c = rect.center();
+    let (hw, hh) = (rect.width() / 2.0, rect.height() / 2.0);
+    (0..96)
+        .map(|k| {
+            let a = k as f32 / 96.0 * std::f32::consts::TAU;
+            let r = 0.86 + 0.14 * (4.0 * a).sin().abs();
+            c + egui::vec2(hw * r * a.cos(), hh * r * a.sin())
+        })
+        .collect()
+}
+
+/// Fill the polygon `points`, every point of which can be seen from
+/// `center`, as a triangle fan.
+fn fill_fan(painter: &egui::Painter, center: Pos2, points: &[Pos2], color: Color32) {
+    let mut mesh = egui::Mesh::default();
+    mesh.colored_vertex(center, color);
+    for &p in points {
+        mesh.colored_vertex(p, color);
+    }
+    let n = points.len() as u32;
+    for k in 0..n {
+        mesh.add_triangle(0, k + 1, (k + 1) % n + 1);
+    }
+    painter.add(egui::Shape::mesh(mesh));
+}
+
+/// Draw the outline of a `shape` node filling `rect`: shadow, fill and
+/// border. Icon and label go on top.
+pub(super) fn draw_outline(
+    painter: &egui::Painter,
+    shape: NodeShape,
+    rect: egui::Rect,
+    fill: Color32,
+    stroke: Stroke,
+    shadow: Color32,
+    scale: f32,
+) {
+    let offset = egui::vec2(3.0 * scale, 3.0 * scale);
+    match shape {
+        NodeShape::Box => {
+            let radius = 8.0 * scale;
+            painter.rect_filled(rect.translate(offset), radius, shadow);
+            painter.rect_filled(rect, radius, fill);
+            painter.rect_stroke(rect, radius, stroke, egui::StrokeKind::Outside);
+        }
+        NodeShape::Circle => {
+            let r = rect.width().min(rect.height()) / 2.0;
+            painter.circle_filled(rect.center() + offset, r, shadow);
+            painter.circle(rect.center(), r, fill, stroke);
+        }
+        NodeShape::Cylinder => {
+            let cap = cap_height(&rect);
+            let r = egui::vec2(rect.width() / 2.0, cap / 2.0);
+            let top = egui::pos2(rect.center().x, rect.top() + cap / 2.0);
+            let bottom = egui::pos2(rect.center().x, rect.bottom() - cap / 2.0);
+            let mut silhouette = arc(top, r, 180.0, 360.0, 16);
+            silhouette.extend(arc(bottom, r, 0.0, 180.0, 16));
+            let shadow_points = silhouette.iter().map(|p| *p + offset).collect();
+            painter.add(egui::Shape::convex_polygon(
+                shadow_points,
+                shadow,
+                Stroke::NONE,
+            ));
+            painter.add(egui::Shape::convex_polygon(silhouette, fill, stroke));
+            // Front rim of the top cap
+            painter.add(egui::Shape::line(arc(top, r, 0.0, 180.0, 16), stroke));
+        }
+        NodeShape::Cloud => {
+            let outline = cloud_outline(rect);
+            let shadow_points: Vec<Pos2> = outline.iter().map(|p| *p + offset).collect();
+            fill_fan(painter, rect.center() + offset, &shadow_points, shadow);
+            fill_fan(painter, rect.center(), &outline, fill);
+            painter.add(egui::Shape::closed_line(outline, stroke));
+        }
+    }
+}
+
+/// Move the first (`at_start`) or last point of an edge from `rect`'s face
+/// onto a round `shape`'s outline, keeping the edge's last segment straight.
+/// Boxes and cylinders already end on the rect.
+pub(super) fn clip_to_shape(
+    points: &mut [Pos2],
+    shape: NodeShape,
+    rect: &egui::Rect,
+    at_start: bool,
+) {
+    let (rx, ry) = match shape {
+        NodeShape::Box | NodeShape::Cylinder => return,
+        NodeShape::Circle => {
+            let r = rect.width().min(rect.height()) / 2.0;
+            (r, r)
+        }
+        NodeShape::Cloud => (rect.width() * 0.46, rect.height() * 0.46),
+    };
+    if points.len() < 2 {
+        return;
+    }
+    let n = points.len();
+    let (end, next) = if at_start { (0, 1) } else { (n - 1, n - 2) };
+    let p = points[end];
+    let outward = points[next] - p;
+    let c = rect.center();
+    points[end] = if outward.x.abs() < outward.y.abs() {
+        let t = ((p.x - c.x) / rx).clamp(-1.0, 1.0);
+        egui::pos2(p.x, c.y + (ry * (1.0 - t * t).sqrt()).copysign(outward.y))
+    } else {
+        let t = ((p.y - c.y) / ry).clamp(-1.0, 1.0);
+        egui::pos2(c.x + (rx * (1.0 - t * t).sqrt()).copysign(outward.x), p.y)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_diagram;
+    use super::*;
+
+    #[test]
+    fn shape_and_style_come_from_keys() {
+        let (nodes, _, _) = parse_diagram(
+            "- Db (shape: Cylinder, style: muted)\n- Web (shape: hexagon)\n- New (style: primary)",
+        );
+        assert_eq!(shape_of(&nodes[0]), NodeShape::Cylinder);
+        assert_eq!(style_of(&nodes[0]), NodeStyle::Muted);
+        assert_eq!(shape_of(&nodes[1]), NodeShape::Box);
+        assert_eq!(style_of(&nodes[2]), NodeStyle::Primary);
+    }
+
+    #[test]
+    fn clips_edge_ends_onto_circle() {
+        let rect = egui::Rect::from_center_size(egui::pos2(100.0, 100.0), egui::vec2(80.0, 80.0));
+        // Leaving from the right face, off-center
+        let mut points = [egui::pos2(140.0, 124.0), egui::pos2(200.0, 124.0)];
+        clip_to_shape(&mut points, NodeShape::Circle, &rect, true);
+        assert!((points[0].x - 132.0).abs() < 0.01);
+        assert_eq!(points[0].y, 124.0);
+        // Boxes keep the face point
+        let mut points = [egui::pos2(200.0, 100.0), egui::pos2(140.0, 100.0)];
+        clip_to_shape(&mut points, NodeShape::Box, &rect, false);
+        assert_eq!(points[1], egui::pos2(140.0, 100.0));
+    }
+}
@@ -0,0 +1,308 @@
+//! Flowcharts (`@flowchart` or `@architecture flowchart`): the architecture
+//! diagram syntax drawn as steps and decisions, laid out top to bottom.
+//!
+//! Nodes take their shape from a `shape:` key or from their name: labels
+//! ending in `?` are decisions and `Start`/`End` are terminals. Without any
+//! `pos:` hints, nodes are placed by the layered layout running top to
+//! bottom and the grid positions go through the same router as architecture
+//! diagrams. A decision's `yes` branch continues straight down and its `no`
+//! branch turns to the right.
+
+use eframe::egui::{self, Color32, FontId, Pos2, Stroke};
+
+use super::placement::{self, DiagramLayout, Direction};
+use super::{DiagramEdge, DiagramNode, NodeLayout};
+use crate::theme::Theme;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum FlowShape {
+    /// Start or end: a pill.
+    Terminal,
+    /// A step: a box.
+    Process,
+    /// A question: a diamond.
+    Decision,
+    /// Input or output: a parallelogram.
+    Io,
+}
+
+/// Values of the `shape:` key for flowchart nodes.
+pub(super) const SHAPES: &[&str] = &[
+    "start",
+    "end",
+    "terminal",
+    "pill",
+    "process",
+    "box",
+    "decision",
+    "diamond",
+    "io",
+    "input",
+    "output",
+    "parallelogram",
+];
+
+/// The shape of `node`, from its `shape:` key or else its name and label.
+pub(super) fn shape_of(node: &DiagramNode) -> FlowShape {
+    match node.shape.as_str() {
+        "start" | "end" | "terminal" | "pill" => FlowShape::Terminal,
+        "process" | "box" => FlowShape::Process,
+        "decision" | "diamond" => FlowShape::Decision,
+        "io" | "input" | "output" | "parallelogram" => FlowShape::Io,
+        _ if node.label.trim_end().ends_with('?') => FlowShape::Decision,
+        _ => match node.name.to_lowercase().as_str() {
+            "start" | "begin" | "end" | "stop" | "done" => FlowShape::Terminal,
+            _ => FlowShape::Process,
+        },
+    }
+}
+
+/// Where a branch goes among its siblings: `yes` first (straight down,
+/// as a decision lines up with it), `no` last (to the right).
+pub(super) fn branch_rank(edge: &DiagramEdge) -> u8 {
+    match edge.label.trim().to_lowercase().as_str() {
+        "yes" | "y" | "true" => 0,
+        "no" | "n" | "false" => 2,
+        _ => 1,
+    }
+}
+
+/// Give every node a grid position with the layered layout running top to
+/// bottom, unless the source already places some with `pos:`.
+pub(super) fn place_nodes(nodes: &mut [DiagramNode], edges: &[DiagramEdge]) {
+    placement::place_nodes(
+        DiagramLayout::Layered(Direction::TopBottom),
+        nodes,
+        edges,
+        branch_rank,
+    );
+}
+
+/// Flowchart nodes carry no icon, so they are flatter than component boxes.
+/// Diamonds get extra width for their label.
+pub(super) fn fit_shape(shape: FlowShape, layout: &mut NodeLayout) {
+    if shape == FlowShape::Decision {
+        layout.width *= 1.3;
+        layout.height *= 0.9;
+    } else {
+        layout.height *= 0.6;
+    }
+}
+
+/// How far a parallelogram's top edge is shifted right of its bottom edge.
+fn skew(rect: &egui::Rect) -> f32 {
+    (rect.height() * 0.5).min(rect.width() * 0.2)
+}
+
+fn outline(shape: FlowShape, rect: egui::Rect) -> Vec<Pos2> {
+    match shape {
+        FlowShape::Decision => vec![
+            rect.center_top(),
+            rect.right_center(),
+            rect.center_bottom(),
+            rect.left_center(),
+        ],
+        _ => {
+            let skew = skew(&rect);
+            vec![
+                egui::pos2(rect.left() + skew, rect.top()),
+                rect.right_top(),
+                egui::pos2(rect.right() - skew, rect.bottom()),
+                rect.left_bottom(),
+            ]
+        }
+    }
+}
+
+/// Draw a flowchart node of `shape` filling `rect`, with `label` centered.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn draw_node(
+    painter: &egui::Painter,
+    shape: FlowShape,
+    rect: egui::Rect,
+    label: &str,
+    theme: &Theme,
+    fill: Color32,
+    border: Color32,
+    shadow: Color32,
+    text_color: Color32,
+    scale: f32,
+) {
+    let stroke = Stroke::new(2.5 * scale, border);
+    let offset = egui::vec2(3.0 * scale, 3.0 * scale);
+    match shape {
+        FlowShape::Terminal | FlowShape::Process => {
+            let radius = if shape == FlowShape::Terminal {
+                rect.height() / 2.0
+            } else {
+                6.0 * scale
+            };
+            painter.rect_filled(rect.translate(offset), radius, shadow);
+            painter.rect_filled(rect, radius, fill);
+            painter.rect_stroke(rect, radius, stroke, egui::StrokeKind::Outside);
+        }
+        FlowShape::Decision | FlowShape::Io => {
+            let points = outline(shape, rect);
+            let shadow_points = points.iter().map(|p| *p + offset).collect();
+            painter.add(egui::Shape::convex_polygon(
+                shadow_points,
+                shadow,
+                Stroke::NONE,
+            ));
+            painter.add(egui::Shape::convex_polygon(points, fill, stroke));
+        }
+    }
+
+    let wrap = match shape {
+        FlowShape::Decision => rect.width() * 0.7,
+        FlowShape::Io => rect.width() - skew(&rect) * 2.0,
+        _ => rect.width() - 16.0 * scale,
+    };
+    let galley = painter.layout(
+        label.to_string(),
+        FontId::proportional(theme.body_size * 0.6 * scale),
+        text_color,
+        wrap.max(20.0),
+    );
+    let pos = rect.center() - galley.rect.size() / 2.0;
+    painter.galley(pos, galley, text_color);
+}
+
+/// Move the end of an edge that touches `rect` from the rectangle's face to
+/// the outline of `shape`, so arrows meet diamonds and pills. `at_start`
+/// picks which end of `points` to move.
+pub(super) fn clip_to_shape(
+    points: &mut [Pos2],
+    shape: FlowShape,
+    rect: &egui::Rect,
+    at_start: bool,
+) {
+    if points.len() < 2 || shape == FlowShape::Process {
+        return;
+    }
+    let n = points.len();
+    let (end, next) = if at_start { (0, 1) } else { (n - 1, n - 2) };
+    let p = points[end];
+    let outward = points[next] - p;
+    let c = rect.center();
+    let (hw, hh) = (rect.width() / 2.0, rect.height() / 2.0);
+    let vertical = outward.x.abs() < outward.y.abs();
+
+    points[end] = match shape {
+        FlowShape::Decision if vertical => {
+            let dy = hh * (1.0 - (p.x - c.x).abs() / hw).max(0.0);
+            egui::pos2(p.x, c.y + dy.copysign(outward.y))
+        }
+        FlowShape::Decision => {
+            let dx = hw * (1.0 - (p.y - c.y).abs() / hh).max(0.0);
+            egui::pos2(c.x + dx.copysign(outward.x), p.y)
+        }
+        FlowShape::Terminal => {
+            let r = hh.min(hw);
+            let straight = hw - r;
+            if vertical {
+                let beyond = ((p.x - c.x).abs() - straight).clamp(0.0, r);
+                let dy = (r * r - beyond * beyond).sqrt();
+                egui::pos2(p.x, c.y + dy.copysign(outward.y))
+            } else {
+                let dy = (p.y - c.y).abs().min(r);
+                let dx = straight + (r * r - dy * dy).sqrt();
+                egui::pos2(c.x + dx.copysign(outward.x), p.y)
+            }
+        }
+        FlowShape::Io if !vertical => {
+            let skew = skew(rect);
+            let x = if outward.x < 0.0 {
+                rect.left() + skew * (rect.bottom() - p.y) / rect.height()
+            } else {
+                rect.right() - skew * (p.y - rect.top()) / rect.height()
+            };
+            egui::pos2(x, p.y)
+        }
+        FlowShape::Io | FlowShape::Process => p,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::diagram::parse_diagram;
+
+    fn positions(content: &str) -> Vec<(String, (u32, u32))> {
+        let (mut nodes, edges, _) = parse_diagram(content);
+        place_nodes(&mut nodes, &edges);
+        nodes
+            .into_iter()
+            .map(|n| (n.name, n.grid_pos.unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn infers_shapes_from_names_and_keys() {
+        let (nodes, _, _) = parse_diagram(
+            "- Start\n- Valid?: Is the form valid?\n- Save (shape: io)\n- Log\n- Check (shape: diamond)",
+        );
+        let shapes: Vec<FlowShape> = nodes.iter().map(shape_of).collect();
+        assert_eq!(
+            shapes,
+            [
+                FlowShape::Terminal,
+                FlowShape::Decision,
+                FlowShape::Io,
+                FlowShape::Process,
+                FlowShape::Decision,
+            ]
+        );
+    }
+
+    #[test]
+    fn lays_out_layers_with_yes_down_and_no_right() {
+        let placed = positions(
+            "- Start -> Read\n- Read -> Valid?\n- Valid? -> Fix: no\n- Valid? -> Save: yes\n\
+             - Fix -> Read\n- Save -> End",
+        );
+        let pos = |name: &str| placed.iter().find(|(n, _)| n == name).unwrap().1;
+        assert_eq!(pos("Start"), (1, 1));
+        assert_eq!(pos("Read"), (1, 2));
+        assert_eq!(pos("Valid?"), (1, 3));
+        // `yes` stays in the column, `no` turns right; the loop back to
+        // Read does not push Read down
+        assert_eq!(pos("Save"), (1, 4));
+        assert_eq!(pos("Fix"), (2, 4));
+        assert_eq!(pos("End"), (1, 5));
+    }
+
+    #[test]
+    fn centers_fan_out_and_keeps_explicit_positions() {
+        let placed = positions("- A -> B\n- A -> C\n- A -> D\n- B -> E\n- D -> E");
+        let pos = |name: &str| placed.iter().find(|(n, _)| n == name).unwrap().1;
+        assert_eq!((pos("B"), pos("C"), pos("D")), ((1, 2), (2, 2), (3, 2)));
+        assert_eq!(pos("A"), (2, 1));
+        assert_eq!(pos("E"), (2, 3));
+
+        let (mut nodes, edges, _) = parse_diagram("- A (pos: 3,1)\n- A -> B");
+        place_nodes(&mut nodes, &edges);
+        assert_eq!(nodes[0].grid_pos, Some((3, 1)));
+        assert_eq!(nodes[1].grid_pos, None);
+    }
+
+    #[test]
+    fn clips_edges_to_the_outline() {
+        let rect = egui::Rect::from_center_size(Pos2::ZERO, egui::vec2(100.0, 60.0));
+        // Entering the diamond from above, off-center
+        let mut points = [
+            egui::pos2(0.0, -100.0),
+            egui::pos2(25.0, -100.0),
+            egui::pos2(25.0, -30.0),
+        ];
+        clip_to_shape(&mut points, FlowShape::Decision, &rect, false);
+        assert_eq!(points[2], egui::pos2(25.0, -15.0));
+        // Leaving the pill to the right
+        let mut points = [egui::pos2(50.0, 0.0), egui::pos2(90.0, 0.0)];
+        clip_to_shape(&mut points, FlowShape::Terminal, &rect, true);
+        assert_eq!(points[0], egui::pos2(50.0, 0.0));
+        let mut points = [egui::pos2(0.0, -30.0), egui::pos2(0.0, -90.0)];
+        clip_to_shape(&mut points, FlowShape::Process, &rect, true);
+        assert_eq!(points[0], egui::pos2(0.0, -30.0));
+    }
+}
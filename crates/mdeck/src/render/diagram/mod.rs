@@ -1,4 +1,8 @@
+mod component;
+mod flowchart;
+mod placement;
 pub mod routing;
+mod sequence;
 
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
@@ -7,7 +11,7 @@ use std::sync::{Arc, LazyLock, Mutex, mpsc};
 use std::time::Instant;
 
 use crate::check::{CheckCategory, CheckReport, CheckWarning};
-
+use crate::parser::DiagramKind;
 use crate::render::image_cache::ImageCache;
 use crate::theme::Theme;
 use eframe::egui::{self, Color32, FontFamily, FontId, Pos2, Stroke};
@@ -28,8 +32,12 @@ pub fn clear_route_cache() {
 
 /// Check a single diagram's routes and return any failure warning strings.
 /// Also populates the route cache as a side effect.
-pub fn check_diagram_routes(content: &str) -> Vec<String> {
-    let (nodes, edges, _scale_directive) = parse_diagram(content);
+pub fn check_diagram_routes(kind: DiagramKind, content: &str) -> Vec<String> {
+    // Sequence diagrams are not routed
+    if kind == DiagramKind::Sequence {
+        return Vec::new();
+    }
+    let (nodes, edges, _scale_directive) = parse_routed_diagram(kind, content);
     if nodes.is_empty() || edges.is_empty() {
         return Vec::new();
     }
@@ -43,7 +51,7 @@ pub fn check_diagram_routes(content: &str) -> Vec<String> {
     let area_height = diagram_height - padding * 2.0;
     let lane_spacing = 20.0 * scale;
 
-    let (layouts, grid) = layout_nodes(&nodes, area_width, area_height, 0.0, 0.0, scale);
+    let (layouts, grid) = layout_nodes(kind, &nodes, area_width, area_height, 0.0, 0.0, scale);
 
     let mut node_rects: HashMap<String, egui::Rect> = HashMap::new();
     for (i, node) in nodes.iter().enumerate() {
@@ -65,6 +73,7 @@ pub fn check_diagram_routes(content: &str) -> Vec<String> {
                 name: n.name.clone(),
                 col: (col + 1) as i32,
                 row: (row + 1) as i32,
+                group: n.group,
             })
         })
         .collect();
@@ -113,25 +122,98 @@ pub fn check_diagram_routes(content: &str) -> Vec<String> {
         .collect()
 }
 
+/// Check the node keys of a component diagram or flowchart: unknown keys,
+/// invalid `style`, `color` and `shape` values, unknown `@layout:` values and
+/// a radial layout of grouped nodes. Sequence diagrams get their own checks,
+/// see [`sequence::check`].
+pub fn check_diagram_keys(kind: DiagramKind, content: &str) -> Vec<String> {
+    if kind == DiagramKind::Sequence {
+        return sequence::check(content);
+    }
+    let shapes = if kind == DiagramKind::Flowchart {
+        flowchart::SHAPES
+    } else {
+        component::SHAPES
+    };
+    let mut warnings = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(Err(value)) = placement::parse_directive(line) {
+            warnings.push(format!(
+                "Unknown @layout '{value}' (expected grid, layered [LR|TB] or radial)"
+            ));
+        }
+        if line.starts_with('#') || line.starts_with("@layout:") {
+            continue;
+        }
+        let meta = parse_node_metadata(strip_reveal_marker(line).0);
+        if detect_arrow(meta.before).is_some() {
+            continue;
+        }
+        let name = meta.before.split(": ").next().unwrap_or_default().trim();
+        for key in &meta.unknown {
+            warnings.push(format!(
+                "Unknown key '{key}' on node '{name}' (known: {})",
+                NODE_KEYS.join(", ")
+            ));
+        }
+        if !meta.style.is_empty() && !NODE_STYLES.contains(&meta.style.as_str()) {
+            warnings.push(format!(
+                "Unknown style '{}' on node '{name}' (expected {})",
+                meta.style,
+                NODE_STYLES.join(", ")
+            ));
+        }
+        if !meta.color.is_empty() && crate::theme::parse_hex_color(&meta.color).is_none() {
+            warnings.push(format!(
+                "Invalid color '{}' on node '{name}' (expected #RGB or #RRGGBB)",
+                meta.color
+            ));
+        }
+        if !meta.shape.is_empty() && !shapes.contains(&meta.shape.as_str()) {
+            warnings.push(format!(
+                "Unknown shape '{}' on node '{name}' (expected {})",
+                meta.shape,
+                shapes.join(", ")
+            ));
+        }
+    }
+    if placement::diagram_layout(content) == Some(placement::DiagramLayout::Radial)
+        && !parse_groups(content).is_empty()
+        && parse_diagram(content)
+            .0
+            .iter()
+            .all(|n| n.grid_pos.is_none())
+    {
+        warnings.push(
+            "@layout: radial does not keep groups together (use layered or grid)".to_string(),
+        );
+    }
+    warnings
+}
+
 /// Pre-compute routes for all diagrams and collect a `CheckReport` with any warnings.
 /// The `cancel` flag is checked before each computation; set it to `true` to abort
 /// early (e.g. on file reload). Routing already uses rayon internally, so a single
 /// background thread is sufficient to saturate cores.
 ///
-/// `diagrams` is a list of `(1-indexed slide number, diagram content)`.
+/// `diagrams` is a list of `(1-indexed slide number, diagram kind, diagram content)`.
 pub fn precache_all_diagrams_with_report(
-    diagrams: Vec<(usize, String)>,
+    diagrams: Vec<(usize, DiagramKind, String)>,
     cancel: Arc<AtomicBool>,
 ) -> mpsc::Receiver<CheckReport> {
     let (tx, rx) = mpsc::channel();
     std::thread::spawn(move || {
         let mut report = CheckReport::new();
-        for (slide_num, content) in &diagrams {
+        for (slide_num, kind, content) in &diagrams {
             if cancel.load(Ordering::Relaxed) {
                 let _ = tx.send(report);
                 return;
             }
-            for warning_msg in check_diagram_routes(content) {
+            for warning_msg in check_diagram_keys(*kind, content)
+                .into_iter()
+                .chain(check_diagram_routes(*kind, content))
+            {
                 report.add(CheckWarning {
                     slide: *slide_num,
                     category: CheckCategory::DiagramRouting,
@@ -163,6 +245,7 @@ fn route_cache_key(
         n.name.hash(&mut hasher);
         n.col.hash(&mut hasher);
         n.row.hash(&mut hasher);
+        n.group.hash(&mut hasher);
     }
     for e in edges {
         e.source.hash(&mut hasher);
@@ -175,11 +258,15 @@ fn route_cache_key(
     config.weights.turn.to_bits().hash(&mut hasher);
     config.weights.lane_change.to_bits().hash(&mut hasher);
     config.weights.crossing.to_bits().hash(&mut hasher);
+    config.weights.group_border.to_bits().hash(&mut hasher);
     hasher.finish()
 }
 
 // ─── Diagram data structures ─────────────────────────────────────────────────
 
+/// How long an edge revealed by a forward press takes to draw in.
+const REVEAL_ANIMATION_SECS: f32 = 0.4;
+
 /// How the diagram should handle overflow / sizing.
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum DiagramScale {
@@ -208,10 +295,44 @@ struct DiagramNode {
     icon: String,
     grid_pos: Option<(u32, u32)>,
     prompt: Option<String>,
+    /// The `shape:` key, lowercase; empty when not given.
+    shape: String,
+    /// The `style:` key, lowercase; empty when not given.
+    style: String,
+    /// The `color:` key, replacing the border color.
+    color: Option<Color32>,
+    /// Index into the diagram's groups when declared under a `# Group:` line.
+    group: Option<usize>,
     reveal: DiagramReveal,
     parse_order: usize,
 }
 
+impl DiagramNode {
+    /// A node labelled with its name and no metadata.
+    fn new(name: &str, reveal: DiagramReveal, parse_order: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            label: name.to_string(),
+            icon: String::new(),
+            grid_pos: None,
+            prompt: None,
+            shape: String::new(),
+            style: String::new(),
+            color: None,
+            group: None,
+            reveal,
+            parse_order,
+        }
+    }
+}
+
+/// A `# Group: Name (style: dashed)` box drawn behind the nodes declared
+/// under it.
+struct DiagramGroup {
+    name: String,
+    dashed: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum ArrowKind {
     Forward,       // ->
@@ -911,91 +1032,125 @@ fn draw_routed_edge(
 // ─── Diagram parser ──────────────────────────────────────────────────────────
 
 /// Parsed metadata from parenthetical notation like `(icon: database, pos: 1,2, prompt: "...")`.
+#[derive(Default)]
 struct NodeMetadata<'a> {
     before: &'a str,
     icon: String,
     grid_pos: Option<(u32, u32)>,
     prompt: Option<String>,
+    shape: String,
+    label: Option<String>,
+    /// The `style:` key, lowercase; empty when not given.
+    style: String,
+    /// The `color:` key as written; empty when not given.
+    color: String,
+    /// Keys (or bare words) that are not node keys, in order.
+    unknown: Vec<String>,
+}
+
+/// The keys `parse_node_metadata` understands.
+const NODE_KEYS: &[&str] = &["icon", "pos", "prompt", "label", "style", "color", "shape"];
+
+/// Node emphasis values for the `style:` key.
+const NODE_STYLES: &[&str] = &["primary", "secondary", "muted"];
+
+impl NodeMetadata<'_> {
+    /// Copy the keys that were given onto `node`.
+    fn apply(&self, node: &mut DiagramNode) {
+        if !self.icon.is_empty() {
+            node.icon = self.icon.clone();
+        }
+        if self.grid_pos.is_some() {
+            node.grid_pos = self.grid_pos;
+        }
+        if self.prompt.is_some() {
+            node.prompt = self.prompt.clone();
+        }
+        if !self.shape.is_empty() {
+            node.shape = self.shape.clone();
+        }
+        if let Some(label) = &self.label {
+            node.label = label.clone();
+        }
+        if !self.style.is_empty() {
+            node.style = self.style.clone();
+        }
+        if let Some(color) = crate::theme::parse_hex_color(&self.color) {
+            node.color = Some(color);
+        }
+    }
 }
 
 /// Parse parenthetical metadata like `(icon: database, pos: 1,2, prompt: "...")`.
 /// Returns the line content without the metadata and extracted fields.
 fn parse_node_metadata(s: &str) -> NodeMetadata<'_> {
     let trimmed = s.trim_end();
+    let plain = NodeMetadata {
+        before: trimmed,
+        ..Default::default()
+    };
     if !trimmed.ends_with(')') {
-        return NodeMetadata {
-            before: trimmed,
-            icon: String::new(),
-            grid_pos: None,
-            prompt: None,
-        };
+        return plain;
     }
     let Some(paren_start) = trimmed.rfind('(') else {
-        return NodeMetadata {
-            before: trimmed,
-            icon: String::new(),
-            grid_pos: None,
-            prompt: None,
-        };
+        return plain;
     };
     // Only parse if there's whitespace before the paren
     if paren_start == 0 || trimmed.as_bytes()[paren_start - 1] != b' ' {
-        return NodeMetadata {
-            before: trimmed,
-            icon: String::new(),
-            grid_pos: None,
-            prompt: None,
-        };
+        return plain;
     }
 
-    let before = trimmed[..paren_start].trim_end();
+    let mut meta = NodeMetadata {
+        before: trimmed[..paren_start].trim_end(),
+        ..Default::default()
+    };
     let meta_str = &trimmed[paren_start + 1..trimmed.len() - 1]; // contents between parens
 
-    let mut icon = String::new();
-    let mut grid_pos = None;
-    let mut prompt = None;
-
     // Extract quoted prompt first (it may contain commas)
-    let meta_str = extract_prompt(meta_str, &mut prompt);
+    let meta_str = extract_prompt(meta_str, &mut meta.prompt);
 
     for part in meta_str.split(',') {
         let part = part.trim();
-        if let Some(val) = part
-            .strip_prefix("icon:")
-            .or_else(|| part.strip_prefix("icon :"))
-        {
-            icon = val.trim().to_string();
-        } else if let Some(val) = part
-            .strip_prefix("pos:")
-            .or_else(|| part.strip_prefix("pos :"))
-        {
-            let val = val.trim();
-            // pos can be "x,y" but we already split on comma, so handle both forms
-            if let Some((x_str, y_str)) = val.split_once(',') {
-                if let (Ok(x), Ok(y)) = (x_str.trim().parse(), y_str.trim().parse()) {
-                    grid_pos = Some((x, y));
-                }
-            } else if grid_pos.is_none() {
-                // Might be split across commas: "pos: 1" then next part is "2"
-                // Store x and look for y in next iteration
-                if let Ok(x) = val.parse::<u32>() {
-                    grid_pos = Some((x, 0)); // placeholder, y filled below
+        let (key, val) = match part.split_once(':') {
+            Some((key, val)) => (key.trim(), val.trim()),
+            None => ("", part),
+        };
+        match key {
+            "icon" => meta.icon = val.to_string(),
+            "shape" => meta.shape = val.to_lowercase(),
+            "style" => meta.style = val.to_lowercase(),
+            "color" => meta.color = val.to_string(),
+            "prompt" => meta.prompt = Some(val.to_string()),
+            "label" => meta.label = Some(val.trim_matches(['"', '\'']).to_string()),
+            "pos" => {
+                // pos can be "x,y" but we already split on comma, so handle both forms
+                if let Some((x_str, y_str)) = val.split_once(',') {
+                    if let (Ok(x), Ok(y)) = (x_str.trim().parse(), y_str.trim().parse()) {
+                        meta.grid_pos = Some((x, y));
+                    }
+                } else if meta.grid_pos.is_none() {
+                    // Might be split across commas: "pos: 1" then next part is "2"
+                    // Store x and look for y in next iteration
+                    if let Ok(x) = val.parse::<u32>() {
+                        meta.grid_pos = Some((x, 0)); // placeholder, y filled below
+                    }
                 }
             }
-        } else if let Some((x, 0)) = grid_pos {
-            // Continuation of pos value split by comma
-            if let Ok(y) = part.trim().parse::<u32>() {
-                grid_pos = Some((x, y));
+            "" => {
+                // Continuation of pos value split by comma
+                if let Some((x, 0)) = meta.grid_pos
+                    && let Ok(y) = val.parse::<u32>()
+                {
+                    meta.grid_pos = Some((x, y));
+                } else if !val.is_empty() {
+                    meta.unknown.push(val.to_string());
+                }
             }
+            key => meta.unknown.push(key.to_string()),
         }
     }
 
-    NodeMetadata {
-        before,
-        icon,
-        grid_pos,
-        prompt,
-    }
+    meta
 }
 
 /// Extract a `prompt: "..."` or `prompt: '...'` value from the metadata string,
@@ -1060,55 +1215,124 @@ fn detect_arrow(s: &str) -> Option<(usize, usize, ArrowKind)> {
     None
 }
 
+/// The `# scale: fit|scroll|<factor>` directive, if `line` is one.
+fn parse_scale_directive(line: &str) -> Option<DiagramScale> {
+    let val = line
+        .strip_prefix("# scale:")
+        .or_else(|| line.strip_prefix("#scale:"))?
+        .trim();
+    if val.eq_ignore_ascii_case("fit") {
+        Some(DiagramScale::Fit)
+    } else if val.eq_ignore_ascii_case("scroll") {
+        Some(DiagramScale::Scroll)
+    } else {
+        let f = val.parse::<f32>().ok()?;
+        Some(DiagramScale::Factor(f.clamp(0.1, 2.0)))
+    }
+}
+
+/// The `# Group: Name (style: dashed)` header, if `line` is one. The group
+/// runs until the next header or a `# End group` line.
+fn parse_group_header(line: &str) -> Option<DiagramGroup> {
+    let rest = line.strip_prefix('#')?.trim_start();
+    let rest = rest
+        .get(..6)
+        .filter(|p| p.eq_ignore_ascii_case("group:"))
+        .map(|_| rest[6..].trim())?;
+    let (name, dashed) = match rest.strip_suffix(')').and_then(|r| r.rsplit_once(" (")) {
+        Some((name, meta)) => {
+            let style = meta
+                .split(',')
+                .find_map(|part| part.trim().strip_prefix("style:"))
+                .map(str::trim)
+                .unwrap_or("");
+            (name.trim(), style.eq_ignore_ascii_case("dashed"))
+        }
+        None => (rest, false),
+    };
+    Some(DiagramGroup {
+        name: name.to_string(),
+        dashed,
+    })
+}
+
+/// Whether `line` is the `# End group` line closing the open group.
+fn is_group_end(line: &str) -> bool {
+    line.strip_prefix('#')
+        .is_some_and(|rest| rest.trim().eq_ignore_ascii_case("end group"))
+}
+
+/// The groups declared in a diagram, in order; `DiagramNode::group` indexes
+/// into this list. A header repeating a group's name reopens that group.
+fn parse_groups(content: &str) -> Vec<DiagramGroup> {
+    let mut groups: Vec<DiagramGroup> = Vec::new();
+    for group in content
+        .lines()
+        .filter_map(|line| parse_group_header(line.trim()))
+    {
+        match groups.iter_mut().find(|g| g.name == group.name) {
+            Some(existing) => existing.dashed |= group.dashed,
+            None => groups.push(group),
+        }
+    }
+    groups
+}
+
+/// Strip a list-style `-`, `+` or `*` prefix, returning the reveal marker it sets.
+fn strip_reveal_marker(line: &str) -> (&str, DiagramReveal) {
+    if let Some(rest) = line.strip_prefix("+ ") {
+        (rest, DiagramReveal::NextStep)
+    } else if let Some(rest) = line.strip_prefix("* ") {
+        (rest, DiagramReveal::WithPrev)
+    } else if let Some(rest) = line.strip_prefix("- ") {
+        (rest, DiagramReveal::Static)
+    } else {
+        (line, DiagramReveal::Static)
+    }
+}
+
 fn parse_diagram(content: &str) -> (Vec<DiagramNode>, Vec<DiagramEdge>, DiagramScale) {
     let mut nodes = Vec::new();
     let mut edges = Vec::new();
     let mut seen_nodes: HashMap<String, usize> = HashMap::new();
     let mut diagram_scale = DiagramScale::Fit;
     let mut parse_order_counter = 0usize;
+    let mut group_names: Vec<String> = Vec::new();
+    let mut current_group = None;
 
     for line in content.lines() {
         let trimmed = line.trim();
 
-        // Parse directives from comment lines (e.g. `# scale: fit`)
+        // `@layout:` is read by `parse_routed_diagram`
+        if trimmed.starts_with("@layout:") {
+            continue;
+        }
+
+        // Parse directives from comment lines (e.g. `# scale: fit`, `# Group: Backend`)
         if trimmed.starts_with('#') {
-            if let Some(rest) = trimmed
-                .strip_prefix("# scale:")
-                .or_else(|| trimmed.strip_prefix("#scale:"))
-            {
-                let val = rest.trim();
-                if val.eq_ignore_ascii_case("fit") {
-                    diagram_scale = DiagramScale::Fit;
-                } else if val.eq_ignore_ascii_case("scroll") {
-                    diagram_scale = DiagramScale::Scroll;
-                } else if let Ok(f) = val.parse::<f32>() {
-                    diagram_scale = DiagramScale::Factor(f.clamp(0.1, 2.0));
+            if let Some(scale) = parse_scale_directive(trimmed) {
+                diagram_scale = scale;
+            } else if let Some(group) = parse_group_header(trimmed) {
+                let idx = group_names.iter().position(|n| *n == group.name);
+                current_group = Some(idx.unwrap_or(group_names.len()));
+                if idx.is_none() {
+                    group_names.push(group.name);
                 }
+            } else if is_group_end(trimmed) {
+                current_group = None;
             }
             continue;
         }
 
-        // Strip list-style prefixes and record reveal marker
-        let (trimmed, reveal) = if let Some(rest) = trimmed.strip_prefix("+ ") {
-            (rest, DiagramReveal::NextStep)
-        } else if let Some(rest) = trimmed.strip_prefix("* ") {
-            (rest, DiagramReveal::WithPrev)
-        } else if let Some(rest) = trimmed.strip_prefix("- ") {
-            (rest, DiagramReveal::Static)
-        } else {
-            (trimmed, DiagramReveal::Static)
-        };
+        let (trimmed, reveal) = strip_reveal_marker(trimmed);
 
         if trimmed.is_empty() {
             continue;
         }
 
-        // Parse and strip trailing metadata (icon, pos, prompt)
+        // Parse and strip trailing metadata (icon, pos, label, ...)
         let meta = parse_node_metadata(trimmed);
         let trimmed = meta.before;
-        let meta_icon = meta.icon;
-        let meta_pos = meta.grid_pos;
-        let meta_prompt = meta.prompt;
 
         if let Some((arrow_pos, arrow_len, arrow_kind)) = detect_arrow(trimmed) {
             let from = trimmed[..arrow_pos].trim().to_string();
@@ -1126,15 +1350,7 @@ fn parse_diagram(content: &str) -> (Vec<DiagramNode>, Vec<DiagramEdge>, DiagramS
             for node_name in [&from, &to] {
                 if !seen_nodes.contains_key(node_name) {
                     seen_nodes.insert(node_name.clone(), nodes.len());
-                    nodes.push(DiagramNode {
-                        name: node_name.clone(),
-                        label: node_name.clone(),
-                        icon: String::new(),
-                        grid_pos: None,
-                        prompt: None,
-                        reveal: DiagramReveal::Static,
-                        parse_order: 0,
-                    });
+                    nodes.push(DiagramNode::new(node_name, DiagramReveal::Static, 0));
                 }
             }
 
@@ -1147,71 +1363,56 @@ fn parse_diagram(content: &str) -> (Vec<DiagramNode>, Vec<DiagramEdge>, DiagramS
                 parse_order: parse_order_counter,
             });
             parse_order_counter += 1;
-        } else if let Some(colon_pos) = trimmed.find(": ") {
-            // Node declaration with label: "Name: Label"
-            let name = trimmed[..colon_pos].trim().to_string();
-            let label = trimmed[colon_pos + 2..].trim().to_string();
-
-            if let Some(&idx) = seen_nodes.get(&name) {
-                nodes[idx].label = label;
-                if !meta_icon.is_empty() {
-                    nodes[idx].icon = meta_icon.clone();
-                }
-                if meta_pos.is_some() {
-                    nodes[idx].grid_pos = meta_pos;
-                }
-                if meta_prompt.is_some() {
-                    nodes[idx].prompt = meta_prompt.clone();
-                }
-                nodes[idx].parse_order = parse_order_counter;
-            } else {
-                seen_nodes.insert(name.clone(), nodes.len());
-                nodes.push(DiagramNode {
-                    name,
-                    label,
-                    icon: meta_icon.clone(),
-                    grid_pos: meta_pos,
-                    prompt: meta_prompt.clone(),
-                    reveal,
-                    parse_order: parse_order_counter,
-                });
-            }
-            parse_order_counter += 1;
         } else {
-            // Plain node name (e.g. "Server" or "Server (icon: server, pos: 1,1)")
-            let name = trimmed.trim().to_string();
-            if !name.is_empty() {
-                if let Some(&idx) = seen_nodes.get(&name) {
-                    if !meta_icon.is_empty() {
-                        nodes[idx].icon = meta_icon.clone();
-                    }
-                    if meta_pos.is_some() {
-                        nodes[idx].grid_pos = meta_pos;
-                    }
-                    if meta_prompt.is_some() {
-                        nodes[idx].prompt = meta_prompt.clone();
-                    }
-                    nodes[idx].parse_order = parse_order_counter;
-                } else {
-                    seen_nodes.insert(name.clone(), nodes.len());
-                    nodes.push(DiagramNode {
-                        name: name.clone(),
-                        label: name,
-                        icon: meta_icon.clone(),
-                        grid_pos: meta_pos,
-                        prompt: meta_prompt.clone(),
-                        reveal,
-                        parse_order: parse_order_counter,
-                    });
-                }
-                parse_order_counter += 1;
+            // Node declaration: "Name" or "Name: Label", e.g. "Server (icon: server, pos: 1,1)"
+            let (name, label) = match trimmed.find(": ") {
+                Some(colon_pos) => (
+                    trimmed[..colon_pos].trim(),
+                    Some(trimmed[colon_pos + 2..].trim()),
+                ),
+                None => (trimmed.trim(), None),
+            };
+            if name.is_empty() {
+                continue;
+            }
+            let idx = *seen_nodes.entry(name.to_string()).or_insert_with(|| {
+                nodes.push(DiagramNode::new(name, reveal, parse_order_counter));
+                nodes.len() - 1
+            });
+            let node = &mut nodes[idx];
+            if let Some(label) = label {
+                node.label = label.to_string();
+            }
+            meta.apply(node);
+            if current_group.is_some() {
+                node.group = current_group;
             }
+            node.parse_order = parse_order_counter;
+            parse_order_counter += 1;
         }
     }
 
     (nodes, edges, diagram_scale)
 }
 
+/// Parse a component diagram or flowchart, placing its nodes by the
+/// `@layout:` line, or flowchart nodes in their flowchart layers without one.
+fn parse_routed_diagram(
+    kind: DiagramKind,
+    content: &str,
+) -> (Vec<DiagramNode>, Vec<DiagramEdge>, DiagramScale) {
+    let (mut nodes, edges, scale) = parse_diagram(content);
+    match placement::diagram_layout(content) {
+        Some(layout) if kind == DiagramKind::Flowchart => {
+            placement::place_nodes(layout, &mut nodes, &edges, flowchart::branch_rank);
+        }
+        Some(layout) => placement::place_nodes(layout, &mut nodes, &edges, placement::same_rank),
+        None if kind == DiagramKind::Flowchart => flowchart::place_nodes(&mut nodes, &edges),
+        None => {}
+    }
+    (nodes, edges, scale)
+}
+
 // ─── Diagram layout ──────────────────────────────────────────────────────────
 
 struct NodeLayout {
@@ -1222,6 +1423,7 @@ struct NodeLayout {
 }
 
 fn layout_nodes(
+    kind: DiagramKind,
     nodes: &[DiagramNode],
     area_width: f32,
     area_height: f32,
@@ -1231,11 +1433,19 @@ fn layout_nodes(
 ) -> (Vec<NodeLayout>, GridInfo) {
     let has_grid = nodes.iter().any(|n| n.grid_pos.is_some());
 
-    if has_grid {
+    let (mut layouts, grid) = if has_grid {
         layout_grid(nodes, area_width, area_height, origin_x, origin_y, scale)
     } else {
         layout_auto(nodes, area_width, area_height, origin_x, origin_y, scale)
+    };
+    for (node, layout) in nodes.iter().zip(&mut layouts) {
+        if kind == DiagramKind::Flowchart {
+            flowchart::fit_shape(flowchart::shape_of(node), layout);
+        } else {
+            component::fit_shape(component::shape_of(node), layout);
+        }
     }
+    (layouts, grid)
 }
 
 fn layout_grid(
@@ -1271,6 +1481,13 @@ fn layout_grid(
         .iter()
         .map(|node| {
             let (col, row) = node.grid_pos.unwrap_or_else(|| {
+                // Group members go to the free cell nearest their group
+                if let Some(cell) =
+                    free_cell_near_group(nodes, node.group, &occupied, max_col, max_row)
+                {
+                    occupied.push(cell);
+                    return cell;
+                }
                 // Find next unoccupied cell
                 loop {
                     let c = next_unplaced % max_col + 1;
@@ -1314,6 +1531,37 @@ fn layout_grid(
     (layouts, grid_info)
 }
 
+/// The free cell within the grid closest to the positioned members of
+/// `group`, if the node is in a group that has any.
+fn free_cell_near_group(
+    nodes: &[DiagramNode],
+    group: Option<usize>,
+    occupied: &[(u32, u32)],
+    max_col: u32,
+    max_row: u32,
+) -> Option<(u32, u32)> {
+    let group = group?;
+    let members: Vec<(u32, u32)> = nodes
+        .iter()
+        .filter(|n| n.group == Some(group))
+        .filter_map(|n| n.grid_pos)
+        .collect();
+    if members.is_empty() {
+        return None;
+    }
+    let distance = |(c, r): (u32, u32)| -> u32 {
+        members
+            .iter()
+            .map(|&(mc, mr)| mc.abs_diff(c) + mr.abs_diff(r))
+            .min()
+            .unwrap_or(0)
+    };
+    (1..=max_row)
+        .flat_map(|r| (1..=max_col).map(move |c| (c, r)))
+        .filter(|cell| !occupied.contains(cell))
+        .min_by_key(|&cell| distance(cell))
+}
+
 fn layout_auto(
     nodes: &[DiagramNode],
     area_width: f32,
@@ -1336,6 +1584,10 @@ fn layout_auto(
         return (Vec::new(), grid_info);
     }
 
+    if nodes.iter().any(|node| node.group.is_some()) {
+        return layout_auto_grouped(nodes, area_width, area_height, origin_x, origin_y, scale);
+    }
+
     // For small node counts, use a single row
     if n <= 5 {
         // Responsive: size nodes to fill available space
@@ -1348,7 +1600,7 @@ fn layout_auto(
         } else {
             0.0
         };
-        let total_w = n as f32 * node_w + (n - 1).max(0) as f32 * gap;
+        let total_w = n as f32 * node_w + (n - 1) as f32 * gap;
         let start_x = (area_width - total_w) / 2.0 + node_w / 2.0;
 
         let cell_w = if n > 1 {
@@ -1424,6 +1676,84 @@ fn layout_auto(
     (layouts, grid_info)
 }
 
+/// Auto-layout for diagrams with groups. Nodes are laid out in blocks from
+/// left to right: each group gets its own block, and the ungrouped nodes
+/// declared between groups share one. Blocks fill top to bottom, then the
+/// next column, so a group's members stay in adjacent cells.
+fn layout_auto_grouped(
+    nodes: &[DiagramNode],
+    area_width: f32,
+    area_height: f32,
+    origin_x: f32,
+    origin_y: f32,
+    scale: f32,
+) -> (Vec<NodeLayout>, GridInfo) {
+    let mut blocks: Vec<Vec<usize>> = Vec::new();
+    let mut ungrouped: Vec<usize> = Vec::new();
+    let mut placed_groups: HashSet<usize> = HashSet::new();
+    for (i, node) in nodes.iter().enumerate() {
+        match node.group {
+            None => ungrouped.push(i),
+            Some(group) => {
+                if placed_groups.insert(group) {
+                    if !ungrouped.is_empty() {
+                        blocks.push(std::mem::take(&mut ungrouped));
+                    }
+                    blocks.push(
+                        (0..nodes.len())
+                            .filter(|&j| nodes[j].group == Some(group))
+                            .collect(),
+                    );
+                }
+            }
+        }
+    }
+    if !ungrouped.is_empty() {
+        blocks.push(ungrouped);
+    }
+
+    let rows = ((nodes.len() as f32).sqrt().ceil() as usize)
+        .min(blocks.iter().map(Vec::len).max().unwrap_or(1))
+        .max(1);
+    let mut cells = vec![(0, 0); nodes.len()];
+    let mut cols = 0;
+    for block in &blocks {
+        let height = block.len().min(rows);
+        let top = (rows - height) / 2;
+        for (k, &i) in block.iter().enumerate() {
+            cells[i] = (cols + k / rows, top + k % rows);
+        }
+        cols += block.len().div_ceil(rows);
+    }
+
+    let cell_w = area_width / cols as f32;
+    let cell_h = area_height / rows as f32;
+    let node_w = (cell_w * 0.65).clamp(100.0 * scale, 220.0 * scale);
+    let node_h = (cell_h * 0.6).clamp(80.0 * scale, 160.0 * scale);
+
+    let layouts = cells
+        .iter()
+        .map(|&(col, row)| NodeLayout {
+            center_x: (col as f32 + 0.5) * cell_w,
+            center_y: (row as f32 + 0.5) * cell_h,
+            width: node_w,
+            height: node_h,
+        })
+        .collect();
+
+    let grid_info = GridInfo {
+        cols,
+        rows,
+        cell_w,
+        cell_h,
+        origin_x,
+        origin_y,
+        occupied: cells.into_iter().collect(),
+    };
+
+    (layouts, grid_info)
+}
+
 // ─── Geometric icon fallbacks ────────────────────────────────────────────────
 
 fn draw_icon_fallback(
@@ -1716,10 +2046,10 @@ fn draw_icon_fallback(
 
 /// Generate a structured text summary of diagram nodes, edges, and routing results.
 /// Used by the debug overlay to show routing engine inputs/outputs.
-pub fn diagram_debug_info(content: &str) -> String {
+pub fn diagram_debug_info(kind: DiagramKind, content: &str) -> String {
     use std::fmt::Write;
 
-    let (nodes, edges, _scale_directive) = parse_diagram(content);
+    let (nodes, edges, _scale_directive) = parse_routed_diagram(kind, content);
     if nodes.is_empty() {
         return "No nodes parsed.".to_string();
     }
@@ -1813,6 +2143,7 @@ pub fn diagram_debug_info(content: &str) -> String {
                 name: node.name.clone(),
                 col: c,
                 row: r,
+                group: node.group,
             }
         })
         .collect();
@@ -1891,6 +2222,155 @@ pub fn diagram_debug_info(content: &str) -> String {
     out
 }
 
+/// Draw a node's `icon` centered at `center`: `{icon-set}/{icon}.png` when the
+/// image exists (default set `media/diagram-icons`), else a built-in line
+/// drawing, `box` for nodes without an icon.
+#[allow(clippy::too_many_arguments)]
+fn draw_node_icon(
+    ui: &egui::Ui,
+    theme: &Theme,
+    image_cache: &ImageCache,
+    icon: &str,
+    center: Pos2,
+    size: f32,
+    opacity: f32,
+    scale: f32,
+) {
+    let painter = ui.painter();
+    if !icon.is_empty() {
+        let icon_dir = theme.icon_set.as_deref().unwrap_or("media/diagram-icons");
+        let icon_path = format!("{icon_dir}/{icon}.png");
+        if let Some(texture) = image_cache.get_or_load(ui, &icon_path) {
+            // Draw the icon image, preserving aspect ratio
+            let max_size = size * 0.85;
+            let tex_size = texture.size_vec2();
+            let aspect = tex_size.x / tex_size.y.max(1.0);
+            let (w, h) = if aspect >= 1.0 {
+                (max_size, max_size / aspect)
+            } else {
+                (max_size * aspect, max_size)
+            };
+            let img_rect = egui::Rect::from_center_size(center, egui::vec2(w, h));
+            let tint = Theme::with_opacity(Color32::WHITE, opacity);
+            painter.image(
+                texture.id(),
+                img_rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                tint,
+            );
+            return;
+        }
+    }
+
+    // Draw geometric fallback icon
+    let icon_name = if icon.is_empty() { "box" } else { icon };
+    let icon_color = Theme::with_opacity(theme.accent, opacity * 0.9);
+    draw_icon_fallback(painter, icon_name, center, size, icon_color, 2.0 * scale);
+}
+
+/// Draw a group as a labelled rounded rectangle around `members`, the union
+/// of its member node rects.
+fn draw_group(
+    painter: &egui::Painter,
+    group: &DiagramGroup,
+    members: egui::Rect,
+    theme: &Theme,
+    opacity: f32,
+    scale: f32,
+) {
+    let font = FontId::proportional(theme.body_size * 0.55 * scale);
+    let margin = 14.0 * scale;
+    let label_h = font.size * 1.3;
+    let rect = egui::Rect::from_min_max(
+        members.min - egui::vec2(margin, margin + label_h),
+        members.max + egui::vec2(margin, margin),
+    );
+    let radius = 12.0 * scale;
+    let border = Theme::with_opacity(theme.foreground, opacity * 0.4);
+    let width = 1.5 * scale;
+
+    painter.rect_filled(
+        rect,
+        radius,
+        Theme::with_opacity(theme.accent, opacity * 0.06),
+    );
+    if group.dashed {
+        let mut outline = Vec::new();
+        let corners = [
+            (rect.right_top(), -90.0_f32),
+            (rect.right_bottom(), 0.0),
+            (rect.left_bottom(), 90.0),
+            (rect.left_top(), 180.0),
+        ];
+        for (corner, start) in corners {
+            let center = corner
+                + egui::vec2(
+                    if corner.x < rect.center().x {
+                        radius
+                    } else {
+                        -radius
+                    },
+                    if corner.y < rect.center().y {
+                        radius
+                    } else {
+                        -radius
+                    },
+                );
+            for k in 0..=6 {
+                let angle = (start + k as f32 * 15.0).to_radians();
+                outline.push(center + radius * egui::vec2(angle.cos(), angle.sin()));
+            }
+        }
+        outline.push(outline[0]);
+        draw_dashed_polyline(painter, &outline, width, border, scale);
+    } else {
+        painter.rect_stroke(
+            rect,
+            radius,
+            Stroke::new(width, border),
+            egui::StrokeKind::Middle,
+        );
+    }
+
+    let text_color = Theme::with_opacity(theme.foreground, opacity * 0.75);
+    let galley = painter.layout(
+        group.name.clone(),
+        font,
+        text_color,
+        rect.width() - 2.0 * radius,
+    );
+    painter.galley(
+        rect.min + egui::vec2(radius, margin * 0.5),
+        galley,
+        text_color,
+    );
+}
+
+/// How far an element revealed at `step` has animated in, from 0 to 1. Only
+/// the step just revealed animates; `needs_repaint` is set while it does.
+fn reveal_progress(
+    step: usize,
+    reveal_step: usize,
+    reveal_timestamp: Option<Instant>,
+    needs_repaint: &mut bool,
+) -> f32 {
+    if step != reveal_step || step == 0 {
+        return 1.0;
+    }
+    let Some(ts) = reveal_timestamp else {
+        return 1.0;
+    };
+    let t = (ts.elapsed().as_secs_f32() / REVEAL_ANIMATION_SECS).min(1.0);
+    if t < 1.0 {
+        *needs_repaint = true;
+    }
+    if t < 0.5 {
+        2.0 * t * t
+    } else {
+        1.0 - (-2.0_f32 * t + 2.0).powi(2) / 2.0
+    }
+}
+
 // ─── Diagram renderer ────────────────────────────────────────────────────────
 
 /// Count the number of reveal steps (`+` markers) in a diagram content string.
@@ -1908,11 +2388,13 @@ pub fn count_diagram_steps(content: &str) -> usize {
     count
 }
 
-/// Draw a diagram parsed from `- Node: label` and `- A -> B: label` lines.
+/// Draw a diagram parsed from `- Node: label` and `- A -> B: label` lines,
+/// as components, a flowchart or a sequence diagram by `kind`.
+/// `max_height` controls the vertical space; pass 0 for a default.
 #[allow(clippy::too_many_arguments)]
-/// Draw a diagram. `max_height` controls the vertical space; pass 0 for a default.
 pub fn draw_diagram_sized(
     ui: &egui::Ui,
+    kind: DiagramKind,
     content: &str,
     theme: &Theme,
     pos: Pos2,
@@ -1924,7 +2406,22 @@ pub fn draw_diagram_sized(
     reveal_timestamp: Option<Instant>,
     scale: f32,
 ) -> f32 {
-    let (nodes, edges, scale_directive) = parse_diagram(content);
+    if kind == DiagramKind::Sequence {
+        return sequence::draw(
+            ui,
+            content,
+            theme,
+            pos,
+            max_width,
+            max_height,
+            opacity,
+            image_cache,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        );
+    }
+    let (nodes, edges, scale_directive) = parse_routed_diagram(kind, content);
 
     // Compute reveal step assignments for each element.
     // Static elements are always visible (step 0). Each `+` increments the step counter.
@@ -2000,6 +2497,7 @@ pub fn draw_diagram_sized(
     let abs_origin_x = pos.x + padding;
     let abs_origin_y = pos.y + padding;
     let (mut layouts, mut grid) = layout_nodes(
+        kind,
         &nodes,
         area_width,
         area_height,
@@ -2052,8 +2550,36 @@ pub fn draw_diagram_sized(
     let node_border_color = Theme::with_opacity(accent, opacity * 0.8);
     let node_fill = Theme::with_opacity(theme.code_background, opacity * 0.95);
     let shadow_color = Theme::with_opacity(Color32::from_rgb(0, 0, 0), opacity * 0.1);
-    let label_color = Theme::with_opacity(theme.foreground, opacity);
-    let icon_color = Theme::with_opacity(accent, opacity * 0.9);
+
+    // Draw group boxes behind their nodes, once any member is revealed
+    let groups = parse_groups(content);
+    for (g, group) in groups.iter().enumerate() {
+        let members: Vec<usize> = (0..nodes.len())
+            .filter(|&i| nodes[i].group == Some(g))
+            .collect();
+        if !members
+            .iter()
+            .any(|&i| node_steps.get(i).copied().unwrap_or(0) <= reveal_step)
+        {
+            continue;
+        }
+        let bounds = members
+            .iter()
+            .map(|&i| {
+                let layout = &layouts[i];
+                egui::Rect::from_center_size(
+                    egui::pos2(
+                        pos.x + padding + layout.center_x,
+                        pos.y + padding + layout.center_y,
+                    ),
+                    egui::vec2(layout.width, layout.height),
+                )
+            })
+            .reduce(|a, b| a.union(b));
+        if let Some(bounds) = bounds {
+            draw_group(painter, group, bounds, theme, opacity, scale);
+        }
+    }
 
     // Draw nodes (skip those not yet revealed)
     for (i, node) in nodes.iter().enumerate() {
@@ -2075,77 +2601,64 @@ pub fn draw_diagram_sized(
             continue;
         }
 
-        let corner_radius = 8.0 * scale;
-
-        // Drop shadow
-        let shadow_rect = node_rect.translate(egui::vec2(3.0 * scale, 3.0 * scale));
-        painter.rect_filled(shadow_rect, corner_radius, shadow_color);
+        // Emphasis from `style:` and `color:`
+        let style = component::style_of(node);
+        let node_opacity = match style {
+            component::NodeStyle::Muted => opacity * 0.45,
+            _ => opacity,
+        };
+        let (border, border_width) = match (node.color, style) {
+            (Some(color), _) => (Theme::with_opacity(color, node_opacity), 2.5 * scale),
+            (None, component::NodeStyle::Primary) => (node_border_color, 2.5 * scale),
+            (None, _) => (
+                Theme::with_opacity(theme.foreground, node_opacity * 0.5),
+                1.5 * scale,
+            ),
+        };
+        let label_color = Theme::with_opacity(theme.foreground, node_opacity);
 
-        // Node background
-        painter.rect_filled(node_rect, corner_radius, node_fill);
+        if kind == DiagramKind::Flowchart {
+            flowchart::draw_node(
+                painter,
+                flowchart::shape_of(node),
+                node_rect,
+                &node.label,
+                theme,
+                node_fill,
+                border,
+                shadow_color,
+                label_color,
+                scale,
+            );
+            continue;
+        }
 
-        // Node border
-        painter.rect_stroke(
+        component::draw_outline(
+            painter,
+            component::shape_of(node),
             node_rect,
-            corner_radius,
-            Stroke::new(2.5 * scale, node_border_color),
-            egui::StrokeKind::Outside,
+            node_fill,
+            Stroke::new(border_width, border),
+            shadow_color,
+            scale,
         );
 
         // Icon area (top portion of node)
-        let icon_size = layout.height * 0.5;
-        let icon_center = Pos2::new(abs_x, abs_y - layout.height * 0.12);
+        let (icon_scale, icon_dy, text_dy) =
+            component::content_placement(component::shape_of(node));
+        let icon_size = layout.height * icon_scale;
+        let icon_center = Pos2::new(abs_x, abs_y + layout.height * icon_dy);
 
-        // Try loading icon image from media/diagram-icons/{icon}.png
-        let icon_path = if !node.icon.is_empty() {
-            format!("media/diagram-icons/{}.png", node.icon)
-        } else {
-            String::new()
-        };
-
-        let has_image = if !icon_path.is_empty() {
-            if let Some(texture) = image_cache.get_or_load(ui, &icon_path) {
-                // Draw the icon image, preserving aspect ratio
-                let max_size = icon_size * 0.85;
-                let tex_size = texture.size_vec2();
-                let aspect = tex_size.x / tex_size.y.max(1.0);
-                let (w, h) = if aspect >= 1.0 {
-                    (max_size, max_size / aspect)
-                } else {
-                    (max_size * aspect, max_size)
-                };
-                let img_rect = egui::Rect::from_center_size(icon_center, egui::vec2(w, h));
-                let tint = Theme::with_opacity(Color32::WHITE, opacity);
-                painter.image(
-                    texture.id(),
-                    img_rect,
-                    egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                    tint,
-                );
-                true
-            } else {
-                false
-            }
-        } else {
-            false
-        };
-
-        if !has_image {
-            // Draw geometric fallback icon
-            let icon_name = if node.icon.is_empty() {
-                "box"
-            } else {
-                &node.icon
-            };
-            draw_icon_fallback(
-                painter,
-                icon_name,
-                icon_center,
-                icon_size,
-                icon_color,
-                2.0 * scale,
-            );
-        }
+        draw_node_icon(
+            ui,
+            theme,
+            image_cache,
+            &node.icon,
+            icon_center,
+            icon_size,
+            node_opacity,
+            scale,
+        );
 
         // Label text below icon
         let label_font_size = theme.body_size * 0.8 * scale;
@@ -2155,7 +2668,7 @@ pub fn draw_diagram_sized(
             label_color,
             layout.width - 8.0 * scale,
         );
-        let text_y = abs_y + layout.height * 0.25;
+        let text_y = abs_y + layout.height * text_dy;
         let text_pos = egui::pos2(abs_x - galley.rect.width() / 2.0, text_y);
         painter.galley(text_pos, galley, label_color);
     }
@@ -2171,7 +2684,6 @@ pub fn draw_diagram_sized(
     let lane_spacing = 20.0 * scale;
     let port_spacing = 22.0 * scale;
 
-    let animation_duration = 0.4; // seconds
     let mut needs_repaint = false;
 
     // Build routing input from diagram data
@@ -2203,6 +2715,7 @@ pub fn draw_diagram_sized(
                 name: n.name.clone(),
                 col: (col + 1) as i32, // convert 0-indexed to 1-based
                 row: (row + 1) as i32,
+                group: n.group,
             })
         })
         .collect();
@@ -2284,27 +2797,10 @@ pub fn draw_diagram_sized(
 
         // Compute animation progress for edges appearing on the current step
         let edge_step = edge_steps.get(edge_idx).copied().unwrap_or(0);
-        let anim_progress = if edge_step == reveal_step && edge_step > 0 {
-            if let Some(ts) = reveal_timestamp {
-                let elapsed = ts.elapsed().as_secs_f32();
-                let t = (elapsed / animation_duration).min(1.0);
-                let eased = if t < 0.5 {
-                    2.0 * t * t
-                } else {
-                    1.0 - (-2.0_f32 * t + 2.0).powi(2) / 2.0
-                };
-                if t < 1.0 {
-                    needs_repaint = true;
-                }
-                eased
-            } else {
-                1.0
-            }
-        } else {
-            1.0
-        };
+        let anim_progress =
+            reveal_progress(edge_step, reveal_step, reveal_timestamp, &mut needs_repaint);
 
-        let pixel_waypoints = match route_result {
+        let mut pixel_waypoints = match route_result {
             routing::types::RouteResult::Success(route) => {
                 // Determine faces from route direction
                 let first = &route.waypoints[0];
@@ -2358,6 +2854,20 @@ pub fn draw_diagram_sized(
             }
         };
 
+        let node_of = |name: &str| nodes.iter().find(|n| n.name == name);
+        if kind == DiagramKind::Flowchart {
+            let shape = |name: &str| {
+                node_of(name).map_or(flowchart::FlowShape::Process, flowchart::shape_of)
+            };
+            flowchart::clip_to_shape(&mut pixel_waypoints, shape(&edge.from), from_rect, true);
+            flowchart::clip_to_shape(&mut pixel_waypoints, shape(&edge.to), to_rect, false);
+        } else {
+            let shape =
+                |name: &str| node_of(name).map_or(component::NodeShape::Box, component::shape_of);
+            component::clip_to_shape(&mut pixel_waypoints, shape(&edge.from), from_rect, true);
+            component::clip_to_shape(&mut pixel_waypoints, shape(&edge.to), to_rect, false);
+        }
+
         // Use edge color as label background so labels visually match their edge
         let edge_label_bg = Theme::with_opacity(current_edge_color, opacity * 0.80);
         draw_routed_edge(
@@ -2610,6 +3120,105 @@ mod diagram_tests {
 
     // ── Rendering geometry tests ─────────────────────────────────────────────
 
+    #[test]
+    fn test_parse_label_style_color_keys() {
+        let (nodes, _, _) = parse_diagram(
+            "- Old: Legacy (style: Muted)\n- New (label: \"New API\", color: #e74c3c, shape: cloud)\n- Old (label: ERP)",
+        );
+        assert_eq!(nodes[0].label, "ERP");
+        assert_eq!(nodes[0].style, "muted");
+        assert_eq!(nodes[1].label, "New API");
+        assert_eq!(nodes[1].color, Some(Color32::from_rgb(0xe7, 0x4c, 0x3c)));
+        assert_eq!(nodes[1].shape, "cloud");
+    }
+
+    #[test]
+    fn test_check_diagram_keys() {
+        let content = "- A (icon: server, colour: red, pos: 1, 2)\n- B (style: loud, color: red, shape: star)\n+ A -> B: calls (async)\n# Group: G (style: dashed)";
+        let warnings = check_diagram_keys(DiagramKind::Architecture, content);
+        assert_eq!(warnings.len(), 4, "{warnings:?}");
+        assert!(warnings[0].starts_with("Unknown key 'colour' on node 'A'"));
+        assert!(warnings[1].starts_with("Unknown style 'loud' on node 'B'"));
+        assert!(warnings[2].starts_with("Invalid color 'red' on node 'B'"));
+        assert!(warnings[3].starts_with("Unknown shape 'star' on node 'B'"));
+        // Flowchart shapes differ
+        assert!(check_diagram_keys(DiagramKind::Flowchart, "- Ok? (shape: decision)").is_empty());
+        assert_eq!(
+            check_diagram_keys(DiagramKind::Flowchart, "- Db (shape: cylinder)").len(),
+            1
+        );
+        // Radial rings cannot keep a group's members next to each other
+        let grouped = "# Group: Edge\n- CDN\n- LB\n# End group\n- CDN -> LB\n- LB -> API";
+        assert_eq!(
+            check_diagram_keys(
+                DiagramKind::Architecture,
+                &format!("@layout: radial\n{grouped}")
+            ),
+            ["@layout: radial does not keep groups together (use layered or grid)"]
+        );
+        assert!(
+            check_diagram_keys(
+                DiagramKind::Architecture,
+                &format!("@layout: layered\n{grouped}")
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_parse_groups() {
+        let content = "- Client\n# Group: Backend (style: dashed)\n- API\n- DB: Database\n# End group\n- Cache\n# group: Edge\n- CDN\n- Client -> API";
+        let (nodes, _, _) = parse_diagram(content);
+        let group_of = |name: &str| nodes.iter().find(|n| n.name == name).unwrap().group;
+        assert_eq!(group_of("Client"), None);
+        assert_eq!(group_of("API"), Some(0));
+        assert_eq!(group_of("DB"), Some(0));
+        assert_eq!(group_of("Cache"), None);
+        assert_eq!(group_of("CDN"), Some(1));
+
+        let groups = parse_groups(content);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "Backend");
+        assert!(groups[0].dashed);
+        assert_eq!(groups[1].name, "Edge");
+        assert!(!groups[1].dashed);
+    }
+
+    #[test]
+    fn test_layout_auto_keeps_groups_contiguous() {
+        let content =
+            "- A\n# Group: G\n- B\n# End group\n- C\n- D\n# Group: G2\n- E\n# Group: G\n- F";
+        let (nodes, _, _) = parse_diagram(content);
+        let (layouts, grid) = layout_auto(&nodes, 1200.0, 600.0, 0.0, 0.0, 1.0);
+        let cell = |name: &str| {
+            let i = nodes.iter().position(|n| n.name == name).unwrap();
+            grid.cell_at(egui::pos2(layouts[i].center_x, layouts[i].center_y))
+                .unwrap()
+        };
+        // B and F share group G's block, in one column; E's group comes after
+        let (b, f) = (cell("B"), cell("F"));
+        assert_eq!(b.0, f.0);
+        assert_eq!(b.1.abs_diff(f.1), 1);
+        assert!(cell("A").0 < b.0);
+        assert!(cell("C").0 > b.0);
+        assert!(cell("E").0 > cell("C").0);
+    }
+
+    #[test]
+    fn test_layout_grid_places_members_near_group() {
+        let content = "- X (pos: 1,1)\n# Group: G\n- A (pos: 3,3)\n- B\n# End group\n- C";
+        let (nodes, _, _) = parse_diagram(content);
+        let (layouts, grid) = layout_grid(&nodes, 900.0, 900.0, 0.0, 0.0, 1.0);
+        let cell = |i: usize| {
+            grid.cell_at(egui::pos2(layouts[i].center_x, layouts[i].center_y))
+                .unwrap()
+        };
+        let b = cell(2);
+        assert_eq!(b.0.abs_diff(2) + b.1.abs_diff(2), 1);
+        // Ungrouped nodes still take the first free cell
+        assert_eq!(cell(3), (1, 0));
+    }
+
     #[test]
     fn test_apply_rounded_corners() {
         let pts = vec![
@@ -3028,11 +3637,13 @@ mod diagram_tests {
                 name: "A".into(),
                 col: 1,
                 row: 1,
+                group: None,
             },
             routing::types::DiagramNode {
                 name: "B".into(),
                 col: 2,
                 row: 1,
+                group: None,
             },
         ];
         let edges = vec![routing::types::DiagramEdge {
@@ -3070,16 +3681,19 @@ mod diagram_tests {
                 name: "A".into(),
                 col: 1,
                 row: 1,
+                group: None,
             },
             routing::types::DiagramNode {
                 name: "B".into(),
                 col: 2,
                 row: 1,
+                group: None,
             },
             routing::types::DiagramNode {
                 name: "C".into(),
                 col: 2,
                 row: 2,
+                group: None,
             },
         ];
         let edges = vec![routing::types::DiagramEdge {
@@ -3112,11 +3726,13 @@ mod diagram_tests {
                 name: "A".into(),
                 col: 1,
                 row: 1,
+                group: None,
             },
             routing::types::DiagramNode {
                 name: "B".into(),
                 col: 2,
                 row: 1,
+                group: None,
             },
         ];
         let edges = vec![routing::types::DiagramEdge {
@@ -3179,11 +3795,13 @@ mod diagram_tests {
                 name: "A".into(),
                 col: 1,
                 row: 1,
+                group: None,
             },
             routing::types::DiagramNode {
                 name: "B".into(),
                 col: 2,
                 row: 1,
+                group: None,
             },
         ];
         let edges = vec![
@@ -3248,6 +3866,7 @@ mod diagram_tests {
                 turns: 1,
                 lane_changes: 0,
                 crossings: 0,
+                group_crossings: 0,
             },
         };
 
@@ -3422,7 +4041,7 @@ mod diagram_tests {
     #[test]
     fn test_diagram_debug_info_basic() {
         let content = "A (pos: 1,1)\nB (pos: 2,1)\nA -> B: link";
-        let info = diagram_debug_info(content);
+        let info = diagram_debug_info(DiagramKind::Architecture, content);
         assert!(info.contains("NODES (2):"));
         assert!(info.contains("A @ (1,1)"));
         assert!(info.contains("B @ (2,1)"));
@@ -3434,7 +4053,7 @@ mod diagram_tests {
 
     #[test]
     fn test_diagram_debug_info_empty() {
-        let info = diagram_debug_info("");
+        let info = diagram_debug_info(DiagramKind::Architecture, "");
         assert_eq!(info, "No nodes parsed.");
     }
 
@@ -3557,7 +4176,7 @@ mod diagram_tests {
     #[test]
     fn test_diagram_debug_info_auto_layout() {
         let content = "A\nB\nC\nA -> B\nB -> C";
-        let info = diagram_debug_info(content);
+        let info = diagram_debug_info(DiagramKind::Architecture, content);
         // Auto-layout: 3 nodes → single row at (1,1), (2,1), (3,1)
         assert!(info.contains("NODES (3):"));
         assert!(info.contains("A @ (1,1)"));
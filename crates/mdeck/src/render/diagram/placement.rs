@@ -0,0 +1,785 @@
+//! Automatic placement for diagrams without `pos:` hints, chosen with an
+//! `@layout:` line inside the diagram:
+//!
+//! - `grid` fills the cells in declaration order (the default for component
+//!   diagrams).
+//! - `layered` is a Sugiyama-style layout: cycles are broken, nodes are put
+//!   in layers along the edges, each layer is reordered by the barycenter of
+//!   its neighbors to reduce crossings, and nodes are aligned with their
+//!   neighbors. Layers run top to bottom (`TB`) or left to right (`LR`, the
+//!   default), and edges that skip layers keep a free cell in each layer
+//!   they pass so the router has a straight lane. Each group gets a band of
+//!   cells across all layers that only its members use. Flowcharts use it
+//!   top to bottom by default, ranking branches so `yes` goes straight down.
+//! - `radial` puts the most connected node in the middle and the others on
+//!   rings around it by their distance from it. It does not keep groups
+//!   together, which `--check` points out.
+//!
+//! All of them only assign grid positions; sizing and routing work as for
+//! hand-placed diagrams.
+
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+
+use super::{ArrowKind, DiagramEdge, DiagramNode};
+
+/// Crossing-reduction sweeps over the layers (each one down and back up).
+const SWEEPS: usize = 8;
+
+/// Rank of an edge reversed to break a cycle: it never decides alignment
+/// when a node has other neighbors.
+const REVERSED: u8 = u8::MAX;
+
+/// An edge between node indices with its [`BranchRank`].
+type Link = (usize, usize, u8);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum DiagramLayout {
+    Grid,
+    Layered(Direction),
+    Radial,
+}
+
+/// Which way a layered layout's layers run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Direction {
+    /// Layers are rows, from the top down.
+    TopBottom,
+    /// Layers are columns, from left to right.
+    LeftRight,
+}
+
+/// The `@layout: layered|grid|radial [LR|TB]` value of `line`, if it is one.
+/// An unknown layout name gives `Err` with the value.
+pub(super) fn parse_directive(line: &str) -> Option<Result<DiagramLayout, String>> {
+    let value = line.strip_prefix("@layout:")?.trim();
+    let mut words = value.split_whitespace();
+    let layout = match words.next().map(str::to_lowercase).as_deref() {
+        Some("grid") => DiagramLayout::Grid,
+        Some("radial") => DiagramLayout::Radial,
+        Some("layered") => match words.next().map(str::to_uppercase).as_deref() {
+            None | Some("LR") => DiagramLayout::Layered(Direction::LeftRight),
+            Some("TB") => DiagramLayout::Layered(Direction::TopBottom),
+            Some(_) => return Some(Err(value.to_string())),
+        },
+        _ => return Some(Err(value.to_string())),
+    };
+    if words.next().is_some() {
+        return Some(Err(value.to_string()));
+    }
+    Some(Ok(layout))
+}
+
+/// The diagram's `@layout:` choice, if it makes one.
+pub(super) fn diagram_layout(content: &str) -> Option<DiagramLayout> {
+    content
+        .lines()
+        .filter_map(|line| parse_directive(line.trim()))
+        .filter_map(Result::ok)
+        .next_back()
+}
+
+/// Rank of an edge among its source's outgoing edges in a layered layout:
+/// lower ranks come first in the layer below and are the ones a node lines
+/// up with when it has several neighbors.
+pub(super) type BranchRank = fn(&DiagramEdge) -> u8;
+
+/// The [`BranchRank`] of diagrams whose edges are all alike.
+pub(super) fn same_rank(_: &DiagramEdge) -> u8 {
+    0
+}
+
+/// Place the nodes by `layout` unless the source already places some with
+/// `pos:`.
+pub(super) fn place_nodes(
+    layout: DiagramLayout,
+    nodes: &mut [DiagramNode],
+    edges: &[DiagramEdge],
+    rank: BranchRank,
+) {
+    if nodes.is_empty() || nodes.iter().any(|n| n.grid_pos.is_some()) {
+        return;
+    }
+    let links = links(nodes, edges, rank);
+    let cells = match layout {
+        DiagramLayout::Grid => return,
+        DiagramLayout::Layered(direction) => {
+            let groups: Vec<Option<usize>> = nodes.iter().map(|n| n.group).collect();
+            let (layer, position) = layered(&groups, &links);
+            layer
+                .iter()
+                .zip(&position)
+                .map(|(&l, &p)| match direction {
+                    Direction::TopBottom => (p, l),
+                    Direction::LeftRight => (l, p),
+                })
+                .collect()
+        }
+        DiagramLayout::Radial => radial(nodes.len(), &links),
+    };
+    for (node, (col, row)) in nodes.iter_mut().zip(cells) {
+        node.grid_pos = Some((col as u32 + 1, row as u32 + 1));
+    }
+}
+
+/// Edges as `(from, to, rank)` with node indices, `<-` pointing the other
+/// way, without self-loops, stably sorted by rank.
+fn links(nodes: &[DiagramNode], edges: &[DiagramEdge], rank: BranchRank) -> Vec<Link> {
+    let index: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.name.as_str(), i))
+        .collect();
+    let mut links = edges
+        .iter()
+        .filter_map(|edge| {
+            let from = *index.get(edge.from.as_str())?;
+            let to = *index.get(edge.to.as_str())?;
+            let (from, to) = if edge.arrow == ArrowKind::Reverse {
+                (to, from)
+            } else {
+                (from, to)
+            };
+            (from != to).then_some((from, to, rank(edge)))
+        })
+        .collect::<Vec<_>>();
+    links.sort_by_key(|&(_, _, rank)| rank);
+    links
+}
+
+// ─── Layered ─────────────────────────────────────────────────────────────────
+
+/// Layer and position within the layer of each node, given the group of
+/// each.
+fn layered(groups: &[Option<usize>], links: &[Link]) -> (Vec<usize>, Vec<usize>) {
+    let n = groups.len();
+    let forward = acyclic(n, links);
+
+    // Longest path from the sources, then sources pulled down next to their
+    // first child so they do not all crowd the first layer
+    let mut layer = vec![0usize; n];
+    let order = topological(n, &forward);
+    for &v in &order {
+        for &(from, to, _) in &forward {
+            if from == v {
+                layer[to] = layer[to].max(layer[v] + 1);
+            }
+        }
+    }
+    for &v in order.iter().rev() {
+        let is_source = !forward.iter().any(|&(_, to, _)| to == v);
+        let lowest_child = forward
+            .iter()
+            .filter(|&&(from, _, _)| from == v)
+            .map(|&(_, to, _)| layer[to])
+            .min();
+        if is_source && let Some(child) = lowest_child {
+            layer[v] = child - 1;
+        }
+    }
+
+    // Edges spanning several layers get a dummy node in each layer between,
+    // in the group of the edge's ends when they share one
+    let mut total = n;
+    let mut segments: Vec<Link> = Vec::new();
+    let mut node_layer = layer.clone();
+    let mut group = groups.to_vec();
+    for &(from, to, rank) in &forward {
+        let mut prev = from;
+        for l in layer[from] + 1..layer[to] {
+            node_layer.push(l);
+            group.push(groups[from].filter(|_| groups[from] == groups[to]));
+            segments.push((prev, total, rank));
+            prev = total;
+            total += 1;
+        }
+        segments.push((prev, to, rank));
+    }
+
+    let layers = node_layer.iter().copied().max().unwrap_or(0) + 1;
+    let mut rows: Vec<Vec<usize>> = vec![Vec::new(); layers];
+    for v in order.iter().copied().chain(n..total) {
+        rows[node_layer[v]].push(v);
+    }
+    let up = neighbors(total, &segments, false);
+    let down = neighbors(total, &segments, true);
+    let all = |links: &[Vec<(usize, u8)>]| -> Vec<Vec<usize>> {
+        links
+            .iter()
+            .map(|l| l.iter().map(|&(u, _)| u).collect())
+            .collect()
+    };
+    reduce_crossings(&mut rows, &all(&up), &all(&down));
+
+    let bounds = bands(&mut rows, &group);
+    let position = assign_positions(&rows, &strongest(&up), &strongest(&down), &bounds);
+    (layer, position[..n].to_vec())
+}
+
+/// The links with every edge that closes a cycle reversed and ranked
+/// [`REVERSED`], found by a depth-first search from the sources in
+/// declaration order.
+fn acyclic(n: usize, links: &[Link]) -> Vec<Link> {
+    fn visit(v: usize, links: &[Link], state: &mut [u8], forward: &mut Vec<Link>) {
+        state[v] = 1;
+        for &(from, to, rank) in links {
+            if from != v {
+                continue;
+            }
+            match state[to] {
+                0 => {
+                    forward.push((v, to, rank));
+                    visit(to, links, state, forward);
+                }
+                // Still being visited: the edge closes a cycle
+                1 => forward.push((to, v, REVERSED)),
+                _ => forward.push((v, to, rank)),
+            }
+        }
+        state[v] = 2;
+    }
+
+    let mut state = vec![0u8; n];
+    let mut forward = Vec::with_capacity(links.len());
+    let sources = (0..n).filter(|&v| !links.iter().any(|&(_, to, _)| to == v));
+    for root in sources.chain(0..n).collect::<Vec<_>>() {
+        if state[root] == 0 {
+            visit(root, links, &mut state, &mut forward);
+        }
+    }
+    forward
+}
+
+/// The nodes in an order where every edge of the acyclic `forward` points
+/// later, ties kept in declaration order.
+fn topological(n: usize, forward: &[Link]) -> Vec<usize> {
+    let mut indegree = vec![0usize; n];
+    for &(_, to, _) in forward {
+        indegree[to] += 1;
+    }
+    let mut ready: VecDeque<usize> = (0..n).filter(|&v| indegree[v] == 0).collect();
+    let mut order = Vec::with_capacity(n);
+    while let Some(v) = ready.pop_front() {
+        order.push(v);
+        for &(from, to, _) in forward {
+            if from == v {
+                indegree[to] -= 1;
+                if indegree[to] == 0 {
+                    ready.push_back(to);
+                }
+            }
+        }
+    }
+    order
+}
+
+/// For each node, its neighbors in the layer below (`below`) or above, with
+/// the rank of the link.
+fn neighbors(total: usize, segments: &[Link], below: bool) -> Vec<Vec<(usize, u8)>> {
+    let mut result = vec![Vec::new(); total];
+    for &(from, to, rank) in segments {
+        if below {
+            result[from].push((to, rank));
+        } else {
+            result[to].push((from, rank));
+        }
+    }
+    result
+}
+
+/// For each node, the neighbors linked with the lowest rank: the ones it
+/// lines up with.
+fn strongest(links: &[Vec<(usize, u8)>]) -> Vec<Vec<usize>> {
+    links
+        .iter()
+        .map(|linked| {
+            let best = linked.iter().map(|&(_, rank)| rank).min();
+            linked
+                .iter()
+                .filter(|&&(_, rank)| Some(rank) == best)
+                .map(|&(u, _)| u)
+                .collect()
+        })
+        .collect()
+}
+
+/// Reorder each layer by the mean position of its neighbors in the layer
+/// just placed, sweeping down and up, and keep the order with the fewest
+/// crossings.
+fn reduce_crossings(rows: &mut [Vec<usize>], up: &[Vec<usize>], down: &[Vec<usize>]) {
+    let mut best = rows.to_vec();
+    let mut best_crossings = crossings(rows, down);
+    for _ in 0..SWEEPS {
+        for l in 1..rows.len() {
+            let (placed, rest) = rows.split_at_mut(l);
+            sort_by_barycenter(&mut rest[0], &placed[l - 1], up);
+        }
+        for l in (0..rows.len().saturating_sub(1)).rev() {
+            let (rest, placed) = rows.split_at_mut(l + 1);
+            sort_by_barycenter(&mut rest[l], &placed[0], down);
+        }
+        let count = crossings(rows, down);
+        if count < best_crossings {
+            best_crossings = count;
+            best = rows.to_vec();
+        }
+        if count == 0 {
+            break;
+        }
+    }
+    rows.clone_from_slice(&best);
+}
+
+/// Stable-sort `row` by the mean index in `fixed` of each node's `links`;
+/// nodes without any keep their place.
+fn sort_by_barycenter(row: &mut [usize], fixed: &[usize], links: &[Vec<usize>]) {
+    let index: HashMap<usize, usize> = fixed.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+    let mut keyed: Vec<(f32, usize)> = row
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            let linked: Vec<usize> = links[v]
+                .iter()
+                .filter_map(|u| index.get(u))
+                .copied()
+                .collect();
+            let key = if linked.is_empty() {
+                i as f32
+            } else {
+                linked.iter().sum::<usize>() as f32 / linked.len() as f32
+            };
+            (key, v)
+        })
+        .collect();
+    keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    for (slot, (_, v)) in row.iter_mut().zip(keyed) {
+        *slot = v;
+    }
+}
+
+/// Number of crossings between consecutive layers.
+fn crossings(rows: &[Vec<usize>], down: &[Vec<usize>]) -> usize {
+    let mut count = 0;
+    for pair in rows.windows(2) {
+        let below: HashMap<usize, usize> =
+            pair[1].iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        let ends: Vec<(usize, usize)> = pair[0]
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &v)| {
+                down[v]
+                    .iter()
+                    .filter_map(|u| below.get(u))
+                    .map(move |&j| (i, j))
+            })
+            .collect();
+        for (k, &(a, b)) in ends.iter().enumerate() {
+            for &(c, d) in &ends[k + 1..] {
+                if (a < c && b > d) || (a > c && b < d) {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+/// Split the cells of every layer into bands: one per group, used only by
+/// its members in all layers, and between them bands for the nodes outside
+/// groups, each going to the gap nearest its place in the layer. Reorders
+/// the layers band by band and returns each node's band as a cell range.
+/// Without groups there is one band as wide as the widest layer.
+fn bands(rows: &mut [Vec<usize>], group: &[Option<usize>]) -> Vec<Range<usize>> {
+    // Where each node sits across its layer, from 0 to 1
+    let mut place = vec![0.0f32; group.len()];
+    for row in rows.iter() {
+        for (i, &v) in row.iter().enumerate() {
+            place[v] = (i as f32 + 0.5) / row.len() as f32;
+        }
+    }
+    // Groups in order of their members' mean place
+    let mut members: Vec<(f32, usize)> = Vec::new();
+    for (v, g) in group.iter().enumerate() {
+        let Some(g) = *g else { continue };
+        if members.len() <= g {
+            members.resize(g + 1, (0.0, 0));
+        }
+        members[g].0 += place[v];
+        members[g].1 += 1;
+    }
+    let mut groups: Vec<(f32, usize)> = members
+        .iter()
+        .enumerate()
+        .filter(|&(_, &(_, count))| count > 0)
+        .map(|(g, &(sum, count))| (sum / count as f32, g))
+        .collect();
+    groups.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Bands alternate: free nodes in gap k are band 2k, group j band 2j + 1
+    let band: Vec<usize> = (0..group.len())
+        .map(|v| match group[v] {
+            Some(g) => 2 * groups.iter().position(|&(_, h)| h == g).unwrap_or(0) + 1,
+            None => 2 * groups.iter().filter(|&&(mean, _)| mean < place[v]).count(),
+        })
+        .collect();
+    let mut widths = vec![0usize; 2 * groups.len() + 1];
+    for row in rows.iter_mut() {
+        row.sort_by_key(|&v| band[v]);
+        let mut counts = vec![0usize; widths.len()];
+        for &v in row.iter() {
+            counts[band[v]] += 1;
+        }
+        for (width, count) in widths.iter_mut().zip(counts) {
+            *width = (*width).max(count);
+        }
+    }
+    let mut starts = Vec::with_capacity(widths.len());
+    let mut start = 0;
+    for width in &widths {
+        starts.push(start);
+        start += width;
+    }
+    band.iter()
+        .map(|&b| starts[b]..starts[b] + widths[b])
+        .collect()
+}
+
+/// Cell index of every node within its layer, inside its band from
+/// [`bands`]: each node moves as close to its neighbors' mean as the order
+/// allows.
+fn assign_positions(
+    rows: &[Vec<usize>],
+    up: &[Vec<usize>],
+    down: &[Vec<usize>],
+    bounds: &[Range<usize>],
+) -> Vec<usize> {
+    // Start each band's nodes centered in it
+    let mut position = vec![0usize; bounds.len()];
+    for row in rows {
+        let mut i = 0;
+        while i < row.len() {
+            let band = &bounds[row[i]];
+            let count = row[i..].iter().take_while(|&&v| bounds[v] == *band).count();
+            let offset = band.start + (band.len() - count) / 2;
+            for (k, &v) in row[i..i + count].iter().enumerate() {
+                position[v] = offset + k;
+            }
+            i += count;
+        }
+    }
+    let passes = [(up, false), (down, true), (up, false)];
+    for (links, reverse) in passes {
+        let order: Vec<&Vec<usize>> = if reverse {
+            rows.iter().rev().collect()
+        } else {
+            rows.iter().collect()
+        };
+        for row in order {
+            let desired: Vec<f32> = row
+                .iter()
+                .map(|&v| match links[v].as_slice() {
+                    [] => position[v] as f32,
+                    linked => {
+                        linked.iter().map(|&u| position[u] as f32).sum::<f32>()
+                            / linked.len() as f32
+                    }
+                })
+                .collect();
+            // Nearest to the desired cells while keeping the order and bands
+            let len = row.len();
+            let mut placed = vec![0usize; len];
+            for i in 0..len {
+                let band = &bounds[row[i]];
+                let lowest = if i == 0 { 0 } else { placed[i - 1] + 1 };
+                placed[i] = (desired[i].round().max(0.0) as usize)
+                    .max(lowest)
+                    .max(band.start);
+            }
+            for i in (0..len).rev() {
+                let band = &bounds[row[i]];
+                let highest = if i + 1 == len {
+                    band.end - 1
+                } else {
+                    (placed[i + 1] - 1).min(band.end - 1)
+                };
+                placed[i] = placed[i].min(highest);
+            }
+            for (&v, p) in row.iter().zip(placed) {
+                position[v] = p;
+            }
+        }
+    }
+    position
+}
+
+// ─── Radial ──────────────────────────────────────────────────────────────────
+
+/// Cell of each of `n` nodes: the most connected node in the center and the
+/// rest on square rings around it, one ring per step away from it, each
+/// near the angle of the node it hangs off.
+fn radial(n: usize, links: &[Link]) -> Vec<(usize, usize)> {
+    let mut adjacent = vec![Vec::new(); n];
+    for &(a, b, _) in links {
+        adjacent[a].push(b);
+        adjacent[b].push(a);
+    }
+    // First node with the most links
+    let hub = (0..n).rev().max_by_key(|&v| adjacent[v].len()).unwrap_or(0);
+
+    // Breadth-first from the hub; unconnected nodes go one ring further out
+    let mut distance = vec![usize::MAX; n];
+    let mut parent = vec![hub; n];
+    distance[hub] = 0;
+    let mut queue = VecDeque::from([hub]);
+    let mut visit_order = Vec::with_capacity(n);
+    while let Some(v) = queue.pop_front() {
+        visit_order.push(v);
+        for &u in &adjacent[v] {
+            if distance[u] == usize::MAX {
+                distance[u] = distance[v] + 1;
+                parent[u] = v;
+                queue.push_back(u);
+            }
+        }
+    }
+    let farthest = visit_order.iter().map(|&v| distance[v]).max().unwrap_or(0);
+    for (v, d) in distance.iter_mut().enumerate() {
+        if *d == usize::MAX {
+            *d = farthest + 1;
+            visit_order.push(v);
+        }
+    }
+
+    // Cells are relative to the hub until shifted into the grid at the end
+    let center = 0;
+    let angle_of = |(c, r): (i32, i32)| {
+        let a = ((r - center) as f32).atan2((c - center) as f32) + std::f32::consts::FRAC_PI_2;
+        a.rem_euclid(std::f32::consts::TAU)
+    };
+
+    let mut cell = vec![(center, center); n];
+    let mut taken = vec![(center, center)];
+    let mut ring = 1;
+    let first_ring: Vec<usize> = visit_order
+        .iter()
+        .copied()
+        .filter(|&v| distance[v] == 1)
+        .collect();
+    for &v in visit_order.iter().filter(|&&v| v != hub) {
+        ring = ring.max(distance[v] as i32);
+        let target = if distance[v] == 1 {
+            let i = first_ring.iter().position(|&u| u == v).unwrap_or(0);
+            i as f32 / first_ring.len() as f32 * std::f32::consts::TAU
+        } else {
+            angle_of(cell[parent[v]])
+        };
+        loop {
+            let free = ring_cells(center, ring)
+                .filter(|c| !taken.contains(c))
+                .min_by(|&a, &b| {
+                    angle_gap(angle_of(a), target).total_cmp(&angle_gap(angle_of(b), target))
+                });
+            if let Some(c) = free {
+                cell[v] = c;
+                taken.push(c);
+                break;
+            }
+            ring += 1;
+        }
+    }
+
+    // Drop empty rows and columns at the edges
+    let min_col = cell.iter().map(|c| c.0).min().unwrap_or(0);
+    let min_row = cell.iter().map(|c| c.1).min().unwrap_or(0);
+    cell.iter()
+        .map(|&(c, r)| ((c - min_col) as usize, (r - min_row) as usize))
+        .collect()
+}
+
+/// The cells at Chebyshev distance `ring` from (`center`, `center`),
+/// clockwise from the top.
+fn ring_cells(center: i32, ring: i32) -> impl Iterator<Item = (i32, i32)> {
+    let lo = center - ring;
+    let hi = center + ring;
+    (lo..=hi).flat_map(move |r| {
+        (lo..=hi).filter_map(move |c| (r == lo || r == hi || c == lo || c == hi).then_some((c, r)))
+    })
+}
+
+/// Distance between two angles in radians, around the circle.
+fn angle_gap(a: f32, b: f32) -> f32 {
+    let d = (a - b).rem_euclid(std::f32::consts::TAU);
+    d.min(std::f32::consts::TAU - d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_diagram;
+    use super::*;
+
+    fn placed(content: &str, layout: DiagramLayout) -> HashMap<String, (u32, u32)> {
+        let (mut nodes, edges, _) = parse_diagram(content);
+        place_nodes(layout, &mut nodes, &edges, same_rank);
+        nodes
+            .into_iter()
+            .map(|n| (n.name, n.grid_pos.unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_layout_directive() {
+        assert_eq!(
+            parse_directive("@layout: layered"),
+            Some(Ok(DiagramLayout::Layered(Direction::LeftRight)))
+        );
+        assert_eq!(
+            parse_directive("@layout: Layered tb"),
+            Some(Ok(DiagramLayout::Layered(Direction::TopBottom)))
+        );
+        assert_eq!(
+            parse_directive("@layout: radial"),
+            Some(Ok(DiagramLayout::Radial))
+        );
+        assert_eq!(
+            parse_directive("@layout: spiral"),
+            Some(Err("spiral".into()))
+        );
+        assert_eq!(parse_directive("- A -> B"), None);
+        assert_eq!(
+            diagram_layout("@layout: grid\n- A -> B\n@layout: radial"),
+            Some(DiagramLayout::Radial)
+        );
+    }
+
+    #[test]
+    fn layered_follows_edges_and_breaks_cycles() {
+        let cells = placed(
+            "- A -> B\n- B -> C\n- C -> A\n- A -> D\n- D -> C",
+            DiagramLayout::Layered(Direction::TopBottom),
+        );
+        let row = |name: &str| cells[name].1;
+        assert_eq!(row("A"), 1);
+        assert!(row("B") > row("A") && row("D") > row("A"));
+        assert!(row("C") > row("B") && row("C") > row("D"));
+        // Left to right swaps the axes
+        let cells = placed("- A -> B", DiagramLayout::Layered(Direction::LeftRight));
+        assert_eq!(cells["A"], (1, 1));
+        assert_eq!(cells["B"], (2, 1));
+    }
+
+    #[test]
+    fn layered_removes_avoidable_crossings() {
+        // Declared so that the naive order crosses: A-Y and B-X
+        let content = "- A\n- B\n- X\n- Y\n- A -> Y\n- B -> X\n- A -> Q\n- B -> P\n- P\n- Q";
+        let cells = placed(content, DiagramLayout::Layered(Direction::TopBottom));
+        let col = |name: &str| cells[name].0 as i32;
+        let (a, b) = (col("A"), col("B"));
+        assert_eq!((a < b), (col("Y") < col("X")), "{cells:?}");
+        assert_eq!((a < b), (col("Q") < col("P")), "{cells:?}");
+        let mut all: Vec<_> = cells.values().collect();
+        all.sort();
+        all.dedup();
+        assert_eq!(all.len(), 6, "cells overlap: {cells:?}");
+    }
+
+    #[test]
+    fn layered_keeps_groups_together() {
+        // Without bands Cache would sit between API and DB in the second
+        // layer, inside the Backend box
+        let content = "@layout: layered TB\n- Client\n# Group: Backend\n- API\n- DB\n# End group\n\
+             - Cache\n# Group: Edge\n- CDN\n- LB\n# End group\n- Client -> API\n- Client -> Cache\n\
+             - Client -> DB\n- API -> LB\n- DB -> CDN\n- Cache -> Log\n- LB -> CDN";
+        let (mut nodes, edges, _) = parse_diagram(content);
+        place_nodes(
+            diagram_layout(content).unwrap(),
+            &mut nodes,
+            &edges,
+            same_rank,
+        );
+        let cells: Vec<(u32, u32)> = nodes.iter().map(|n| n.grid_pos.unwrap()).collect();
+        let mut unique = cells.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), nodes.len(), "cells overlap: {cells:?}");
+        for group in [0, 1] {
+            let members: Vec<(u32, u32)> = nodes
+                .iter()
+                .zip(&cells)
+                .filter(|(n, _)| n.group == Some(group))
+                .map(|(_, &c)| c)
+                .collect();
+            let cols = members.iter().map(|c| c.0);
+            let rows = members.iter().map(|c| c.1);
+            let (c0, c1) = (cols.clone().min().unwrap(), cols.max().unwrap());
+            let (r0, r1) = (rows.clone().min().unwrap(), rows.max().unwrap());
+            for (node, &(c, r)) in nodes.iter().zip(&cells) {
+                if node.group != Some(group) {
+                    assert!(
+                        !((c0..=c1).contains(&c) && (r0..=r1).contains(&r)),
+                        "{} inside group {group}: {cells:?}",
+                        node.name
+                    );
+                }
+            }
+        }
+        // Layers still follow the edges
+        let row = |name: &str| cells[nodes.iter().position(|n| n.name == name).unwrap()].1;
+        assert!(row("Client") < row("API") && row("API") < row("LB") && row("LB") < row("CDN"));
+    }
+
+    #[test]
+    fn ranked_branches_line_up_and_order() {
+        // A rank-0 child sits straight below its parent, higher ranks to its
+        // right, and a cycle's reversed edge does not pull the layout
+        fn rank(edge: &DiagramEdge) -> u8 {
+            edge.label.parse().unwrap_or(1)
+        }
+        let (mut nodes, edges, _) =
+            parse_diagram("- A -> B\n- B -> D: 2\n- B -> C: 0\n- D -> A\n- C -> E");
+        place_nodes(
+            DiagramLayout::Layered(Direction::TopBottom),
+            &mut nodes,
+            &edges,
+            rank,
+        );
+        let cell = |name: &str| {
+            nodes
+                .iter()
+                .find(|n| n.name == name)
+                .unwrap()
+                .grid_pos
+                .unwrap()
+        };
+        assert_eq!(cell("A").0, cell("B").0);
+        assert_eq!(cell("C").0, cell("B").0);
+        assert_eq!(cell("D").0, cell("C").0 + 1);
+        assert_eq!(cell("E").0, cell("C").0);
+    }
+
+    #[test]
+    fn radial_puts_hub_in_the_middle() {
+        let cells = placed(
+            "- Hub -> A\n- Hub -> B\n- Hub -> C\n- Hub -> D\n- A -> E\n- F",
+            DiagramLayout::Radial,
+        );
+        let hub = cells["Hub"];
+        for name in ["A", "B", "C", "D"] {
+            let (c, r) = cells[name];
+            assert_eq!(c.abs_diff(hub.0).max(r.abs_diff(hub.1)), 1, "{name}");
+        }
+        let (c, r) = cells["E"];
+        assert_eq!(c.abs_diff(hub.0).max(r.abs_diff(hub.1)), 2);
+        let mut all: Vec<_> = cells.values().collect();
+        all.sort();
+        all.dedup();
+        assert_eq!(all.len(), 7);
+    }
+
+    #[test]
+    fn keeps_explicit_positions() {
+        let (mut nodes, edges, _) = parse_diagram("- A (pos: 2,2)\n- A -> B");
+        place_nodes(DiagramLayout::Radial, &mut nodes, &edges, same_rank);
+        assert_eq!(nodes[0].grid_pos, Some((2, 2)));
+        assert_eq!(nodes[1].grid_pos, None);
+    }
+}
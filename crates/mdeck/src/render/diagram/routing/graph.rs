@@ -13,6 +13,9 @@ pub struct RoutingGraph {
     pub occupied_cells: HashSet<GridCoord>,
     /// Lane capacity for each segment (h_lane_capacity for horizontal, v_lane_capacity for vertical).
     pub segment_capacities: HashMap<SegmentId, i32>,
+    /// Group interiors in doubled coordinates, as exclusive `(min_col2, min_row2, max_col2,
+    /// max_row2)` bounds: the border streets around a group's cells lie outside.
+    pub group_bounds: Vec<(i32, i32, i32, i32)>,
 }
 
 impl RoutingGraph {
@@ -26,6 +29,7 @@ impl RoutingGraph {
                 adjacency: HashMap::new(),
                 occupied_cells: HashSet::new(),
                 segment_capacities: HashMap::new(),
+                group_bounds: Vec::new(),
             };
         }
 
@@ -110,9 +114,31 @@ impl RoutingGraph {
             adjacency,
             occupied_cells,
             segment_capacities,
+            group_bounds: Vec::new(),
         }
     }
 
+    /// Add group borders. Each group is given by the `(min_col, min_row, max_col, max_row)`
+    /// cell bounds of its members; its border runs along the streets around those cells.
+    pub fn with_groups(mut self, groups: &[(i32, i32, i32, i32)]) -> Self {
+        self.group_bounds = groups
+            .iter()
+            .map(|&(c0, r0, c1, r1)| (2 * c0 - 1, 2 * r0 - 1, 2 * c1 + 1, 2 * r1 + 1))
+            .collect();
+        self
+    }
+
+    /// Number of group borders crossed by stepping from `a` to the adjacent `b`.
+    pub fn group_borders_crossed(&self, a: GridCoord, b: GridCoord) -> u32 {
+        let inside = |p: GridCoord, &(c0, r0, c1, r1): &(i32, i32, i32, i32)| {
+            c0 < p.col2 && p.col2 < c1 && r0 < p.row2 && p.row2 < r1
+        };
+        self.group_bounds
+            .iter()
+            .filter(|g| inside(a, g) != inside(b, g))
+            .count() as u32
+    }
+
     /// Check if a coordinate is within the graph.
     pub fn contains(&self, coord: &GridCoord) -> bool {
         self.adjacency.contains_key(coord)
@@ -227,6 +227,7 @@ pub fn compute_complexity(waypoints: &[Waypoint]) -> super::types::RouteComplexi
         turns,
         lane_changes,
         crossings: 0,
+        group_crossings: 0,
     }
 }
 
@@ -41,7 +41,8 @@ pub fn route_all_edges(
 
     // Build the routing graph.
     let positions: Vec<(i32, i32)> = nodes.iter().map(|n| (n.col, n.row)).collect();
-    let graph = RoutingGraph::build(&positions, config.h_lane_capacity, config.v_lane_capacity);
+    let graph = RoutingGraph::build(&positions, config.h_lane_capacity, config.v_lane_capacity)
+        .with_groups(&group_bounds(nodes));
 
     // Lane occupancy tracker.
     let mut occupancy = LaneOccupancy::new();
@@ -84,3 +85,20 @@ pub fn route_all_edges(
 
     RoutingOutput { results }
 }
+
+/// Cell bounds `(min_col, min_row, max_col, max_row)` of each group's members.
+fn group_bounds(nodes: &[DiagramNode]) -> Vec<(i32, i32, i32, i32)> {
+    let mut bounds: Vec<Option<(i32, i32, i32, i32)>> = Vec::new();
+    for node in nodes {
+        let Some(group) = node.group else { continue };
+        if bounds.len() <= group {
+            bounds.resize(group + 1, None);
+        }
+        let b = bounds[group].get_or_insert((node.col, node.row, node.col, node.row));
+        b.0 = b.0.min(node.col);
+        b.1 = b.1.min(node.row);
+        b.2 = b.2.max(node.col);
+        b.3 = b.3.max(node.row);
+    }
+    bounds.into_iter().flatten().collect()
+}
@@ -32,6 +32,7 @@ struct SearchState {
     turns_so_far: u32,
     lane_changes_so_far: u32,
     crossings_so_far: u32,
+    group_crossings_so_far: u32,
 }
 
 impl SearchState {
@@ -140,7 +141,9 @@ fn astar_single_direction(
     // Seed the open set with states at the first junction.
     for &lane in &first_lanes {
         let h = heuristic(first_junction, target);
-        let g = weights.length * 0.5; // Weighted length of one step.
+        let group_crossings = graph.group_borders_crossed(source, first_junction);
+        let g = weights.length * 0.5 // Weighted length of one step.
+            + weights.group_border * group_crossings as f64;
         let state = SearchState {
             coord: first_junction,
             lane,
@@ -151,6 +154,7 @@ fn astar_single_direction(
             turns_so_far: 0,
             lane_changes_so_far: 0,
             crossings_so_far: 0,
+            group_crossings_so_far: group_crossings,
         };
         let key = state.key();
         best_g.insert(key, g);
@@ -238,12 +242,14 @@ fn astar_single_direction(
                 // Per-lane crossing detection: includes pass-through crossings
                 // and turn conflicts (lane-dependent).
                 let crossing_count = occupancy.count_crossings(&seg, next_lane, &[source, target]);
+                let group_crossings = graph.group_borders_crossed(current.coord, neighbor);
 
                 let new_g = current.g_cost
                     + weights.length * step_length
                     + weights.turn * turn_raw
                     + weights.lane_change * lane_change_raw
-                    + weights.crossing * crossing_count as f64;
+                    + weights.crossing * crossing_count as f64
+                    + weights.group_border * group_crossings as f64;
                 let new_h = heuristic(neighbor, target);
 
                 let new_key = StateKey {
@@ -273,6 +279,7 @@ fn astar_single_direction(
                     lane_changes_so_far: current.lane_changes_so_far
                         + if lane_changed && !is_turn { 1 } else { 0 },
                     crossings_so_far: current.crossings_so_far + crossing_count,
+                    group_crossings_so_far: current.group_crossings_so_far + group_crossings,
                 };
 
                 open.push(PqEntry {
@@ -346,6 +353,7 @@ fn reconstruct_route(
         turns: final_state.turns_so_far,
         lane_changes: final_state.lane_changes_so_far,
         crossings: final_state.crossings_so_far,
+        group_crossings: final_state.group_crossings_so_far,
     };
 
     Route {
@@ -376,6 +384,7 @@ pub fn find_best_route(
                 turns: 0,
                 lane_changes: 0,
                 crossings: 0,
+                group_crossings: 0,
             },
         });
     }
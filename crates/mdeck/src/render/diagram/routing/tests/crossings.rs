@@ -720,6 +720,7 @@ fn weighted_complexity_total() {
         turns: 2,
         lane_changes: 1,
         crossings: 4,
+        group_crossings: 0,
     };
 
     // Default weights: 3 + 2 + 1 + 4 = 10
@@ -731,6 +732,7 @@ fn weighted_complexity_total() {
         turn: 3.0,
         lane_change: 0.5,
         crossing: 10.0,
+        group_border: 1.0,
     };
     assert!((c.total(&w) - 52.5).abs() < f64::EPSILON);
 }
@@ -744,6 +746,7 @@ fn weighted_complexity_zero_weights() {
         turns: 3,
         lane_changes: 2,
         crossings: 1,
+        group_crossings: 0,
     };
 
     let zero_weights = CostWeights {
@@ -751,10 +754,46 @@ fn weighted_complexity_zero_weights() {
         turn: 0.0,
         lane_change: 0.0,
         crossing: 0.0,
+        group_border: 0.0,
     };
     assert!((c.total(&zero_weights)).abs() < f64::EPSILON);
 }
 
+#[test]
+fn group_border_weight_routes_around_group() {
+    // A(1,2) -> B(3,2) with a group spanning column 2: the straight route
+    // crosses into the group and back out.
+    let grouped = |name: &str, col, row| DiagramNode {
+        group: Some(0),
+        ..node(name, col, row)
+    };
+    let nodes = vec![
+        node("A", 1, 2),
+        node("B", 3, 2),
+        grouped("G1", 2, 1),
+        grouped("G2", 2, 3),
+    ];
+    let edges = vec![edge("A", "B")];
+
+    let free = CostWeights {
+        group_border: 0.0,
+        ..CostWeights::default()
+    };
+    let output = route_all_edges(&nodes, &edges, &config_weighted(3, 3, free));
+    assert_all_success(&output);
+    let route = get_route(&output, 0);
+    assert_eq!(route.complexity.turns, 0);
+    assert_eq!(route.complexity.group_crossings, 2);
+
+    let penalized = CostWeights {
+        group_border: 100.0,
+        ..CostWeights::default()
+    };
+    let output = route_all_edges(&nodes, &edges, &config_weighted(3, 3, penalized));
+    assert_all_success(&output);
+    assert_eq!(get_route(&output, 0).complexity.group_crossings, 0);
+}
+
 #[test]
 fn high_turn_weight_prefers_fewer_turns() {
     // A(1,1) -> B(2,2): L-shaped route needs 1 turn.
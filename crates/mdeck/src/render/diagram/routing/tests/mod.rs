@@ -16,6 +16,7 @@ fn node(name: &str, col: i32, row: i32) -> DiagramNode {
         name: name.to_string(),
         col,
         row,
+        group: None,
     }
 }
 
@@ -217,6 +217,8 @@ pub struct RouteComplexity {
     pub lane_changes: u32,
     /// Number of crossings with already-claimed routes.
     pub crossings: u32,
+    /// Number of group borders crossed.
+    pub group_crossings: u32,
 }
 
 impl RouteComplexity {
@@ -226,9 +228,10 @@ impl RouteComplexity {
             + weights.turn * self.turns as f64
             + weights.lane_change * self.lane_changes as f64
             + weights.crossing * self.crossings as f64
+            + weights.group_border * self.group_crossings as f64
     }
 
-    /// Total complexity using default weights (all 1.0).
+    /// Total complexity using default weights.
     fn total_default(&self) -> f64 {
         self.total(&CostWeights::default())
     }
@@ -249,6 +252,7 @@ impl Ord for RouteComplexity {
             .then(self.turns.cmp(&other.turns))
             .then(self.lane_changes.cmp(&other.lane_changes))
             .then(self.crossings.cmp(&other.crossings))
+            .then(self.group_crossings.cmp(&other.group_crossings))
     }
 }
 
@@ -274,6 +278,8 @@ pub struct DiagramNode {
     pub name: String,
     pub col: i32,
     pub row: i32,
+    /// Index of the group the node belongs to, if any.
+    pub group: Option<usize>,
 }
 
 /// An edge in the diagram connecting two nodes by name.
@@ -292,6 +298,9 @@ pub struct CostWeights {
     pub turn: f64,
     pub lane_change: f64,
     pub crossing: f64,
+    /// Cost of crossing a group border. Borders stay crossable, but routes prefer
+    /// to go around a group rather than through it.
+    pub group_border: f64,
 }
 
 impl Default for CostWeights {
@@ -301,6 +310,7 @@ impl Default for CostWeights {
             turn: 1.0,
             lane_change: 1.0,
             crossing: 1.0,
+            group_border: 2.0,
         }
     }
 }
@@ -0,0 +1,1175 @@
+//! Sequence diagrams (`@architecture sequence`): one lifeline per participant
+//! and messages drawn top to bottom in source order.
+//!
+//! ```text
+//! - Client (icon: user)
+//! - API: API Gateway
+//! + Client -> API: GET /orders
+//! activate API
+//! alt cached
+//! + API --> Client: 200 (cache)
+//! else miss
+//! + API -> DB: SELECT
+//! * DB --> API: rows
+//! end
+//! note over Client, API: JSON over HTTPS
+//! deactivate API
+//! ```
+//!
+//! Participants are declared like architecture nodes or inferred from
+//! messages in order of first appearance. `activate`/`deactivate` draw an
+//! activation bar from the preceding message to the message before
+//! `deactivate`; `note over|left of|right of` adds a note; `alt`/`else`,
+//! `opt` and `loop` open fragments closed by `end`. The `+`/`*` markers
+//! reveal any line step by step, as in architecture diagrams.
+
+use std::time::Instant;
+
+use eframe::egui::{self, Color32, FontId, Pos2, Stroke};
+
+use super::{
+    ArrowKind, DiagramReveal, DiagramScale, detect_arrow, draw_dashed_polyline, draw_node_icon,
+    draw_routed_edge, parse_node_metadata, parse_scale_directive, reveal_progress,
+    strip_reveal_marker,
+};
+use crate::render::image_cache::ImageCache;
+use crate::theme::Theme;
+
+/// The keys a participant declaration uses.
+const PARTICIPANT_KEYS: &[&str] = &["icon", "label"];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Participant {
+    name: String,
+    label: String,
+    icon: String,
+    step: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NotePlacement {
+    /// Spanning the participants from the first to the second index.
+    Over(usize, usize),
+    LeftOf(usize),
+    RightOf(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FragmentKind {
+    Alt,
+    Opt,
+    Loop,
+}
+
+impl FragmentKind {
+    fn keyword(self) -> &'static str {
+        match self {
+            FragmentKind::Alt => "alt",
+            FragmentKind::Opt => "opt",
+            FragmentKind::Loop => "loop",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum RowKind {
+    Message {
+        from: usize,
+        to: usize,
+        arrow: ArrowKind,
+        label: String,
+    },
+    Note {
+        placement: NotePlacement,
+        text: String,
+    },
+    FragmentStart(usize),
+    Else {
+        fragment: usize,
+        label: String,
+    },
+    FragmentEnd(usize),
+}
+
+/// One horizontal band of the diagram, revealed at `step`.
+#[derive(Debug, Clone, PartialEq)]
+struct Row {
+    kind: RowKind,
+    step: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Fragment {
+    kind: FragmentKind,
+    label: String,
+    step: usize,
+    start_row: usize,
+    /// The `end` row; unclosed fragments run to the last row.
+    end_row: Option<usize>,
+    /// How many fragments enclose this one.
+    depth: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Activation {
+    participant: usize,
+    /// The row the bar starts at; `None` starts it at the top.
+    start: Option<usize>,
+    /// The row the bar ends at; `None` runs it to the last row.
+    end: Option<usize>,
+    /// How many bars of the same participant it is nested in.
+    depth: usize,
+    step: usize,
+}
+
+#[derive(Debug)]
+struct Sequence {
+    participants: Vec<Participant>,
+    rows: Vec<Row>,
+    fragments: Vec<Fragment>,
+    activations: Vec<Activation>,
+    scale: DiagramScale,
+    /// Problems `--check` reports: lines drawn differently than written.
+    warnings: Vec<String>,
+}
+
+impl Sequence {
+    /// The index of participant `name`, adding it (always visible) if new.
+    fn participant(&mut self, name: &str) -> usize {
+        if let Some(i) = self.participants.iter().position(|p| p.name == name) {
+            return i;
+        }
+        self.participants.push(Participant {
+            name: name.to_string(),
+            label: name.to_string(),
+            icon: String::new(),
+            step: 0,
+        });
+        self.participants.len() - 1
+    }
+
+    /// The first and last participant columns inside `fragment`, all of them
+    /// when it holds no messages or notes.
+    fn fragment_columns(&self, fragment: &Fragment) -> (usize, usize) {
+        let end = fragment.end_row.unwrap_or(self.rows.len() - 1);
+        let columns = self.rows[fragment.start_row..=end]
+            .iter()
+            .flat_map(|row| match row.kind {
+                RowKind::Message { from, to, .. } => vec![from, to],
+                RowKind::Note {
+                    placement: NotePlacement::Over(a, b),
+                    ..
+                } => vec![a, b],
+                RowKind::Note {
+                    placement: NotePlacement::LeftOf(a) | NotePlacement::RightOf(a),
+                    ..
+                } => vec![a],
+                _ => vec![],
+            })
+            .collect::<Vec<_>>();
+        let first = columns.iter().copied().min().unwrap_or(0);
+        let last = columns
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(self.participants.len() - 1);
+        (first, last)
+    }
+
+    /// How many activation bars of `participant` cover row `row`.
+    fn active_depth(&self, participant: usize, row: usize) -> usize {
+        self.activations
+            .iter()
+            .filter(|a| {
+                a.participant == participant
+                    && a.start.is_none_or(|s| s <= row)
+                    && a.end.is_none_or(|e| row <= e)
+            })
+            .count()
+    }
+}
+
+/// The rest of `line` after the keyword `word`, if it starts with it.
+fn keyword<'a>(line: &'a str, word: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(word)?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+fn parse(content: &str) -> Sequence {
+    let mut seq = Sequence {
+        participants: Vec::new(),
+        rows: Vec::new(),
+        fragments: Vec::new(),
+        activations: Vec::new(),
+        scale: DiagramScale::Fit,
+        warnings: Vec::new(),
+    };
+    let mut step_counter = 0usize;
+    let mut open_fragments: Vec<usize> = Vec::new();
+    let mut open_activations: Vec<usize> = Vec::new();
+    let mut declared: Vec<&str> = Vec::new();
+    let mut referenced: Vec<&str> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            if let Some(scale) = parse_scale_directive(trimmed) {
+                seq.scale = scale;
+            }
+            continue;
+        }
+        let (body, reveal) = strip_reveal_marker(trimmed);
+        let body = body.trim();
+        if body.is_empty() {
+            continue;
+        }
+        let step = match reveal {
+            DiagramReveal::Static => 0,
+            DiagramReveal::NextStep => {
+                step_counter += 1;
+                step_counter
+            }
+            DiagramReveal::WithPrev => step_counter,
+        };
+        let last_row = seq.rows.len().checked_sub(1);
+        if reveal == DiagramReveal::NextStep
+            && (body == "end" || keyword(body, "deactivate").is_some())
+        {
+            seq.warnings.push(format!(
+                "Reveal marker on '{body}' adds a step that shows nothing"
+            ));
+        }
+
+        if let Some(name) = keyword(body, "activate") {
+            referenced.push(name);
+            let participant = seq.participant(name);
+            let depth = open_activations
+                .iter()
+                .filter(|&&a| seq.activations[a].participant == participant)
+                .count();
+            open_activations.push(seq.activations.len());
+            seq.activations.push(Activation {
+                participant,
+                start: last_row,
+                end: None,
+                depth,
+                step,
+            });
+        } else if let Some(name) = keyword(body, "deactivate") {
+            referenced.push(name);
+            let participant = seq.participant(name);
+            if let Some(pos) = open_activations
+                .iter()
+                .rposition(|&a| seq.activations[a].participant == participant)
+            {
+                let a = open_activations.remove(pos);
+                seq.activations[a].end = last_row;
+            } else {
+                seq.warnings
+                    .push(format!("'deactivate {name}' without a matching activate"));
+            }
+        } else if let Some(rest) = keyword(body, "note") {
+            let (target, text) = rest.split_once(':').unwrap_or((rest, ""));
+            let target = target.trim();
+            let placement = if let Some(names) = keyword(target, "over") {
+                let mut names = names.split(',').map(str::trim).filter(|n| !n.is_empty());
+                let Some(first) = names.next() else {
+                    seq.warnings
+                        .push(format!("Note '{body}' names no participant after 'over'"));
+                    continue;
+                };
+                referenced.push(first);
+                let first = seq.participant(first);
+                let last = names.next_back().map_or(first, |n| {
+                    referenced.push(n);
+                    seq.participant(n)
+                });
+                NotePlacement::Over(first.min(last), first.max(last))
+            } else if let Some(name) = target.strip_prefix("left of ") {
+                referenced.push(name.trim());
+                NotePlacement::LeftOf(seq.participant(name.trim()))
+            } else if let Some(name) = target.strip_prefix("right of ") {
+                referenced.push(name.trim());
+                NotePlacement::RightOf(seq.participant(name.trim()))
+            } else {
+                seq.warnings.push(format!(
+                    "Note '{body}' is not drawn (expected note over, left of or right of)"
+                ));
+                continue;
+            };
+            seq.rows.push(Row {
+                kind: RowKind::Note {
+                    placement,
+                    text: text.trim().to_string(),
+                },
+                step,
+            });
+        } else if let Some((kind, label)) = keyword(body, "alt")
+            .map(|l| (FragmentKind::Alt, l))
+            .or_else(|| keyword(body, "opt").map(|l| (FragmentKind::Opt, l)))
+            .or_else(|| keyword(body, "loop").map(|l| (FragmentKind::Loop, l)))
+        {
+            let fragment = seq.fragments.len();
+            seq.fragments.push(Fragment {
+                kind,
+                label: label.to_string(),
+                step,
+                start_row: seq.rows.len(),
+                end_row: None,
+                depth: open_fragments.len(),
+            });
+            open_fragments.push(fragment);
+            seq.rows.push(Row {
+                kind: RowKind::FragmentStart(fragment),
+                step,
+            });
+        } else if let Some(label) = keyword(body, "else") {
+            if let Some(&fragment) = open_fragments.last() {
+                seq.rows.push(Row {
+                    kind: RowKind::Else {
+                        fragment,
+                        label: label.to_string(),
+                    },
+                    step: step.max(seq.fragments[fragment].step),
+                });
+            } else {
+                seq.warnings
+                    .push("'else' outside an alt, opt or loop fragment".to_string());
+            }
+        } else if body == "end" {
+            if let Some(fragment) = open_fragments.pop() {
+                seq.fragments[fragment].end_row = Some(seq.rows.len());
+                seq.rows.push(Row {
+                    kind: RowKind::FragmentEnd(fragment),
+                    step: seq.fragments[fragment].step,
+                });
+            } else {
+                seq.warnings
+                    .push("'end' without an open alt, opt or loop fragment".to_string());
+            }
+        } else if let Some((arrow_pos, arrow_len, arrow)) = detect_arrow(body) {
+            let from = body[..arrow_pos].trim();
+            let rest = &body[arrow_pos + arrow_len..];
+            let (to, label) = rest.split_once(": ").unwrap_or((rest, ""));
+            referenced.extend([from, to.trim()]);
+            let from = seq.participant(from);
+            let to = seq.participant(to.trim());
+            seq.rows.push(Row {
+                kind: RowKind::Message {
+                    from,
+                    to,
+                    arrow,
+                    label: label.trim().to_string(),
+                },
+                step,
+            });
+        } else {
+            // Participant declaration: "Name", "Name: Label", "Name (icon: x)"
+            let meta = parse_node_metadata(body);
+            let (name, label) = match meta.before.split_once(": ") {
+                Some((name, label)) => (name.trim(), label.trim()),
+                None => (meta.before.trim(), meta.before.trim()),
+            };
+            declared.push(name);
+            for key in &meta.unknown {
+                seq.warnings.push(format!(
+                    "Unknown key '{key}' on participant '{name}' (known: {})",
+                    PARTICIPANT_KEYS.join(", ")
+                ));
+            }
+            for (key, set) in [
+                ("shape", !meta.shape.is_empty()),
+                ("style", !meta.style.is_empty()),
+                ("color", !meta.color.is_empty()),
+                ("pos", meta.grid_pos.is_some()),
+                ("prompt", meta.prompt.is_some()),
+            ] {
+                if set {
+                    seq.warnings.push(format!(
+                        "Key '{key}' on participant '{name}' is ignored in sequence diagrams (known: {})",
+                        PARTICIPANT_KEYS.join(", ")
+                    ));
+                }
+            }
+            let existed = seq.participants.iter().any(|p| p.name == name);
+            let i = seq.participant(name);
+            let participant = &mut seq.participants[i];
+            participant.label = meta.label.unwrap_or_else(|| label.to_string());
+            if !meta.icon.is_empty() {
+                participant.icon = meta.icon;
+            }
+            if !existed {
+                participant.step = step;
+            }
+        }
+    }
+
+    for &fragment in &open_fragments {
+        let fragment = &seq.fragments[fragment];
+        let opening = format!("{} {}", fragment.kind.keyword(), fragment.label);
+        seq.warnings
+            .push(format!("'{}' has no matching 'end'", opening.trim_end()));
+    }
+    // Participants may be inferred from messages, but an undeclared name
+    // that nearly matches a declared one is most likely a typo of it
+    let mut undeclared: Vec<&str> = Vec::new();
+    for name in referenced {
+        if !declared.contains(&name) && !undeclared.contains(&name) {
+            undeclared.push(name);
+        }
+    }
+    for name in undeclared {
+        if let Some(similar) = declared.iter().find(|d| nearly_equal(name, d)) {
+            seq.warnings.push(format!(
+                "Participant '{name}' is not declared, did you mean '{similar}'?"
+            ));
+        }
+    }
+    seq
+}
+
+/// Whether `a` and `b` differ only in case and, when both have at least
+/// three characters, at most one inserted, removed, replaced or swapped
+/// character.
+fn nearly_equal(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().flat_map(char::to_lowercase).collect();
+    let b: Vec<char> = b.chars().flat_map(char::to_lowercase).collect();
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if short.len() < 3 {
+        return short == long;
+    }
+    let prefix = short.iter().zip(&long).take_while(|(s, l)| s == l).count();
+    match long.len() - short.len() {
+        0 if prefix == short.len() => true,
+        0 => {
+            let swapped = prefix + 1 < short.len()
+                && short[prefix] == long[prefix + 1]
+                && short[prefix + 1] == long[prefix];
+            let rest = if swapped { prefix + 2 } else { prefix + 1 };
+            short[rest..] == long[rest..]
+        }
+        1 => short[prefix..] == long[prefix + 1..],
+        _ => false,
+    }
+}
+
+/// Warnings for `--check`: unknown participant keys, undeclared
+/// participants, unbalanced fragments and lines that are not drawn.
+pub(super) fn check(content: &str) -> Vec<String> {
+    parse(content).warnings
+}
+
+/// Vertical metrics of a sequence diagram at scale `s`.
+struct Metrics {
+    /// Message and note text size.
+    font: f32,
+    /// Participant label size.
+    head_font: f32,
+    icon_size: f32,
+    head_h: f32,
+    row_tops: Vec<f32>,
+    row_heights: Vec<f32>,
+    /// Height from the top of the heads to the end of the lifelines.
+    height: f32,
+}
+
+impl Metrics {
+    /// The y offset of row `i`'s arrow, or its middle for other rows.
+    fn anchor(&self, seq: &Sequence, i: usize) -> f32 {
+        let top = self.row_tops[i];
+        match &seq.rows[i].kind {
+            RowKind::Message { from, to, .. } if from == to => top + self.font * 1.4,
+            RowKind::Message { .. } => top + self.row_heights[i] - self.font * 0.6,
+            _ => top + self.row_heights[i] / 2.0,
+        }
+    }
+
+    /// Where an activation bar ending at row `i` stops: below the return of
+    /// a self-call, else at the row's anchor.
+    fn bar_end(&self, seq: &Sequence, i: usize) -> f32 {
+        match &seq.rows[i].kind {
+            RowKind::Message { from, to, .. } if from == to => {
+                self.anchor(seq, i) + self.font * 1.2
+            }
+            _ => self.anchor(seq, i),
+        }
+    }
+}
+
+fn measure(ui: &egui::Ui, seq: &Sequence, theme: &Theme, s: f32, col_w: f32) -> Metrics {
+    let painter = ui.painter();
+    let font = theme.body_size * 0.65 * s;
+    let head_font = theme.body_size * 0.8 * s;
+    let text_h = |text: &str, size: f32, wrap: f32| {
+        if text.is_empty() {
+            0.0
+        } else {
+            painter
+                .layout(
+                    text.to_string(),
+                    FontId::proportional(size),
+                    Color32::WHITE,
+                    wrap.max(20.0),
+                )
+                .rect
+                .height()
+        }
+    };
+
+    let has_icons = seq.participants.iter().any(|p| !p.icon.is_empty());
+    let icon_size = if has_icons { font * 2.6 } else { 0.0 };
+    let head_label_h = seq
+        .participants
+        .iter()
+        .map(|p| text_h(&p.label, head_font, col_w * 0.8))
+        .fold(head_font, f32::max);
+    let head_h = head_label_h + font * 1.2 + if has_icons { icon_size } else { 0.0 };
+
+    let mut row_tops = Vec::with_capacity(seq.rows.len());
+    let mut row_heights = Vec::with_capacity(seq.rows.len());
+    let mut y = head_h + font;
+    for row in &seq.rows {
+        let h = match &row.kind {
+            RowKind::Message {
+                from, to, label, ..
+            } => {
+                let span = (*from as f32 - *to as f32).abs().max(1.0) * col_w;
+                if from == to {
+                    text_h(label, font, col_w * 0.9).max(font) + font * 2.6
+                } else {
+                    text_h(label, font, span * 0.9) + font * 1.6
+                }
+            }
+            RowKind::Note { placement, text } => {
+                let wrap = note_width(*placement, col_w) - font;
+                text_h(text, font, wrap).max(font) + font * 1.6
+            }
+            RowKind::FragmentStart(_) => font * 2.2,
+            RowKind::Else { .. } => font * 1.8,
+            RowKind::FragmentEnd(_) => font * 0.8,
+        };
+        row_tops.push(y);
+        row_heights.push(h);
+        y += h;
+    }
+    Metrics {
+        font,
+        head_font,
+        icon_size,
+        head_h,
+        row_tops,
+        row_heights,
+        height: y + font,
+    }
+}
+
+fn note_width(placement: NotePlacement, col_w: f32) -> f32 {
+    match placement {
+        NotePlacement::Over(first, last) => (last - first) as f32 * col_w + col_w * 0.8,
+        NotePlacement::LeftOf(_) | NotePlacement::RightOf(_) => col_w * 0.8,
+    }
+}
+
+/// Draw a sequence diagram and return the height it takes.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn draw(
+    ui: &egui::Ui,
+    content: &str,
+    theme: &Theme,
+    pos: Pos2,
+    max_width: f32,
+    max_height: f32,
+    opacity: f32,
+    image_cache: &ImageCache,
+    reveal_step: usize,
+    reveal_timestamp: Option<Instant>,
+    scale: f32,
+) -> f32 {
+    let seq = parse(content);
+    if seq.participants.is_empty() {
+        return 0.0;
+    }
+
+    let padding = 30.0 * scale;
+    let area_width = max_width - padding * 2.0;
+    let col_w = area_width / seq.participants.len() as f32;
+    let mut m = measure(ui, &seq, theme, scale, col_w);
+    let available = max_height - padding * 2.0;
+    match seq.scale {
+        DiagramScale::Fit if max_height > 0.0 && m.height > available => {
+            let s = scale * (available / m.height).clamp(0.3, 1.0);
+            m = measure(ui, &seq, theme, s, col_w);
+        }
+        DiagramScale::Factor(f) => m = measure(ui, &seq, theme, scale * f, col_w),
+        _ => {}
+    }
+    let height = if max_height > 0.0 {
+        max_height.max(m.height + padding * 2.0)
+    } else {
+        m.height + padding * 2.0
+    };
+    let s = m.font / (theme.body_size * 0.65);
+    let origin_x = pos.x + padding;
+    let origin_y = pos.y + padding + ((available - m.height) / 2.0).max(0.0);
+    let center_x = |i: usize| origin_x + (i as f32 + 0.5) * col_w;
+    let bar_w = m.font * 0.8;
+
+    let painter = ui.painter();
+    let fg = theme.foreground;
+    let label_color = Theme::with_opacity(fg, opacity);
+    let muted = Theme::with_opacity(fg, opacity * 0.35);
+    let box_fill = Theme::with_opacity(theme.code_background, opacity * 0.95);
+    let border = Theme::with_opacity(theme.accent, opacity * 0.8);
+    let visible = |step: usize| step <= reveal_step;
+    let font = FontId::proportional(m.font);
+
+    // Fragment frames, outermost first
+    for fragment in &seq.fragments {
+        if !visible(fragment.step) {
+            continue;
+        }
+        let end = fragment.end_row.unwrap_or(seq.rows.len() - 1);
+        let (first, last) = seq.fragment_columns(fragment);
+        let inset = fragment.depth as f32 * m.font * 0.4;
+        let rect = egui::Rect::from_min_max(
+            egui::pos2(
+                center_x(first) - col_w * 0.45 + inset,
+                origin_y + m.row_tops[fragment.start_row] + m.font * 0.3,
+            ),
+            egui::pos2(
+                center_x(last) + col_w * 0.45 - inset,
+                origin_y + m.row_tops[end] + m.row_heights[end] - m.font * 0.3,
+            ),
+        );
+        painter.rect_stroke(
+            rect,
+            4.0 * s,
+            Stroke::new(1.5 * s, muted),
+            egui::StrokeKind::Middle,
+        );
+        let tab = painter.layout_no_wrap(
+            fragment.kind.keyword().to_string(),
+            FontId::proportional(m.font * 0.9),
+            label_color,
+        );
+        let tab_rect = egui::Rect::from_min_size(
+            rect.min,
+            tab.rect.size() + egui::vec2(m.font * 0.8, m.font * 0.4),
+        );
+        painter.rect_filled(tab_rect, 4.0 * s, muted);
+        painter.galley(
+            tab_rect.min + egui::vec2(m.font * 0.4, m.font * 0.2),
+            tab,
+            label_color,
+        );
+        if !fragment.label.is_empty() {
+            painter.text(
+                egui::pos2(tab_rect.right() + m.font * 0.5, tab_rect.center().y),
+                egui::Align2::LEFT_CENTER,
+                format!("[{}]", fragment.label),
+                font.clone(),
+                label_color,
+            );
+        }
+    }
+
+    // Lifelines
+    let lifeline_top = origin_y + m.head_h;
+    let lifeline_bottom = origin_y + m.height;
+    for (i, participant) in seq.participants.iter().enumerate() {
+        if !visible(participant.step) {
+            continue;
+        }
+        let x = center_x(i);
+        draw_dashed_polyline(
+            painter,
+            &[egui::pos2(x, lifeline_top), egui::pos2(x, lifeline_bottom)],
+            1.5 * s,
+            muted,
+            s,
+        );
+    }
+
+    // Activation bars grow with the rows revealed so far
+    for activation in &seq.activations {
+        let start_visible = activation.start.is_none_or(|r| visible(seq.rows[r].step));
+        if !visible(activation.step) || !start_visible {
+            continue;
+        }
+        let top = activation.start.map_or(lifeline_top + m.font * 0.5, |r| {
+            origin_y + m.anchor(&seq, r)
+        });
+        let first = activation.start.map_or(0, |r| r + 1);
+        let end = activation.end.unwrap_or(seq.rows.len().saturating_sub(1));
+        let last = (first..=end).rev().find(|&r| {
+            visible(seq.rows[r].step) && matches!(seq.rows[r].kind, RowKind::Message { .. })
+        });
+        let bottom = last.map_or(top + m.font, |r| {
+            (origin_y + m.bar_end(&seq, r)).max(top + m.font)
+        });
+        let x = center_x(activation.participant) + activation.depth as f32 * bar_w / 2.0;
+        let rect = egui::Rect::from_min_max(
+            egui::pos2(x - bar_w / 2.0, top),
+            egui::pos2(x + bar_w / 2.0, bottom),
+        );
+        painter.rect_filled(rect, 2.0 * s, box_fill);
+        painter.rect_stroke(
+            rect,
+            2.0 * s,
+            Stroke::new(1.5 * s, border),
+            egui::StrokeKind::Middle,
+        );
+    }
+
+    // Participant heads
+    for (i, participant) in seq.participants.iter().enumerate() {
+        if !visible(participant.step) {
+            continue;
+        }
+        let rect = egui::Rect::from_center_size(
+            egui::pos2(center_x(i), origin_y + m.head_h / 2.0),
+            egui::vec2(col_w * 0.85, m.head_h),
+        );
+        let corner_radius = 8.0 * s;
+        painter.rect_filled(
+            rect.translate(egui::vec2(3.0 * s, 3.0 * s)),
+            corner_radius,
+            Theme::with_opacity(Color32::BLACK, opacity * 0.1),
+        );
+        painter.rect_filled(rect, corner_radius, box_fill);
+        painter.rect_stroke(
+            rect,
+            corner_radius,
+            Stroke::new(2.5 * s, border),
+            egui::StrokeKind::Outside,
+        );
+        if !participant.icon.is_empty() {
+            draw_node_icon(
+                ui,
+                theme,
+                image_cache,
+                &participant.icon,
+                egui::pos2(
+                    rect.center().x,
+                    rect.top() + m.font * 0.6 + m.icon_size / 2.0,
+                ),
+                m.icon_size,
+                opacity,
+                s,
+            );
+        }
+        let galley = painter.layout(
+            participant.label.clone(),
+            FontId::proportional(m.head_font),
+            label_color,
+            col_w * 0.8,
+        );
+        let text_pos = egui::pos2(
+            rect.center().x - galley.rect.width() / 2.0,
+            rect.bottom() - m.font * 0.6 - galley.rect.height(),
+        );
+        painter.galley(text_pos, galley, label_color);
+    }
+
+    // Messages, notes and else dividers
+    let edge_palette = theme.edge_palette();
+    let mut needs_repaint = false;
+    let mut message_index = 0;
+    for (i, row) in seq.rows.iter().enumerate() {
+        let is_message = matches!(row.kind, RowKind::Message { .. });
+        if is_message {
+            message_index += 1;
+        }
+        if !visible(row.step) {
+            continue;
+        }
+        let top = origin_y + m.row_tops[i];
+        let y = origin_y + m.anchor(&seq, i);
+        match &row.kind {
+            RowKind::Message {
+                from,
+                to,
+                arrow,
+                label,
+            } => {
+                let base = edge_palette[(message_index - 1) % edge_palette.len()];
+                let is_dashed = matches!(arrow, ArrowKind::DashedLine | ArrowKind::DashedArrow);
+                let color = Theme::with_opacity(base, opacity * if is_dashed { 0.6 } else { 0.9 });
+                let progress =
+                    reveal_progress(row.step, reveal_step, reveal_timestamp, &mut needs_repaint);
+                // Arrows stop at the outermost activation bar
+                let depth = |p: usize| seq.active_depth(p, i) as f32;
+                let right_edge = |p: usize| center_x(p) + depth(p) * bar_w / 2.0;
+                let left_edge =
+                    |p: usize| center_x(p) - if depth(p) > 0.0 { bar_w / 2.0 } else { 0.0 };
+                let (waypoints, label_rect_x) = if from == to {
+                    let x = right_edge(*from);
+                    let w = col_w * 0.3;
+                    let h = m.font * 1.2;
+                    (
+                        vec![
+                            egui::pos2(x, y),
+                            egui::pos2(x + w, y),
+                            egui::pos2(x + w, y + h),
+                            egui::pos2(x, y + h),
+                        ],
+                        (x + w + m.font * 0.4, None),
+                    )
+                } else if from < to {
+                    let (a, b) = (right_edge(*from), left_edge(*to));
+                    (
+                        vec![egui::pos2(a, y), egui::pos2(b, y)],
+                        ((a + b) / 2.0, Some(b - a)),
+                    )
+                } else {
+                    let (a, b) = (left_edge(*from), right_edge(*to));
+                    (
+                        vec![egui::pos2(a, y), egui::pos2(b, y)],
+                        ((a + b) / 2.0, Some(a - b)),
+                    )
+                };
+                draw_routed_edge(
+                    painter,
+                    &waypoints,
+                    *arrow,
+                    "",
+                    color,
+                    Color32::TRANSPARENT,
+                    label_color,
+                    3.0 * s,
+                    16.0 * s,
+                    6.0 * s,
+                    theme,
+                    s,
+                    opacity,
+                    progress,
+                );
+                if !label.is_empty() {
+                    let text_color = Theme::with_opacity(fg, opacity * progress);
+                    let (x, span) = label_rect_x;
+                    let wrap = span.map_or(col_w * 0.9, |w| w * 0.9);
+                    let galley =
+                        painter.layout(label.clone(), font.clone(), text_color, wrap.max(20.0));
+                    let text_pos = match span {
+                        Some(_) => egui::pos2(
+                            x - galley.rect.width() / 2.0,
+                            y - m.font * 0.3 - galley.rect.height(),
+                        ),
+                        None => egui::pos2(x, y - m.font * 0.2),
+                    };
+                    painter.galley(text_pos, galley, text_color);
+                }
+            }
+            RowKind::Note { placement, text } => {
+                let width = note_width(*placement, col_w);
+                let left = match *placement {
+                    NotePlacement::Over(first, last) => {
+                        (center_x(first) + center_x(last)) / 2.0 - width / 2.0
+                    }
+                    NotePlacement::LeftOf(p) => center_x(p) - bar_w - width,
+                    NotePlacement::RightOf(p) => center_x(p) + bar_w,
+                }
+                .min(origin_x + area_width - width)
+                .max(origin_x);
+                let galley = painter.layout(
+                    text.clone(),
+                    font.clone(),
+                    label_color,
+                    (width - m.font).max(20.0),
+                );
+                let rect = egui::Rect::from_min_size(
+                    egui::pos2(left, top + m.font * 0.3),
+                    egui::vec2(width, galley.rect.height() + m.font),
+                );
+                painter.rect_filled(
+                    rect,
+                    4.0 * s,
+                    Theme::with_opacity(theme.accent, opacity * 0.15),
+                );
+                painter.rect_stroke(
+                    rect,
+                    4.0 * s,
+                    Stroke::new(1.0 * s, Theme::with_opacity(theme.accent, opacity * 0.6)),
+                    egui::StrokeKind::Inside,
+                );
+                painter.galley(
+                    egui::pos2(
+                        rect.center().x - galley.rect.width() / 2.0,
+                        rect.top() + m.font * 0.5,
+                    ),
+                    galley,
+                    label_color,
+                );
+            }
+            RowKind::Else { fragment, label } => {
+                let fragment = &seq.fragments[*fragment];
+                if !visible(fragment.step) {
+                    continue;
+                }
+                let (first, last) = seq.fragment_columns(fragment);
+                let inset = fragment.depth as f32 * m.font * 0.4;
+                let x0 = center_x(first) - col_w * 0.45 + inset;
+                let x1 = center_x(last) + col_w * 0.45 - inset;
+                draw_dashed_polyline(
+                    painter,
+                    &[
+                        egui::pos2(x0, top + m.font * 0.3),
+                        egui::pos2(x1, top + m.font * 0.3),
+                    ],
+                    1.5 * s,
+                    muted,
+                    s,
+                );
+                if !label.is_empty() {
+                    painter.text(
+                        egui::pos2(x0 + m.font * 0.5, top + m.font * 0.5),
+                        egui::Align2::LEFT_TOP,
+                        format!("[{label}]"),
+                        font.clone(),
+                        label_color,
+                    );
+                }
+            }
+            RowKind::FragmentStart(_) | RowKind::FragmentEnd(_) => {}
+        }
+    }
+
+    if needs_repaint {
+        ui.ctx().request_repaint();
+    }
+    height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::headless::Renderer;
+
+    const CHECKOUT: &str = "\
+- Client (icon: user)
+- API: API Gateway
++ Client -> API: POST /orders
+activate API
+alt in stock
++ API -> DB: reserve
+* DB --> API: ok
+else sold out
++ API --> Client: 409
+end
+note over Client, API: JSON over HTTPS
++ API -> API: log
+deactivate API
+loop every 5s
++ Client -> Queue: poll
+";
+
+    #[test]
+    fn parses_participants_messages_and_fragments() {
+        let seq = parse(CHECKOUT);
+        let names: Vec<&str> = seq.participants.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Client", "API", "DB", "Queue"]);
+        assert_eq!(seq.participants[0].icon, "user");
+        assert_eq!(seq.participants[1].label, "API Gateway");
+
+        let steps: Vec<usize> = seq.rows.iter().map(|r| r.step).collect();
+        // msg, alt, msg, msg, else, msg, end, note, self, loop, msg
+        assert_eq!(steps, [1, 0, 2, 2, 0, 3, 0, 0, 4, 0, 5]);
+        assert_eq!(
+            seq.rows[3].kind,
+            RowKind::Message {
+                from: 2,
+                to: 1,
+                arrow: ArrowKind::DashedArrow,
+                label: "ok".into(),
+            }
+        );
+        assert_eq!(
+            seq.rows[7].kind,
+            RowKind::Note {
+                placement: NotePlacement::Over(0, 1),
+                text: "JSON over HTTPS".into(),
+            }
+        );
+
+        assert_eq!(seq.fragments.len(), 2);
+        assert_eq!(seq.fragments[0].kind, FragmentKind::Alt);
+        assert_eq!(seq.fragments[0].label, "in stock");
+        assert_eq!(
+            (seq.fragments[0].start_row, seq.fragments[0].end_row),
+            (1, Some(6))
+        );
+        assert_eq!(seq.fragments[1].kind, FragmentKind::Loop);
+        assert_eq!(seq.fragments[1].end_row, None);
+
+        // The bar runs from the request to the self-call before `deactivate`
+        assert_eq!(seq.activations.len(), 1);
+        assert_eq!(seq.activations[0].start, Some(0));
+        assert_eq!(seq.activations[0].end, Some(8));
+        assert_eq!(seq.active_depth(1, 5), 1);
+        assert_eq!(seq.active_depth(1, 9), 0);
+        assert_eq!(super::super::count_diagram_steps(CHECKOUT), 5);
+    }
+
+    #[test]
+    fn nests_activations_and_fragments() {
+        let seq = parse(
+            "A -> B: one\nactivate B\nB -> B: two\nactivate B\nopt x\nloop y\nB -> A: three\nend\nend\n",
+        );
+        assert_eq!(seq.activations[0].depth, 0);
+        assert_eq!(seq.activations[1].depth, 1);
+        assert_eq!(seq.active_depth(1, 2), 2);
+        assert_eq!(seq.fragments[1].depth, 1);
+        assert!(seq.fragments.iter().all(|f| f.end_row.is_some()));
+    }
+
+    #[test]
+    fn reveals_messages_step_by_step() {
+        let theme = Theme::light();
+        let cache = ImageCache::new(std::path::PathBuf::from("."));
+        let mut renderer = Renderer::new(egui::Context::default());
+        let mut text_at = |step: usize| {
+            let frame = renderer.render([800, 600], |ui| {
+                draw(
+                    ui,
+                    CHECKOUT,
+                    &theme,
+                    Pos2::ZERO,
+                    800.0,
+                    600.0,
+                    1.0,
+                    &cache,
+                    step,
+                    None,
+                    1.0,
+                );
+            });
+            frame.glyphs.iter().map(|g| g.chr).collect::<String>()
+        };
+        let first = text_at(0);
+        assert!(first.contains("API Gateway") && first.contains("Queue"));
+        assert!(first.contains("[in stock]") && first.contains("JSON over HTTPS"));
+        assert!(!first.contains("POST"));
+        let last = text_at(5);
+        assert!(last.contains("POST /orders") && last.contains("reserve"));
+        assert!(last.contains("poll"));
+    }
+
+    #[test]
+    fn checks_keys_participants_and_fragments() {
+        // DB and Queue are inferred from the messages
+        assert_eq!(check(CHECKOUT), ["'loop every 5s' has no matching 'end'"]);
+        assert_eq!(
+            check("- A (icon: user, label: Alice)\n- B\nA -> B: hi\nnote over A, B: ok\n"),
+            Vec::<String>::new()
+        );
+        assert_eq!(parse("- A (label: Alice)\n").participants[0].label, "Alice");
+
+        let warnings = check(
+            "- Client (icon: user, colour: red, shape: box)\n- Server\n+ Clinet -> DB: hi\nnote over server, Servers: x\nnote near DB: y\nopt x\nend\n+ end\nelse z\ndeactivate DB\n",
+        );
+        assert_eq!(
+            warnings,
+            [
+                "Unknown key 'colour' on participant 'Client' (known: icon, label)",
+                "Key 'shape' on participant 'Client' is ignored in sequence diagrams (known: icon, label)",
+                "Note 'note near DB: y' is not drawn (expected note over, left of or right of)",
+                "Reveal marker on 'end' adds a step that shows nothing",
+                "'end' without an open alt, opt or loop fragment",
+                "'else' outside an alt, opt or loop fragment",
+                "'deactivate DB' without a matching activate",
+                "Participant 'Clinet' is not declared, did you mean 'Client'?",
+                "Participant 'server' is not declared, did you mean 'Server'?",
+                "Participant 'Servers' is not declared, did you mean 'Server'?",
+            ]
+        );
+        assert!(nearly_equal("API", "APi") && nearly_equal("Cache", "Cahce"));
+        assert!(!nearly_equal("DB", "API") && !nearly_equal("Client", "Clients2"));
+        assert!(nearly_equal("db", "DB") && !nearly_equal("A", "B"));
+    }
+
+    /// The last step anything in `content` is revealed at.
+    fn last_step(content: &str) -> usize {
+        let seq = parse(content);
+        let rows = seq.rows.iter().map(|r| r.step);
+        let participants = seq.participants.iter().map(|p| p.step);
+        let fragments = seq.fragments.iter().map(|f| f.step);
+        let activations = seq.activations.iter().map(|a| a.step);
+        rows.chain(participants)
+            .chain(fragments)
+            .chain(activations)
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn step_count_matches_the_steps_revealed() {
+        const NOTES_AND_FRAGMENTS: &str = "\
+- A
++ - B
+A -> B: hello
++ note over A, B: first
++ opt retry
+* A -> B: again
++ else give up
+end
++ note right of B: done
+";
+        for content in [
+            CHECKOUT,
+            NOTES_AND_FRAGMENTS,
+            "A -> B: static\nnote left of A: x\n",
+            "+ A -> B: one\n+ activate B\n* B -> A: two\ndeactivate B\n",
+        ] {
+            let steps = super::super::count_diagram_steps(content);
+            assert_eq!(last_step(content), steps, "{content}");
+            // Every counted step reveals something new
+            let seq = parse(content);
+            for step in 1..=steps {
+                let revealed = seq.rows.iter().any(|r| r.step == step)
+                    || seq.participants.iter().any(|p| p.step == step)
+                    || seq.activations.iter().any(|a| a.step == step);
+                assert!(revealed, "step {step} of {content}");
+            }
+        }
+    }
+
+    #[test]
+    fn reveals_notes_and_fragments_step_by_step() {
+        let theme = Theme::light();
+        let cache = ImageCache::new(std::path::PathBuf::from("."));
+        let mut renderer = Renderer::new(egui::Context::default());
+        let content = "A -> B: hello\n+ note over A, B: first note\n+ opt retry\n* A -> B: again\nend\n+ note left of A: last note\n";
+        let mut text_at = |step: usize| {
+            let frame = renderer.render([800, 600], |ui| {
+                draw(
+                    ui,
+                    content,
+                    &theme,
+                    Pos2::ZERO,
+                    800.0,
+                    600.0,
+                    1.0,
+                    &cache,
+                    step,
+                    None,
+                    1.0,
+                );
+            });
+            frame.glyphs.iter().map(|g| g.chr).collect::<String>()
+        };
+        let start = text_at(0);
+        assert!(start.contains("hello"));
+        assert!(!start.contains("first note") && !start.contains("[retry]"));
+        let first = text_at(1);
+        assert!(first.contains("first note") && !first.contains("[retry]"));
+        let second = text_at(2);
+        assert!(second.contains("[retry]") && second.contains("again"));
+        assert!(!second.contains("last note"));
+        assert!(text_at(3).contains("last note"));
+        assert_eq!(super::super::count_diagram_steps(content), 3);
+    }
+}
@@ -0,0 +1,196 @@
+//! Footer and header bands (`@footer`, `@header`).
+//!
+//! The text comes from the slide directive, else the frontmatter; `none` hides
+//! it. Title and section slides only show a band their own directive sets.
+//! Tokens `{slide}`, `{total}`, `{title}`, `{author}` and `{date}` are expanded;
+//! `{date}` is today's date when the frontmatter has none.
+
+use eframe::egui;
+
+use crate::parser::{Layout, PresentationMeta, Slide};
+use crate::theme::{CustomThemes, Theme};
+
+/// Tokens understood in footer and header text.
+pub const TOKENS: &[&str] = &["slide", "total", "title", "author", "date"];
+
+/// Where a slide sits in its deck, for expanding footer and header tokens.
+#[derive(Debug, Clone, Copy)]
+pub struct FooterContext<'a> {
+    pub meta: &'a PresentationMeta,
+    /// 1-based slide number.
+    pub number: usize,
+    pub total: usize,
+}
+
+/// Which band: `@footer` along the bottom edge or `@header` along the top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Band {
+    Footer,
+    Header,
+}
+
+impl Band {
+    fn directive(self) -> &'static str {
+        match self {
+            Band::Footer => "footer",
+            Band::Header => "header",
+        }
+    }
+
+    fn deck_text(self, meta: &PresentationMeta) -> Option<&str> {
+        match self {
+            Band::Footer => meta.footer.as_deref(),
+            Band::Header => meta.header.as_deref(),
+        }
+    }
+}
+
+/// The expanded band text for a slide, or `None` when the slide has no such band.
+pub fn band_text(slide: &Slide, band: Band, ctx: &FooterContext) -> Option<String> {
+    let own = slide.directive(band.directive());
+    let text = match slide.layout {
+        Layout::Title | Layout::Section => own,
+        _ => own.or(band.deck_text(ctx.meta)),
+    }?;
+    if text.is_empty() || text.eq_ignore_ascii_case("none") {
+        return None;
+    }
+    Some(expand_tokens(text, ctx))
+}
+
+/// Replace `{token}`s in `text`; unknown tokens are kept as written.
+pub fn expand_tokens(text: &str, ctx: &FooterContext) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            rest = &rest[open..];
+            break;
+        };
+        match token_value(&after[..close], ctx) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[open..open + close + 2]),
+        }
+        rest = &after[close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `{token}` names in `text` that [`expand_tokens`] does not know.
+pub fn unknown_tokens(text: &str) -> Vec<&str> {
+    let mut unknown = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else { break };
+        let name = &after[..close];
+        if !TOKENS.contains(&name) {
+            unknown.push(name);
+        }
+        rest = &after[close + 1..];
+    }
+    unknown
+}
+
+fn token_value(name: &str, ctx: &FooterContext) -> Option<String> {
+    let meta = ctx.meta;
+    Some(match name {
+        "slide" => ctx.number.to_string(),
+        "total" => ctx.total.to_string(),
+        "title" => meta.title.clone().unwrap_or_default(),
+        "author" => meta.author.clone().unwrap_or_default(),
+        "date" => meta
+            .date
+            .clone()
+            .unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string()),
+        _ => return None,
+    })
+}
+
+/// Draw the slide's header and footer bands, centered on the top and bottom edges.
+#[allow(clippy::too_many_arguments)]
+pub fn draw(
+    ui: &egui::Ui,
+    slide: &Slide,
+    theme: &Theme,
+    custom_themes: &CustomThemes,
+    rect: egui::Rect,
+    opacity: f32,
+    scale: f32,
+    ctx: &FooterContext,
+) {
+    let color = Theme::with_opacity(
+        theme.for_slide(slide, custom_themes).foreground,
+        0.4 * opacity,
+    );
+    for band in [Band::Header, Band::Footer] {
+        let Some(text) = band_text(slide, band, ctx) else {
+            continue;
+        };
+        let galley =
+            ui.painter()
+                .layout_no_wrap(text, egui::FontId::proportional(14.0 * scale), color);
+        let y = match band {
+            Band::Header => rect.top() + 14.0 * scale,
+            Band::Footer => rect.bottom() - 30.0 * scale,
+        };
+        let pos = egui::pos2(rect.center().x - galley.rect.width() / 2.0, y);
+        ui.painter().galley(pos, galley, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn meta() -> PresentationMeta {
+        PresentationMeta {
+            title: Some("Quarterly".to_string()),
+            author: Some("Ada".to_string()),
+            date: Some("2026-03-01".to_string()),
+            footer: Some("{title} · {slide}/{total}".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tokens_expand_and_unknown_ones_stay() {
+        let meta = meta();
+        let ctx = FooterContext {
+            meta: &meta,
+            number: 3,
+            total: 12,
+        };
+        assert_eq!(
+            expand_tokens("{author}, {date} — {slide} of {total} {nope} {open", &ctx),
+            "Ada, 2026-03-01 — 3 of 12 {nope} {open"
+        );
+        assert_eq!(unknown_tokens("{slide} {nope} {}"), vec!["nope", ""]);
+    }
+
+    #[test]
+    fn slide_directive_overrides_and_none_hides() {
+        let meta = meta();
+        let ctx = FooterContext {
+            meta: &meta,
+            number: 2,
+            total: 4,
+        };
+        let deck = crate::parser::parse(
+            "# Title\n\n---\n\n## A\n\n- x\n\n---\n\n@footer: none\n## B\n\n- y\n\n---\n\n@footer: Page {slide}\n@header: {author}\n## C\n\n- z",
+            Path::new("."),
+        );
+        let text = |i: usize, band| band_text(&deck.slides[i], band, &ctx);
+        // Title slides skip the deck footer
+        assert_eq!(text(0, Band::Footer), None);
+        assert_eq!(text(1, Band::Footer).as_deref(), Some("Quarterly · 2/4"));
+        assert_eq!(text(1, Band::Header), None);
+        assert_eq!(text(2, Band::Footer), None);
+        assert_eq!(text(3, Band::Footer).as_deref(), Some("Page 2"));
+        assert_eq!(text(3, Band::Header).as_deref(), Some("Ada"));
+    }
+}
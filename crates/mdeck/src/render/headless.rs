@@ -0,0 +1,518 @@
+//! Window-less rendering: runs egui frames without a window and rasterizes
+//! them on the CPU, so export works on machines without a display or GPU.
+//! The painted text and links are also available, e.g. for the PDF text layer
+//! and link annotations.
+
+use std::collections::HashMap;
+
+use eframe::egui;
+use eframe::epaint::{ClippedPrimitive, ClippedShape, Primitive, Shape, Vertex};
+use egui::{Color32, ColorImage, Pos2, TextureFilter, TextureOptions, TextureWrapMode};
+
+use super::links::{self, LinkRegion};
+
+/// Run a single egui frame of `size` points in `ctx` without a window,
+/// painting with `paint` into a frameless central panel.
+fn run_frame(
+    ctx: &egui::Context,
+    size: egui::Vec2,
+    mut paint: impl FnMut(&mut egui::Ui),
+) -> egui::FullOutput {
+    let input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, size)),
+        ..Default::default()
+    };
+    ctx.run(input, |ctx| {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::NONE)
+            .show(ctx, |ui| paint(ui));
+    })
+}
+
+/// Renders egui frames to images without a window, keeping egui's textures
+/// (the font atlas and loaded images) on the CPU between frames.
+pub struct Renderer {
+    ctx: egui::Context,
+    textures: HashMap<egui::TextureId, Texture>,
+}
+
+/// A rasterized frame.
+pub struct RenderedFrame {
+    /// Opaque pixels, black wherever nothing was painted.
+    pub image: ColorImage,
+    /// The frame's text, in pixels.
+    pub glyphs: Vec<PlacedGlyph>,
+    /// The frame's links, in pixels.
+    pub links: Vec<LinkRegion>,
+}
+
+impl Renderer {
+    pub fn new(ctx: egui::Context) -> Self {
+        Self {
+            ctx,
+            textures: HashMap::new(),
+        }
+    }
+
+    /// Run a frame of `size` pixels (one point per pixel) and rasterize it.
+    pub fn render(
+        &mut self,
+        size: [usize; 2],
+        mut paint: impl FnMut(&mut egui::Ui),
+    ) -> RenderedFrame {
+        let output = run_frame(
+            &self.ctx,
+            egui::vec2(size[0] as f32, size[1] as f32),
+            |ui| {
+                links::begin(ui.ctx());
+                paint(ui);
+            },
+        );
+        let links = links::finish(&self.ctx);
+        for (id, delta) in &output.textures_delta.set {
+            self.set_texture(*id, delta);
+        }
+        let glyphs = text_glyphs(&output.shapes);
+        let pixels_per_point = output.pixels_per_point;
+        let primitives = self.ctx.tessellate(output.shapes, pixels_per_point);
+        let image = rasterize(&primitives, &self.textures, size, pixels_per_point);
+        for id in &output.textures_delta.free {
+            self.textures.remove(id);
+        }
+        RenderedFrame {
+            image,
+            glyphs,
+            links,
+        }
+    }
+
+    /// Run a frame of `size` points and convert what it paints to SVG.
+    pub fn render_svg(&mut self, size: egui::Vec2, paint: impl FnMut(&mut egui::Ui)) -> String {
+        let output = run_frame(&self.ctx, size, paint);
+        for (id, delta) in &output.textures_delta.set {
+            self.set_texture(*id, delta);
+        }
+        let viewport = egui::Rect::from_min_size(Pos2::ZERO, size);
+        let svg = super::svg::to_svg(&output.shapes, viewport, &|id| {
+            self.textures.get(&id).map(|t| &t.image)
+        });
+        for id in &output.textures_delta.free {
+            self.textures.remove(id);
+        }
+        svg
+    }
+
+    fn set_texture(&mut self, id: egui::TextureId, delta: &egui::epaint::ImageDelta) {
+        let egui::ImageData::Color(patch) = &delta.image;
+        match delta.pos {
+            None => {
+                self.textures.insert(
+                    id,
+                    Texture {
+                        image: (**patch).clone(),
+                        options: delta.options,
+                    },
+                );
+            }
+            Some([x, y]) => {
+                let Some(texture) = self.textures.get_mut(&id) else {
+                    return;
+                };
+                texture.options = delta.options;
+                let width = texture.image.width();
+                for row in 0..patch.height() {
+                    let start = (y + row) * width + x;
+                    let src = &patch.pixels[row * patch.width()..(row + 1) * patch.width()];
+                    texture.image.pixels[start..start + patch.width()].copy_from_slice(src);
+                }
+            }
+        }
+    }
+}
+
+struct Texture {
+    image: ColorImage,
+    options: TextureOptions,
+}
+
+impl Texture {
+    /// Premultiplied RGBA (0–255) at `uv`, filtered like a GPU sampler would.
+    fn sample(&self, uv: Pos2, filter: TextureFilter) -> [f32; 4] {
+        let [w, h] = self.image.size;
+        let x = uv.x * w as f32;
+        let y = uv.y * h as f32;
+        match filter {
+            TextureFilter::Nearest => self.texel(x.floor() as i64, y.floor() as i64),
+            TextureFilter::Linear => {
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (fx, fy) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+                let top = lerp(self.texel(x0, y0), self.texel(x0 + 1, y0), fx);
+                let bottom = lerp(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), fx);
+                lerp(top, bottom, fy)
+            }
+        }
+    }
+
+    fn texel(&self, x: i64, y: i64) -> [f32; 4] {
+        let [w, h] = self.image.size;
+        let x = wrap(x, w, self.options.wrap_mode);
+        let y = wrap(y, h, self.options.wrap_mode);
+        let c = self.image.pixels[y * w + x];
+        [c.r() as f32, c.g() as f32, c.b() as f32, c.a() as f32]
+    }
+}
+
+fn wrap(i: i64, len: usize, mode: TextureWrapMode) -> usize {
+    let len = len as i64;
+    let i = match mode {
+        TextureWrapMode::ClampToEdge => i.clamp(0, len - 1),
+        TextureWrapMode::Repeat => i.rem_euclid(len),
+        TextureWrapMode::MirroredRepeat => {
+            let i = i.rem_euclid(2 * len);
+            if i < len { i } else { 2 * len - 1 - i }
+        }
+    };
+    i as usize
+}
+
+fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+/// Fill tessellated meshes into an opaque black image of `size` pixels,
+/// blending premultiplied colors the way egui's GPU backends do. Paint
+/// callbacks are skipped.
+fn rasterize(
+    primitives: &[ClippedPrimitive],
+    textures: &HashMap<egui::TextureId, Texture>,
+    size: [usize; 2],
+    pixels_per_point: f32,
+) -> ColorImage {
+    let mut canvas = Canvas {
+        width: size[0],
+        pixels: vec![[0.0, 0.0, 0.0, 255.0]; size[0] * size[1]],
+    };
+    let screen = egui::Rect::from_min_size(Pos2::ZERO, egui::vec2(size[0] as f32, size[1] as f32));
+    for clipped in primitives {
+        let Primitive::Mesh(mesh) = &clipped.primitive else {
+            continue;
+        };
+        let Some(texture) = textures.get(&mesh.texture_id) else {
+            continue;
+        };
+        // Scissor rect in whole pixels, as the GPU backends round it
+        let clip = egui::Rect::from_min_max(
+            (clipped.clip_rect.min * pixels_per_point).round(),
+            (clipped.clip_rect.max * pixels_per_point).round(),
+        )
+        .intersect(screen);
+        if !clip.is_positive() {
+            continue;
+        }
+        for tri in mesh.indices.chunks_exact(3) {
+            let vertex = |i: u32| {
+                let v = mesh.vertices[i as usize];
+                Vertex {
+                    pos: (v.pos.to_vec2() * pixels_per_point).to_pos2(),
+                    ..v
+                }
+            };
+            canvas.fill_triangle(
+                [vertex(tri[0]), vertex(tri[1]), vertex(tri[2])],
+                clip,
+                texture,
+            );
+        }
+    }
+    let pixels = canvas
+        .pixels
+        .iter()
+        .map(|p| {
+            let [r, g, b, a] = p.map(|c| c.round().clamp(0.0, 255.0) as u8);
+            Color32::from_rgba_premultiplied(r, g, b, a)
+        })
+        .collect();
+    ColorImage::new(size, pixels)
+}
+
+/// Premultiplied RGBA (0–255) accumulation buffer.
+struct Canvas {
+    width: usize,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Canvas {
+    /// Fill the pixels whose centers lie inside the triangle. Pixels on an
+    /// edge go to exactly one of the triangles sharing it, so the seams of
+    /// translucent shapes are not blended twice.
+    fn fill_triangle(&mut self, [a, b, c]: [Vertex; 3], clip: egui::Rect, texture: &Texture) {
+        let (a, mut b, mut c) = (a, b, c);
+        let mut area = edge(a.pos, b.pos, c.pos);
+        if area < 0.0 {
+            std::mem::swap(&mut b, &mut c);
+            area = -area;
+        }
+        if area <= f32::EPSILON {
+            return;
+        }
+
+        let min = a.pos.min(b.pos).min(c.pos).max(clip.min);
+        let max = a.pos.max(b.pos).max(c.pos).min(clip.max);
+        if min.x >= max.x || min.y >= max.y {
+            return;
+        }
+
+        // Magnifying when a texel covers more than a pixel
+        let texels = edge(a.uv, b.uv, c.uv).abs()
+            * texture.image.size[0] as f32
+            * texture.image.size[1] as f32;
+        let filter = if texels > area {
+            texture.options.minification
+        } else {
+            texture.options.magnification
+        };
+        let solid = (a.color == b.color && b.color == c.color && a.uv == b.uv && b.uv == c.uv)
+            .then(|| {
+                modulate(
+                    texture.sample(a.uv, filter),
+                    a.color,
+                    b.color,
+                    c.color,
+                    [1.0, 0.0, 0.0],
+                )
+            });
+
+        let owns = [
+            owns_edge(b.pos, c.pos),
+            owns_edge(c.pos, a.pos),
+            owns_edge(a.pos, b.pos),
+        ];
+        let edges = [(b.pos, c.pos), (c.pos, a.pos), (a.pos, b.pos)];
+        for y in min.y.floor() as usize..max.y.ceil() as usize {
+            let Some((x0, x1)) = row_span(edges, y as f32 + 0.5, min.x, max.x) else {
+                continue;
+            };
+            for x in x0..x1 {
+                let p = egui::pos2(x as f32 + 0.5, y as f32 + 0.5);
+                let w = [
+                    edge(b.pos, c.pos, p),
+                    edge(c.pos, a.pos, p),
+                    edge(a.pos, b.pos, p),
+                ];
+                if !(0..3).all(|i| w[i] > 0.0 || (w[i] == 0.0 && owns[i])) {
+                    continue;
+                }
+                let src = solid.unwrap_or_else(|| {
+                    let l = w.map(|w| w / area);
+                    let uv =
+                        (a.uv.to_vec2() * l[0] + b.uv.to_vec2() * l[1] + c.uv.to_vec2() * l[2])
+                            .to_pos2();
+                    modulate(texture.sample(uv, filter), a.color, b.color, c.color, l)
+                });
+                let dst = &mut self.pixels[y * self.width + x];
+                let keep = 1.0 - src[3] / 255.0;
+                *dst = std::array::from_fn(|i| src[i] + dst[i] * keep);
+            }
+        }
+    }
+}
+
+/// Twice the signed area of `a`, `b`, `p`; positive when `p` is clockwise
+/// of `a`→`b` on screen (y down).
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Pixel columns of row `y` (pixel center) that may lie inside the triangle
+/// with counter-clockwise `edges`, within `min_x..max_x`. Padded by a pixel;
+/// the caller still tests each pixel exactly.
+fn row_span(edges: [(Pos2, Pos2); 3], y: f32, min_x: f32, max_x: f32) -> Option<(usize, usize)> {
+    let (mut lo, mut hi) = (min_x, max_x);
+    for (a, b) in edges {
+        // edge(a, b, (x, y)) = slope * x + offset must not be negative
+        let slope = a.y - b.y;
+        let offset = (b.x - a.x) * (y - a.y) + (b.y - a.y) * a.x;
+        if slope > 0.0 {
+            lo = lo.max(-offset / slope);
+        } else if slope < 0.0 {
+            hi = hi.min(-offset / slope);
+        } else if offset < 0.0 {
+            return None;
+        }
+    }
+    let x0 = (lo - 1.0).floor().max(min_x.floor());
+    let x1 = (hi + 1.0).ceil().min(max_x.ceil());
+    (x0 < x1).then_some((x0 as usize, x1 as usize))
+}
+
+/// Whether pixel centers exactly on the edge `a`→`b` belong to this triangle.
+/// The neighbour walks the edge the other way, so exactly one of them does.
+fn owns_edge(a: Pos2, b: Pos2) -> bool {
+    let d = b - a;
+    d.y > 0.0 || (d.y == 0.0 && d.x < 0.0)
+}
+
+/// Texel times the vertex color interpolated with barycentric weights `l`.
+fn modulate(texel: [f32; 4], a: Color32, b: Color32, c: Color32, l: [f32; 3]) -> [f32; 4] {
+    let (a, b, c) = (a.to_array(), b.to_array(), c.to_array());
+    std::array::from_fn(|i| {
+        let color = a[i] as f32 * l[0] + b[i] as f32 * l[1] + c[i] as f32 * l[2];
+        texel[i] * color / 255.0
+    })
+}
+
+/// One painted character, positioned on its baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedGlyph {
+    pub chr: char,
+    /// Start of the glyph on the baseline, in points.
+    pub baseline: egui::Pos2,
+    /// Approximate font size in points.
+    pub size: f32,
+    /// Clockwise rotation in radians (rotated word cloud words).
+    pub angle: f32,
+}
+
+/// Every visible glyph in `shapes`, in paint order. Glyphs outside their
+/// clip rect (scrolled or clipped away) are left out.
+fn text_glyphs(shapes: &[ClippedShape]) -> Vec<PlacedGlyph> {
+    let mut glyphs = Vec::new();
+    for clipped in shapes {
+        collect_glyphs(&clipped.shape, clipped.clip_rect, &mut glyphs);
+    }
+    glyphs
+}
+
+fn collect_glyphs(shape: &Shape, clip: egui::Rect, out: &mut Vec<PlacedGlyph>) {
+    match shape {
+        Shape::Vec(shapes) => {
+            for shape in shapes {
+                collect_glyphs(shape, clip, out);
+            }
+        }
+        Shape::Text(text) => {
+            if text.opacity_factor <= 0.0 {
+                return;
+            }
+            let rot = egui::emath::Rot2::from_angle(text.angle);
+            for row in &text.galley.rows {
+                for glyph in &row.glyphs {
+                    if glyph.chr.is_control() {
+                        continue;
+                    }
+                    let baseline = text.pos + rot * (row.pos + glyph.pos.to_vec2()).to_vec2();
+                    if !clip.contains(baseline) {
+                        continue;
+                    }
+                    out.push(PlacedGlyph {
+                        chr: glyph.chr,
+                        baseline,
+                        // Row height includes line gap; the em size is a bit smaller
+                        size: glyph.font_height / 1.2,
+                        angle: text.angle,
+                    });
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_painted_text_with_positions() {
+        let ctx = egui::Context::default();
+        let output = run_frame(&ctx, egui::vec2(400.0, 200.0), |ui| {
+            ui.painter().text(
+                egui::pos2(10.0, 20.0),
+                egui::Align2::LEFT_TOP,
+                "Hi there",
+                egui::FontId::proportional(20.0),
+                egui::Color32::WHITE,
+            );
+            // Outside the clip rect: dropped
+            ui.painter().text(
+                egui::pos2(10.0, 500.0),
+                egui::Align2::LEFT_TOP,
+                "Hidden",
+                egui::FontId::proportional(20.0),
+                egui::Color32::WHITE,
+            );
+        });
+        let glyphs = text_glyphs(&output.shapes);
+        let text: String = glyphs.iter().map(|g| g.chr).collect();
+        assert_eq!(text, "Hi there");
+        assert!(glyphs[0].baseline.x >= 10.0 && glyphs[0].baseline.y > 20.0);
+        assert!(glyphs[1].baseline.x > glyphs[0].baseline.x);
+        assert!((glyphs[0].size - 20.0).abs() < 6.0, "{}", glyphs[0].size);
+    }
+
+    #[test]
+    fn rasterizes_shapes_and_text() {
+        let mut renderer = Renderer::new(egui::Context::default());
+        let frame = renderer.render([64, 32], |ui| {
+            let painter = ui.painter();
+            painter.rect_filled(
+                egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(32.0, 32.0)),
+                0.0,
+                Color32::RED,
+            );
+            // Translucent: the diagonal seam between its two triangles must not blend twice
+            painter.rect_filled(
+                egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(16.0, 16.0)),
+                0.0,
+                Color32::from_black_alpha(128),
+            );
+            painter.text(
+                egui::pos2(34.0, 4.0),
+                egui::Align2::LEFT_TOP,
+                "Hi",
+                egui::FontId::proportional(20.0),
+                Color32::WHITE,
+            );
+        });
+        let px = |x: usize, y: usize| frame.image.pixels[y * 64 + x];
+        assert_eq!(frame.image.size, [64, 32]);
+        assert_eq!(px(20, 20), Color32::RED);
+        assert_eq!(px(63, 31), Color32::BLACK);
+        let shaded = px(4, 10);
+        assert_eq!(px(8, 8), shaded);
+        assert_eq!(px(10, 4), shaded);
+        assert!(shaded.r() > 100 && shaded.r() < 150, "{shaded:?}");
+        let lit = (34..64)
+            .flat_map(|x| (0..32).map(move |y| (x, y)))
+            .filter(|&(x, y)| px(x, y).g() > 128)
+            .count();
+        assert!(lit > 20, "text should paint pixels, got {lit}");
+        assert_eq!(frame.glyphs.len(), 2);
+    }
+
+    #[test]
+    fn samples_image_textures() {
+        let ctx = egui::Context::default();
+        let mut renderer = Renderer::new(ctx.clone());
+        let texture = ctx.load_texture(
+            "checker",
+            ColorImage::new(
+                [2, 1],
+                vec![Color32::from_rgb(0, 0, 255), Color32::from_rgb(0, 255, 0)],
+            ),
+            TextureOptions::NEAREST,
+        );
+        let frame = renderer.render([8, 4], |ui| {
+            ui.painter().image(
+                texture.id(),
+                egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(8.0, 4.0)),
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                Color32::WHITE,
+            );
+        });
+        assert_eq!(frame.image.pixels[0], Color32::from_rgb(0, 0, 255));
+        assert_eq!(frame.image.pixels[7], Color32::from_rgb(0, 255, 0));
+    }
+}
@@ -273,7 +273,7 @@ fn measure_block_height(
     match block {
         Block::Heading { level, inlines } => {
             let size = theme.heading_size(*level) * scale;
-            let job = text::inlines_to_job(inlines, size, theme.heading_color, max_width);
+            let job = text::heading_to_job(inlines, size, theme.heading_color, max_width);
             ui.painter().layout_job(job).rect.height()
         }
         Block::Paragraph { inlines } | Block::BlockQuote { inlines } => {
@@ -2,7 +2,7 @@ use std::time::Instant;
 
 use eframe::egui::{self, Pos2};
 
-use crate::parser::{Block, Slide};
+use crate::parser::{Block, DiagramKind, Slide};
 use crate::render::diagram;
 use crate::render::image_cache::ImageCache;
 use crate::render::text;
@@ -28,15 +28,15 @@ pub fn render(
 
     // Find heading and diagram blocks
     let mut heading: Option<&Block> = None;
-    let mut diagram_content: Option<&str> = None;
+    let mut diagram: Option<(DiagramKind, &str)> = None;
 
     for block in &slide.blocks {
         match block {
             Block::Heading { .. } if heading.is_none() => {
                 heading = Some(block);
             }
-            Block::Diagram { content } if diagram_content.is_none() => {
-                diagram_content = Some(content);
+            Block::Diagram { kind, content } if diagram.is_none() => {
+                diagram = Some((*kind, content));
             }
             _ => {}
         }
@@ -58,11 +58,12 @@ pub fn render(
     }
 
     // Draw diagram filling the remaining vertical space
-    if let Some(content) = diagram_content {
+    if let Some((kind, content)) = diagram {
         let remaining_height = rect.bottom() - y - padding;
         if remaining_height > 50.0 * scale {
             diagram::draw_diagram_sized(
                 ui,
+                kind,
                 content,
                 theme,
                 Pos2::new(content_left, y),
@@ -99,14 +99,17 @@ pub fn render(
     }
 }
 
-struct Cell {
-    x: f32,
-    y: f32,
-    w: f32,
-    h: f32,
+/// A grid cell, relative to the top-left of the gallery area.
+pub struct Cell {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
 }
 
-fn compute_grid(count: usize, width: f32, height: f32, gap: f32) -> Vec<Cell> {
+/// Cells for `count` images in a `width`×`height` area, as described on
+/// [`render`].
+pub fn compute_grid(count: usize, width: f32, height: f32, gap: f32) -> Vec<Cell> {
     match count {
         0 => Vec::new(),
         1 => {
@@ -2,7 +2,7 @@ use eframe::egui::{self, Pos2};
 
 use crate::parser::{Block, Slide};
 use crate::render::image_cache::ImageCache;
-use crate::render::text;
+use crate::render::{links, text};
 use crate::theme::Theme;
 
 /// Image slide layout: prominent image with optional heading and caption.
@@ -143,7 +143,8 @@ pub fn render(
         let galley = ui.painter().layout_job(job);
         let caption_x =
             image_drawn_rect.left() + (image_drawn_rect.width() - galley.rect.width()) / 2.0;
-        ui.painter()
-            .galley(Pos2::new(caption_x, caption_y), galley, caption_color);
+        let pos = Pos2::new(caption_x, caption_y);
+        links::record(ui, &galley, pos, inlines, caption_color);
+        ui.painter().galley(pos, galley, caption_color);
     }
 }
@@ -3,7 +3,7 @@ use eframe::egui::{self, Pos2};
 use crate::parser::{Block, Inline, Slide};
 use crate::render::image_cache::ImageCache;
 use crate::render::layouts::image_split;
-use crate::render::text;
+use crate::render::{links, text};
 use crate::theme::Theme;
 
 #[allow(clippy::too_many_arguments)]
@@ -95,10 +95,8 @@ fn render_quote_content(
         match block {
             Block::Heading { level, inlines } => heading = Some((*level, inlines)),
             Block::BlockQuote { inlines } => quote_inlines = Some(inlines),
-            Block::Paragraph { inlines } => {
-                if quote_inlines.is_some() {
-                    attribution = Some(inlines);
-                }
+            Block::Paragraph { inlines } if quote_inlines.is_some() => {
+                attribution = Some(inlines);
             }
             _ => {}
         }
@@ -157,7 +155,9 @@ fn render_quote_content(
         let text_x = quote_x + (quote_width - text_width) / 2.0;
 
         // Draw the quote text (marks are part of the text flow)
-        ui.painter().galley(Pos2::new(text_x, y), galley, color);
+        let pos = Pos2::new(text_x, y);
+        links::record(ui, &galley, pos, &quoted_inlines, color);
+        ui.painter().galley(pos, galley, color);
 
         // Draw left accent bar spanning the quote text
         let bar_rect =
@@ -178,7 +178,9 @@ fn render_quote_content(
 
         let galley = ui.painter().layout_job(job);
         let x = content_rect.right() - galley.rect.width() - 40.0 * scale;
-        ui.painter().galley(Pos2::new(x, y), galley, color);
+        let pos = Pos2::new(x, y);
+        links::record(ui, &galley, pos, &cleaned, color);
+        ui.painter().galley(pos, galley, color);
     }
 }
 
@@ -1,7 +1,7 @@
 use eframe::egui::{self, Pos2};
 
 use crate::parser::{Block, Slide};
-use crate::render::text;
+use crate::render::{links, text};
 use crate::theme::Theme;
 
 pub fn render(
@@ -24,13 +24,14 @@ pub fn render(
                 theme.h2_size * 1.1 * scale
             };
             let color = Theme::with_opacity(theme.heading_color, opacity);
-            let job = text::inlines_to_job(inlines, size, color, content_rect.width());
+            let job = text::heading_to_job(inlines, size, color, content_rect.width());
             let galley = ui.painter().layout_job(job);
 
             // Center both horizontally and vertically
             let x = content_rect.left() + (content_rect.width() - galley.rect.width()) / 2.0;
             let y = content_rect.center().y - galley.rect.height() / 2.0;
             let pos = Pos2::new(x, y);
+            links::record(ui, &galley, pos, inlines, color);
             ui.painter().galley(pos, galley, color);
             return;
         }
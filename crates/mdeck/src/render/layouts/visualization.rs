@@ -12,7 +12,8 @@ use crate::render::visualizations::{
 };
 use crate::theme::Theme;
 
-fn is_viz_block(block: &Block) -> bool {
+/// Whether `block` is one of the `@`-chart visualizations (not a diagram).
+pub fn is_viz_block(block: &Block) -> bool {
     matches!(
         block,
         Block::WordCloud { .. }
@@ -109,232 +110,228 @@ pub fn render(
     if let Some(block) = viz_block {
         let remaining_height = rect.bottom() - y - padding;
         if remaining_height > 50.0 * scale {
-            let viz_pos = Pos2::new(content_left, y);
-            let ts = reveal_timestamp;
-            match block {
-                Block::WordCloud { content } => {
-                    word_cloud::draw_word_cloud(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        scale,
-                    );
-                }
-                Block::Timeline { content } => {
-                    timeline::draw_timeline(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        scale,
-                    );
-                }
-                Block::PieChart { content } => {
-                    pie_chart::draw_pie_chart(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::BarChart { content } => {
-                    bar_chart::draw_bar_chart(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::LineChart { content } => {
-                    line_chart::draw_line_chart(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::DonutChart { content } => {
-                    donut_chart::draw_donut_chart(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::KpiCards { content } => {
-                    kpi_cards::draw_kpi_cards(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::FunnelChart { content } => {
-                    funnel_chart::draw_funnel_chart(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::RadarChart { content } => {
-                    radar_chart::draw_radar_chart(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::StackedBar { content } => {
-                    stacked_bar::draw_stacked_bar(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::VennDiagram { content } => {
-                    venn_diagram::draw_venn_diagram(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::ProgressBars { content } => {
-                    progress_bars::draw_progress_bars(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::ScatterPlot { content } => {
-                    scatter_plot::draw_scatter_plot(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::OrgChart { content } => {
-                    org_chart::draw_org_chart(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::GanttChart { content } => {
-                    gantt_chart::draw_gantt_chart(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        ts,
-                        scale,
-                    );
-                }
-                Block::GitGraph { content } => {
-                    git_graph::draw_gitgraph(
-                        ui,
-                        content,
-                        theme,
-                        viz_pos,
-                        content_width,
-                        remaining_height,
-                        opacity,
-                        reveal_step,
-                        scale,
-                    );
-                }
-                _ => {}
-            }
+            draw_viz_block(
+                ui,
+                block,
+                theme,
+                Pos2::new(content_left, y),
+                content_width,
+                remaining_height,
+                opacity,
+                reveal_step,
+                reveal_timestamp,
+                scale,
+            );
         }
     }
 }
+
+/// Draw a visualization block in the `width` × `height` area at `pos`; a
+/// `height` of 0 lets the chart pick its own. Returns the height used, or 0
+/// for blocks that are not visualizations.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_viz_block(
+    ui: &egui::Ui,
+    block: &Block,
+    theme: &Theme,
+    pos: Pos2,
+    width: f32,
+    height: f32,
+    opacity: f32,
+    reveal_step: usize,
+    reveal_timestamp: Option<Instant>,
+    scale: f32,
+) -> f32 {
+    match block {
+        Block::WordCloud { content } => word_cloud::draw_word_cloud(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            scale,
+        ),
+        Block::Timeline { content } => timeline::draw_timeline(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            scale,
+        ),
+        Block::PieChart { content } => pie_chart::draw_pie_chart(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::BarChart { content } => bar_chart::draw_bar_chart(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::LineChart { content } => line_chart::draw_line_chart(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::DonutChart { content } => donut_chart::draw_donut_chart(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::KpiCards { content } => kpi_cards::draw_kpi_cards(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::FunnelChart { content } => funnel_chart::draw_funnel_chart(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::RadarChart { content } => radar_chart::draw_radar_chart(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::StackedBar { content } => stacked_bar::draw_stacked_bar(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::VennDiagram { content } => venn_diagram::draw_venn_diagram(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::ProgressBars { content } => progress_bars::draw_progress_bars(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::ScatterPlot { content } => scatter_plot::draw_scatter_plot(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::OrgChart { content } => org_chart::draw_org_chart(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::GanttChart { content } => gantt_chart::draw_gantt_chart(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            reveal_timestamp,
+            scale,
+        ),
+        Block::GitGraph { content } => git_graph::draw_gitgraph(
+            ui,
+            content,
+            theme,
+            pos,
+            width,
+            height,
+            opacity,
+            reveal_step,
+            scale,
+        ),
+        _ => 0.0,
+    }
+}
@@ -0,0 +1,187 @@
+//! Where links land on screen, so the presenter can make them clickable and
+//! export can keep them as PDF link annotations.
+//!
+//! Recording is off unless a caller brackets painting with [`begin`] and
+//! [`finish`], so grid thumbnails and transition frames record nothing.
+
+use std::ops::Range;
+
+use eframe::egui::{self, Color32, Galley, Pos2, Rect};
+
+use crate::parser::Inline;
+
+/// The screen area of a link's text, one per wrapped row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkRegion {
+    pub rect: Rect,
+    pub url: String,
+}
+
+fn storage_id() -> egui::Id {
+    egui::Id::new("mdeck_link_regions")
+}
+
+/// Start recording the links painted in `ctx`.
+pub fn begin(ctx: &egui::Context) {
+    ctx.data_mut(|d| d.insert_temp(storage_id(), Some(Vec::<LinkRegion>::new())));
+}
+
+/// Stop recording and return the links painted since [`begin`].
+pub fn finish(ctx: &egui::Context) -> Vec<LinkRegion> {
+    ctx.data_mut(|d| d.remove_temp::<Option<Vec<LinkRegion>>>(storage_id()))
+        .flatten()
+        .unwrap_or_default()
+}
+
+/// The topmost link under `pos`.
+pub fn hit(regions: &[LinkRegion], pos: Pos2) -> Option<&LinkRegion> {
+    regions
+        .iter()
+        .rev()
+        .find(|region| region.rect.contains(pos))
+}
+
+/// Record the links of `inlines`, laid out as `galley` and painted at `pos`.
+/// Invisible text (fully faded out) is skipped.
+pub fn record(ui: &egui::Ui, galley: &Galley, pos: Pos2, inlines: &[Inline], color: Color32) {
+    if color.a() == 0 {
+        return;
+    }
+    let mut ranges = Vec::new();
+    link_ranges(inlines, &mut 0, &mut ranges);
+    if ranges.is_empty() {
+        return;
+    }
+    let clip = ui.clip_rect();
+    let regions = ranges
+        .into_iter()
+        .flat_map(|(chars, url)| {
+            row_rects(galley, chars)
+                .into_iter()
+                .map(move |rect| (rect, url))
+        })
+        .filter_map(|(rect, url)| {
+            let rect = rect.translate(pos.to_vec2()).intersect(clip);
+            rect.is_positive().then(|| LinkRegion {
+                rect,
+                url: url.to_string(),
+            })
+        })
+        .collect::<Vec<_>>();
+    ui.ctx().data_mut(|d| {
+        if let Some(list) = d.get_temp_mut_or_default::<Option<Vec<LinkRegion>>>(storage_id()) {
+            list.extend(regions);
+        }
+    });
+}
+
+/// Character ranges of the links in `inlines`, counted the way the text
+/// layout counts them.
+fn link_ranges<'a>(
+    inlines: &'a [Inline],
+    offset: &mut usize,
+    out: &mut Vec<(Range<usize>, &'a str)>,
+) {
+    for inline in inlines {
+        match inline {
+            Inline::Text(s) | Inline::Code(s) => *offset += s.chars().count(),
+            Inline::Bold(children) | Inline::Italic(children) | Inline::Strikethrough(children) => {
+                link_ranges(children, offset, out)
+            }
+            Inline::Link { text, url } => {
+                let start = *offset;
+                link_ranges(text, offset, out);
+                out.push((start..*offset, url));
+            }
+        }
+    }
+}
+
+/// The galley-relative bounds of characters `chars`, one rect per row.
+fn row_rects(galley: &Galley, chars: Range<usize>) -> Vec<Rect> {
+    let mut rects = Vec::new();
+    let mut row_start = 0;
+    for row in &galley.rows {
+        let mut rect = Rect::NOTHING;
+        for (i, glyph) in row.glyphs.iter().enumerate() {
+            if chars.contains(&(row_start + i)) {
+                rect = rect.union(glyph.logical_rect());
+            }
+        }
+        if rect.is_positive() {
+            rects.push(rect.translate(row.pos.to_vec2()));
+        }
+        row_start += row.char_count_including_newline();
+    }
+    rects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::text::inlines_to_job;
+
+    #[test]
+    fn records_link_rows_only_while_collecting() {
+        let inlines = vec![
+            Inline::Text("See ".into()),
+            Inline::Link {
+                text: vec![Inline::Bold(vec![Inline::Text("the results".into())])],
+                url: "#results".into(),
+            },
+            Inline::Text(" for more.".into()),
+        ];
+        let ctx = egui::Context::default();
+        let mut painted = Vec::new();
+        for collect in [false, true] {
+            if collect {
+                begin(&ctx);
+            }
+            let _ = ctx.run(egui::RawInput::default(), |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let job = inlines_to_job(&inlines, 20.0, Color32::WHITE, 1000.0);
+                    let galley = ui.painter().layout_job(job);
+                    record(
+                        ui,
+                        &galley,
+                        egui::pos2(10.0, 10.0),
+                        &inlines,
+                        Color32::WHITE,
+                    );
+                });
+            });
+            painted.push(finish(&ctx));
+        }
+        assert!(painted[0].is_empty());
+        assert_eq!(painted[1].len(), 1);
+        let region = &painted[1][0];
+        assert_eq!(region.url, "#results");
+        // Starts after "See " and ends before " for more."
+        assert!(region.rect.left() > 30.0, "{:?}", region.rect);
+        assert!(region.rect.width() > 60.0 && region.rect.width() < 200.0);
+        assert_eq!(hit(&painted[1], region.rect.center()), Some(region));
+        assert_eq!(hit(&painted[1], egui::pos2(12.0, 12.0)), None);
+    }
+
+    #[test]
+    fn splits_wrapped_links_into_one_region_per_row() {
+        let inlines = vec![Inline::Link {
+            text: vec![Inline::Text(
+                "a link long enough to wrap onto more rows".into(),
+            )],
+            url: "https://example.com".into(),
+        }];
+        let ctx = egui::Context::default();
+        begin(&ctx);
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let job = inlines_to_job(&inlines, 20.0, Color32::WHITE, 120.0);
+                let galley = ui.painter().layout_job(job);
+                record(ui, &galley, egui::Pos2::ZERO, &inlines, Color32::WHITE);
+            });
+        });
+        let regions = finish(&ctx);
+        assert!(regions.len() > 1, "{regions:?}");
+        assert!(regions[1].rect.top() >= regions[0].rect.bottom() - 1.0);
+    }
+}
@@ -1,6 +1,11 @@
+pub mod aspect;
 pub mod diagram;
+pub mod footer;
+pub mod headless;
 pub mod image_cache;
 pub mod layouts;
+pub mod links;
+pub mod svg;
 pub mod syntax;
 pub mod text;
 pub mod transition;
@@ -11,7 +16,7 @@ use std::time::Instant;
 use eframe::egui;
 
 use crate::parser::{Layout, Slide};
-use crate::theme::Theme;
+use crate::theme::{self, CustomThemes, Theme};
 
 use image_cache::ImageCache;
 
@@ -39,19 +44,110 @@ pub fn measure_slide_content_height(
     (content_height, available_height)
 }
 
-/// Render a single slide using its inferred layout.
+/// Render a single slide: its own background (if any), its layout, and its
+/// header and footer.
+///
+/// `theme` is the deck theme; slide directives (`@theme`, `@background`,
+/// `@code-theme`, `@class`) are resolved on top of it, looking up custom
+/// theme names in `custom_themes`. `footer` places the slide in its deck for
+/// the footer and header tokens.
 #[allow(clippy::too_many_arguments)]
 pub fn render_slide(
     ui: &egui::Ui,
     slide: &Slide,
     theme: &Theme,
+    custom_themes: &CustomThemes,
     rect: egui::Rect,
     opacity: f32,
     image_cache: &ImageCache,
     reveal_step: usize,
     reveal_timestamp: Option<Instant>,
     scale: f32,
+    footer: &footer::FooterContext,
 ) {
+    draw_slide_background(ui, slide, theme, custom_themes, rect, opacity, image_cache);
+    render_slide_content(
+        ui,
+        slide,
+        theme,
+        custom_themes,
+        rect,
+        opacity,
+        image_cache,
+        reveal_step,
+        reveal_timestamp,
+        scale,
+    );
+    footer::draw(
+        ui,
+        slide,
+        theme,
+        custom_themes,
+        rect,
+        opacity,
+        scale,
+        footer,
+    );
+}
+
+/// Paint the slide's own background when it differs from the deck theme
+/// (`@background` color or image, slide `@theme`, `@class: invert`).
+/// The caller is expected to have painted the deck background already.
+pub fn draw_slide_background(
+    ui: &egui::Ui,
+    slide: &Slide,
+    theme: &Theme,
+    custom_themes: &CustomThemes,
+    rect: egui::Rect,
+    opacity: f32,
+    image_cache: &ImageCache,
+) {
+    let slide_theme = theme.for_slide(slide, custom_themes);
+    if slide_theme.background != theme.background {
+        ui.painter()
+            .rect_filled(rect, 0.0, slide_theme.background.gamma_multiply(opacity));
+    }
+
+    let Some(path) = slide
+        .directive("background")
+        .filter(|v| theme::parse_hex_color(v).is_none())
+    else {
+        return;
+    };
+    let Some(texture) = image_cache.get_or_load(ui, path) else {
+        return;
+    };
+
+    // Cover the slide, cropping whatever overflows
+    let tex_size = texture.size_vec2();
+    let cover = (rect.width() / tex_size.x).max(rect.height() / tex_size.y);
+    let image_rect = egui::Rect::from_center_size(rect.center(), tex_size * cover);
+    let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+    ui.painter()
+        .with_clip_rect(rect.intersect(ui.clip_rect()))
+        .image(
+            texture.id(),
+            image_rect,
+            uv,
+            Theme::with_opacity(egui::Color32::WHITE, opacity),
+        );
+}
+
+/// Render the slide's layout (no background) with the slide-resolved theme.
+#[allow(clippy::too_many_arguments)]
+pub fn render_slide_content(
+    ui: &egui::Ui,
+    slide: &Slide,
+    theme: &Theme,
+    custom_themes: &CustomThemes,
+    rect: egui::Rect,
+    opacity: f32,
+    image_cache: &ImageCache,
+    reveal_step: usize,
+    reveal_timestamp: Option<Instant>,
+    scale: f32,
+) {
+    let theme = &*theme.for_slide(slide, custom_themes);
     match slide.layout {
         Layout::Title => layouts::title::render(ui, slide, theme, rect, opacity, scale),
         Layout::Section => layouts::section::render(ui, slide, theme, rect, opacity, scale),
@@ -0,0 +1,541 @@
+//! SVG output for painted egui shapes, so charts, diagrams and slides drawn
+//! with the painter become vector graphics. Text stays text, placed glyph by
+//! glyph where egui laid it out.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use base64::Engine;
+use eframe::egui::{self, Color32, ColorImage, FontFamily, Pos2, Rect, Stroke};
+use eframe::epaint::{
+    ClippedShape, ColorMode, PathStroke, RectShape, Shape, StrokeKind, TextShape,
+};
+
+use crate::fonts;
+
+/// CSS font family names used by SVG text. Pages embedding the SVG can
+/// define them with `@font-face` to use the deck's fonts.
+pub const BODY_FONT: &str = "mdeck-body";
+pub const HEADING_FONT: &str = "mdeck-heading";
+pub const MONO_FONT: &str = "mdeck-mono";
+
+/// Convert `shapes` to an SVG document showing `viewport`. `texture` looks up
+/// the pixels behind images painted from egui textures.
+pub fn to_svg<'a>(
+    shapes: &[ClippedShape],
+    viewport: Rect,
+    texture: &dyn Fn(egui::TextureId) -> Option<&'a ColorImage>,
+) -> String {
+    let mut writer = SvgWriter {
+        body: String::new(),
+        defs: String::new(),
+        clips: HashMap::new(),
+        images: HashMap::new(),
+        texture,
+    };
+    for clipped in shapes {
+        let clip = clipped.clip_rect.intersect(viewport);
+        let bounds = clipped.shape.visual_bounding_rect();
+        if !clip.intersects(bounds) {
+            continue;
+        }
+        if clip.contains_rect(bounds) {
+            writer.shape(&clipped.shape);
+        } else {
+            let id = writer.clip_id(clip);
+            let _ = write!(writer.body, r#"<g clip-path="url(#{id})">"#);
+            writer.shape(&clipped.shape);
+            writer.body.push_str("</g>");
+        }
+    }
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xml:space="preserve" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        num(viewport.width()),
+        num(viewport.height()),
+        num(viewport.min.x),
+        num(viewport.min.y),
+        num(viewport.width()),
+        num(viewport.height()),
+    );
+    if !writer.defs.is_empty() {
+        let _ = write!(svg, "<defs>{}</defs>", writer.defs);
+    }
+    svg.push_str(&writer.body);
+    svg.push_str("</svg>");
+    svg
+}
+
+struct SvgWriter<'t, 'a> {
+    body: String,
+    defs: String,
+    clips: HashMap<[u32; 4], String>,
+    /// PNG data URIs of already embedded texture regions.
+    images: HashMap<(egui::TextureId, [u32; 4]), String>,
+    texture: &'t dyn Fn(egui::TextureId) -> Option<&'a ColorImage>,
+}
+
+impl SvgWriter<'_, '_> {
+    fn clip_id(&mut self, clip: Rect) -> String {
+        let key = rect_key(clip);
+        let next = self.clips.len();
+        self.clips
+            .entry(key)
+            .or_insert_with(|| {
+                let id = format!("clip{next}");
+                let _ = write!(
+                    self.defs,
+                    r#"<clipPath id="{id}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+                    num(clip.min.x),
+                    num(clip.min.y),
+                    num(clip.width()),
+                    num(clip.height()),
+                );
+                id
+            })
+            .clone()
+    }
+
+    fn shape(&mut self, shape: &Shape) {
+        match shape {
+            Shape::Noop | Shape::Callback(_) => {}
+            Shape::Vec(shapes) => {
+                for shape in shapes {
+                    self.shape(shape);
+                }
+            }
+            Shape::Circle(c) => {
+                let _ = write!(
+                    self.body,
+                    r#"<circle cx="{}" cy="{}" r="{}"{}{}/>"#,
+                    num(c.center.x),
+                    num(c.center.y),
+                    num(c.radius),
+                    fill(c.fill),
+                    stroke(c.stroke),
+                );
+            }
+            Shape::Ellipse(e) => {
+                let _ = write!(
+                    self.body,
+                    r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}"{}{}/>"#,
+                    num(e.center.x),
+                    num(e.center.y),
+                    num(e.radius.x),
+                    num(e.radius.y),
+                    fill(e.fill),
+                    stroke(e.stroke),
+                );
+            }
+            Shape::LineSegment { points, stroke: s } => {
+                let _ = write!(
+                    self.body,
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{}/>"#,
+                    num(points[0].x),
+                    num(points[0].y),
+                    num(points[1].x),
+                    num(points[1].y),
+                    stroke(*s),
+                );
+            }
+            Shape::Path(path) => {
+                if path.points.len() < 2 {
+                    return;
+                }
+                let element = if path.closed { "polygon" } else { "polyline" };
+                let fill = if path.closed {
+                    fill(path.fill)
+                } else {
+                    fill(Color32::TRANSPARENT)
+                };
+                let _ = write!(
+                    self.body,
+                    r#"<{element} points="{}"{fill}{}/>"#,
+                    points(&path.points),
+                    path_stroke(&path.stroke, shape.visual_bounding_rect()),
+                );
+            }
+            Shape::Rect(rect) => self.rect(rect),
+            Shape::Text(text) => self.text(text),
+            Shape::Mesh(mesh) => {
+                // Flat-shaded triangles; meshes here are untextured fills
+                for tri in mesh.indices.chunks_exact(3) {
+                    let v = [tri[0], tri[1], tri[2]].map(|i| mesh.vertices[i as usize]);
+                    let color = v[0].color;
+                    if color.a() == 0 {
+                        continue;
+                    }
+                    let _ = write!(
+                        self.body,
+                        r#"<path d="M{} {}L{} {}L{} {}Z"{}/>"#,
+                        num(v[0].pos.x),
+                        num(v[0].pos.y),
+                        num(v[1].pos.x),
+                        num(v[1].pos.y),
+                        num(v[2].pos.x),
+                        num(v[2].pos.y),
+                        fill(color),
+                    );
+                }
+            }
+            Shape::QuadraticBezier(b) => {
+                let [p0, p1, p2] = b.points;
+                let _ = write!(
+                    self.body,
+                    r#"<path d="M{} {}Q{} {} {} {}{}"{}{}/>"#,
+                    num(p0.x),
+                    num(p0.y),
+                    num(p1.x),
+                    num(p1.y),
+                    num(p2.x),
+                    num(p2.y),
+                    if b.closed { "Z" } else { "" },
+                    fill(if b.closed {
+                        b.fill
+                    } else {
+                        Color32::TRANSPARENT
+                    }),
+                    path_stroke(&b.stroke, shape.visual_bounding_rect()),
+                );
+            }
+            Shape::CubicBezier(b) => {
+                let [p0, p1, p2, p3] = b.points;
+                let _ = write!(
+                    self.body,
+                    r#"<path d="M{} {}C{} {} {} {} {} {}{}"{}{}/>"#,
+                    num(p0.x),
+                    num(p0.y),
+                    num(p1.x),
+                    num(p1.y),
+                    num(p2.x),
+                    num(p2.y),
+                    num(p3.x),
+                    num(p3.y),
+                    if b.closed { "Z" } else { "" },
+                    fill(if b.closed {
+                        b.fill
+                    } else {
+                        Color32::TRANSPARENT
+                    }),
+                    path_stroke(&b.stroke, shape.visual_bounding_rect()),
+                );
+            }
+        }
+    }
+
+    fn rect(&mut self, shape: &RectShape) {
+        let rect = shape.rect;
+        let textured = shape
+            .brush
+            .as_ref()
+            .filter(|brush| brush.uv != Rect::ZERO)
+            .and_then(|brush| self.image_uri(brush.fill_texture_id, brush.uv));
+        if let Some(uri) = textured {
+            let opacity = shape.fill.a() as f32 / 255.0;
+            let _ = write!(
+                self.body,
+                r#"<image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="none"{} href="{uri}"/>"#,
+                num(rect.min.x),
+                num(rect.min.y),
+                num(rect.width()),
+                num(rect.height()),
+                if opacity < 1.0 {
+                    format!(r#" opacity="{}""#, num(opacity))
+                } else {
+                    String::new()
+                },
+            );
+        } else if shape.fill.a() > 0 {
+            let _ = write!(
+                self.body,
+                "{}",
+                rect_element(rect, shape.corner_radius, &fill(shape.fill))
+            );
+        }
+
+        if shape.stroke.width > 0.0 && shape.stroke.color.a() > 0 {
+            let half = shape.stroke.width / 2.0;
+            let outline = match shape.stroke_kind {
+                StrokeKind::Inside => rect.shrink(half),
+                StrokeKind::Middle => rect,
+                StrokeKind::Outside => rect.expand(half),
+            };
+            let attrs = format!("{}{}", fill(Color32::TRANSPARENT), stroke(shape.stroke));
+            let _ = write!(
+                self.body,
+                "{}",
+                rect_element(outline, shape.corner_radius, &attrs)
+            );
+        }
+    }
+
+    /// A PNG data URI of the `uv` part of a texture.
+    fn image_uri(&mut self, id: egui::TextureId, uv: Rect) -> Option<String> {
+        let key = (id, rect_key(uv));
+        if let Some(uri) = self.images.get(&key) {
+            return Some(uri.clone());
+        }
+        let image = (self.texture)(id)?;
+        let [w, h] = image.size;
+        let x0 = (uv.min.x * w as f32).round().clamp(0.0, w as f32) as usize;
+        let x1 = (uv.max.x * w as f32).round().clamp(0.0, w as f32) as usize;
+        let y0 = (uv.min.y * h as f32).round().clamp(0.0, h as f32) as usize;
+        let y1 = (uv.max.y * h as f32).round().clamp(0.0, h as f32) as usize;
+        if x0 >= x1 || y0 >= y1 {
+            return None;
+        }
+        let mut pixels = Vec::with_capacity((x1 - x0) * (y1 - y0) * 4);
+        for y in y0..y1 {
+            for c in &image.pixels[y * w + x0..y * w + x1] {
+                pixels.extend_from_slice(&c.to_srgba_unmultiplied());
+            }
+        }
+        let mut png = Vec::new();
+        image::write_buffer_with_format(
+            &mut std::io::Cursor::new(&mut png),
+            &pixels,
+            (x1 - x0) as u32,
+            (y1 - y0) as u32,
+            image::ColorType::Rgba8,
+            image::ImageFormat::Png,
+        )
+        .ok()?;
+        let uri = format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(&png)
+        );
+        self.images.insert(key, uri.clone());
+        Some(uri)
+    }
+
+    /// One `<text>` per run of glyphs sharing a format, each glyph at the x
+    /// egui gave it.
+    fn text(&mut self, text: &TextShape) {
+        if text.opacity_factor <= 0.0 {
+            return;
+        }
+        let galley = &text.galley;
+        let job = &galley.job;
+        let rotated = text.angle != 0.0;
+        if rotated {
+            let _ = write!(
+                self.body,
+                r#"<g transform="rotate({} {} {})">"#,
+                num(text.angle.to_degrees()),
+                num(text.pos.x),
+                num(text.pos.y),
+            );
+        }
+
+        // Rows hold one glyph per char of the job text, minus row-ending newlines
+        let mut chars = job.text.char_indices();
+        for row in &galley.rows {
+            let mut runs: Vec<(usize, Vec<&egui::epaint::text::Glyph>)> = Vec::new();
+            for glyph in &row.glyphs {
+                let byte = chars.next().map_or(job.text.len(), |(i, _)| i);
+                if glyph.chr.is_control() {
+                    continue;
+                }
+                let section = job
+                    .sections
+                    .iter()
+                    .position(|s| s.byte_range.contains(&byte))
+                    .unwrap_or(0);
+                match runs.last_mut() {
+                    Some((s, glyphs)) if *s == section => glyphs.push(glyph),
+                    _ => runs.push((section, vec![glyph])),
+                }
+            }
+            if row.ends_with_newline {
+                chars.next();
+            }
+
+            let origin = text.pos + row.pos.to_vec2();
+            for (section, glyphs) in runs {
+                let Some(format) = job.sections.get(section).map(|s| &s.format) else {
+                    continue;
+                };
+                let mut color = text.override_text_color.unwrap_or(format.color);
+                if color == Color32::PLACEHOLDER {
+                    color = text.fallback_color;
+                }
+                let color = color.gamma_multiply(text.opacity_factor);
+                let first = glyphs[0];
+                let last = glyphs[glyphs.len() - 1];
+                let left = origin.x + first.pos.x;
+                let right = origin.x + last.pos.x + last.advance_width;
+                let baseline = origin.y + first.pos.y;
+
+                if format.background.a() > 0 {
+                    let _ = write!(
+                        self.body,
+                        r#"<rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+                        num(left),
+                        num(origin.y),
+                        num(right - left),
+                        num(row.size.y),
+                        fill(format.background),
+                    );
+                }
+
+                let text_chars: String = glyphs.iter().map(|g| escape_char(g.chr)).collect();
+                let xs: Vec<String> = glyphs.iter().map(|g| num(origin.x + g.pos.x)).collect();
+                let _ = write!(
+                    self.body,
+                    r#"<text x="{}" y="{}" font-family="{}" font-size="{}"{}{}>{text_chars}</text>"#,
+                    xs.join(" "),
+                    num(baseline),
+                    font_family(&format.font_id.family),
+                    num(format.font_id.size),
+                    if format.italics {
+                        r#" font-style="italic""#
+                    } else {
+                        ""
+                    },
+                    fill(color),
+                );
+
+                for (line, y) in [
+                    (format.underline, baseline + 0.1 * format.font_id.size),
+                    (format.strikethrough, baseline - 0.3 * format.font_id.size),
+                ] {
+                    if line.width > 0.0 && line.color.a() > 0 {
+                        let _ = write!(
+                            self.body,
+                            r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{}/>"#,
+                            num(left),
+                            num(y),
+                            num(right),
+                            num(y),
+                            stroke(Stroke::new(line.width, line.color)),
+                        );
+                    }
+                }
+            }
+        }
+
+        if rotated {
+            self.body.push_str("</g>");
+        }
+    }
+}
+
+fn font_family(family: &FontFamily) -> String {
+    match family {
+        FontFamily::Monospace => format!("{MONO_FONT}, monospace"),
+        f if *f == fonts::heading_family() => format!("{HEADING_FONT}, {BODY_FONT}, sans-serif"),
+        _ => format!("{BODY_FONT}, sans-serif"),
+    }
+}
+
+fn rect_element(rect: Rect, radius: egui::CornerRadius, attrs: &str) -> String {
+    let (x, y, w, h) = (rect.min.x, rect.min.y, rect.width(), rect.height());
+    if radius == egui::CornerRadius::ZERO {
+        return format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}"{attrs}/>"#,
+            num(x),
+            num(y),
+            num(w),
+            num(h)
+        );
+    }
+    let max = w.min(h) / 2.0;
+    let [nw, ne, sw, se] =
+        [radius.nw, radius.ne, radius.sw, radius.se].map(|r| (r as f32).min(max));
+    if nw == ne && ne == sw && sw == se {
+        return format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}"{attrs}/>"#,
+            num(x),
+            num(y),
+            num(w),
+            num(h),
+            num(nw)
+        );
+    }
+    let (r, b) = (rect.max.x, rect.max.y);
+    format!(
+        r#"<path d="M{} {y}H{}A{ne} {ne} 0 0 1 {r} {}V{}A{se} {se} 0 0 1 {} {b}H{}A{sw} {sw} 0 0 1 {x} {}V{}A{nw} {nw} 0 0 1 {} {y}Z"{attrs}/>"#,
+        num(x + nw),
+        num(r - ne),
+        num(y + ne),
+        num(b - se),
+        num(r - se),
+        num(x + sw),
+        num(b - sw),
+        num(y + nw),
+        num(x + nw),
+    )
+}
+
+fn fill(color: Color32) -> String {
+    if color.a() == 0 {
+        return r#" fill="none""#.to_string();
+    }
+    let (hex, opacity) = paint(color);
+    match opacity {
+        Some(o) => format!(r#" fill="{hex}" fill-opacity="{o}""#),
+        None => format!(r#" fill="{hex}""#),
+    }
+}
+
+fn stroke(stroke: Stroke) -> String {
+    if stroke.width <= 0.0 || stroke.color.a() == 0 {
+        return String::new();
+    }
+    let (hex, opacity) = paint(stroke.color);
+    let mut attrs = format!(r#" stroke="{hex}" stroke-width="{}""#, num(stroke.width));
+    if let Some(o) = opacity {
+        let _ = write!(attrs, r#" stroke-opacity="{o}""#);
+    }
+    attrs
+}
+
+fn path_stroke(path_stroke: &PathStroke, bounds: Rect) -> String {
+    let color = match &path_stroke.color {
+        ColorMode::Solid(color) => *color,
+        ColorMode::UV(f) => f(bounds, bounds.center()),
+    };
+    stroke(Stroke::new(path_stroke.width, color))
+}
+
+/// `#rrggbb` and, when translucent, the opacity.
+fn paint(color: Color32) -> (String, Option<String>) {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    let hex = format!("#{r:02x}{g:02x}{b:02x}");
+    (hex, (a < 255).then(|| num(a as f32 / 255.0)))
+}
+
+fn points(points: &[Pos2]) -> String {
+    points
+        .iter()
+        .map(|p| format!("{},{}", num(p.x), num(p.y)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A coordinate with at most two decimals and no trailing zeros.
+fn num(v: f32) -> String {
+    let s = format!("{v:.2}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+fn rect_key(rect: Rect) -> [u32; 4] {
+    [rect.min.x, rect.min.y, rect.max.x, rect.max.y].map(f32::to_bits)
+}
+
+fn escape_char(c: char) -> String {
+    match c {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '"' => "&quot;".to_string(),
+        c => c.to_string(),
+    }
+}
@@ -2,7 +2,7 @@ use std::sync::LazyLock;
 
 use eframe::egui::{self, Color32, FontFamily, FontId};
 use syntect::easy::HighlightLines;
-use syntect::highlighting::ThemeSet;
+use syntect::highlighting::{Theme as SyntectTheme, ThemeSet};
 use syntect::parsing::SyntaxSet;
 
 use crate::theme::Theme;
@@ -10,6 +10,22 @@ use crate::theme::Theme;
 static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
 static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
 
+/// Look up a bundled syntect theme by name (case-insensitive).
+pub fn find_code_theme(name: &str) -> Option<&'static SyntectTheme> {
+    let ts = &*THEME_SET;
+    ts.themes.get(name).or_else(|| {
+        ts.themes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, theme)| theme)
+    })
+}
+
+/// Names of the bundled syntect themes usable with `@code-theme`.
+pub fn code_theme_names() -> Vec<&'static str> {
+    THEME_SET.themes.keys().map(|k| k.as_str()).collect()
+}
+
 /// Create a syntax-highlighted `LayoutJob` for a code block.
 pub fn highlight_code(
     code: &str,
@@ -26,10 +42,13 @@ pub fn highlight_code(
         .and_then(|lang| ss.find_syntax_by_token(lang))
         .unwrap_or_else(|| ss.find_syntax_plain_text());
 
-    let theme_name = theme.syntect_theme_name();
-    let syntect_theme = ts
-        .themes
-        .get(theme_name)
+    let syntect_theme = find_code_theme(theme.syntect_theme_name())
+        .or_else(|| {
+            // Unknown @code-theme: fall back to the default for this background
+            let mut fallback = theme.clone();
+            fallback.code_theme = None;
+            find_code_theme(fallback.syntect_theme_name())
+        })
         .unwrap_or_else(|| ts.themes.values().next().unwrap());
 
     let mut job = egui::text::LayoutJob::default();
@@ -68,3 +87,16 @@ pub fn highlight_code(
 
     job
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_code_theme_is_case_insensitive() {
+        assert!(find_code_theme("InspiredGitHub").is_some());
+        assert!(find_code_theme("solarized (dark)").is_some());
+        assert!(find_code_theme("no-such-theme").is_none());
+        assert!(code_theme_names().contains(&"base16-ocean.dark"));
+    }
+}
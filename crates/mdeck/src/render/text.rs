@@ -1,6 +1,9 @@
+use crate::fonts;
 use crate::parser::{Block, ImageDirectives, Inline, ListItem, ListMarker};
 use crate::render::diagram::draw_diagram_sized;
 use crate::render::image_cache::ImageCache;
+use crate::render::layouts::visualization::draw_viz_block;
+use crate::render::links;
 use crate::theme::Theme;
 use eframe::egui::{self, Color32, FontFamily, FontId, Pos2, Stroke};
 
@@ -10,16 +13,49 @@ pub fn inlines_to_job(
     font_size: f32,
     color: Color32,
     max_width: f32,
+) -> egui::text::LayoutJob {
+    family_job(
+        inlines,
+        &FontFamily::Proportional,
+        font_size,
+        color,
+        max_width,
+    )
+}
+
+/// Create a LayoutJob for heading text, set in the deck's heading font.
+pub fn heading_to_job(
+    inlines: &[Inline],
+    font_size: f32,
+    color: Color32,
+    max_width: f32,
+) -> egui::text::LayoutJob {
+    family_job(
+        inlines,
+        &fonts::heading_family(),
+        font_size,
+        color,
+        max_width,
+    )
+}
+
+fn family_job(
+    inlines: &[Inline],
+    family: &FontFamily,
+    font_size: f32,
+    color: Color32,
+    max_width: f32,
 ) -> egui::text::LayoutJob {
     let mut job = egui::text::LayoutJob::default();
     job.wrap.max_width = max_width;
-    append_inlines(&mut job, inlines, font_size, color, false, false);
+    append_inlines(&mut job, inlines, family, font_size, color, false, false);
     job
 }
 
 fn append_inlines(
     job: &mut egui::text::LayoutJob,
     inlines: &[Inline],
+    family: &FontFamily,
     font_size: f32,
     color: Color32,
     bold: bool,
@@ -30,7 +66,7 @@ fn append_inlines(
             Inline::Text(s) => {
                 let size = if bold { font_size + 1.0 } else { font_size };
                 let format = egui::text::TextFormat {
-                    font_id: FontId::new(size, FontFamily::Proportional),
+                    font_id: FontId::new(size, family.clone()),
                     color,
                     italics: italic,
                     ..Default::default()
@@ -38,14 +74,22 @@ fn append_inlines(
                 job.append(s, 0.0, format);
             }
             Inline::Bold(children) => {
-                append_inlines(job, children, font_size, color, true, italic);
+                append_inlines(job, children, family, font_size, color, true, italic);
             }
             Inline::Italic(children) => {
-                append_inlines(job, children, font_size, color, bold, true);
+                append_inlines(job, children, family, font_size, color, bold, true);
             }
             Inline::Strikethrough(children) => {
                 let mut inner_job = egui::text::LayoutJob::default();
-                append_inlines(&mut inner_job, children, font_size, color, bold, italic);
+                append_inlines(
+                    &mut inner_job,
+                    children,
+                    family,
+                    font_size,
+                    color,
+                    bold,
+                    italic,
+                );
                 // Apply strikethrough to all sections
                 for section in &inner_job.sections {
                     let mut format = section.format.clone();
@@ -63,9 +107,9 @@ fn append_inlines(
                 job.append(s, 0.0, format);
             }
             Inline::Link { text, .. } => {
-                // Render link text in accent color
-                let link_color = Color32::from_rgb(0x52, 0x94, 0xE2);
-                append_inlines(job, text, font_size, link_color, bold, italic);
+                // Render link text in accent color, fading with the text
+                let link_color = Color32::from_rgba_unmultiplied(0x52, 0x94, 0xE2, color.a());
+                append_inlines(job, text, family, font_size, link_color, bold, italic);
             }
         }
     }
@@ -83,6 +127,7 @@ pub fn draw_inlines(
     let job = inlines_to_job(inlines, font_size, color, max_width);
     let galley = ui.painter().layout_job(job);
     let height = galley.rect.height();
+    links::record(ui, &galley, pos, inlines, color);
     ui.painter().galley(pos, galley, color);
     height
 }
@@ -101,7 +146,13 @@ pub fn draw_heading(
 ) -> f32 {
     let size = theme.heading_size(level) * scale;
     let color = Theme::with_opacity(theme.heading_color, opacity);
-    draw_inlines(ui, inlines, pos, size, color, max_width)
+    let galley = ui
+        .painter()
+        .layout_job(heading_to_job(inlines, size, color, max_width));
+    let height = galley.rect.height();
+    links::record(ui, &galley, pos, inlines, color);
+    ui.painter().galley(pos, galley, color);
+    height
 }
 
 /// Draw a paragraph. Returns height used.
@@ -476,7 +527,7 @@ pub fn measure_single_block_height(
     match block {
         Block::Heading { level, inlines } => {
             let size = theme.heading_size(*level) * scale;
-            let job = inlines_to_job(inlines, size, theme.heading_color, max_width);
+            let job = heading_to_job(inlines, size, theme.heading_color, max_width);
             ui.painter().layout_job(job).rect.height()
         }
         Block::Paragraph { inlines } | Block::BlockQuote { inlines } => {
@@ -598,8 +649,9 @@ pub fn draw_block(
             image_cache,
             scale,
         ),
-        Block::Diagram { content } => draw_diagram_sized(
+        Block::Diagram { kind, content } => draw_diagram_sized(
             ui,
+            *kind,
             content,
             theme,
             pos,
@@ -611,177 +663,24 @@ pub fn draw_block(
             None,
             scale,
         ),
-        Block::WordCloud { content } => crate::render::visualizations::word_cloud::draw_word_cloud(
-            ui,
-            content,
-            theme,
-            pos,
-            max_width,
-            0.0,
-            opacity,
-            reveal_step,
-            scale,
-        ),
-        Block::Timeline { content } => crate::render::visualizations::timeline::draw_timeline(
-            ui,
-            content,
-            theme,
-            pos,
-            max_width,
-            0.0,
-            opacity,
-            reveal_step,
-            scale,
-        ),
-        Block::PieChart { content } => crate::render::visualizations::pie_chart::draw_pie_chart(
-            ui,
-            content,
-            theme,
-            pos,
-            max_width,
-            0.0,
-            opacity,
-            reveal_step,
-            None,
-            scale,
-        ),
-        Block::BarChart { content } => crate::render::visualizations::bar_chart::draw_bar_chart(
-            ui,
-            content,
-            theme,
-            pos,
-            max_width,
-            0.0,
-            opacity,
-            reveal_step,
-            None,
-            scale,
-        ),
-        Block::LineChart { content } => crate::render::visualizations::line_chart::draw_line_chart(
-            ui,
-            content,
-            theme,
-            pos,
-            max_width,
-            0.0,
-            opacity,
-            reveal_step,
-            None,
-            scale,
-        ),
-        Block::DonutChart { content } => {
-            crate::render::visualizations::donut_chart::draw_donut_chart(
-                ui,
-                content,
-                theme,
-                pos,
-                max_width,
-                0.0,
-                opacity,
-                reveal_step,
-                None,
-                scale,
-            )
-        }
-        Block::KpiCards { content } => crate::render::visualizations::kpi_cards::draw_kpi_cards(
-            ui,
-            content,
-            theme,
-            pos,
-            max_width,
-            0.0,
-            opacity,
-            reveal_step,
-            None,
-            scale,
-        ),
-        Block::FunnelChart { content } => {
-            crate::render::visualizations::funnel_chart::draw_funnel_chart(
-                ui,
-                content,
-                theme,
-                pos,
-                max_width,
-                0.0,
-                opacity,
-                reveal_step,
-                None,
-                scale,
-            )
-        }
-        Block::RadarChart { content } => {
-            crate::render::visualizations::radar_chart::draw_radar_chart(
-                ui,
-                content,
-                theme,
-                pos,
-                max_width,
-                0.0,
-                opacity,
-                reveal_step,
-                None,
-                scale,
-            )
-        }
-        Block::StackedBar { content } => {
-            crate::render::visualizations::stacked_bar::draw_stacked_bar(
-                ui,
-                content,
-                theme,
-                pos,
-                max_width,
-                0.0,
-                opacity,
-                reveal_step,
-                None,
-                scale,
-            )
-        }
-        Block::VennDiagram { content } => {
-            crate::render::visualizations::venn_diagram::draw_venn_diagram(
-                ui,
-                content,
-                theme,
-                pos,
-                max_width,
-                0.0,
-                opacity,
-                reveal_step,
-                None,
-                scale,
-            )
-        }
-        Block::ProgressBars { content } => {
-            crate::render::visualizations::progress_bars::draw_progress_bars(
-                ui,
-                content,
-                theme,
-                pos,
-                max_width,
-                0.0,
-                opacity,
-                reveal_step,
-                None,
-                scale,
-            )
-        }
-        Block::ScatterPlot { content } => {
-            crate::render::visualizations::scatter_plot::draw_scatter_plot(
-                ui,
-                content,
-                theme,
-                pos,
-                max_width,
-                0.0,
-                opacity,
-                reveal_step,
-                None,
-                scale,
-            )
-        }
-        Block::OrgChart { content } => crate::render::visualizations::org_chart::draw_org_chart(
+        Block::WordCloud { .. }
+        | Block::Timeline { .. }
+        | Block::PieChart { .. }
+        | Block::BarChart { .. }
+        | Block::LineChart { .. }
+        | Block::DonutChart { .. }
+        | Block::KpiCards { .. }
+        | Block::FunnelChart { .. }
+        | Block::RadarChart { .. }
+        | Block::StackedBar { .. }
+        | Block::VennDiagram { .. }
+        | Block::ProgressBars { .. }
+        | Block::ScatterPlot { .. }
+        | Block::OrgChart { .. }
+        | Block::GanttChart { .. }
+        | Block::GitGraph { .. } => draw_viz_block(
             ui,
-            content,
+            block,
             theme,
             pos,
             max_width,
@@ -791,31 +690,6 @@ pub fn draw_block(
             None,
             scale,
         ),
-        Block::GanttChart { content } => {
-            crate::render::visualizations::gantt_chart::draw_gantt_chart(
-                ui,
-                content,
-                theme,
-                pos,
-                max_width,
-                0.0,
-                opacity,
-                reveal_step,
-                None,
-                scale,
-            )
-        }
-        Block::GitGraph { content } => crate::render::visualizations::git_graph::draw_gitgraph(
-            ui,
-            content,
-            theme,
-            pos,
-            max_width,
-            0.0,
-            opacity,
-            reveal_step,
-            scale,
-        ),
         Block::HorizontalRule => {
             let color = Theme::with_opacity(theme.accent, opacity * 0.5);
             let y = pos.y + 10.0 * scale;
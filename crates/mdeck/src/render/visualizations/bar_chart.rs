@@ -531,6 +531,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_parse_bar_chart_decimal_values() {
         let content = "- A: 3.14\n- B: 2.71";
         let data = parse_bar_chart(content);
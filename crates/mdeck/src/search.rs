@@ -0,0 +1,367 @@
+//! Slide search for the search palette (`/` or Ctrl+F while presenting).
+//!
+//! Each slide is indexed by its first heading, the text of its blocks and
+//! its speaker notes. Queries match fuzzily within a line: the query's
+//! characters must appear in order, and runs of consecutive characters,
+//! matches at word starts and heading matches rank higher.
+
+use crate::parser::{self, Block, ListItem, Presentation};
+
+/// Longest snippet shown for a matching line, in characters.
+const SNIPPET_CHARS: usize = 80;
+
+/// Where on a slide a query matched.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Heading,
+    Text,
+    Notes,
+}
+
+/// The best match on one slide.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    /// Slide index, counting from 0.
+    pub slide: usize,
+    pub field: Field,
+    /// The matching line, shortened around the match.
+    pub snippet: String,
+    score: i32,
+}
+
+/// The searchable text of one slide.
+#[derive(Debug, Clone, Default)]
+struct SlideText {
+    heading: String,
+    text: String,
+    notes: String,
+}
+
+/// State of the open search palette: the slide index, the query and its hits.
+pub struct Palette {
+    index: Vec<SlideText>,
+    query: String,
+    hits: Vec<Hit>,
+    selected: usize,
+}
+
+impl Palette {
+    pub fn new(presentation: &Presentation) -> Self {
+        Self {
+            index: index(presentation),
+            query: String::new(),
+            hits: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    /// Re-index after the deck was reloaded, keeping the query.
+    pub fn reindex(&mut self, presentation: &Presentation) {
+        self.index = index(presentation);
+        self.refresh();
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Hits, best first.
+    pub fn hits(&self) -> &[Hit] {
+        &self.hits
+    }
+
+    /// Position of the selected hit in `hits()`.
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected(&self) -> Option<&Hit> {
+        self.hits.get(self.selected)
+    }
+
+    /// The first heading of slide `index`, empty when it has none.
+    pub fn heading(&self, index: usize) -> &str {
+        self.index
+            .get(index)
+            .map_or("", |text| text.heading.as_str())
+    }
+
+    /// Whether slide `index` matches the query.
+    pub fn matches(&self, index: usize) -> bool {
+        self.hits.iter().any(|hit| hit.slide == index)
+    }
+
+    /// Append typed text to the query.
+    pub fn insert(&mut self, text: &str) {
+        self.query.extend(text.chars().filter(|c| !c.is_control()));
+        self.refresh();
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.refresh();
+    }
+
+    /// Move the selection by `delta` hits, stopping at either end.
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.hits.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    fn refresh(&mut self) {
+        self.hits = search(&self.index, &self.query);
+        self.selected = 0;
+    }
+}
+
+fn index(presentation: &Presentation) -> Vec<SlideText> {
+    presentation
+        .slides
+        .iter()
+        .map(|slide| {
+            let mut lines = Vec::new();
+            for block in &slide.blocks {
+                block_lines(block, &mut lines);
+            }
+            SlideText {
+                heading: slide.title(),
+                text: lines.join("\n"),
+                notes: slide.notes.clone().unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// The text of a block, one entry per line of the slide.
+fn block_lines(block: &Block, lines: &mut Vec<String>) {
+    match block {
+        Block::Heading { inlines, .. }
+        | Block::Paragraph { inlines }
+        | Block::BlockQuote { inlines } => lines.push(parser::inlines_to_text(inlines)),
+        Block::List { items, .. } => list_lines(items, lines),
+        Block::Image { alt, .. } => lines.push(alt.clone()),
+        Block::CodeBlock { code, .. } => lines.extend(code.lines().map(String::from)),
+        Block::Table { headers, rows } => {
+            for row in std::iter::once(headers).chain(rows) {
+                let cells: Vec<String> = row.iter().map(|c| parser::inlines_to_text(c)).collect();
+                lines.push(cells.join(" | "));
+            }
+        }
+        Block::HorizontalRule | Block::ColumnSeparator => {}
+        Block::Diagram { content, .. }
+        | Block::WordCloud { content }
+        | Block::Timeline { content }
+        | Block::PieChart { content }
+        | Block::BarChart { content }
+        | Block::LineChart { content }
+        | Block::DonutChart { content }
+        | Block::KpiCards { content }
+        | Block::FunnelChart { content }
+        | Block::RadarChart { content }
+        | Block::StackedBar { content }
+        | Block::VennDiagram { content }
+        | Block::ProgressBars { content }
+        | Block::ScatterPlot { content }
+        | Block::OrgChart { content }
+        | Block::GanttChart { content }
+        | Block::GitGraph { content } => lines.extend(content.lines().map(String::from)),
+    }
+}
+
+fn list_lines(items: &[ListItem], lines: &mut Vec<String>) {
+    for item in items {
+        lines.push(parser::inlines_to_text(&item.inlines));
+        list_lines(&item.children, lines);
+    }
+}
+
+/// The best hit on each matching slide, best first.
+fn search(index: &[SlideText], query: &str) -> Vec<Hit> {
+    let query: Vec<char> = query.trim().chars().map(fold_case).collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let query = query.as_slice();
+    let mut hits: Vec<Hit> = index
+        .iter()
+        .enumerate()
+        .filter_map(|(slide, text)| {
+            [
+                (Field::Heading, &text.heading, 20),
+                (Field::Text, &text.text, 10),
+                (Field::Notes, &text.notes, 0),
+            ]
+            .into_iter()
+            .flat_map(|(field, text, bonus)| {
+                text.lines().filter_map(move |line| {
+                    let (score, at) = fuzzy_match(query, line)?;
+                    Some(Hit {
+                        slide,
+                        field,
+                        snippet: snippet(line, at),
+                        score: score + bonus,
+                    })
+                })
+            })
+            .max_by_key(|hit| hit.score)
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.cmp(&a.score).then(a.slide.cmp(&b.slide)));
+    hits
+}
+
+/// Score `query` (case-folded) against `line`, with the character position
+/// in `line` where the match starts; `None` when the line does not contain
+/// the query's characters in order, or they are too scattered to be a match.
+fn fuzzy_match(query: &[char], line: &str) -> Option<(i32, usize)> {
+    // Fold per character so positions stay those of `line`: lowercasing
+    // the whole string can change its length, as `İ` becomes `i̇`
+    let line: Vec<char> = line.chars().map(fold_case).collect();
+    // Try each occurrence of the first character and keep the best run
+    (0..line.len())
+        .filter(|&start| line[start] == query[0])
+        .filter_map(|start| {
+            let mut score = 0;
+            let mut pos = start;
+            let mut previous: Option<usize> = None;
+            for &c in query {
+                pos += line[pos..].iter().position(|&l| l == c)?;
+                let word_start = pos == 0 || !line[pos - 1].is_alphanumeric();
+                score += 1;
+                if word_start {
+                    score += 6;
+                }
+                match previous {
+                    Some(p) if p + 1 == pos => score += 4,
+                    Some(p) => score -= (pos - p - 1).min(3) as i32,
+                    None => {}
+                }
+                previous = Some(pos);
+                pos += 1;
+            }
+            Some((score, start))
+        })
+        .max_by_key(|&(score, start)| (score, std::cmp::Reverse(start)))
+        .filter(|&(score, _)| score >= query.len() as i32)
+}
+
+/// `c` lowercased to a single character, for case-insensitive matching.
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// `line` trimmed, shortened to a window around character `at`.
+fn snippet(line: &str, at: usize) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let first = chars.iter().position(|c| !c.is_whitespace()).unwrap_or(0);
+    let last = chars
+        .iter()
+        .rposition(|c| !c.is_whitespace())
+        .map_or(0, |p| p + 1);
+    if last - first <= SNIPPET_CHARS {
+        return chars[first..last].iter().collect();
+    }
+    let start = at
+        .saturating_sub(SNIPPET_CHARS / 4)
+        .clamp(first, last - SNIPPET_CHARS);
+    let end = start + SNIPPET_CHARS;
+    let mut text: String = chars[start..end].iter().collect();
+    if start > first {
+        text.insert(0, '\u{2026}');
+    }
+    if end < last {
+        text.push('\u{2026}');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_palette(markdown: &str) -> Palette {
+        Palette::new(&parser::parse(markdown, std::path::Path::new(".")))
+    }
+
+    fn slides(palette: &Palette) -> Vec<usize> {
+        palette.hits().iter().map(|hit| hit.slide).collect()
+    }
+
+    const DECK: &str = "# Welcome\n\nIntro to the quarterly review\n\n---\n\n\
+        # Revenue growth\n\n- Europe up 12%\n- Asia flat\n\n???\nMention the new \
+        Berlin office\n\n---\n\n# Roadmap\n\n| Quarter | Goal |\n|---|---|\n| Q3 | Berlin launch |\n";
+
+    #[test]
+    fn finds_headings_body_text_and_notes() {
+        let mut palette = open_palette(DECK);
+        palette.insert("revenue");
+        assert_eq!(slides(&palette), vec![1]);
+        assert_eq!(palette.selected().unwrap().field, Field::Heading);
+
+        palette.backspace();
+        assert_eq!(palette.query(), "revenu");
+        assert_eq!(slides(&palette), vec![1]);
+
+        let mut palette = open_palette(DECK);
+        palette.insert("Berlin");
+        // The table cell on slide 3 ranks above the notes on slide 2
+        assert_eq!(slides(&palette), vec![2, 1]);
+        assert_eq!(palette.hits()[0].field, Field::Text);
+        assert_eq!(palette.hits()[0].snippet, "Q3 | Berlin launch");
+        assert_eq!(palette.hits()[1].field, Field::Notes);
+        assert!(palette.matches(1) && !palette.matches(0));
+    }
+
+    #[test]
+    fn matches_fuzzily_and_ranks_tight_matches_first() {
+        let mut palette = open_palette(DECK);
+        palette.insert("rdmp");
+        assert_eq!(slides(&palette), vec![2]);
+
+        let query: Vec<char> = "grow".chars().collect();
+        let (tight, _) = fuzzy_match(&query, "Revenue growth").unwrap();
+        let (loose, _) = fuzzy_match(&query, "great rows").unwrap();
+        assert!(tight > loose);
+        // Characters spread across a long line are not a match
+        assert_eq!(fuzzy_match(&query, "bag bar zoo saw"), None);
+        assert_eq!(fuzzy_match(&query, "worg"), None);
+    }
+
+    #[test]
+    fn match_positions_are_in_the_original_line() {
+        // `İ` lowercases to two characters, which must not shift the match
+        let query: Vec<char> = "berlin".chars().collect();
+        assert_eq!(fuzzy_match(&query, "İİİ Berlin").map(|(_, at)| at), Some(4));
+
+        let line = format!("{} Berlin {}", "İ".repeat(80), "x".repeat(80));
+        let mut palette = open_palette(&format!("# Cities\n\n{line}\n"));
+        palette.insert("berlin");
+        assert!(palette.hits()[0].snippet.contains("Berlin"));
+    }
+
+    #[test]
+    fn selection_stays_within_the_hits() {
+        let mut palette = open_palette(DECK);
+        palette.insert("e");
+        let count = palette.hits().len();
+        assert!(count > 1);
+        palette.move_selection(-1);
+        assert_eq!(palette.selected_index(), 0);
+        palette.move_selection(100);
+        assert_eq!(palette.selected_index(), count - 1);
+        palette.insert("zzz");
+        assert_eq!(palette.selected_index(), 0);
+        assert!(palette.selected().is_none());
+    }
+
+    #[test]
+    fn shortens_long_lines_around_the_match() {
+        let line = format!("  {} needle {}", "a".repeat(100), "b".repeat(100));
+        let at = line.find("needle").unwrap();
+        let text = snippet(&line, at);
+        assert!(text.starts_with('\u{2026}') && text.ends_with('\u{2026}'));
+        assert!(text.contains("needle"));
+        assert_eq!(text.chars().count(), SNIPPET_CHARS + 2);
+        assert_eq!(snippet("  short  ", 2), "short");
+    }
+}
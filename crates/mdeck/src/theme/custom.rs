@@ -0,0 +1,472 @@
+//! Custom themes loaded from YAML files.
+//!
+//! A theme named `corporate` is looked up, in order, as:
+//!
+//! 1. `corporate.theme.yaml` next to the presentation
+//! 2. `themes/corporate.yaml` next to the presentation
+//! 3. `<config dir>/mdeck/themes/corporate.yaml`
+//!
+//! A `@theme` value ending in `.yaml`/`.yml` is taken as a path relative to the
+//! presentation instead. Every field is optional; unset fields come from the
+//! theme named by `extends` (default `light`), which may itself be custom.
+//! Font paths in a theme file are relative to that file.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use eframe::egui::Color32;
+use serde::Deserialize;
+
+use super::{Theme, parse_hex_color};
+use crate::check::{CheckCategory, CheckWarning};
+use crate::parser::Presentation;
+
+/// Guard against runaway `extends` chains.
+const MAX_EXTENDS_DEPTH: usize = 16;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct ThemeFile {
+    name: Option<String>,
+    extends: Option<String>,
+    background: Option<String>,
+    foreground: Option<String>,
+    heading_color: Option<String>,
+    accent: Option<String>,
+    code_background: Option<String>,
+    code_foreground: Option<String>,
+    code_theme: Option<String>,
+    font_family: Option<String>,
+    font_family_heading: Option<String>,
+    font_family_mono: Option<String>,
+    font_fallback: Option<Vec<String>>,
+    font_size_h1: Option<f32>,
+    font_size_h2: Option<f32>,
+    font_size_h3: Option<f32>,
+    font_size_body: Option<f32>,
+    font_size_code: Option<f32>,
+    icon_set: Option<String>,
+    diagram_colors: Option<Vec<String>>,
+}
+
+/// Custom themes loaded for one deck, keyed by the name used in `@theme`.
+#[derive(Debug, Clone, Default)]
+pub struct CustomThemes {
+    themes: HashMap<String, Theme>,
+    files: Vec<PathBuf>,
+}
+
+impl CustomThemes {
+    /// A loaded custom theme by name.
+    pub fn get(&self, name: &str) -> Option<&Theme> {
+        self.themes.get(name)
+    }
+
+    /// Every theme file read while loading, including `extends` parents and
+    /// files that failed to parse, so callers can watch them for changes.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+}
+
+/// Load every custom theme the deck references (frontmatter `@theme` and slide
+/// `@theme` directives). Returns the loaded themes and one warning per theme
+/// that failed to load; slide 0 stands for the frontmatter. Fonts only apply
+/// deck-wide, so a slide theme that sets fonts also gets a warning.
+pub fn load_deck_themes(
+    presentation: &Presentation,
+    base_dir: &Path,
+) -> (CustomThemes, Vec<CheckWarning>) {
+    let references = std::iter::once((0, presentation.meta.theme.as_deref())).chain(
+        presentation
+            .slides
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i + 1, s.directive("theme"))),
+    );
+
+    let mut custom = CustomThemes::default();
+    let mut failed = Vec::new();
+    let mut warnings = Vec::new();
+    for (slide, name) in references.clone() {
+        let Some(name) = name else { continue };
+        if Theme::builtin(name).is_some()
+            || custom.themes.contains_key(name)
+            || failed.iter().any(|f| f == name)
+        {
+            continue;
+        }
+        match load_chain(name, base_dir, None, &mut Vec::new(), &mut custom.files) {
+            Ok(theme) => {
+                custom.themes.insert(name.to_string(), theme);
+            }
+            Err(e) => {
+                warnings.push(CheckWarning {
+                    slide,
+                    category: CheckCategory::Theme,
+                    message: format!("Theme '{name}': {e:#}"),
+                });
+                failed.push(name.to_string());
+            }
+        }
+    }
+
+    let deck_theme = presentation.meta.theme.as_deref();
+    let mut font_warned = Vec::new();
+    for (slide, name) in references.skip(1) {
+        let Some(name) = name else { continue };
+        if Some(name) == deck_theme || font_warned.contains(&name) {
+            continue;
+        }
+        if custom.get(name).is_some_and(Theme::sets_fonts) {
+            warnings.push(CheckWarning {
+                slide,
+                category: CheckCategory::Font,
+                message: format!(
+                    "Theme '{name}' sets fonts, which only apply from the frontmatter @theme; this slide keeps the deck fonts"
+                ),
+            });
+            font_warned.push(name);
+        }
+    }
+    (custom, warnings)
+}
+
+/// Load one theme of an `extends` chain. `from` is the directory of the theme
+/// file whose `extends` names this one (`None` for a deck's `@theme`); `chain`
+/// holds the names above it (for cycle detection); every file read is
+/// recorded in `files`.
+fn load_chain(
+    name: &str,
+    base_dir: &Path,
+    from: Option<&Path>,
+    chain: &mut Vec<String>,
+    files: &mut Vec<PathBuf>,
+) -> Result<Theme> {
+    if let Some(theme) = Theme::builtin(name) {
+        return Ok(theme);
+    }
+    if chain.iter().any(|n| n == name) {
+        bail!("circular extends: {} -> {name}", chain.join(" -> "));
+    }
+    if chain.len() >= MAX_EXTENDS_DEPTH {
+        bail!("extends chain deeper than {MAX_EXTENDS_DEPTH} themes");
+    }
+    chain.push(name.to_string());
+
+    let candidates = match from {
+        Some(dir) => extends_candidate_paths(name, dir, base_dir),
+        None => candidate_paths(name, base_dir),
+    };
+    let path = candidates.iter().find(|p| p.is_file()).ok_or_else(|| {
+        let tried: Vec<String> = candidates.iter().map(|p| p.display().to_string()).collect();
+        anyhow::anyhow!("theme file not found (looked for {})", tried.join(", "))
+    })?;
+    if !files.contains(path) {
+        files.push(path.clone());
+    }
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let file: ThemeFile =
+        serde_yaml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+
+    let dir = path.parent().unwrap_or(base_dir);
+    let parent = load_chain(
+        file.extends.as_deref().unwrap_or("light"),
+        base_dir,
+        Some(dir),
+        chain,
+        files,
+    )?;
+    file.apply(parent, name, dir)
+        .with_context(|| format!("in {}", path.display()))
+}
+
+/// Where a theme called `name` may live, in search order.
+pub fn candidate_paths(name: &str, base_dir: &Path) -> Vec<PathBuf> {
+    if name.ends_with(".yaml") || name.ends_with(".yml") {
+        return vec![base_dir.join(name)];
+    }
+    let mut paths = vec![
+        base_dir.join(format!("{name}.theme.yaml")),
+        base_dir.join("themes").join(format!("{name}.yaml")),
+    ];
+    if let Some(dir) = config_themes_dir() {
+        paths.push(dir.join(format!("{name}.yaml")));
+    }
+    paths
+}
+
+/// Where the theme named by an `extends` in a file in `dir` may live: a path
+/// is relative to `dir`; a name is looked up next to that file first, then
+/// as for a deck.
+fn extends_candidate_paths(name: &str, dir: &Path, base_dir: &Path) -> Vec<PathBuf> {
+    if name.ends_with(".yaml") || name.ends_with(".yml") {
+        return vec![dir.join(name)];
+    }
+    let mut paths = vec![
+        dir.join(format!("{name}.theme.yaml")),
+        dir.join(format!("{name}.yaml")),
+    ];
+    for path in candidate_paths(name, base_dir) {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// `<config dir>/mdeck/themes`, next to `config.yaml`.
+pub fn config_themes_dir() -> Option<PathBuf> {
+    crate::config::Config::path()
+        .ok()
+        .and_then(|p| p.parent().map(|d| d.join("themes")))
+}
+
+impl ThemeFile {
+    /// Overlay this file on `theme`. Font paths are resolved against `dir`,
+    /// the directory holding the theme file.
+    fn apply(self, mut theme: Theme, name: &str, dir: &Path) -> Result<Theme> {
+        theme.name = self.name.unwrap_or_else(|| name.to_string());
+        set_color(&mut theme.background, self.background, "background")?;
+        set_color(&mut theme.foreground, self.foreground, "foreground")?;
+        set_color(
+            &mut theme.heading_color,
+            self.heading_color,
+            "heading-color",
+        )?;
+        set_color(&mut theme.accent, self.accent, "accent")?;
+        set_color(
+            &mut theme.code_background,
+            self.code_background,
+            "code-background",
+        )?;
+        set_color(
+            &mut theme.code_foreground,
+            self.code_foreground,
+            "code-foreground",
+        )?;
+        set_size(&mut theme.h1_size, self.font_size_h1, "font-size-h1")?;
+        set_size(&mut theme.h2_size, self.font_size_h2, "font-size-h2")?;
+        set_size(&mut theme.h3_size, self.font_size_h3, "font-size-h3")?;
+        set_size(&mut theme.body_size, self.font_size_body, "font-size-body")?;
+        set_size(&mut theme.code_size, self.font_size_code, "font-size-code")?;
+
+        if let Some(code_theme) = self.code_theme {
+            if crate::render::syntax::find_code_theme(&code_theme).is_none() {
+                bail!(
+                    "unknown code-theme '{code_theme}' (available: {})",
+                    crate::render::syntax::code_theme_names().join(", ")
+                );
+            }
+            theme.code_theme = Some(code_theme);
+        }
+        if let Some(font) = self.font_family {
+            theme.font_family = Some(dir.join(font));
+        }
+        if let Some(font) = self.font_family_heading {
+            theme.font_family_heading = Some(dir.join(font));
+        }
+        if let Some(font) = self.font_family_mono {
+            theme.font_family_mono = Some(dir.join(font));
+        }
+        if let Some(fallback) = self.font_fallback {
+            theme.font_fallback = fallback.iter().map(|f| dir.join(f)).collect();
+        }
+        if self.icon_set.is_some() {
+            theme.icon_set = self.icon_set;
+        }
+        if let Some(colors) = self.diagram_colors {
+            if colors.is_empty() {
+                bail!("diagram-colors must list at least one color");
+            }
+            let palette = colors
+                .iter()
+                .map(|c| parse_color(c, "diagram-colors"))
+                .collect::<Result<Vec<Color32>>>()?;
+            theme.diagram_colors = Some(palette);
+        }
+        Ok(theme)
+    }
+}
+
+fn parse_color(value: &str, field: &str) -> Result<Color32> {
+    parse_hex_color(value)
+        .ok_or_else(|| anyhow::anyhow!("invalid color for {field}: '{value}' (expected #RRGGBB)"))
+}
+
+fn set_color(target: &mut Color32, value: Option<String>, field: &str) -> Result<()> {
+    if let Some(value) = value {
+        *target = parse_color(&value, field)?;
+    }
+    Ok(())
+}
+
+fn set_size(target: &mut f32, value: Option<f32>, field: &str) -> Result<()> {
+    if let Some(value) = value {
+        if !(value.is_finite() && value > 0.0) {
+            bail!("{field} must be a positive number, got {value}");
+        }
+        *target = value;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn load_theme(name: &str, base_dir: &Path) -> Result<Theme> {
+        load_chain(name, base_dir, None, &mut Vec::new(), &mut Vec::new())
+    }
+
+    #[test]
+    fn extends_builtin_and_overrides_fields() {
+        let dir = TempDir::new("theme-extends");
+        dir.write("corp.theme.yaml",
+            "extends: dark\naccent: \"#FF6600\"\nfont-size-h1: 80\ncode-theme: Solarized (dark)\nicon-set: brand-icons\ndiagram-colors: [\"#112233\", \"#445566\"]\nfont-family: fonts/Inter.ttf\n",
+        );
+        let theme = load_theme("corp", &dir).unwrap();
+        assert_eq!(theme.name, "corp");
+        assert_eq!(theme.base, "dark");
+        assert_eq!(theme.accent, Color32::from_rgb(0xFF, 0x66, 0x00));
+        assert_eq!(theme.background, Theme::dark().background);
+        assert_eq!(theme.h1_size, 80.0);
+        assert_eq!(theme.syntect_theme_name(), "Solarized (dark)");
+        assert_eq!(theme.icon_set.as_deref(), Some("brand-icons"));
+        assert_eq!(theme.font_family, Some(dir.join("fonts/Inter.ttf")));
+        assert_eq!(
+            theme.edge_palette(),
+            vec![
+                Color32::from_rgb(0x11, 0x22, 0x33),
+                Color32::from_rgb(0x44, 0x55, 0x66)
+            ]
+        );
+    }
+
+    #[test]
+    fn extends_another_custom_theme() {
+        let dir = TempDir::new("theme-chain");
+        dir.write("themes/base.yaml", "extends: nord\naccent: \"#010203\"\n");
+        dir.write("themes/child.yaml", "extends: base\nname: Child\n");
+        let theme = load_theme("child", &dir).unwrap();
+        assert_eq!(theme.name, "Child");
+        assert_eq!(theme.base, "nord");
+        assert_eq!(theme.accent, Color32::from_rgb(1, 2, 3));
+    }
+
+    #[test]
+    fn extends_is_relative_to_the_declaring_file() {
+        let dir = TempDir::new("theme-nested");
+        dir.write(
+            "themes/corp/base.yaml",
+            "extends: nord\naccent: \"#010203\"\n",
+        );
+        dir.write(
+            "themes/corp/dark.yaml",
+            "extends: base.yaml\nname: Corp Dark\n",
+        );
+        dir.write("themes/corp/light.yaml", "extends: base\n");
+        // A same-named theme next to the deck does not shadow the sibling
+        dir.write("base.theme.yaml", "extends: dark\n");
+
+        let theme = load_theme("themes/corp/dark.yaml", &dir).unwrap();
+        assert_eq!(theme.name, "Corp Dark");
+        assert_eq!(theme.base, "nord");
+        assert_eq!(theme.accent, Color32::from_rgb(1, 2, 3));
+        let theme = load_theme("themes/corp/light.yaml", &dir).unwrap();
+        assert_eq!(theme.base, "nord");
+    }
+
+    #[test]
+    fn default_parent_is_light() {
+        let dir = TempDir::new("theme-plain");
+        dir.write("plain.theme.yaml", "heading-color: \"#000\"\n");
+        let theme = load_theme("plain", &dir).unwrap();
+        assert_eq!(theme.base, "light");
+        assert_eq!(theme.heading_color, Color32::BLACK);
+    }
+
+    #[test]
+    fn path_reference_is_relative_to_deck() {
+        let dir = TempDir::new("theme-path");
+        dir.write("styles/brand.yml", "extends: dark\n");
+        let theme = load_theme("styles/brand.yml", &dir).unwrap();
+        assert_eq!(theme.base, "dark");
+    }
+
+    #[test]
+    fn font_paths_are_relative_to_theme_file() {
+        let dir = TempDir::new("theme-fonts");
+        dir.write(
+            "themes/brand.yaml",
+            "font-family: fonts/Inter.ttf\nfont-fallback: [cjk.otf]\n",
+        );
+        let theme = load_theme("brand", &dir).unwrap();
+        assert_eq!(theme.font_family, Some(dir.join("themes/fonts/Inter.ttf")));
+        assert_eq!(theme.font_fallback, vec![dir.join("themes/cjk.otf")]);
+    }
+
+    #[test]
+    fn errors_are_descriptive() {
+        let dir = TempDir::new("theme-errors");
+        dir.write("a.theme.yaml", "extends: b\n");
+        dir.write("b.theme.yaml", "extends: a\n");
+        dir.write("typo.theme.yaml", "accentt: \"#fff\"\n");
+        dir.write("badcolor.theme.yaml", "accent: orange\n");
+
+        let err = format!("{:#}", load_theme("a", &dir).unwrap_err());
+        assert!(err.contains("circular extends: a -> b -> a"), "{err}");
+        let err = format!("{:#}", load_theme("typo", &dir).unwrap_err());
+        assert!(err.contains("accentt"), "{err}");
+        let err = format!("{:#}", load_theme("badcolor", &dir).unwrap_err());
+        assert!(err.contains("invalid color for accent"), "{err}");
+        let err = format!("{:#}", load_theme("missing", &dir).unwrap_err());
+        assert!(err.contains("theme file not found"), "{err}");
+    }
+
+    #[test]
+    fn deck_themes_are_scoped_to_the_deck() {
+        let dir = TempDir::new("theme-deck");
+        dir.write("themes/base.yaml", "extends: dark\n");
+        dir.write("corp.theme.yaml", "extends: base\naccent: \"#FF6600\"\n");
+        let deck = crate::parser::parse(
+            "---\n@theme: corp\n---\n# A\n\n---\n\n@theme: gone\n# B",
+            &dir,
+        );
+
+        let (custom, warnings) = load_deck_themes(&deck, &dir);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].slide, 2);
+        assert_eq!(
+            custom.files(),
+            [dir.join("corp.theme.yaml"), dir.join("themes/base.yaml")]
+        );
+        let theme = Theme::for_deck(&deck.meta, &custom);
+        assert_eq!(theme.accent, Color32::from_rgb(0xFF, 0x66, 0x00));
+        assert_eq!(theme.base, "dark");
+
+        // Without the deck's themes the same name falls back to light
+        assert_eq!(
+            Theme::for_deck(&deck.meta, &CustomThemes::default()).name,
+            "light"
+        );
+    }
+
+    #[test]
+    fn slide_theme_fonts_are_reported() {
+        let dir = TempDir::new("theme-slide-fonts");
+        dir.write("serif.theme.yaml", "font-family: serif.ttf\n");
+        dir.write("plain.theme.yaml", "accent: \"#000000\"\n");
+        let deck = crate::parser::parse(
+            "# A\n\n---\n\n@theme: serif\n# B\n\n---\n\n@theme: plain\n# C\n\n---\n\n@theme: serif\n# D",
+            &dir,
+        );
+        let (_, warnings) = load_deck_themes(&deck, &dir);
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert_eq!(warnings[0].slide, 2);
+        assert_eq!(warnings[0].category, CheckCategory::Font);
+    }
+}
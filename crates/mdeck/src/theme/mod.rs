@@ -0,0 +1,436 @@
+pub mod custom;
+
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use eframe::egui::Color32;
+
+use crate::parser::{PresentationMeta, Slide};
+
+pub use custom::{CustomThemes, load_deck_themes};
+
+/// Names of the themes compiled into mdeck.
+pub const BUILTIN_THEMES: &[&str] = &["light", "dark", "nord"];
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub name: String,
+    /// Built-in theme this one derives from (`light`, `dark` or `nord`);
+    /// selects the default trend colors and edge palette.
+    pub base: String,
+    pub background: Color32,
+    pub foreground: Color32,
+    pub heading_color: Color32,
+    pub accent: Color32,
+    pub code_background: Color32,
+    pub code_foreground: Color32,
+    pub h1_size: f32,
+    pub h2_size: f32,
+    pub h3_size: f32,
+    pub body_size: f32,
+    pub code_size: f32,
+    /// Syntect theme override from `@code-theme`; `None` picks one to match the background.
+    pub code_theme: Option<String>,
+    /// Body/heading font file from a custom theme (`font-family`), resolved
+    /// against the theme file's directory.
+    pub font_family: Option<PathBuf>,
+    /// Heading font file from a custom theme (`font-family-heading`).
+    pub font_family_heading: Option<PathBuf>,
+    /// Code font file from a custom theme (`font-family-mono`).
+    pub font_family_mono: Option<PathBuf>,
+    /// Fallback font files for missing glyphs (`font-fallback`).
+    pub font_fallback: Vec<PathBuf>,
+    /// Directory of diagram icon images (`icon-set`), relative to the deck.
+    pub icon_set: Option<String>,
+    /// Custom palette for diagram edges and visualizations (`diagram-colors`).
+    pub diagram_colors: Option<Vec<Color32>>,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            name: "dark".to_string(),
+            base: "dark".to_string(),
+            background: Color32::from_rgb(0x1E, 0x1E, 0x1E),
+            foreground: Color32::from_rgb(0xC8, 0xC8, 0xC8),
+            heading_color: Color32::WHITE,
+            accent: Color32::from_rgb(0x52, 0x94, 0xE2),
+            code_background: Color32::from_rgb(0x2D, 0x2D, 0x2D),
+            code_foreground: Color32::from_rgb(0xD4, 0xD4, 0xD4),
+            h1_size: 96.0,
+            h2_size: 72.0,
+            h3_size: 52.0,
+            body_size: 44.0,
+            code_size: 30.0,
+            code_theme: None,
+            font_family: None,
+            font_family_heading: None,
+            font_family_mono: None,
+            font_fallback: Vec::new(),
+            icon_set: None,
+            diagram_colors: None,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            name: "light".to_string(),
+            base: "light".to_string(),
+            background: Color32::WHITE,
+            foreground: Color32::from_rgb(0x1A, 0x1A, 0x2E),
+            heading_color: Color32::from_rgb(0x16, 0x21, 0x3E),
+            accent: Color32::from_rgb(0x0F, 0x34, 0x60),
+            code_background: Color32::from_rgb(0xF5, 0xF5, 0xF5),
+            code_foreground: Color32::from_rgb(0x33, 0x33, 0x33),
+            h1_size: 96.0,
+            h2_size: 72.0,
+            h3_size: 52.0,
+            body_size: 44.0,
+            code_size: 30.0,
+            code_theme: None,
+            font_family: None,
+            font_family_heading: None,
+            font_family_mono: None,
+            font_fallback: Vec::new(),
+            icon_set: None,
+            diagram_colors: None,
+        }
+    }
+
+    /// Nord: an arctic, blue-gray theme inspired by the polar landscape.
+    /// Calm, muted, and professional — distinct from both dark and light.
+    pub fn nord() -> Self {
+        Self {
+            name: "nord".to_string(),
+            base: "nord".to_string(),
+            background: Color32::from_rgb(0x2E, 0x34, 0x40), // Polar Night
+            foreground: Color32::from_rgb(0xD8, 0xDE, 0xE9), // Snow Storm
+            heading_color: Color32::from_rgb(0xEC, 0xEF, 0xF4), // Snow Storm bright
+            accent: Color32::from_rgb(0x81, 0xA1, 0xC1),     // Frost medium blue
+            code_background: Color32::from_rgb(0x3B, 0x42, 0x52), // Polar Night lighter
+            code_foreground: Color32::from_rgb(0xD8, 0xDE, 0xE9), // Snow Storm
+            h1_size: 96.0,
+            h2_size: 72.0,
+            h3_size: 52.0,
+            body_size: 44.0,
+            code_size: 30.0,
+            code_theme: None,
+            font_family: None,
+            font_family_heading: None,
+            font_family_mono: None,
+            font_fallback: Vec::new(),
+            icon_set: None,
+            diagram_colors: None,
+        }
+    }
+
+    /// A built-in theme by name, or `None` for anything else.
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "light" => Some(Self::light()),
+            "dark" => Some(Self::dark()),
+            "nord" => Some(Self::nord()),
+            _ => None,
+        }
+    }
+
+    /// Whether the theme names any font files.
+    pub fn sets_fonts(&self) -> bool {
+        self.font_family.is_some()
+            || self.font_family_heading.is_some()
+            || self.font_family_mono.is_some()
+            || !self.font_fallback.is_empty()
+    }
+
+    /// A built-in theme by name; unknown names fall back to light.
+    pub fn from_name(name: &str) -> Self {
+        Self::builtin(name).unwrap_or_else(Self::light)
+    }
+
+    /// Look up a theme by name: built-ins first, then the deck's custom themes
+    /// (see [`load_deck_themes`]). Unknown names fall back to light.
+    pub fn lookup(name: &str, custom: &CustomThemes) -> Self {
+        custom
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| Self::from_name(name))
+    }
+
+    /// Build the deck-wide theme from frontmatter (`@theme`, `@code-theme`).
+    pub fn for_deck(meta: &PresentationMeta, custom: &CustomThemes) -> Self {
+        let mut theme = Self::lookup(meta.theme.as_deref().unwrap_or("light"), custom);
+        if meta.code_theme.is_some() {
+            theme.code_theme = meta.code_theme.clone();
+        }
+        theme
+    }
+
+    /// Resolve the theme for one slide: its `@theme`, `@code-theme`, `@background`
+    /// and `@class` directives override the deck theme. Borrows when nothing changes.
+    pub fn for_slide(&self, slide: &Slide, custom: &CustomThemes) -> Cow<'_, Theme> {
+        let theme_name = slide.directive("theme");
+        let code_theme = slide.directive("code-theme");
+        let background = slide.directive("background").and_then(parse_hex_color);
+        let invert = slide.has_class("invert");
+        if theme_name.is_none() && code_theme.is_none() && background.is_none() && !invert {
+            return Cow::Borrowed(self);
+        }
+
+        let mut theme = match theme_name {
+            Some(name) => {
+                let mut theme = Self::lookup(name, custom);
+                if self.code_theme.is_some() {
+                    theme.code_theme = self.code_theme.clone();
+                }
+                theme
+            }
+            None => self.clone(),
+        };
+        if let Some(name) = code_theme {
+            theme.code_theme = Some(name.to_string());
+        }
+        if invert {
+            theme.invert();
+        }
+        if let Some(color) = background {
+            theme.background = color;
+        }
+        Cow::Owned(theme)
+    }
+
+    /// Swap light and dark: background and text colors trade places.
+    pub fn invert(&mut self) {
+        std::mem::swap(&mut self.background, &mut self.foreground);
+        self.heading_color = self.foreground;
+        self.code_background = mix(self.background, self.foreground, 0.08);
+        self.code_foreground = mix(self.foreground, self.background, 0.1);
+    }
+
+    /// Whether the background is light (used to pick contrasting defaults).
+    pub fn is_light(&self) -> bool {
+        let c = self.background;
+        0.299 * c.r() as f32 + 0.587 * c.g() as f32 + 0.114 * c.b() as f32 > 140.0
+    }
+
+    /// Cycle to the next built-in theme: dark → light → nord → dark.
+    /// A custom theme continues the cycle from the theme it extends.
+    pub fn next(&self) -> Self {
+        match self.base.as_str() {
+            "dark" => Self::light(),
+            "light" => Self::nord(),
+            _ => Self::dark(),
+        }
+    }
+
+    /// Apply opacity to a color
+    pub fn with_opacity(color: Color32, opacity: f32) -> Color32 {
+        Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), (opacity * 255.0) as u8)
+    }
+
+    pub fn heading_size(&self, level: u8) -> f32 {
+        match level {
+            1 => self.h1_size,
+            2 => self.h2_size,
+            3 => self.h3_size,
+            _ => self.body_size,
+        }
+    }
+
+    /// Return the syntect theme name for code blocks: the `@code-theme` override
+    /// if set, otherwise one that matches the background.
+    pub fn syntect_theme_name(&self) -> &str {
+        if let Some(ref name) = self.code_theme {
+            return name;
+        }
+        if self.is_light() {
+            "InspiredGitHub"
+        } else {
+            // Both dark and nord use the same syntect theme
+            "base16-ocean.dark"
+        }
+    }
+
+    /// Theme-aware positive trend color (green).
+    pub fn positive_color(&self) -> Color32 {
+        match self.base.as_str() {
+            "light" => Color32::from_rgb(0x16, 0x7A, 0x3E), // dark green on light bg
+            "nord" => Color32::from_rgb(0xA3, 0xBE, 0x8C),  // aurora green
+            _ => Color32::from_rgb(0x5C, 0xDB, 0x95),       // mint green on dark bg
+        }
+    }
+
+    /// Theme-aware negative trend color (red).
+    pub fn negative_color(&self) -> Color32 {
+        match self.base.as_str() {
+            "light" => Color32::from_rgb(0xB9, 0x2D, 0x2D), // dark red on light bg
+            "nord" => Color32::from_rgb(0xBF, 0x61, 0x6A),  // aurora red
+            _ => Color32::from_rgb(0xFF, 0x6B, 0x6B),       // bright red on dark bg
+        }
+    }
+
+    /// Return a palette of distinct colors for diagram edges and visualizations.
+    /// Colors are chosen to be visually distinct and readable against the theme background.
+    pub fn edge_palette(&self) -> Vec<Color32> {
+        if let Some(ref colors) = self.diagram_colors {
+            return colors.clone();
+        }
+        match self.base.as_str() {
+            "light" => vec![
+                Color32::from_rgb(0x1A, 0x6B, 0xB5), // deep blue
+                Color32::from_rgb(0xC7, 0x3E, 0x1D), // brick red
+                Color32::from_rgb(0x1E, 0x8A, 0x5A), // forest green
+                Color32::from_rgb(0xB8, 0x7B, 0x0A), // dark amber
+                Color32::from_rgb(0x7B, 0x3F, 0xA0), // purple
+                Color32::from_rgb(0x18, 0x8A, 0x8D), // teal
+                Color32::from_rgb(0xC4, 0x3B, 0x7A), // magenta
+                Color32::from_rgb(0x5A, 0x7A, 0x2B), // olive
+            ],
+            "nord" => vec![
+                Color32::from_rgb(0x88, 0xC0, 0xD0), // frost teal
+                Color32::from_rgb(0xBF, 0x61, 0x6A), // aurora red
+                Color32::from_rgb(0xA3, 0xBE, 0x8C), // aurora green
+                Color32::from_rgb(0xEB, 0xCB, 0x8B), // aurora yellow
+                Color32::from_rgb(0xB4, 0x8E, 0xAD), // aurora purple
+                Color32::from_rgb(0x5E, 0x81, 0xAC), // frost blue
+                Color32::from_rgb(0xD0, 0x87, 0x70), // aurora orange
+                Color32::from_rgb(0x8F, 0xBC, 0xBB), // frost light teal
+            ],
+            _ => vec![
+                // dark
+                Color32::from_rgb(0x5C, 0xB8, 0xFF), // bright blue
+                Color32::from_rgb(0xFF, 0x7E, 0x67), // coral
+                Color32::from_rgb(0x5C, 0xDB, 0x95), // mint green
+                Color32::from_rgb(0xE8, 0xA8, 0x38), // amber
+                Color32::from_rgb(0xC0, 0x7E, 0xF1), // purple
+                Color32::from_rgb(0x4E, 0xD4, 0xD4), // teal
+                Color32::from_rgb(0xF0, 0x6E, 0xAA), // pink
+                Color32::from_rgb(0xA3, 0xBE, 0x58), // olive green
+            ],
+        }
+    }
+}
+
+/// Parse a `#RGB`, `#RRGGBB` or `#RRGGBBAA` hex color.
+pub fn parse_hex_color(s: &str) -> Option<Color32> {
+    let hex = s.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    match hex.len() {
+        3 => {
+            let nibble = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|v| v * 17);
+            Some(Color32::from_rgb(nibble(0)?, nibble(1)?, nibble(2)?))
+        }
+        6 => Some(Color32::from_rgb(byte(0)?, byte(2)?, byte(4)?)),
+        8 => Some(Color32::from_rgba_unmultiplied(
+            byte(0)?,
+            byte(2)?,
+            byte(4)?,
+            byte(6)?,
+        )),
+        _ => None,
+    }
+}
+
+/// Linear blend from `a` towards `b` by `t` (0.0–1.0).
+fn mix(a: Color32, b: Color32, t: f32) -> Color32 {
+    let lerp = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    Color32::from_rgb(lerp(a.r(), b.r()), lerp(a.g(), b.g()), lerp(a.b(), b.b()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+    use std::path::Path;
+
+    fn slide(src: &str) -> Slide {
+        parser::parse(src, Path::new(".")).slides.remove(0)
+    }
+
+    #[test]
+    fn parse_hex_color_forms() {
+        assert_eq!(parse_hex_color("#fff"), Some(Color32::WHITE));
+        assert_eq!(
+            parse_hex_color("#1A2B3C"),
+            Some(Color32::from_rgb(0x1A, 0x2B, 0x3C))
+        );
+        assert_eq!(
+            parse_hex_color("#00000080"),
+            Some(Color32::from_rgba_unmultiplied(0, 0, 0, 0x80))
+        );
+        assert_eq!(parse_hex_color("1A2B3C"), None);
+        assert_eq!(parse_hex_color("#12345"), None);
+        assert_eq!(parse_hex_color("#zzzzzz"), None);
+        assert_eq!(parse_hex_color("images/bg.png"), None);
+    }
+
+    #[test]
+    fn for_slide_borrows_without_directives() {
+        let deck = Theme::light();
+        let s = slide("# Plain\n\nText");
+        assert!(matches!(
+            deck.for_slide(&s, &CustomThemes::default()),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn for_slide_theme_keeps_deck_code_theme() {
+        let mut deck = Theme::light();
+        deck.code_theme = Some("Solarized (dark)".to_string());
+        let theme = deck.for_slide(&slide("@theme: nord\n# Nord"), &CustomThemes::default());
+        assert_eq!(theme.name, "nord");
+        assert_eq!(theme.syntect_theme_name(), "Solarized (dark)");
+    }
+
+    #[test]
+    fn for_slide_code_theme_overrides_deck() {
+        let mut deck = Theme::dark();
+        deck.code_theme = Some("Solarized (dark)".to_string());
+        let theme = deck.for_slide(
+            &slide("@code-theme: InspiredGitHub\n# Code"),
+            &CustomThemes::default(),
+        );
+        assert_eq!(theme.name, "dark");
+        assert_eq!(theme.syntect_theme_name(), "InspiredGitHub");
+    }
+
+    #[test]
+    fn for_slide_background_color_and_invert() {
+        let deck = Theme::light();
+        let theme = deck.for_slide(
+            &slide("@background: #102030\n# Colored"),
+            &CustomThemes::default(),
+        );
+        assert_eq!(theme.background, Color32::from_rgb(0x10, 0x20, 0x30));
+        assert_eq!(theme.foreground, deck.foreground);
+
+        let inverted = deck.for_slide(
+            &slide("@class: invert\n# Inverted"),
+            &CustomThemes::default(),
+        );
+        assert_eq!(inverted.background, deck.foreground);
+        assert_eq!(inverted.foreground, deck.background);
+        assert!(!inverted.is_light());
+        assert_eq!(inverted.syntect_theme_name(), "base16-ocean.dark");
+    }
+
+    #[test]
+    fn for_slide_image_background_keeps_theme_color() {
+        let deck = Theme::dark();
+        let s = slide("@background: images/stage.jpg\n# Photo");
+        assert_eq!(
+            deck.for_slide(&s, &CustomThemes::default()).background,
+            deck.background
+        );
+    }
+
+    #[test]
+    fn builtin_syntect_defaults_unchanged() {
+        assert_eq!(Theme::light().syntect_theme_name(), "InspiredGitHub");
+        assert_eq!(Theme::dark().syntect_theme_name(), "base16-ocean.dark");
+        assert_eq!(Theme::nord().syntect_theme_name(), "base16-ocean.dark");
+    }
+}